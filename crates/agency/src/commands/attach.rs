@@ -1,6 +1,9 @@
+use std::time::{Duration, Instant};
+
 use anyhow::Result;
 
 use crate::config::AppContext;
+use crate::daemon_protocol::SessionInfo;
 use crate::daemon_protocol::TaskMeta;
 use crate::daemon_protocol::TuiListItem;
 use crate::daemon_protocol::{
@@ -10,16 +13,39 @@ use crate::utils::daemon as dutil;
 use crate::utils::daemon::get_project_state;
 use crate::utils::git::{open_main_repo, repo_workdir_or};
 use crate::utils::interactive;
+use crate::utils::log::t;
 use crate::utils::session::{build_session_plan, start_session_for_task};
 use crate::utils::task::{TaskRef, read_task_content, resolve_id_or_slug};
 use crate::utils::tmux;
+use crate::{log_info, log_warn};
 use crossbeam_channel::unbounded;
 use crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
 mod overlay;
 use overlay::{OverlayMode, OverlayUI};
 use std::process::Child;
 
-pub fn run_with_task(ctx: &AppContext, ident: &str) -> Result<()> {
+/// How long `--reconnect` waits for a new session after the attached one exits.
+const RECONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How often `--wait-ready-ms` checks whether the session showed up.
+const READY_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Attach to the latest session of the task, starting one when there is none.
+///
+/// With a non-zero `wait_ready`, a session that is still being started (e.g.
+/// by a concurrent `agency start`) is awaited for up to that long before a new
+/// one is started, or before failing in `read_only` mode.
+///
+/// # Errors
+/// Returns an error if there is no TTY, the task cannot be resolved, the
+/// daemon cannot be reached or no session can be attached.
+pub fn run_with_task(
+  ctx: &AppContext,
+  ident: &str,
+  read_only: bool,
+  reconnect: bool,
+  wait_ready: Duration,
+) -> Result<()> {
   if !ctx.tty.is_interactive() {
     anyhow::bail!("attach requires an interactive terminal (TTY). Run this command in an interactive shell or terminal.");
   }
@@ -33,41 +59,184 @@ pub fn run_with_task(ctx: &AppContext, ident: &str) -> Result<()> {
   let task = resolve_id_or_slug(&ctx.paths, ident)?;
 
   // Query existing sessions and join the latest for this task; error if none
-  let entries = get_project_state(ctx)?.sessions;
-  let target = entries
-    .into_iter()
-    .filter(|e| e.task.id == task.id && e.task.slug == task.slug)
-    .max_by_key(|e| e.created_at_ms);
+  let target = wait_for_session(ctx, wait_ready, |e| {
+    e.task.id == task.id && e.task.slug == task.slug
+  })?;
 
   let task_meta = TaskMeta {
     id: task.id,
     slug: task.slug.clone(),
   };
   if target.is_some() {
-    return interactive::scope(|| tmux::attach_session(&ctx.config, &task_meta));
+    return interactive::scope(|| attach(ctx, &task_meta, read_only, reconnect));
+  }
+  if read_only {
+    anyhow::bail!("No running session for task {}-{}", task.id, task.slug);
   }
   // Auto-start when missing using shared session helpers, then attach
-  let plan = build_session_plan(ctx, &task)?;
+  let plan = build_session_plan(ctx, &task, None)?;
 
   crate::utils::daemon::notify_after_task_change(ctx, || {
     start_session_for_task(ctx, &plan, false)?;
-    interactive::scope(|| tmux::attach_session(&ctx.config, &plan.task_meta))
+    interactive::scope(|| attach(ctx, &plan.task_meta, false, reconnect))
   })
 }
 
-pub fn run_join_session(ctx: &AppContext, session_id: u64) -> Result<()> {
+/// Attach to the session with `session_id`, waiting up to `wait_ready` for it
+/// to show up.
+///
+/// # Errors
+/// Returns an error if there is no TTY, the daemon cannot be reached or the
+/// session does not exist.
+pub fn run_join_session(
+  ctx: &AppContext,
+  session_id: u64,
+  read_only: bool,
+  reconnect: bool,
+  wait_ready: Duration,
+) -> Result<()> {
   if !ctx.tty.is_interactive() {
     anyhow::bail!("attach requires an interactive terminal (TTY). Run this command in an interactive shell or terminal.");
   }
 
-  let entries = get_project_state(ctx)?.sessions;
-  let Some(si) = entries.into_iter().find(|e| e.session_id == session_id) else {
+  let Some(si) = wait_for_session(ctx, wait_ready, |e| e.session_id == session_id)? else {
     anyhow::bail!("Session not found: {session_id}");
   };
-  interactive::scope(|| tmux::attach_session(&ctx.config, &si.task))
+  interactive::scope(|| attach(ctx, &si.task, read_only, reconnect))
+}
+
+/// Latest session matching `matches`, polling the daemon for up to `wait`
+/// until one shows up. A zero `wait` checks once.
+fn wait_for_session(
+  ctx: &AppContext,
+  wait: Duration,
+  matches: impl Fn(&SessionInfo) -> bool,
+) -> Result<Option<SessionInfo>> {
+  let deadline = Instant::now() + wait;
+  loop {
+    let found = get_project_state(ctx)?
+      .sessions
+      .into_iter()
+      .filter(|si| matches(si))
+      .max_by_key(|si| si.created_at_ms);
+    if found.is_some() || Instant::now() >= deadline {
+      return Ok(found);
+    }
+    std::thread::sleep(READY_POLL_INTERVAL);
+  }
+}
+
+/// Attach to the task's session. With `reconnect`, attach again whenever the
+/// session exits and a new one for the task shows up.
+///
+/// Without a known session (e.g. the daemon is unreachable) a detach cannot be
+/// told apart from an exit, so the client does not reconnect.
+fn attach(ctx: &AppContext, task: &TaskMeta, read_only: bool, reconnect: bool) -> Result<()> {
+  let known = latest_session(ctx, task.id).filter(|_| reconnect);
+  let Some(mut session_id) = known.map(|si| si.session_id) else {
+    return attach_once(ctx, task, read_only);
+  };
+  loop {
+    let attached = attach_once(ctx, task, read_only);
+    let Some(next) = wait_for_replacement_session(ctx, task, session_id) else {
+      return attached;
+    };
+    session_id = next.session_id;
+  }
+}
+
+fn attach_once(ctx: &AppContext, task: &TaskMeta, read_only: bool) -> Result<()> {
+  if read_only {
+    return tmux::attach_session_read_only(&ctx.config, task);
+  }
+  tmux::attach_session(&ctx.config, task)
+}
+
+fn task_sessions(ctx: &AppContext, task_id: u32) -> Vec<SessionInfo> {
+  let Ok(state) = get_project_state(ctx) else {
+    return Vec::new();
+  };
+  state
+    .sessions
+    .into_iter()
+    .filter(|si| si.task.id == task_id)
+    .collect()
+}
+
+fn latest_session(ctx: &AppContext, task_id: u32) -> Option<SessionInfo> {
+  task_sessions(ctx, task_id)
+    .into_iter()
+    .max_by_key(|si| si.created_at_ms)
+}
+
+/// What a task's sessions say about an attach client that returned.
+#[derive(Debug)]
+enum AfterAttach {
+  /// The attached session still runs, so the client detached
+  Detached,
+  /// The attached session is gone and this newer one took its place
+  Replaced(SessionInfo),
+  /// The attached session is gone and nothing replaced it yet
+  Exited,
+}
+
+fn after_attach(sessions: Vec<SessionInfo>, previous: u64) -> AfterAttach {
+  if sessions.iter().any(|si| si.session_id == previous) {
+    return AfterAttach::Detached;
+  }
+  match sessions.into_iter().max_by_key(|si| si.created_at_ms) {
+    Some(si) => AfterAttach::Replaced(si),
+    None => AfterAttach::Exited,
+  }
 }
 
-pub fn run_follow(ctx: &AppContext, tui_id_opt: Option<u32>) -> Result<()> {
+/// After an attach client returned, wait for a session that replaces
+/// `previous`.
+///
+/// Returns `None` right away when `previous` is still running (the client
+/// detached) and after `RECONNECT_TIMEOUT` when no new session appeared.
+fn wait_for_replacement_session(
+  ctx: &AppContext,
+  task: &TaskMeta,
+  previous: u64,
+) -> Option<SessionInfo> {
+  let deadline = Instant::now() + RECONNECT_TIMEOUT;
+  let mut announced = false;
+  loop {
+    match after_attach(task_sessions(ctx, task.id), previous) {
+      AfterAttach::Detached => return None,
+      AfterAttach::Replaced(si) => {
+        log_info!(
+          "Re-attaching to new session for task {} {}",
+          t::id(task.id),
+          t::slug(&task.slug)
+        );
+        return Some(si);
+      }
+      AfterAttach::Exited => {}
+    }
+    if Instant::now() >= deadline {
+      log_warn!(
+        "No new session for task {} {} within {}s",
+        t::id(task.id),
+        t::slug(&task.slug),
+        RECONNECT_TIMEOUT.as_secs()
+      );
+      return None;
+    }
+    if !announced {
+      log_info!(
+        "Session for task {} {} exited, waiting for a new one",
+        t::id(task.id),
+        t::slug(&task.slug)
+      );
+      announced = true;
+    }
+    std::thread::sleep(Duration::from_millis(200));
+  }
+}
+
+pub fn run_follow(ctx: &AppContext, tui_id_opt: Option<u32>, reconnect: bool) -> Result<()> {
   if !ctx.tty.is_interactive() {
     anyhow::bail!("attach --follow requires an interactive terminal (TTY). Run this command in an interactive shell or terminal.");
   }
@@ -172,6 +341,7 @@ pub fn run_follow(ctx: &AppContext, tui_id_opt: Option<u32>) -> Result<()> {
     overlay_task_id,
     current_task_id,
     overlay_ui,
+    reconnect,
   )
 }
 
@@ -186,7 +356,10 @@ fn run_follow_loop(
   mut overlay_task_id: Option<u32>,
   mut current_task_id: Option<u32>,
   mut overlay_ui: Option<OverlayUI>,
+  reconnect: bool,
 ) -> Result<()> {
+  // Session behind the attach child, used by `reconnect` to tell a detach from an exit
+  let mut attached = current_task_id.and_then(|tid| latest_session(ctx, tid));
   loop {
     // Draw overlay when active and poll for key events
     if overlay_active && handle_overlay(ctx, &mut overlay_ui, &mut overlay_task_id)? {
@@ -218,6 +391,7 @@ fn run_follow_loop(
           tmux::prepare_session_for_attach(&ctx.config, &task);
           child_gen = child_gen.wrapping_add(1);
           current_child = Some((tmux::spawn_attach_session(&ctx.config, &task)?, child_gen));
+          attached = latest_session(ctx, task.id);
         }
       }
       Ok(_other) => {}
@@ -226,6 +400,20 @@ fn run_follow_loop(
     // Handle attach child lifecycle: user detach vs. session end
     if let Some((mut ch, cur_gen)) = current_child.take() {
       if let Ok(Some(_status)) = ch.try_wait() {
+        if reconnect
+          && cur_gen == child_gen
+          && let Some(previous) = attached.take()
+          && let Some(next) = wait_for_replacement_session(ctx, &previous.task, previous.session_id)
+        {
+          tmux::prepare_session_for_attach(&ctx.config, &next.task);
+          child_gen = child_gen.wrapping_add(1);
+          current_child = Some((
+            tmux::spawn_attach_session(&ctx.config, &next.task)?,
+            child_gen,
+          ));
+          attached = Some(next);
+          continue;
+        }
         match decide_follow_on_child_exit(ctx, child_gen, cur_gen, current_task_id) {
           FollowExitAction::CancelFollow => {
             if let Some(ui) = overlay_ui.take() {
@@ -262,6 +450,7 @@ fn run_follow_loop(
       tmux::prepare_session_for_attach(&ctx.config, &task);
       child_gen = child_gen.wrapping_add(1);
       current_child = Some((tmux::spawn_attach_session(&ctx.config, &task)?, child_gen));
+      attached = latest_session(ctx, task.id);
       overlay_active = false;
       overlay_task_id = None;
     }
@@ -329,7 +518,7 @@ fn handle_overlay(
           .find(|t| t.id == task_id)
           .map_or_else(|| format!("task-{task_id}"), |t| t.slug.clone());
         let tref = TaskRef { id: task_id, slug };
-        let plan = build_session_plan(ctx, &tref)?;
+        let plan = build_session_plan(ctx, &tref, None)?;
         let _ = start_session_for_task(ctx, &plan, false);
       }
     }
@@ -463,6 +652,37 @@ fn activate_overlay(overlay_active: &mut bool, overlay_task_id: &mut Option<u32>
 mod tests {
   use super::*;
 
+  fn session(session_id: u64, created_at_ms: u64) -> SessionInfo {
+    SessionInfo {
+      session_id,
+      task: TaskMeta {
+        id: 1,
+        slug: "task".to_string(),
+      },
+      created_at_ms,
+      status: "Running".to_string(),
+      clients: 0,
+      cwd: String::new(),
+      pid: 0,
+      bytes_out: 0,
+      log_dropped_bytes: 0,
+    }
+  }
+
+  #[test]
+  fn after_attach_tells_detach_from_exit() {
+    assert!(matches!(
+      after_attach(vec![session(7, 1)], 7),
+      AfterAttach::Detached
+    ));
+    assert!(matches!(after_attach(Vec::new(), 7), AfterAttach::Exited));
+    let replaced = after_attach(vec![session(8, 2), session(9, 3)], 7);
+    assert!(
+      matches!(&replaced, AfterAttach::Replaced(si) if si.session_id == 9),
+      "unexpected outcome: {replaced:?}"
+    );
+  }
+
   #[test]
   fn pick_tui_id_auto_and_errors() {
     let items: Vec<TuiListItem> = vec![];
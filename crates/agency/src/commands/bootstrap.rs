@@ -6,7 +6,7 @@ use crate::config::AppContext;
 use crate::utils::bootstrap::{create_worktree_for_task, run_bootstrap_cmd_with_env};
 use crate::utils::git::{ensure_branch_at, open_main_repo, repo_workdir_or, rev_parse};
 use crate::utils::task::{
-  TaskFrontmatterExt, branch_name, parse_task_markdown, resolve_id_or_slug, task_file,
+  TaskFrontmatterExt, parse_task_markdown, resolve_id_or_slug, task_branch, task_file,
 };
 
 /// User-facing bootstrap: prepares worktree and runs bootstrap for a task.
@@ -21,7 +21,7 @@ pub fn run(ctx: &AppContext, ident: &str) -> Result<()> {
   let base = frontmatter.base_branch(ctx);
 
   let repo = open_main_repo(ctx.paths.root())?;
-  let branch = branch_name(&task);
+  let branch = task_branch(&ctx.paths, &task);
   // Ensure base branch resolves to a commit; provide friendly guidance when unborn
   if rev_parse(repo.workdir().unwrap_or(ctx.paths.root()), &base).is_err() {
     anyhow::bail!(
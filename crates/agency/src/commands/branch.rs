@@ -1,11 +1,34 @@
-use anyhow::Result;
+use anyhow::{Context, Result, bail};
 
 use crate::config::AppContext;
-use crate::utils::task::{branch_name, resolve_id_or_slug};
+use crate::utils::git::{branch_exists_at, git_workdir, push_branch_tracking_at};
+use crate::utils::task::{resolve_id_or_slug, task_branch};
 
-pub fn run(ctx: &AppContext, ident: &str) -> Result<()> {
+/// Print the task branch, or its ref on `remote`, optionally pushing it there first.
+///
+/// # Errors
+/// Returns an error if the task cannot be resolved, the branch does not exist
+/// yet when pushing, or the push fails.
+pub fn run(ctx: &AppContext, ident: &str, remote: Option<&str>, push: Option<&str>) -> Result<()> {
   let tref = resolve_id_or_slug(&ctx.paths, ident)?;
-  let name = branch_name(&tref);
+  let name = task_branch(&ctx.paths, &tref);
+  if let Some(remote) = push {
+    let repo_workdir = git_workdir(ctx.paths.root())?;
+    if !branch_exists_at(&repo_workdir, &name)? {
+      bail!(
+        "Branch {name} does not exist yet. Run `agency bootstrap {}` first",
+        tref.id
+      );
+    }
+    push_branch_tracking_at(&repo_workdir, remote, &name)
+      .with_context(|| format!("Push of {name} to {remote} failed"))?;
+    anstream::println!("{remote}/{name}");
+    return Ok(());
+  }
+  if let Some(remote) = remote {
+    anstream::println!("{remote}/{name}");
+    return Ok(());
+  }
   anstream::println!("{}", name);
   Ok(())
 }
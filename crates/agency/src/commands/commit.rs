@@ -0,0 +1,59 @@
+use anyhow::{Result, bail};
+
+use crate::config::AppContext;
+use crate::log_success;
+use crate::utils::files::local_files_dir;
+use crate::utils::git::{commit_staged_at, has_staged_changes_at, stage_all_at};
+use crate::utils::log::t;
+use crate::utils::task::{resolve_id_or_slug, worktree_dir};
+use crate::utils::template::{render_template, today_utc};
+
+/// Commit message used when `commit_template` is not configured.
+const DEFAULT_COMMIT_TEMPLATE: &str = "Update $SLUG";
+
+/// Stage every change in the task's worktree, except the attached files
+/// link, and commit it.
+///
+/// The message is `message` when given, otherwise the configured
+/// `commit_template` with `$SLUG`, `$ID` and `$DATE` replaced.
+///
+/// # Errors
+/// Returns an error if the task or its worktree is missing, there is nothing
+/// to commit, or git fails.
+pub fn run(ctx: &AppContext, ident: &str, message: Option<&str>) -> Result<()> {
+  let task = resolve_id_or_slug(&ctx.paths, ident)?;
+  let wt_dir = worktree_dir(&ctx.paths, &task);
+  if !wt_dir.exists() {
+    bail!(
+      "worktree not found at {}. Run `agency bootstrap {}` or `agency start {}` first",
+      wt_dir.display(),
+      task.id,
+      task.id
+    );
+  }
+
+  // The attached files symlink is Agency's, not part of the task's changes
+  stage_all_at(&wt_dir, &[&local_files_dir()])?;
+  if !has_staged_changes_at(&wt_dir)? {
+    bail!("Nothing to commit in {}", wt_dir.display());
+  }
+  let message = match message {
+    Some(message) => message.to_string(),
+    None => {
+      let template = ctx
+        .config
+        .commit_template
+        .as_deref()
+        .unwrap_or(DEFAULT_COMMIT_TEMPLATE);
+      render_template(template, &task, &today_utc())
+    }
+  };
+  commit_staged_at(&wt_dir, &message)?;
+  log_success!(
+    "Committed changes of task {} {}: {}",
+    t::id(task.id),
+    t::slug(&task.slug),
+    message
+  );
+  Ok(())
+}
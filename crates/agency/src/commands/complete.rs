@@ -1,25 +1,60 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
 
-use crate::commands::merge::perform_merge;
+use crate::commands::merge::{perform_merge, perform_squash_merge};
 use crate::config::AppContext;
 use crate::utils::daemon::notify_after_task_change;
-use crate::utils::git::git_workdir;
+use crate::utils::events::record_completed;
+use crate::utils::git::{git_workdir, push_branch_at};
 use crate::utils::log::t;
-use crate::utils::task::{cleanup_task_artifacts, resolve_task_ident};
+use crate::utils::task::{KeepArtifacts, TaskRef, cleanup_task_artifacts, resolve_task_ident};
 use crate::{log_info, log_success, log_warn};
 
+/// How `agency complete` merges and cleans up a task. `Default` merges into
+/// the task's base and deletes the task afterwards, asking for confirmation.
+#[derive(Debug, Default)]
+pub struct CompleteOptions<'a> {
+  /// Base branch override; defaults to the task's base.
+  pub base: Option<&'a str>,
+  /// Skip the confirmation and the squash commit message editor.
+  pub yes: bool,
+  /// Remote to push the advanced base branch to.
+  pub push: Option<&'a str>,
+  /// Keep the branch, worktree, and file instead of deleting them.
+  pub keep: bool,
+  pub squash: bool,
+}
+
 /// Complete a task by merging it into base and cleaning up.
 ///
+/// With `squash`, the task branch lands on base as a single commit whose
+/// message is edited in the editor unless `yes` is set.
+/// With `push`, the advanced base branch is pushed to that remote before
+/// cleanup. Nothing is pushed when the task was already merged. With `keep`,
+/// the task is only marked completed and its branch, worktree, and file are
+/// kept for review.
+///
 /// # Errors
-/// Returns an error if the task cannot be resolved or cleanup fails.
+/// Returns an error if the task cannot be resolved, the push fails, or cleanup fails.
 /// Merge errors are ignored if the task is already up-to-date with base.
-pub fn run(ctx: &AppContext, ident: Option<&str>, base: Option<&str>, yes: bool) -> Result<()> {
+/// A failed push leaves the task branch, worktree, and file in place.
+pub fn run(ctx: &AppContext, ident: Option<&str>, opts: &CompleteOptions) -> Result<()> {
+  let CompleteOptions {
+    base,
+    yes,
+    push,
+    keep,
+    squash,
+  } = *opts;
   let task = resolve_task_ident(&ctx.paths, ident)?;
   let ident_str = task.id.to_string();
 
   notify_after_task_change(ctx, || {
     // Try to merge; if already up-to-date, skip and just clean up
-    let merge_result = perform_merge(ctx, &ident_str, base);
+    let merge_result = if squash {
+      perform_squash_merge(ctx, &ident_str, base, yes)
+    } else {
+      perform_merge(ctx, &ident_str, base)
+    };
     let already_merged = match &merge_result {
       Ok(_) => false,
       Err(e) if e.to_string().contains("No changes to merge") => {
@@ -32,6 +67,37 @@ pub fn run(ctx: &AppContext, ident: Option<&str>, base: Option<&str>, yes: bool)
       }
     };
 
+    if let (Some(remote), Ok(merged)) = (push, &merge_result) {
+      log_info!("Push {} to {}", merged.base_branch, remote);
+      push_branch_at(&merged.repo_workdir, remote, &merged.base_branch).with_context(|| {
+        format!(
+          "Push of {} to {remote} failed; task {} {} was kept",
+          merged.base_branch,
+          t::id(task.id),
+          t::slug(&task.slug)
+        )
+      })?;
+      log_success!("Pushed {} to {}", merged.base_branch, remote);
+    } else if push.is_some() {
+      log_info!("Base branch did not change, skipping push");
+    }
+
+    if keep {
+      record_completion(ctx, &task);
+      let action = if already_merged {
+        "completed"
+      } else {
+        "merged"
+      };
+      log_success!(
+        "Task {} {} {}, branch, worktree, and file kept",
+        t::id(task.id),
+        t::slug(&task.slug),
+        action
+      );
+      return Ok(());
+    }
+
     log_warn!("This will delete the task branch, worktree, and file.");
     if !ctx.tty.confirm("Proceed?", true, yes)? {
       log_warn!("Cancelled");
@@ -44,7 +110,8 @@ pub fn run(ctx: &AppContext, ident: Option<&str>, base: Option<&str>, yes: bool)
       merge_result?.repo_workdir
     };
 
-    cleanup_task_artifacts(ctx, &task, &repo_workdir)?;
+    cleanup_task_artifacts(ctx, &task, &repo_workdir, KeepArtifacts::default())?;
+    record_completion(ctx, &task);
     log_success!(
       "Task {} {} {}",
       t::id(task.id),
@@ -60,5 +127,19 @@ pub fn run(ctx: &AppContext, ident: Option<&str>, base: Option<&str>, yes: bool)
 /// # Errors
 /// Returns an error if the task cannot be resolved or cleanup fails.
 pub fn run_force(ctx: &AppContext, ident: &str, base: Option<&str>) -> Result<()> {
-  run(ctx, Some(ident), base, true)
+  run(
+    ctx,
+    Some(ident),
+    &CompleteOptions {
+      base,
+      yes: true,
+      ..CompleteOptions::default()
+    },
+  )
+}
+
+fn record_completion(ctx: &AppContext, task: &TaskRef) {
+  if let Err(err) = record_completed(&ctx.paths, task) {
+    log_warn!("Failed to record completion: {:#}", err);
+  }
 }
@@ -1,7 +1,11 @@
-use anyhow::{Context, Result};
+use std::path::Path;
+
+use anyhow::{Context, Result, bail};
 
 use crate::config::{self, AppContext};
 use crate::utils::editor::open_path;
+use crate::utils::git::resolve_main_workdir;
+use crate::{log_error, log_info, log_success};
 
 pub fn run(ctx: &AppContext) -> Result<()> {
   let cfg_path = config::global_config_path()?;
@@ -16,3 +20,41 @@ pub fn run(ctx: &AppContext) -> Result<()> {
   }
   open_path(&ctx.config, &cfg_path, ctx.paths.root())
 }
+
+/// Lint the merged config, or a single file merged over the defaults, and
+/// print every problem found.
+///
+/// Runs without an `AppContext` so configs that fail to load can be reported.
+///
+/// # Errors
+/// Returns an error if the config has any problem or `path` does not exist.
+pub fn validate(path: Option<&Path>) -> Result<()> {
+  let files = match path {
+    Some(path) => {
+      if !path.is_file() {
+        bail!("Config file {} not found", path.display());
+      }
+      vec![path.to_path_buf()]
+    }
+    None => {
+      let cwd = std::env::current_dir()?;
+      config::config_files(&resolve_main_workdir(&cwd))
+    }
+  };
+  for file in &files {
+    log_info!("Checking {}", file.display());
+  }
+
+  let problems = match config::load_config_files(&files) {
+    Ok(cfg) => config::config_problems(&cfg),
+    Err(err) => vec![format!("{err:#}")],
+  };
+  if problems.is_empty() {
+    log_success!("Config is valid");
+    return Ok(());
+  }
+  for problem in &problems {
+    log_error!("{}", problem);
+  }
+  bail!("Config has {} error(s)", problems.len());
+}
@@ -1,32 +1,80 @@
+use std::fs::OpenOptions;
+use std::io::Write as _;
 use std::os::unix::net::UnixStream;
+use std::path::Path;
 use std::process::Command as ProcCommand;
 use std::thread;
 use std::time::{Duration, Instant};
 
 use anyhow::{Context, Result};
 use log::{info, warn};
+use serde::Serialize;
 
-use crate::config::{compute_socket_path, load_config};
+use crate::commands::logs::print_lines;
+use crate::config::{compute_log_path, compute_socket_path, load_config};
 use crate::daemon as slim_daemon;
-use crate::daemon_protocol::{C2D, C2DControl, write_frame};
-use crate::utils::daemon::connect_daemon_socket;
+use crate::daemon_protocol::{
+  C2D, C2DControl, D2C, D2CControl, LogLevel, LogLine, read_frame, write_frame,
+};
+use crate::utils::daemon::{connect_daemon_socket, list_attachments, query_version, resize_all};
+use crate::utils::recorder;
+use crate::utils::term::print_table;
 use crate::utils::tmux;
 use crate::AppContext;
+use crate::{log_success, log_warn};
 
-pub fn run_blocking() -> Result<()> {
-  // Initialize env_logger similar to pty-demo main
-  let _ = env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info"))
-    .format_timestamp_secs()
-    .try_init();
+/// Log files above this size are truncated when the daemon starts.
+const MAX_LOG_BYTES: u64 = 5 * 1024 * 1024;
 
+pub fn run_blocking() -> Result<()> {
   // Compute socket path from config (project + XDG)
   let cwd = std::env::current_dir()?;
   let cfg = load_config(&cwd)?;
   let socket = compute_socket_path(&cfg);
 
+  init_json_logger(&compute_log_path(&cfg))?;
+
   slim_daemon::run_daemon(&socket, &cfg)
 }
 
+/// Route daemon logs to `path` as JSON lines, one object per record.
+fn init_json_logger(path: &Path) -> Result<()> {
+  let too_large = std::fs::metadata(path).is_ok_and(|meta| meta.len() > MAX_LOG_BYTES);
+  let file = OpenOptions::new()
+    .create(true)
+    .append(!too_large)
+    .write(true)
+    .truncate(too_large)
+    .open(path)
+    .with_context(|| format!("failed to open {}", path.display()))?;
+  let _ = env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info"))
+    .target(env_logger::Target::Pipe(Box::new(file)))
+    .format(|buf, record| {
+      let line = LogLine {
+        ts: buf.timestamp_seconds().to_string(),
+        level: LogLevel::from(record.level()),
+        target: record.target().to_string(),
+        message: record.args().to_string(),
+      };
+      let json = serde_json::to_string(&line).map_err(std::io::Error::other)?;
+      writeln!(buf, "{json}")
+    })
+    .try_init();
+  Ok(())
+}
+
+/// Record a session's pipe-pane output, sending the recorder's warnings to
+/// `daemon_log` since tmux discards the pipe command's stderr.
+///
+/// # Errors
+/// Returns an error if the daemon log, stamp or session log cannot be opened.
+pub fn record(stamp: &Path, log: &Path, max_bytes: u64, daemon_log: Option<&Path>) -> Result<()> {
+  if let Some(daemon_log) = daemon_log {
+    init_json_logger(daemon_log)?;
+  }
+  recorder::run(stamp, log, max_bytes)
+}
+
 pub fn start() -> Result<()> {
   let cwd = std::env::current_dir()?;
   let cfg = load_config(&cwd)?;
@@ -166,10 +214,68 @@ pub fn restart(ctx: &AppContext, yes: bool) -> Result<()> {
   Ok(())
 }
 
-/// Show the status of the daemon and tmux server.
-#[allow(clippy::unnecessary_wraps)]
-pub fn status(ctx: &AppContext) -> Result<()> {
+/// Kill every session and the tmux server, then start daemon and tmux fresh.
+///
+/// Asks for confirmation unless `yes` is set, as running agents are terminated.
+///
+/// # Errors
+/// Returns an error if the tmux server cannot be stopped or daemon/tmux fail to start.
+pub fn restart_hard(ctx: &AppContext, yes: bool) -> Result<()> {
+  let (sessions, _) = tmux::count_sessions_and_clients(&ctx.config);
+  let prompt =
+    format!("Hard restart kills {sessions} running session(s) and the tmux server. Continue?");
+  if !ctx.tty.confirm(&prompt, false, yes)? {
+    log_warn!("Cancelled");
+    return Ok(());
+  }
+
+  let _ = stop_daemon_only();
+  tmux::stop_server(&ctx.config, true)?;
+  start()?;
+  tmux::ensure_server_inherit_stderr(&ctx.config)?;
+
+  log_success!(
+    "Killed {} session(s) and restarted daemon and tmux server",
+    sessions
+  );
+  Ok(())
+}
+
+/// `agency daemon status --json` output. Only `running` is set when the daemon is down.
+#[derive(Debug, Serialize)]
+struct DaemonStatusJson {
+  running: bool,
+  #[serde(flatten, skip_serializing_if = "Option::is_none")]
+  details: Option<DaemonDetailsJson>,
+}
+
+#[derive(Debug, Serialize)]
+struct DaemonDetailsJson {
+  version: String,
+  socket_path: String,
+  #[serde(flatten)]
+  health: DaemonHealth,
+}
+
+/// Counters from the daemon's `Health` reply.
+#[derive(Debug, Serialize)]
+struct DaemonHealth {
+  uptime_secs: u64,
+  pid: u32,
+  session_count: u32,
+  attached_client_count: u32,
+  subscriber_count: u32,
+}
+
+/// Show the status of the daemon and tmux server, as text or as JSON.
+///
+/// # Errors
+/// Returns an error if a running daemon replies unexpectedly or JSON serialization fails.
+pub fn status(ctx: &AppContext, json: bool) -> Result<()> {
   let socket = compute_socket_path(&ctx.config);
+  if json {
+    return print_status_json(&socket);
+  }
   let tmux_socket = tmux::tmux_socket_path(&ctx.config);
 
   // Check daemon status
@@ -199,6 +305,134 @@ pub fn status(ctx: &AppContext) -> Result<()> {
   Ok(())
 }
 
+fn print_status_json(socket: &Path) -> Result<()> {
+  let details = if UnixStream::connect(socket).is_ok() {
+    let health = query_health(socket)?;
+    Some(DaemonDetailsJson {
+      version: query_version(socket)?,
+      socket_path: socket.display().to_string(),
+      health,
+    })
+  } else {
+    None
+  };
+  let status = DaemonStatusJson {
+    running: details.is_some(),
+    details,
+  };
+  let out = serde_json::to_string_pretty(&status).context("failed to serialize daemon status")?;
+  println!("{out}");
+  Ok(())
+}
+
+/// Print the last daemon log lines, oldest first.
+///
+/// # Errors
+/// Returns an error if the daemon is not running or replies unexpectedly.
+pub fn logs(ctx: &AppContext, max_lines: usize, level_filter: Option<LogLevel>) -> Result<()> {
+  let socket = compute_socket_path(&ctx.config);
+  let mut stream = connect_daemon_socket(&socket)?;
+  write_frame(
+    &mut stream,
+    &C2D::Control(C2DControl::Logs {
+      max_lines: Some(max_lines),
+      level_filter,
+    }),
+  )?;
+  let lines = match read_frame::<_, D2C>(&mut stream)? {
+    D2C::Control(D2CControl::Logs { lines }) => lines,
+    D2C::Control(D2CControl::Error { code, message }) => anyhow::bail!("Daemon {code}: {message}"),
+    D2C::Control(other) => anyhow::bail!("Unexpected daemon reply: {other:?}"),
+  };
+  print_lines(&lines);
+  Ok(())
+}
+
+/// Print daemon uptime and load counters.
+///
+/// # Errors
+/// Returns an error if the daemon is not running or replies unexpectedly.
+pub fn health(ctx: &AppContext) -> Result<()> {
+  let socket = compute_socket_path(&ctx.config);
+  let health = query_health(&socket)?;
+  println!("Uptime:      {}s", health.uptime_secs);
+  println!("Sessions:    {}", health.session_count);
+  println!("Clients:     {}", health.attached_client_count);
+  println!("Subscribers: {}", health.subscriber_count);
+  Ok(())
+}
+
+fn query_health(socket: &Path) -> Result<DaemonHealth> {
+  let mut stream = connect_daemon_socket(socket)?;
+  write_frame(&mut stream, &C2D::Control(C2DControl::Health))?;
+  match read_frame::<_, D2C>(&mut stream)? {
+    D2C::Control(D2CControl::Health {
+      uptime_secs,
+      pid,
+      session_count,
+      attached_client_count,
+      subscriber_count,
+    }) => Ok(DaemonHealth {
+      uptime_secs,
+      pid,
+      session_count,
+      attached_client_count,
+      subscriber_count,
+    }),
+    D2C::Control(D2CControl::Error { code, message }) => anyhow::bail!("Daemon {code}: {message}"),
+    D2C::Control(other) => anyhow::bail!("Unexpected daemon reply: {other:?}"),
+  }
+}
+
+/// Print the clients attached to the project's task sessions.
+///
+/// # Errors
+/// Returns an error if the daemon is not running or replies unexpectedly.
+pub fn attachments(ctx: &AppContext) -> Result<()> {
+  let headers = ["ATTACHMENT", "SESSION", "TASK", "SIZE", "IDLE"];
+  let rows: Vec<Vec<String>> = list_attachments(ctx)?
+    .into_iter()
+    .map(|item| {
+      vec![
+        item.attachment_id,
+        item.session_id.to_string(),
+        format!("{}-{}", item.task.id, item.task.slug),
+        format!("{}x{}", item.cols, item.rows),
+        format!("{}s", item.idle_ms / 1000),
+      ]
+    })
+    .collect();
+  print_table(&headers, &rows);
+  Ok(())
+}
+
+/// Resize the session viewed by `attachment_id` to `size` (rows, cols) for all of
+/// its attached clients. Without a size the window follows its clients again.
+///
+/// # Errors
+/// Returns an error if the daemon is unreachable, the attachment is unknown or
+/// the resize fails.
+pub fn resize(ctx: &AppContext, attachment_id: &str, size: Option<(u16, u16)>) -> Result<()> {
+  let (rows, cols) = size.unwrap_or((0, 0));
+  let (session_id, clients) = resize_all(ctx, attachment_id, rows, cols)?;
+  if size.is_none() {
+    log_success!(
+      "Restored automatic size of session {} for {} client(s)",
+      session_id,
+      clients
+    );
+    return Ok(());
+  }
+  log_success!(
+    "Resized session {} to {}x{} for {} client(s)",
+    session_id,
+    cols,
+    rows,
+    clients
+  );
+  Ok(())
+}
+
 fn count_tmux_sessions(cfg: &crate::config::AgencyConfig) -> usize {
   let output = std::process::Command::new("tmux")
     .args(tmux::tmux_args_base(cfg))
@@ -0,0 +1,37 @@
+use anyhow::Result;
+
+use crate::config::AppContext;
+use crate::log_info;
+use crate::utils::git::{branch_exists_at, diff_range_at, git_workdir};
+use crate::utils::task::{
+  TaskFrontmatterExt, read_task_frontmatter, resolve_id_or_slug, task_branch,
+};
+
+/// Show the changes of a task branch against its base branch.
+///
+/// Pages the diff when interactive and prints it raw when piped.
+///
+/// # Errors
+/// Returns an error if the task cannot be resolved or git fails to diff.
+pub fn run(ctx: &AppContext, ident: &str, stat: bool) -> Result<()> {
+  let task = resolve_id_or_slug(&ctx.paths, ident)?;
+  let repo_workdir = git_workdir(ctx.paths.root())?;
+  let branch = task_branch(&ctx.paths, &task);
+  if !branch_exists_at(&repo_workdir, &branch)? {
+    log_info!("no changes");
+    return Ok(());
+  }
+  let base = read_task_frontmatter(&ctx.paths, &task).base_branch(ctx);
+  let diff = diff_range_at(
+    &repo_workdir,
+    &base,
+    &branch,
+    stat,
+    ctx.tty.is_interactive(),
+  )?;
+  if diff.trim().is_empty() {
+    log_info!("no changes");
+    return Ok(());
+  }
+  ctx.tty.page(&diff)
+}
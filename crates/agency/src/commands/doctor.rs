@@ -0,0 +1,126 @@
+use std::path::Path;
+
+use anyhow::{Result, bail};
+
+use crate::config::{AppContext, compute_socket_path};
+use crate::utils::daemon::query_version;
+use crate::utils::git::open_main_repo;
+use crate::utils::tmux;
+use crate::utils::version::get_version;
+use crate::utils::which::which;
+use crate::{log_error, log_success, log_warn};
+
+/// Outcome of a single doctor check.
+enum Check {
+  Pass(String),
+  Warn(String),
+  Fail(String),
+}
+
+/// Check the environment agency depends on and print a pass/warn/fail report.
+///
+/// # Errors
+/// Returns an error if any hard check fails.
+pub fn run(ctx: &AppContext) -> Result<()> {
+  let mut checks = vec![check_git_repo(ctx.paths.cwd())];
+  checks.extend(check_tmux(ctx));
+  checks.push(check_socket_dir(&compute_socket_path(&ctx.config)));
+  checks.push(check_daemon(ctx));
+  checks.extend(check_agents(ctx));
+
+  let mut failures = 0;
+  for check in &checks {
+    match check {
+      Check::Pass(message) => log_success!("pass  {}", message),
+      Check::Warn(message) => log_warn!("warn  {}", message),
+      Check::Fail(message) => {
+        failures += 1;
+        log_error!("fail  {}", message);
+      }
+    }
+  }
+  if failures > 0 {
+    bail!("{failures} check(s) failed");
+  }
+  Ok(())
+}
+
+fn check_git_repo(cwd: &Path) -> Check {
+  let Ok(repo) = open_main_repo(cwd) else {
+    return Check::Fail(format!("Not inside a git repository: {}", cwd.display()));
+  };
+  let Some(workdir) = repo.workdir() else {
+    return Check::Fail("Git repository is bare, agency needs a working tree".to_string());
+  };
+  Check::Pass(format!("Git repository at {}", workdir.display()))
+}
+
+fn check_tmux(ctx: &AppContext) -> Vec<Check> {
+  let Some(binary) = which("tmux") else {
+    return vec![Check::Fail("tmux not found in PATH".to_string())];
+  };
+  let server = if tmux::is_server_running(&ctx.config) {
+    Check::Pass("tmux server is running".to_string())
+  } else {
+    Check::Warn("tmux server is not running, `agency daemon start` starts it".to_string())
+  };
+  vec![
+    Check::Pass(format!("tmux found at {}", binary.display())),
+    server,
+  ]
+}
+
+fn check_socket_dir(socket: &Path) -> Check {
+  let Some(dir) = socket.parent() else {
+    return Check::Fail(format!("Invalid daemon socket path {}", socket.display()));
+  };
+  let probe = dir.join(format!(".agency-doctor-{}", std::process::id()));
+  match std::fs::write(&probe, b"") {
+    Ok(()) => {
+      let _ = std::fs::remove_file(&probe);
+      Check::Pass(format!("Socket directory {} is writable", dir.display()))
+    }
+    Err(err) => Check::Fail(format!(
+      "Socket directory {} is not writable: {err}",
+      dir.display()
+    )),
+  }
+}
+
+fn check_daemon(ctx: &AppContext) -> Check {
+  let socket = compute_socket_path(&ctx.config);
+  let Ok(version) = query_version(&socket) else {
+    return Check::Warn("Daemon is not running, `agency daemon start` starts it".to_string());
+  };
+  let cli_version = get_version();
+  if version == cli_version {
+    Check::Pass(format!("Daemon is running version {version}"))
+  } else {
+    Check::Warn(format!(
+      "Daemon runs version {version} but the CLI is {cli_version}, run `agency daemon restart`"
+    ))
+  }
+}
+
+/// The default agent must be installed, other configured agents only warn.
+fn check_agents(ctx: &AppContext) -> Vec<Check> {
+  let default_agent = ctx.config.agent.as_deref();
+  ctx
+    .config
+    .agents
+    .iter()
+    .filter_map(|(name, agent)| {
+      let program = agent.cmd.first()?;
+      let is_default = default_agent == Some(name.as_str());
+      Some(match which(program) {
+        Some(path) => Check::Pass(format!("Agent {name} found at {}", path.display())),
+        None if is_default => Check::Fail(format!(
+          "Default agent {name} needs `{program}`, which is not in PATH"
+        )),
+        None => Check::Warn(format!(
+          "Agent {name} needs `{program}`, which is not in PATH"
+        )),
+      })
+    })
+    .collect()
+}
@@ -1,5 +1,9 @@
-use std::process::Command as ProcCommand;
+use std::os::unix::process::CommandExt as _;
+use std::process::{Child, Command as ProcCommand, ExitStatus};
+use std::sync::atomic::{AtomicI32, Ordering};
+use std::time::{Duration, Instant};
 
+use anstream::eprintln;
 use anyhow::{Context, Result, bail};
 
 use crate::config::AppContext;
@@ -8,7 +12,28 @@ use crate::utils::git::{open_main_repo, repo_workdir_or};
 use crate::utils::session::build_task_env;
 use crate::utils::task::{read_task_content, resolve_id_or_slug, worktree_dir};
 
-pub fn run(ctx: &AppContext, ident: &str, cmd: &[String]) -> Result<i32> {
+/// Exit code returned when `--timeout` kills the command, matching GNU `timeout`.
+pub const TIMEOUT_EXIT_CODE: i32 = 124;
+
+/// How often a command with a timeout is checked for completion.
+const POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+/// Run `cmd` in the task's worktree and return its exit code.
+///
+/// With a `timeout`, the command runs in its own process group, which is
+/// killed once the deadline passes; the result is then [`TIMEOUT_EXIT_CODE`].
+/// The group becomes the terminal's foreground group while it runs, and
+/// SIGINT or SIGTERM sent to agency are forwarded to it.
+///
+/// # Errors
+/// Returns an error if the task or its worktree is missing or the command
+/// cannot be spawned.
+pub fn run(
+  ctx: &AppContext,
+  ident: &str,
+  cmd: &[String],
+  timeout: Option<Duration>,
+) -> Result<i32> {
   // Resolve task
   let tref = resolve_id_or_slug(&ctx.paths, ident)?;
   let wt_dir = worktree_dir(&ctx.paths, &tref);
@@ -38,13 +63,144 @@ pub fn run(ctx: &AppContext, ident: &str, cmd: &[String]) -> Result<i32> {
   let env_map = build_task_env(tref.id, description, &repo_root, task_has_files);
 
   // Execute command (no log output from agency)
-  let status = ProcCommand::new(program)
-    .args(args)
-    .current_dir(&wt_dir)
-    .envs(&env_map)
-    .status()
-    .with_context(|| format!("failed to execute: {program}"))?;
+  let mut command = ProcCommand::new(program);
+  command.args(args).current_dir(&wt_dir).envs(&env_map);
+  let Some(timeout) = timeout else {
+    let status = command
+      .status()
+      .with_context(|| format!("failed to execute: {program}"))?;
+    return Ok(status.code().unwrap_or(1));
+  };
 
-  // Return exit code
+  let forwarding = SignalForwarding::install();
+  let mut child = command
+    .process_group(0)
+    .spawn()
+    .with_context(|| format!("failed to execute: {program}"))?;
+  let pgid = libc::pid_t::try_from(child.id()).context("command pid out of range")?;
+  let terminal = TerminalHandoff::to_group(pgid);
+  let waited = wait_until(&mut child, pgid, Instant::now() + timeout);
+  drop(terminal);
+  drop(forwarding);
+  let Some(status) = waited? else {
+    kill_process_group(&mut child, pgid)?;
+    eprintln!("agency: command timed out after {}s", timeout.as_secs());
+    return Ok(TIMEOUT_EXIT_CODE);
+  };
   Ok(status.code().unwrap_or(1))
 }
+
+/// Wait for `child` to exit, returning `None` if `deadline` passes first.
+/// Signals caught by [`SignalForwarding`] are passed on to the group `pgid`.
+fn wait_until(
+  child: &mut Child,
+  pgid: libc::pid_t,
+  deadline: Instant,
+) -> Result<Option<ExitStatus>> {
+  loop {
+    if let Some(status) = child.try_wait().context("failed to wait for command")? {
+      return Ok(Some(status));
+    }
+    let signal = PENDING_SIGNAL.swap(0, Ordering::SeqCst);
+    if signal != 0 {
+      // SAFETY: kill has no memory safety requirements; it only takes plain integers
+      unsafe { libc::kill(-pgid, signal) };
+    }
+    if Instant::now() >= deadline {
+      return Ok(None);
+    }
+    std::thread::sleep(POLL_INTERVAL);
+  }
+}
+
+/// Kill the process group `pgid` led by `child` and reap the child.
+fn kill_process_group(child: &mut Child, pgid: libc::pid_t) -> Result<()> {
+  // SAFETY: kill has no memory safety requirements; it only takes plain integers
+  let result = unsafe { libc::kill(-pgid, libc::SIGKILL) };
+  if result != 0 {
+    // The group may already be gone; fall back to the direct child
+    let _ = child.kill();
+  }
+  child.wait().context("failed to wait for command")?;
+  Ok(())
+}
+
+/// Last SIGINT or SIGTERM received while a command with a timeout runs.
+static PENDING_SIGNAL: AtomicI32 = AtomicI32::new(0);
+
+extern "C" fn record_signal(signal: libc::c_int) {
+  PENDING_SIGNAL.store(signal, Ordering::SeqCst);
+}
+
+/// Catches SIGINT and SIGTERM for [`wait_until`] to forward, restoring the
+/// previous handlers on drop.
+struct SignalForwarding {
+  previous: [(libc::c_int, libc::sighandler_t); 2],
+}
+
+impl SignalForwarding {
+  fn install() -> Self {
+    PENDING_SIGNAL.store(0, Ordering::SeqCst);
+    let handler = record_signal as extern "C" fn(libc::c_int) as libc::sighandler_t;
+    // SAFETY: the handler only stores into an atomic, which is async-signal-safe
+    let previous = [libc::SIGINT, libc::SIGTERM]
+      .map(|signal| (signal, unsafe { libc::signal(signal, handler) }));
+    Self { previous }
+  }
+}
+
+impl Drop for SignalForwarding {
+  fn drop(&mut self) {
+    for (signal, handler) in self.previous {
+      // SAFETY: restores the handler that was installed before
+      unsafe { libc::signal(signal, handler) };
+    }
+  }
+}
+
+/// Makes a process group the terminal's foreground group while it runs, so
+/// Ctrl-C reaches it and reading the terminal does not stop it. Gives the
+/// terminal back to agency on drop. Does nothing when stdin is not the
+/// terminal agency runs in the foreground of.
+struct TerminalHandoff {
+  own_group: Option<libc::pid_t>,
+}
+
+impl TerminalHandoff {
+  fn to_group(pgid: libc::pid_t) -> Self {
+    // SAFETY: these calls only take and return plain integers
+    let own_group = unsafe {
+      let own = libc::getpgrp();
+      (libc::isatty(libc::STDIN_FILENO) == 1 && libc::tcgetpgrp(libc::STDIN_FILENO) == own)
+        .then_some(own)
+    };
+    if own_group.is_some() {
+      // SAFETY: as above; SIGCONT resumes the group if it read the terminal
+      // before it became the foreground group and was stopped for it
+      unsafe {
+        libc::tcsetpgrp(libc::STDIN_FILENO, pgid);
+        libc::kill(-pgid, libc::SIGCONT);
+      }
+    }
+    Self { own_group }
+  }
+}
+
+impl Drop for TerminalHandoff {
+  fn drop(&mut self) {
+    let Some(own) = self.own_group else {
+      return;
+    };
+    // SAFETY: the signal sets are initialized by sigemptyset before use.
+    // SIGTTOU is blocked because agency is a background group at this point.
+    unsafe {
+      let mut block: libc::sigset_t = std::mem::zeroed();
+      let mut previous: libc::sigset_t = std::mem::zeroed();
+      libc::sigemptyset(&raw mut block);
+      libc::sigaddset(&raw mut block, libc::SIGTTOU);
+      libc::pthread_sigmask(libc::SIG_BLOCK, &raw const block, &raw mut previous);
+      libc::tcsetpgrp(libc::STDIN_FILENO, own);
+      libc::pthread_sigmask(libc::SIG_SETMASK, &raw const previous, std::ptr::null_mut());
+    }
+  }
+}
@@ -0,0 +1,86 @@
+use std::thread;
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result, bail};
+use regex::Regex;
+
+use crate::config::AppContext;
+use crate::daemon_protocol::TaskMeta;
+use crate::utils::task::resolve_id_or_slug;
+use crate::utils::tmux;
+
+/// Delay between pane captures while waiting for the pattern.
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Optionally type `input` (followed by Enter) into a task session, then wait
+/// until the output produced afterwards matches the regex `pattern`.
+///
+/// Prints the output read so far, also when `timeout` elapses first.
+///
+/// # Errors
+/// Returns an error if the pattern is invalid, the session cannot be reached,
+/// or the pattern did not match before `timeout` elapsed.
+pub fn run(
+  ctx: &AppContext,
+  ident: &str,
+  input: Option<&str>,
+  pattern: &str,
+  timeout: Duration,
+) -> Result<()> {
+  let regex = Regex::new(pattern).with_context(|| format!("invalid pattern '{pattern}'"))?;
+  let task = resolve_id_or_slug(&ctx.paths, ident)?;
+  let meta = TaskMeta {
+    id: task.id,
+    slug: task.slug.clone(),
+  };
+  let target = tmux::session_name(task.id, &task.slug);
+
+  let baseline = tmux::capture_pane(&ctx.config, &meta)
+    .with_context(|| format!("Task {}-{} is not running", task.id, task.slug))?;
+  if let Some(input) = input {
+    tmux::send_text(&ctx.config, &target, input)?;
+    tmux::send_keys_enter(&ctx.config, &target)?;
+  }
+
+  let deadline = Instant::now() + timeout;
+  loop {
+    let current = tmux::capture_pane(&ctx.config, &meta)?;
+    let output = output_since(&baseline, &current);
+    if regex.is_match(output) {
+      println!("{}", output.trim_end());
+      return Ok(());
+    }
+    if Instant::now() >= deadline {
+      println!("{}", output.trim_end());
+      bail!("Timed out after {timeout:?} waiting for '{pattern}'");
+    }
+    thread::sleep(POLL_INTERVAL);
+  }
+}
+
+/// Text the pane gained since `baseline` was captured.
+///
+/// tmux drops trailing blank lines, so the baseline is compared without them.
+/// Falls back to the whole capture when the history no longer starts with the
+/// baseline, e.g. after a `clear` or once the scrollback limit is reached.
+fn output_since<'a>(baseline: &str, current: &'a str) -> &'a str {
+  let baseline = baseline.trim_end();
+  current.strip_prefix(baseline).unwrap_or(current)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::output_since;
+
+  #[test]
+  fn output_since_returns_only_new_text() {
+    let baseline = "$ \n\n\n";
+    let current = "$ echo hi\nhi\n$ \n\n";
+    assert_eq!(output_since(baseline, current), " echo hi\nhi\n$ \n\n");
+  }
+
+  #[test]
+  fn output_since_falls_back_to_whole_capture() {
+    assert_eq!(output_since("old screen", "cleared\n"), "cleared\n");
+  }
+}
@@ -0,0 +1,71 @@
+use std::fs;
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+
+use crate::config::AppContext;
+use crate::utils::archive::{TaskArchive, write_task_archive};
+use crate::utils::files::{file_path, list_files};
+use crate::utils::git::{branch_exists_at, diff_patch_at, git_workdir};
+use crate::utils::log::t;
+use crate::utils::task::{
+  TaskFrontmatterExt, parse_task_markdown, resolve_id_or_slug, task_branch, task_file,
+};
+use crate::{log_info, log_success};
+
+/// Bundle a task's markdown, attached files and branch patch into a tar.gz archive.
+///
+/// Without a task branch only the markdown and files are exported.
+///
+/// # Errors
+/// Returns an error if the task cannot be read, the diff fails, or the archive cannot be written.
+pub fn run(ctx: &AppContext, ident: &str, output: Option<&str>) -> Result<()> {
+  let task = resolve_id_or_slug(&ctx.paths, ident)?;
+  let md_path = task_file(&ctx.paths, &task);
+  let task_md = fs::read_to_string(&md_path)
+    .with_context(|| format!("failed to read {}", md_path.display()))?;
+
+  let mut files = Vec::new();
+  for file in list_files(&ctx.paths, &task)? {
+    let path = file_path(&ctx.paths, &task, &file);
+    let data = fs::read(&path).with_context(|| format!("failed to read {}", path.display()))?;
+    files.push((file.filename(), data));
+  }
+
+  let repo_workdir = git_workdir(ctx.paths.root())?;
+  let branch = task_branch(&ctx.paths, &task);
+  let patch = if branch_exists_at(&repo_workdir, &branch)? {
+    let (frontmatter, _) = parse_task_markdown(&task_md);
+    let base = frontmatter.base_branch(ctx);
+    Some(diff_patch_at(&repo_workdir, &base, &branch)?)
+  } else {
+    log_info!(
+      "Branch {} does not exist yet; exporting markdown and files only",
+      t::slug(&branch)
+    );
+    None
+  };
+
+  let out = output.map_or_else(
+    || {
+      ctx
+        .paths
+        .cwd()
+        .join(format!("{}-{}.tar.gz", task.id, task.slug))
+    },
+    PathBuf::from,
+  );
+  let archive = TaskArchive {
+    slug: task.slug.clone(),
+    task_md,
+    files,
+    patch,
+  };
+  write_task_archive(&out, &archive)?;
+  log_success!(
+    "Exported task {} to {}",
+    t::slug(&task.slug),
+    t::path(out.display())
+  );
+  Ok(())
+}
@@ -6,6 +6,7 @@ use crate::config::AppContext;
 use crate::log_info;
 use crate::utils::clipboard::read_image_from_clipboard;
 use crate::utils::daemon::notify_tasks_changed;
+use crate::utils::download::download;
 use crate::utils::files::{add_file, add_file_from_bytes};
 use crate::utils::log::t;
 use crate::utils::task::resolve_id_or_slug;
@@ -15,15 +16,19 @@ pub fn run(
   task_ident: &str,
   source: Option<&str>,
   from_clipboard: Option<&str>,
+  from_url: Option<&str>,
 ) -> Result<()> {
   let task = resolve_id_or_slug(&ctx.paths, task_ident)?;
 
-  let file_ref = if let Some(filename) = from_clipboard {
+  let file_ref = if let Some(url) = from_url {
+    let downloaded = download(url)?;
+    add_file_from_bytes(&ctx.paths, &task, &downloaded.filename, &downloaded.data)?
+  } else if let Some(filename) = from_clipboard {
     let data = read_image_from_clipboard()?;
     add_file_from_bytes(&ctx.paths, &task, filename, &data)?
   } else {
     let source_path = source.ok_or_else(|| {
-      anyhow::anyhow!("Provide a source path or use --from-clipboard")
+      anyhow::anyhow!("Provide a source path or use --from-clipboard or --from-url")
     })?;
     let path = PathBuf::from(source_path);
     add_file(&ctx.paths, &task, &path)?
@@ -2,16 +2,23 @@ use std::collections::HashMap;
 use std::io::Write as _;
 use std::process::{Command, Stdio};
 
-use anyhow::{Result, bail};
+use anyhow::{Context, Result, bail};
 
 use crate::config::AppContext;
 use crate::utils::daemon::get_project_state;
 use crate::utils::sessions::latest_sessions_by_task;
 use crate::utils::status::derive_status;
-use crate::utils::task::{TaskRef, list_tasks, worktree_dir};
+use crate::utils::task::{
+  TaskRef, list_tasks, parse_task_markdown, resolve_id_or_slug, task_file, worktree_dir,
+};
 use crate::utils::which;
 
-pub fn run(ctx: &AppContext) -> Result<()> {
+/// Select a task with fzf and print its id. With `preview`, fzf shows the
+/// task file of the highlighted task next to the list.
+///
+/// # Errors
+/// Returns an error if fzf is missing, there are no tasks, or fzf fails.
+pub fn run(ctx: &AppContext, preview: bool) -> Result<()> {
   if which::which("fzf").is_none() {
     bail!("fzf is not installed. Install it from https://github.com/junegunn/fzf");
   }
@@ -35,7 +42,16 @@ pub fn run(ctx: &AppContext) -> Result<()> {
     .collect();
 
   let input = lines.join("\n");
-  let selected = run_fzf(&input)?;
+  let preview_cmd = if preview {
+    let exe = std::env::current_exe().context("failed to get current exe")?;
+    Some(format!(
+      "{} __fzf-preview {{1}}",
+      shell_words::quote(&exe.display().to_string())
+    ))
+  } else {
+    None
+  };
+  let selected = run_fzf(&input, preview_cmd.as_deref())?;
 
   let Some(selected) = selected else {
     std::process::exit(1);
@@ -71,13 +87,39 @@ fn get_task_state(
   (sessions, wt_exists_map)
 }
 
+/// Print the task file of `ident`, front matter included, for the fzf preview window.
+///
+/// # Errors
+/// Returns an error if the task cannot be resolved or its file cannot be read.
+pub fn preview(ctx: &AppContext, ident: &str) -> Result<()> {
+  let task = resolve_id_or_slug(&ctx.paths, ident)?;
+  let path = task_file(&ctx.paths, &task);
+  let text =
+    std::fs::read_to_string(&path).with_context(|| format!("failed to read {}", path.display()))?;
+  let (_, body) = parse_task_markdown(&text);
+  if !text.trim().is_empty() {
+    println!("{}", text.trim_end());
+  }
+  if body.trim().is_empty() {
+    println!("(no description)");
+  }
+  Ok(())
+}
+
 /// Runs fzf with the given input and returns the selected line, or None if cancelled.
 ///
 /// # Errors
 /// Returns an error if fzf fails to spawn or encounters an I/O error.
-fn run_fzf(input: &str) -> Result<Option<String>> {
-  let mut child = Command::new("fzf")
-    .args(["--no-multi", "--height=~50%"])
+fn run_fzf(input: &str, preview_cmd: Option<&str>) -> Result<Option<String>> {
+  let mut command = Command::new("fzf");
+  command.args(["--no-multi", "--height=~50%"]);
+  if let Some(preview_cmd) = preview_cmd {
+    command
+      .arg("--preview")
+      .arg(preview_cmd)
+      .arg("--preview-window=right,60%,wrap");
+  }
+  let mut child = command
     .stdin(Stdio::piped())
     .stdout(Stdio::piped())
     .stderr(Stdio::inherit())
@@ -33,7 +33,31 @@ fn list_agency_branches(repo: &gix::Repository) -> Result<Vec<String>> {
   Ok(names)
 }
 
-pub fn run(ctx: &AppContext) -> Result<()> {
+/// Which orphaned artifacts `agency gc` cleans up.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum GcScope {
+  #[default]
+  All,
+  WorktreesOnly,
+  BranchesOnly,
+}
+
+impl GcScope {
+  fn worktrees(self) -> bool {
+    self != Self::BranchesOnly
+  }
+
+  fn branches(self) -> bool {
+    self != Self::WorktreesOnly
+  }
+}
+
+/// Remove worktrees and `agency/*` branches that no task refers to, limited to `scope`.
+///
+/// # Errors
+/// Returns an error if the repository, the task list, or the worktree root cannot be read,
+/// or git fails to prune a worktree or delete a branch.
+pub fn run(ctx: &AppContext, scope: GcScope) -> Result<()> {
   let repo = open_main_repo(ctx.paths.root())?;
 
   // Build set of valid task keys: "<id>-<slug>"
@@ -43,10 +67,10 @@ pub fn run(ctx: &AppContext) -> Result<()> {
     .collect();
   log_info!("Found {} valid tasks", valid.len());
 
-  // Sweep worktrees under .agency/worktrees/* first
+  // Sweep worktrees under the worktree root first
   let wt_root = ctx.paths.worktrees_dir();
   let mut pruned_worktrees = 0usize;
-  if wt_root.exists() {
+  if scope.worktrees() && wt_root.exists() {
     for entry in std::fs::read_dir(&wt_root)
       .with_context(|| format!("failed to read {}", wt_root.display()))?
     {
@@ -71,7 +95,12 @@ pub fn run(ctx: &AppContext) -> Result<()> {
   // Sweep branches under refs/heads/agency/*
   // Safety: Only delete branches with no task AND no worktree dir.
   let mut deleted_branches = 0usize;
-  for short in list_agency_branches(&repo)? {
+  let branches = if scope.branches() {
+    list_agency_branches(&repo)?
+  } else {
+    Vec::new()
+  };
+  for short in branches {
     if !valid.contains(&short) {
       let wt_dir_for_branch = wt_root.join(&short);
       if wt_dir_for_branch.exists() {
@@ -89,10 +118,20 @@ pub fn run(ctx: &AppContext) -> Result<()> {
     }
   }
 
-  log_success!(
-    "Garbage collected {} branches, {} worktrees",
-    deleted_branches,
-    pruned_worktrees
-  );
+  match scope {
+    GcScope::All => log_success!(
+      "Garbage collected {} branches, {} worktrees",
+      deleted_branches,
+      pruned_worktrees
+    ),
+    GcScope::WorktreesOnly => log_success!(
+      "Garbage collected {} worktrees (branches untouched)",
+      pruned_worktrees
+    ),
+    GcScope::BranchesOnly => log_success!(
+      "Garbage collected {} branches (worktrees untouched)",
+      deleted_branches
+    ),
+  }
   Ok(())
 }
@@ -0,0 +1,43 @@
+use anyhow::Result;
+
+use crate::config::AppContext;
+use crate::log_info;
+use crate::utils::events::{events_path, read_events};
+use crate::utils::log::t;
+use crate::utils::task::resolve_id_or_slug;
+
+/// Print the recorded status transitions of a task, oldest first.
+///
+/// Completed tasks no longer have a task file, so `ident` is also matched
+/// against the ids and slugs stored in the event log.
+///
+/// # Errors
+/// Returns an error if no task and no recorded events match `ident`.
+pub fn run(ctx: &AppContext, ident: &str) -> Result<()> {
+  let task = resolve_id_or_slug(&ctx.paths, ident).ok();
+  let events: Vec<_> = read_events(&events_path(&ctx.paths))
+    .into_iter()
+    .filter(|event| match &task {
+      Some(task) => event.task_id == task.id && event.slug == task.slug,
+      None => event.task_id.to_string() == ident || event.slug == ident,
+    })
+    .collect();
+  if events.is_empty() {
+    if task.is_none() {
+      anyhow::bail!("Task not found: {ident}");
+    }
+    log_info!("No status changes recorded");
+    return Ok(());
+  }
+  for event in events {
+    anstream::println!(
+      "{} {} {} {} -> {}",
+      event.utc_time(),
+      t::id(event.task_id),
+      t::slug(&event.slug),
+      event.from,
+      event.to
+    );
+  }
+  Ok(())
+}
@@ -0,0 +1,125 @@
+use std::fs;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+
+use crate::config::AppContext;
+use crate::utils::archive::{TaskArchive, read_task_archive};
+use crate::utils::bootstrap::create_worktree_for_task;
+use crate::utils::daemon::notify_after_task_change;
+use crate::utils::files::{add_file_from_bytes, parse_file_name};
+use crate::utils::git::{
+  apply_patch_at, commit_staged_at, ensure_branch_at, git_workdir, open_main_repo, rev_parse,
+};
+use crate::utils::log::t;
+use crate::utils::task::{
+  KeepArtifacts, TaskFrontmatterExt, TaskRef, branch_name, cleanup_task_artifacts,
+  compute_unique_slug, next_id, normalize_and_validate_slug, parse_task_markdown,
+  read_task_content, task_file, write_task_content,
+};
+use crate::{log_success, log_warn};
+
+/// Restore a task archive created by `agency export` as a new task with a fresh id.
+///
+/// The branch patch is committed onto a new task branch. When the base branch is
+/// missing locally the patch is attached as a file instead. A failed import removes
+/// the partially created task, branch and worktree again.
+///
+/// # Errors
+/// Returns an error if the archive is invalid or the task cannot be written.
+pub fn run(ctx: &AppContext, archive_path: &str) -> Result<TaskRef> {
+  let archive = read_task_archive(Path::new(archive_path))?;
+  let base_slug = normalize_and_validate_slug(&archive.slug)?;
+
+  notify_after_task_change(ctx, || {
+    let tasks = ctx.paths.tasks_dir();
+    fs::create_dir_all(&tasks).with_context(|| format!("failed to create {}", tasks.display()))?;
+    let task = TaskRef {
+      id: next_id(&tasks)?,
+      slug: compute_unique_slug(&tasks, &base_slug)?,
+    };
+    let md_path = task_file(&ctx.paths, &task);
+    fs::write(&md_path, &archive.task_md)
+      .with_context(|| format!("failed to write {}", md_path.display()))?;
+    // Until the adopted branch is dropped, cleanup would target the source repo's branch
+    if let Err(err) = drop_adopted_branch(ctx, &task) {
+      let _ = fs::remove_file(&md_path);
+      return Err(err);
+    }
+    if let Err(err) = restore_contents(ctx, &task, &archive) {
+      discard_partial_import(ctx, &task);
+      return Err(err);
+    }
+
+    log_success!(
+      "Imported task {} (id {})",
+      t::slug(&task.slug),
+      t::id(task.id)
+    );
+    Ok(task)
+  })
+}
+
+/// Attach the archived files and commit the archived changes onto the task branch.
+fn restore_contents(ctx: &AppContext, task: &TaskRef, archive: &TaskArchive) -> Result<()> {
+  for (filename, data) in &archive.files {
+    let name = parse_file_name(filename).map_or_else(|| filename.clone(), |(_, name)| name);
+    add_file_from_bytes(&ctx.paths, task, &name, data)?;
+  }
+
+  let Some(patch) = archive
+    .patch
+    .as_deref()
+    .filter(|patch| !patch.trim_ascii().is_empty())
+  else {
+    return Ok(());
+  };
+  let (frontmatter, _) = parse_task_markdown(&archive.task_md);
+  apply_changes(ctx, task, &frontmatter.base_branch(ctx), patch)
+}
+
+/// Remove everything a failed import created so the repo looks as before.
+fn discard_partial_import(ctx: &AppContext, task: &TaskRef) {
+  log_warn!(
+    "Import failed, removing partially imported task {}",
+    t::slug(&task.slug)
+  );
+  let repo_workdir =
+    git_workdir(ctx.paths.root()).unwrap_or_else(|_| ctx.paths.root().to_path_buf());
+  let _ = cleanup_task_artifacts(ctx, task, &repo_workdir, KeepArtifacts::default());
+}
+
+/// Imported tasks get a fresh task branch, so forget a branch adopted in the source repo.
+fn drop_adopted_branch(ctx: &AppContext, task: &TaskRef) -> Result<()> {
+  let mut content = read_task_content(&ctx.paths, task)?;
+  let Some(frontmatter) = content
+    .frontmatter
+    .as_mut()
+    .filter(|frontmatter| frontmatter.branch.is_some())
+  else {
+    return Ok(());
+  };
+  frontmatter.branch = None;
+  write_task_content(&ctx.paths, task, &content)
+}
+
+fn apply_changes(ctx: &AppContext, task: &TaskRef, base: &str, patch: &[u8]) -> Result<()> {
+  let repo = open_main_repo(ctx.paths.root())?;
+  if rev_parse(repo.workdir().unwrap_or(ctx.paths.root()), base).is_err() {
+    add_file_from_bytes(&ctx.paths, task, "changes.patch", patch)?;
+    log_warn!(
+      "Base branch {} not found; attached changes.patch to the task instead",
+      base
+    );
+    return Ok(());
+  }
+
+  let branch = branch_name(task);
+  ensure_branch_at(&repo, &branch, base)?;
+  let worktree = create_worktree_for_task(ctx, &repo, task, &branch)?;
+  apply_patch_at(&worktree.worktree_dir, patch)?;
+  commit_staged_at(
+    &worktree.worktree_dir,
+    &format!("Import changes for {}", task.slug),
+  )
+}
@@ -1,13 +1,65 @@
-use anyhow::Result;
+use anyhow::{Context, Result, bail};
+use serde::Serialize;
 
 use crate::config::AppContext;
-use crate::utils::context::{detect_task_from_env, is_in_worktree};
-use crate::utils::files::{list_files, print_files_table};
+use crate::daemon_protocol::SessionInfo;
+use crate::utils::context::{detect_task_from_env, is_in_worktree, task_for_cwd};
+use crate::utils::files::{file_path, list_files, print_files_table};
+use crate::utils::git::{open_main_repo, repo_workdir_or};
 use crate::utils::task::{
-  TaskFrontmatterExt, agent_for_task, read_task_frontmatter, worktree_dir,
+  TaskFrontmatterExt, TaskRef, agent_for_task, read_task_frontmatter, task_branch, worktree_dir,
 };
+use crate::utils::tmux;
+
+/// Task context as emitted by `agency info --json`; only `task: null` outside a task.
+#[derive(Serialize)]
+struct InfoJson {
+  task: Option<InfoTask>,
+  #[serde(flatten)]
+  context: Option<InfoContext>,
+}
+
+#[derive(Serialize)]
+struct InfoTask {
+  id: u32,
+  slug: String,
+  agent: Option<String>,
+  base_branch: String,
+}
+
+#[derive(Serialize)]
+struct InfoContext {
+  worktree: String,
+  branch: String,
+  files: Vec<InfoFile>,
+  session: Option<SessionInfo>,
+}
+
+#[derive(Serialize)]
+struct InfoFile {
+  id: u32,
+  name: String,
+  path: String,
+}
+
+/// Show the current task context, or with `task_id_only` print `<id>-<slug>` of
+/// the task worktree containing the current directory. With `json` the context
+/// is printed as JSON.
+///
+/// # Errors
+/// Returns an error if no task can be detected or its files cannot be listed.
+pub fn run(ctx: &AppContext, task_id_only: bool, json: bool) -> Result<()> {
+  if json {
+    return print_json(ctx);
+  }
+  if task_id_only {
+    let Some(task) = task_for_cwd(&ctx.paths)? else {
+      bail!("Not inside a task worktree");
+    };
+    println!("{}-{}", task.id, task.slug);
+    return Ok(());
+  }
 
-pub fn run(ctx: &AppContext) -> Result<()> {
   let task = detect_task_from_env(&ctx.paths)?;
   let frontmatter = read_task_frontmatter(&ctx.paths, &task);
   let base_branch = frontmatter.base_branch(ctx);
@@ -36,3 +88,57 @@ pub fn run(ctx: &AppContext) -> Result<()> {
 
   Ok(())
 }
+
+/// Print the task context as JSON. The task comes from `AGENCY_TASK_ID` or the
+/// worktree containing the current directory; without one only `task: null`
+/// is printed.
+fn print_json(ctx: &AppContext) -> Result<()> {
+  let task = match detect_task_from_env(&ctx.paths) {
+    Ok(task) => Some(task),
+    Err(_) => task_for_cwd(&ctx.paths)?,
+  };
+  let info = match task {
+    Some(task) => task_info(ctx, &task)?,
+    None => InfoJson {
+      task: None,
+      context: None,
+    },
+  };
+  let out = serde_json::to_string_pretty(&info).context("failed to serialize task info")?;
+  println!("{out}");
+  Ok(())
+}
+
+fn task_info(ctx: &AppContext, task: &TaskRef) -> Result<InfoJson> {
+  let frontmatter = read_task_frontmatter(&ctx.paths, task);
+  let files = list_files(&ctx.paths, task)?
+    .iter()
+    .map(|file| InfoFile {
+      id: file.id,
+      name: file.name.clone(),
+      path: file_path(&ctx.paths, task, file).display().to_string(),
+    })
+    .collect();
+  let repo = open_main_repo(ctx.paths.root())?;
+  let repo_root = repo_workdir_or(&repo, ctx.paths.root());
+  // Sessions are read from tmux directly, so this works without the daemon
+  let session = tmux::list_sessions_for_project(&ctx.config, &repo_root)
+    .unwrap_or_default()
+    .into_iter()
+    .filter(|session| session.task.id == task.id && session.task.slug == task.slug)
+    .max_by_key(|session| session.created_at_ms);
+  Ok(InfoJson {
+    task: Some(InfoTask {
+      id: task.id,
+      slug: task.slug.clone(),
+      agent: agent_for_task(&ctx.config, frontmatter.as_ref()),
+      base_branch: frontmatter.base_branch(ctx),
+    }),
+    context: Some(InfoContext {
+      worktree: worktree_dir(&ctx.paths, task).display().to_string(),
+      branch: task_branch(&ctx.paths, task),
+      files,
+      session,
+    }),
+  })
+}
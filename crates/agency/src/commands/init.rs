@@ -5,6 +5,7 @@ use anyhow::{Context, Result};
 
 use crate::config::{self, AppContext};
 use crate::log_info;
+use crate::utils::fs::atomic_write;
 use crate::utils::log::t;
 
 const SETUP_TEMPLATE: &str = r#"#!/usr/bin/env bash
@@ -79,11 +80,10 @@ fn ensure_config(path: &Path, agent: Option<&str>) -> Result<()> {
     if !existed {
       output = format!("{}\n{}", output.trim_end(), config::config_template());
     }
-    fs::write(path, output).with_context(|| format!("failed to write {}", path.display()))?;
+    atomic_write(path, output)?;
   } else if !existed {
     // Write the template for discoverability
-    fs::write(path, config::config_template())
-      .with_context(|| format!("failed to write {}", path.display()))?;
+    atomic_write(path, config::config_template())?;
   }
   Ok(())
 }
@@ -0,0 +1,175 @@
+use std::fs::File;
+use std::io::{Read as _, Seek as _, SeekFrom};
+use std::os::unix::fs::MetadataExt as _;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use owo_colors::OwoColorize as _;
+
+use crate::config::{AppContext, compute_log_path};
+use crate::daemon::{parse_log_lines, read_log_tail};
+use crate::daemon_protocol::{LogLevel, LogLine};
+
+/// Delay between checks for new lines in follow mode.
+const FOLLOW_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Print the last daemon log lines read from the log file, optionally following it.
+///
+/// Works without a running daemon. In follow mode the file is reopened when it is
+/// truncated or recreated.
+///
+/// # Errors
+/// Returns an error if the log file cannot be read while following.
+pub fn run(
+  ctx: &AppContext,
+  max_lines: usize,
+  level_filter: Option<LogLevel>,
+  follow: bool,
+) -> Result<()> {
+  let path = compute_log_path(&ctx.config);
+  print_lines(&read_log_tail(&path, Some(max_lines), level_filter));
+  if !follow {
+    return Ok(());
+  }
+
+  let mut follower = LogFollower::at_end(&path, level_filter);
+  loop {
+    print_lines(&follower.poll()?);
+    thread::sleep(FOLLOW_POLL_INTERVAL);
+  }
+}
+
+/// Print log lines colored by level, as shared with `agency daemon logs`.
+pub fn print_lines(lines: &[LogLine]) {
+  for line in lines {
+    anstream::println!("{}", format_line(line));
+  }
+}
+
+fn format_line(line: &LogLine) -> String {
+  let level = format!("{:<5}", line.level.as_str());
+  let level = match line.level {
+    LogLevel::Error => level.red().to_string(),
+    LogLevel::Warn => level.yellow().to_string(),
+    LogLevel::Info => level.green().to_string(),
+    LogLevel::Debug | LogLevel::Trace => level.dimmed().to_string(),
+  };
+  format!(
+    "{} {level} {} {}",
+    line.ts.dimmed(),
+    line.target.cyan(),
+    line.message
+  )
+}
+
+/// Reads lines appended to a JSONL log, reopening it after truncation or rotation.
+struct LogFollower {
+  path: PathBuf,
+  file: Option<File>,
+  inode: u64,
+  offset: u64,
+  partial: String,
+  level_filter: Option<LogLevel>,
+}
+
+impl LogFollower {
+  /// Start following after the current end of the file.
+  fn at_end(path: &Path, level_filter: Option<LogLevel>) -> Self {
+    let mut follower = Self {
+      path: path.to_path_buf(),
+      file: None,
+      inode: 0,
+      offset: 0,
+      partial: String::new(),
+      level_filter,
+    };
+    if let Ok(meta) = std::fs::metadata(path) {
+      follower.inode = meta.ino();
+      follower.offset = meta.len();
+    }
+    follower
+  }
+
+  /// Return complete lines appended since the last poll that pass the level filter.
+  fn poll(&mut self) -> Result<Vec<LogLine>> {
+    let Ok(meta) = std::fs::metadata(&self.path) else {
+      // Rotated away and not yet recreated
+      self.reset();
+      return Ok(Vec::new());
+    };
+    if meta.ino() != self.inode || meta.len() < self.offset {
+      self.reset();
+      self.inode = meta.ino();
+    }
+    if self.file.is_none() {
+      let file = File::open(&self.path)
+        .with_context(|| format!("failed to open {}", self.path.display()))?;
+      self.file = Some(file);
+    }
+    let Some(file) = self.file.as_mut() else {
+      return Ok(Vec::new());
+    };
+    file
+      .seek(SeekFrom::Start(self.offset))
+      .with_context(|| format!("failed to seek {}", self.path.display()))?;
+    let mut chunk = String::new();
+    file
+      .read_to_string(&mut chunk)
+      .with_context(|| format!("failed to read {}", self.path.display()))?;
+    self.offset = file
+      .stream_position()
+      .with_context(|| format!("failed to seek {}", self.path.display()))?;
+    self.partial.push_str(&chunk);
+
+    let Some(end) = self.partial.rfind('\n') else {
+      return Ok(Vec::new());
+    };
+    let complete: String = self.partial.drain(..=end).collect();
+    Ok(parse_log_lines(&complete, self.level_filter))
+  }
+
+  fn reset(&mut self) {
+    self.file = None;
+    self.inode = 0;
+    self.offset = 0;
+    self.partial.clear();
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::LogFollower;
+  use std::fs;
+  use std::io::Write as _;
+
+  const ONE: &str = r#"{"ts":"t1","level":"INFO","target":"agency","message":"one"}"#;
+  const TWO: &str = r#"{"ts":"t2","level":"WARN","target":"agency","message":"two"}"#;
+  const THREE: &str = r#"{"ts":"t3","level":"INFO","target":"agency","message":"three"}"#;
+
+  #[test]
+  fn follower_reads_appended_lines_and_survives_truncation() {
+    let dir = tempfile::tempdir().expect("tmp");
+    let path = dir.path().join("agency.logs.jsonl");
+    fs::write(&path, format!("{ONE}\n")).expect("write log");
+
+    let mut follower = LogFollower::at_end(&path, None);
+    assert!(follower.poll().expect("poll").is_empty());
+
+    let mut file = fs::OpenOptions::new()
+      .append(true)
+      .open(&path)
+      .expect("open log");
+    write!(file, "{TWO}\n{}", &THREE[..10]).expect("append");
+    let lines = follower.poll().expect("poll");
+    assert_eq!(lines.len(), 1);
+    assert_eq!(lines[0].message, "two");
+
+    // Truncated by a daemon restart: start over from the beginning
+    fs::write(&path, format!("{THREE}\n")).expect("truncate log");
+    let lines = follower.poll().expect("poll");
+    assert_eq!(lines.len(), 1);
+    assert_eq!(lines[0].message, "three");
+  }
+}
@@ -5,25 +5,59 @@ use anyhow::{Context, Result, bail};
 
 use crate::config::AppContext;
 use crate::utils::daemon::notify_after_task_change;
+use crate::utils::editor::open_path as open_editor;
 use crate::utils::git::{
-  current_branch_name_at, git_workdir, hard_reset_to_head_at, is_fast_forward_at, rebase_onto,
-  rev_parse, stash_pop, stash_push, update_branch_ref_at, worktree_is_clean_at,
+  commits_ahead_at, current_branch_name_at, default_base_branch, diff_is_empty_at, git_workdir,
+  hard_reset_to_head_at, is_fast_forward_at, merge_commit_at, rebase_onto, rev_parse,
+  soft_reset_to_at, squash_merge_at, stash_pop, stash_push, update_branch_ref_at,
+  worktree_is_clean_at,
 };
 use crate::utils::task::{
-  TaskRef, branch_name, parse_task_markdown, resolve_id_or_slug, task_file, worktree_dir,
+  TaskRef, parse_task_markdown, resolve_id_or_slug, task_branch, task_file, worktree_dir,
 };
 use crate::{log_info, log_success, log_warn};
 
+/// Marks the end of the message in the commit message template, like git's
+/// `commit --cleanup=scissors`. Everything from this line on is dropped.
+const SCISSORS: &str = "# ------------------------ >8 ------------------------";
+
 /// Result of a successful merge operation.
 pub struct MergeResult {
   pub task: TaskRef,
   pub repo_workdir: PathBuf,
+  pub base_branch: String,
+}
+
+/// How `agency merge` brings the task branch into base.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MergeMode {
+  /// Rebase onto base and fast-forward base to the task branch.
+  #[default]
+  FastForward,
+  /// Rebase onto base and add all task changes as one commit.
+  Squash,
+  /// Always create a merge commit with base and the task branch as parents.
+  NoFastForward,
 }
 
-/// Run the merge command: rebase and fast-forward, but keep task intact.
-pub fn run(ctx: &AppContext, ident: &str, base_override: Option<&str>) -> Result<()> {
+/// Run the merge command in the given `mode`, but keep task intact.
+///
+/// # Errors
+/// Returns an error if the merge fails. See `perform_merge`, `perform_squash_merge`
+/// and `perform_no_ff_merge`.
+pub fn run(
+  ctx: &AppContext,
+  ident: &str,
+  base_override: Option<&str>,
+  mode: MergeMode,
+  yes: bool,
+) -> Result<()> {
   notify_after_task_change(ctx, || {
-    let result = perform_merge(ctx, ident, base_override)?;
+    let result = match mode {
+      MergeMode::FastForward => perform_merge(ctx, ident, base_override)?,
+      MergeMode::Squash => perform_squash_merge(ctx, ident, base_override, yes)?,
+      MergeMode::NoFastForward => perform_no_ff_merge(ctx, ident, base_override, yes)?,
+    };
 
     log_success!(
       "Merge complete. Run `agency complete {}` to clean up the task.",
@@ -80,11 +114,149 @@ pub fn perform_merge(
   Ok(MergeResult {
     task: inputs.task,
     repo_workdir: inputs.repo_workdir,
+    base_branch: inputs.base_branch,
+  })
+}
+
+/// Perform a squash merge: rebase onto base and add all task changes as one commit.
+/// The task branch is moved onto the squash commit so it stays in sync with base.
+/// The generated message is opened in the editor unless `yes` is set.
+///
+/// # Errors
+/// Returns an error if the task is not found, rebase fails, there is nothing to merge,
+/// or the commit message is left empty.
+pub fn perform_squash_merge(
+  ctx: &AppContext,
+  ident: &str,
+  base_override: Option<&str>,
+  yes: bool,
+) -> Result<MergeResult> {
+  let inputs = compute_merge_inputs(ctx, ident, base_override)?;
+
+  let (refresh_checked_out_base, needs_auto_stash) =
+    assess_base_state(&inputs.repo_workdir, &inputs.base_branch)?;
+
+  log_warn!("Rebase {} onto {}", inputs.branch, inputs.base_branch);
+  perform_rebase(&inputs.wt_dir, &inputs.base_branch)?;
+
+  let (_base_head, new_head) = ensure_can_fast_forward(
+    &inputs.repo_workdir,
+    &inputs.wt_dir,
+    &inputs.base_branch,
+    &inputs.branch,
+  )?;
+  if diff_is_empty_at(&inputs.repo_workdir, &inputs.base_branch, &new_head)? {
+    bail!(
+      "No changes to merge: {} already matches {}",
+      inputs.base_branch,
+      inputs.branch
+    );
+  }
+
+  let mut message = default_squash_message(&inputs.task, &inputs.body);
+  if !yes {
+    message = edit_commit_message(ctx, &inputs.task, &message, "Squash merge")?;
+  }
+
+  let mut pending_stash =
+    maybe_autostash(&inputs.repo_workdir, &inputs.base_branch, needs_auto_stash)?;
+
+  let squash_head = squash_merge_at(
+    &inputs.repo_workdir,
+    &inputs.base_branch,
+    &inputs.branch,
+    &message,
+  )?;
+  log_success!(
+    "Squash {} into {} at {}",
+    inputs.branch,
+    inputs.base_branch,
+    squash_head
+  );
+  update_branch_ref_at(&inputs.repo_workdir, &inputs.base_branch, &squash_head)?;
+  soft_reset_to_at(&inputs.wt_dir, &squash_head)?;
+
+  fast_forward_refresh_and_unstash(
+    &inputs.repo_workdir,
+    &inputs.base_branch,
+    refresh_checked_out_base,
+    &mut pending_stash,
+  )?;
+
+  Ok(MergeResult {
+    task: inputs.task,
+    repo_workdir: inputs.repo_workdir,
+    base_branch: inputs.base_branch,
+  })
+}
+
+/// Merge the task branch into base with a merge commit, even if a fast-forward
+/// is possible. The task branch is not rebased, and a conflicting merge leaves
+/// base, the task branch and its worktree untouched.
+/// The generated message is opened in the editor unless `yes` is set.
+///
+/// # Errors
+/// Returns an error if the task is not found, the branch has no commits beyond
+/// base, the merge conflicts, or the commit message is left empty.
+pub fn perform_no_ff_merge(
+  ctx: &AppContext,
+  ident: &str,
+  base_override: Option<&str>,
+  yes: bool,
+) -> Result<MergeResult> {
+  let inputs = compute_merge_inputs(ctx, ident, base_override)?;
+
+  if commits_ahead_at(&inputs.repo_workdir, &inputs.base_branch, &inputs.branch)? == 0 {
+    bail!(
+      "No changes to merge: {} has no commits beyond {}",
+      inputs.branch,
+      inputs.base_branch
+    );
+  }
+
+  let (refresh_checked_out_base, needs_auto_stash) =
+    assess_base_state(&inputs.repo_workdir, &inputs.base_branch)?;
+
+  let mut message = default_merge_message(&inputs.task, &inputs.body, &inputs.base_branch);
+  if !yes {
+    message = edit_commit_message(ctx, &inputs.task, &message, "Merge")?;
+  }
+
+  let merge_head = merge_commit_at(
+    &inputs.repo_workdir,
+    &inputs.base_branch,
+    &inputs.branch,
+    &message,
+  )?;
+
+  let mut pending_stash =
+    maybe_autostash(&inputs.repo_workdir, &inputs.base_branch, needs_auto_stash)?;
+
+  log_success!(
+    "Merge {} into {} at {}",
+    inputs.branch,
+    inputs.base_branch,
+    merge_head
+  );
+  update_branch_ref_at(&inputs.repo_workdir, &inputs.base_branch, &merge_head)?;
+
+  fast_forward_refresh_and_unstash(
+    &inputs.repo_workdir,
+    &inputs.base_branch,
+    refresh_checked_out_base,
+    &mut pending_stash,
+  )?;
+
+  Ok(MergeResult {
+    task: inputs.task,
+    repo_workdir: inputs.repo_workdir,
+    base_branch: inputs.base_branch,
   })
 }
 
 struct MergeInputs {
   task: TaskRef,
+  body: String,
   branch: String,
   wt_dir: PathBuf,
   base_branch: String,
@@ -97,7 +269,7 @@ fn compute_merge_inputs(
   base_override: Option<&str>,
 ) -> Result<MergeInputs> {
   let task = resolve_id_or_slug(&ctx.paths, ident)?;
-  let branch = branch_name(&task);
+  let branch = task_branch(&ctx.paths, &task);
   let wt_dir = worktree_dir(&ctx.paths, &task);
   let file_path = task_file(&ctx.paths, &task);
   if !file_path.exists() {
@@ -105,16 +277,17 @@ fn compute_merge_inputs(
   }
   let data = fs::read_to_string(&file_path)
     .with_context(|| format!("failed to read {}", file_path.display()))?;
-  let (fm_opt, _body) = parse_task_markdown(&data);
+  let (fm_opt, body) = parse_task_markdown(&data);
   let mut base_branch = fm_opt
     .and_then(|fm| fm.base_branch)
-    .unwrap_or_else(|| "main".to_string());
+    .unwrap_or_else(|| default_base_branch(&ctx.config, ctx.paths.root()));
   if let Some(b) = base_override {
     base_branch = b.to_string();
   }
   let repo_workdir = git_workdir(ctx.paths.root())?;
   Ok(MergeInputs {
     task,
+    body: body.to_string(),
     branch,
     wt_dir,
     base_branch,
@@ -173,6 +346,70 @@ fn ensure_can_fast_forward(
   Ok((base_head, new_head))
 }
 
+/// Build the squash commit message from the task slug and the first paragraph of its body.
+fn default_squash_message(task: &TaskRef, body: &str) -> String {
+  let summary = body.trim().split("\n\n").next().unwrap_or_default().trim();
+  if summary.is_empty() {
+    return format!("{}\n", task.slug);
+  }
+  format!("{}\n\n{}\n", task.slug, summary)
+}
+
+/// Build the merge commit message, followed by the first paragraph of the task body.
+fn default_merge_message(task: &TaskRef, body: &str, base_branch: &str) -> String {
+  let subject = format!("Merge {}-{} into {base_branch}", task.id, task.slug);
+  let summary = body.trim().split("\n\n").next().unwrap_or_default().trim();
+  if summary.is_empty() {
+    return format!("{subject}\n");
+  }
+  format!("{subject}\n\n{summary}\n")
+}
+
+/// Let the user edit a commit message; `kind` names the operation, e.g. `Squash merge`.
+/// Without an interactive terminal the initial message is used as is.
+fn edit_commit_message(
+  ctx: &AppContext,
+  task: &TaskRef,
+  initial: &str,
+  kind: &str,
+) -> Result<String> {
+  if !ctx.tty.is_interactive() {
+    return Ok(initial.to_string());
+  }
+  let state_dir = ctx.paths.state_dir();
+  fs::create_dir_all(&state_dir)
+    .with_context(|| format!("failed to create {}", state_dir.display()))?;
+
+  let temp_path = state_dir.join(format!("{}-{}.merge-msg", task.id, task.slug));
+  let template = format!(
+    "{initial}\n{SCISSORS}\n# {kind} commit message for {}-{}. Lines from the one above on are ignored.\n",
+    task.id, task.slug
+  );
+  fs::write(&temp_path, template)
+    .with_context(|| format!("failed to write {}", temp_path.display()))?;
+
+  let editor_result = open_editor(&ctx.config, &temp_path, ctx.paths.root());
+  let updated = fs::read_to_string(&temp_path)
+    .with_context(|| format!("failed to read {}", temp_path.display()));
+  let _ = fs::remove_file(&temp_path);
+
+  editor_result?;
+  let updated = updated?;
+  let message = strip_message_template(&updated);
+  if message.is_empty() {
+    bail!("{kind} aborted: empty commit message");
+  }
+  Ok(format!("{message}\n"))
+}
+
+/// The edited message without the template's trailing comment block.
+fn strip_message_template(edited: &str) -> &str {
+  let message = edited
+    .split_once(SCISSORS)
+    .map_or(edited, |(message, _)| message);
+  message.trim()
+}
+
 fn maybe_autostash(
   repo_workdir: &Path,
   base_branch: &str,
@@ -256,3 +493,18 @@ impl Drop for AutoStash {
     }
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::{SCISSORS, strip_message_template};
+
+  #[test]
+  fn strip_message_template_keeps_markdown_headings() {
+    let edited = format!("Add login\n\n# Notes\n- works\n{SCISSORS}\n# Squash merge ...\n");
+    assert_eq!(
+      strip_message_template(&edited),
+      "Add login\n\n# Notes\n- works"
+    );
+    assert_eq!(strip_message_template("Only subject\n"), "Only subject");
+  }
+}
@@ -1,27 +1,45 @@
 pub mod attach;
 pub mod bootstrap;
 pub mod branch;
+pub mod commit;
 pub mod complete;
 pub mod config;
 pub mod daemon;
 pub mod defaults;
+pub mod diff;
+pub mod doctor;
 pub mod edit;
 pub mod exec;
+pub mod expect;
+pub mod export;
 pub mod files;
 pub mod fzf;
 pub mod gc;
+pub mod history;
+pub mod import;
 pub mod info;
 pub mod init;
+pub mod logs;
 pub mod merge;
 pub mod new;
 pub mod open;
 pub mod path;
+pub mod pause;
+pub mod prompt;
+pub mod rebase;
+pub mod rename;
+pub mod renumber;
 pub mod reset;
+pub mod restart;
 pub mod rm;
 pub mod sessions;
 pub mod setup;
 pub mod shell;
+pub mod signal;
 pub mod start;
 pub mod stop;
 pub mod tasks;
+pub mod tui;
+pub mod update;
+pub mod worktree;
 pub mod skill;
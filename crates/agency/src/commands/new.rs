@@ -8,23 +8,62 @@ use crate::config::AppContext;
 use crate::log_info;
 use crate::utils::daemon::notify_after_task_change;
 use crate::utils::files::add_file;
-use crate::utils::git::current_branch_name_at;
+use crate::utils::git::{branch_exists_at, default_base_branch, git_workdir};
+use crate::utils::git_backend::GitBackend;
+use crate::utils::idempotency;
 use crate::utils::log::t;
 use crate::utils::task::{
-  TaskContent, TaskFrontmatter, TaskRef, compute_unique_slug, edit_task_description, next_id,
-  normalize_and_validate_slug, write_task_content,
+  Priority, TaskContent, TaskFrontmatter, TaskRef, compute_unique_slug, edit_task_description,
+  list_tasks, next_id, normalize_and_validate_slug, task_branch, write_task_content,
 };
+use crate::utils::template::{load_template, render_template, today_utc};
 
+/// Settings for a new task besides its slug. `Default` gives a plain task
+/// without description, files or labels.
+#[derive(Debug, Default)]
+pub struct NewOptions<'a> {
+  pub agent: Option<&'a str>,
+  pub desc: Option<&'a str>,
+  /// Open the editor even when `desc` is given.
+  pub edit: bool,
+  pub files: &'a [String],
+  pub template: Option<&'a str>,
+  /// Existing branch to adopt instead of creating a new one.
+  pub from_branch: Option<&'a str>,
+  /// Base branch override; defaults to the current branch.
+  pub base: Option<&'a str>,
+  /// Trimmed, deduplicated and stored in the front matter.
+  pub labels: &'a [String],
+  pub priority: Option<Priority>,
+}
+
+/// Create a new task file described by `opts`, reading the current branch
+/// through `git`.
+///
+/// # Errors
+/// Returns an error if the slug, agent, or template is invalid, the base branch
+/// does not exist, the adopted branch is missing or already used by a task, or
+/// the task file cannot be written.
 pub fn run(
   ctx: &AppContext,
+  git: &dyn GitBackend,
   slug: &str,
-  agent: Option<&str>,
-  desc: Option<&str>,
-  edit: bool,
-  files: &[String],
+  opts: &NewOptions,
 ) -> Result<TaskRef> {
+  let NewOptions {
+    agent,
+    desc,
+    edit,
+    files,
+    template,
+    from_branch,
+    base,
+    labels,
+    priority,
+  } = *opts;
   notify_after_task_change(ctx, || {
     let base_slug = normalize_and_validate_slug(slug)?;
+    let template_body = load_template(ctx, template)?;
 
     let tasks = ctx.paths.tasks_dir();
     let _ = ensure_dir(&tasks)?;
@@ -34,27 +73,34 @@ pub fn run(
     let slug = compute_unique_slug(&tasks, &base_slug)?;
 
     // Determine base branch from current working directory
-    let base_branch = match current_branch_name_at(ctx.paths.cwd()) {
+    let current_branch = match git.current_branch(ctx.paths.cwd()) {
       Ok(Some(name)) => name,
-      Ok(None) => "main".to_string(),
+      Ok(None) => default_base_branch(&ctx.config, ctx.paths.root()),
       Err(_) => {
         bail!("Not in a git repository. Please run `git init` or cd to a repo.");
       }
     };
+    let base_branch = match base {
+      Some(name) => validate_base_branch(ctx, name)?,
+      None => current_branch,
+    };
+
+    let branch = match from_branch {
+      Some(name) => Some(validate_adopted_branch(ctx, name, &base_branch)?),
+      None => None,
+    };
 
     // Compose YAML front matter
-    let fm = if let Some(agent_name) = agent {
+    if let Some(agent_name) = agent {
       // Validate agent exists in config
       let _ = ctx.config.get_agent(agent_name)?;
-      TaskFrontmatter {
-        agent: Some(agent_name.to_string()),
-        base_branch: Some(base_branch),
-      }
-    } else {
-      TaskFrontmatter {
-        agent: None,
-        base_branch: Some(base_branch),
-      }
+    }
+    let fm = TaskFrontmatter {
+      agent: agent.map(str::to_string),
+      base_branch: Some(base_branch),
+      branch,
+      labels: normalize_labels(labels),
+      priority,
     };
 
     let task = TaskRef {
@@ -65,13 +111,15 @@ pub fn run(
       frontmatter: Some(fm),
       body: String::new(),
     };
+    let template_body = template_body.map(|body| render_template(&body, &task, &today_utc()));
+    let template_body = template_body.as_deref();
 
     let should_open_editor = edit || desc.is_none();
     if should_open_editor {
       let interactive = std::io::stdout().is_terminal();
       if interactive {
-        let initial = desc.map(str::trim).unwrap_or_default();
-        content.body = initial.to_string();
+        let initial = desc.map(trim_description).unwrap_or_default();
+        content.body = compose_body(template_body, initial);
         match edit_task_description(
           &ctx.config,
           &ctx.paths,
@@ -89,15 +137,14 @@ pub fn run(
           }
         }
       } else {
-        content.body = desc.map(|d| d.trim().to_string()).unwrap_or_default();
+        let desc = desc.map(trim_description).unwrap_or_default();
+        content.body = compose_body(template_body, desc);
         write_task_content(&ctx.paths, &task, &content)?;
         log_info!("Create task {} (id {})", t::slug(&slug), t::id(id));
       }
     } else {
-      content.body = desc
-        .expect("desc must be Some when not opening editor")
-        .trim()
-        .to_string();
+      let desc = trim_description(desc.expect("desc must be Some when not opening editor"));
+      content.body = compose_body(template_body, desc);
       write_task_content(&ctx.paths, &task, &content)?;
       log_info!("Create task {} (id {})", t::slug(&slug), t::id(id));
     }
@@ -118,6 +165,95 @@ pub fn run(
   })
 }
 
+/// Like [`run`], but with an idempotency `key`: when the key already created a
+/// task that still exists, nothing is created and `None` is returned.
+///
+/// # Errors
+/// Returns an error if the key store cannot be read or written, or [`run`] fails.
+pub fn run_with_key(
+  ctx: &AppContext,
+  git: &dyn GitBackend,
+  slug: &str,
+  opts: &NewOptions,
+  key: &str,
+) -> Result<Option<TaskRef>> {
+  let lock = idempotency::lock(&ctx.paths)?;
+  if let Some(task) = idempotency::lookup(&ctx.paths, key)? {
+    log_info!(
+      "Task {} (id {}) already created for key {key}",
+      t::slug(&task.slug),
+      t::id(task.id)
+    );
+    return Ok(None);
+  }
+  let created = run(ctx, git, slug, opts)?;
+  idempotency::record(&ctx.paths, key, &created)?;
+  drop(lock);
+  Ok(Some(created))
+}
+
+/// Trim labels and drop empty and repeated ones, keeping the given order.
+fn normalize_labels(labels: &[String]) -> Vec<String> {
+  let mut normalized: Vec<String> = Vec::new();
+  for label in labels.iter().map(|label| label.trim()) {
+    if !label.is_empty() && !normalized.iter().any(|known| known == label) {
+      normalized.push(label.to_string());
+    }
+  }
+  normalized
+}
+
+/// Check that the explicit base branch `name` exists locally.
+fn validate_base_branch(ctx: &AppContext, name: &str) -> Result<String> {
+  let repo_workdir = git_workdir(ctx.paths.root())?;
+  if !branch_exists_at(&repo_workdir, name)? {
+    bail!("Base branch {name} does not exist");
+  }
+  Ok(name.to_string())
+}
+
+/// Check that `name` can be adopted: it must exist, differ from the base branch,
+/// and not belong to another task.
+fn validate_adopted_branch(ctx: &AppContext, name: &str, base_branch: &str) -> Result<String> {
+  let repo_workdir = git_workdir(ctx.paths.root())?;
+  if !branch_exists_at(&repo_workdir, name)? {
+    bail!("Branch {name} does not exist");
+  }
+  if name == base_branch {
+    bail!("Cannot adopt {name}: it is the base branch of the new task");
+  }
+  for task in list_tasks(&ctx.paths)? {
+    if task_branch(&ctx.paths, &task) == name {
+      bail!(
+        "Branch {name} is already managed by task {}-{}",
+        task.id,
+        task.slug
+      );
+    }
+  }
+  Ok(name.to_string())
+}
+
+/// Drop surrounding blank lines and trailing whitespace, keeping the
+/// indentation of the first line (e.g. a body starting with a code block).
+fn trim_description(desc: &str) -> &str {
+  let trimmed = desc.trim_end();
+  let blank_prefix = trimmed.len() - trimmed.trim_start().len();
+  let first_line = trimmed[..blank_prefix].rfind('\n').map_or(0, |pos| pos + 1);
+  &trimmed[first_line..]
+}
+
+/// Prepend the rendered template (if any) to the task description.
+fn compose_body(template: Option<&str>, desc: &str) -> String {
+  let Some(template) = template.map(str::trim_end).filter(|body| !body.is_empty()) else {
+    return desc.to_string();
+  };
+  if desc.is_empty() {
+    return format!("{template}\n");
+  }
+  format!("{template}\n\n{desc}")
+}
+
 fn ensure_dir(dir: &Path) -> Result<bool> {
   if dir.exists() {
     return Ok(false);
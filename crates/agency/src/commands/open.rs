@@ -1,15 +1,28 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
 
 use crate::config::AppContext;
 // Use macro via module path
 use crate::log_info;
-use crate::utils::editor::open_path;
+use crate::utils::editor::open_path_with;
 use crate::utils::log::t;
-use crate::utils::task::{resolve_id_or_slug, worktree_dir};
+use crate::utils::task::{agent_for_task, read_task_frontmatter, resolve_id_or_slug, worktree_dir};
 
-pub fn run(ctx: &AppContext, ident: &str) -> Result<()> {
+/// Open the task's worktree with `cmd`, or the configured `open_cmd`/editor when `None`.
+///
+/// # Errors
+/// Returns an error if the task cannot be resolved, `cmd` cannot be parsed,
+/// or the program is missing or fails.
+pub fn run(ctx: &AppContext, ident: &str, cmd: Option<&str>) -> Result<()> {
   let tref = resolve_id_or_slug(&ctx.paths, ident)?;
   let wt_dir = worktree_dir(&ctx.paths, &tref);
+  let argv = match cmd {
+    Some(cmd) => shell_words::split(cmd).with_context(|| format!("invalid --cmd '{cmd}'"))?,
+    None => {
+      let frontmatter = read_task_frontmatter(&ctx.paths, &tref);
+      let agent = agent_for_task(&ctx.config, frontmatter.as_ref());
+      ctx.config.open_argv(agent.as_deref())
+    }
+  };
   log_info!("Open worktree {}", t::path(wt_dir.display()));
-  open_path(&ctx.config, &wt_dir, ctx.paths.root())
+  open_path_with(&argv, &wt_dir, ctx.paths.root())
 }
@@ -2,11 +2,32 @@ use anstream::println;
 use anyhow::Result;
 
 use crate::config::AppContext;
-use crate::utils::task::{resolve_id_or_slug, worktree_dir};
+use crate::utils::files::files_dir_for_task;
+use crate::utils::task::{resolve_id_or_slug, task_file, worktree_dir};
 
-pub fn run(ctx: &AppContext, ident: &str) -> Result<()> {
+/// Which of a task's paths `agency path` prints.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PathKind {
+  #[default]
+  Worktree,
+  Files,
+  TaskFile,
+}
+
+/// Print the absolute path of the task's worktree, files directory or task file.
+///
+/// The path is printed even if it does not exist yet, so scripts can create it.
+///
+/// # Errors
+/// Returns an error if the task cannot be resolved.
+pub fn run(ctx: &AppContext, ident: &str, kind: PathKind) -> Result<()> {
   let tref = resolve_id_or_slug(&ctx.paths, ident)?;
-  let dir = worktree_dir(&ctx.paths, &tref);
-  println!("{}", dir.display());
+  let path = match kind {
+    PathKind::Worktree => worktree_dir(&ctx.paths, &tref),
+    PathKind::Files => files_dir_for_task(&ctx.paths, &tref),
+    PathKind::TaskFile => task_file(&ctx.paths, &tref),
+  };
+  let path = path.canonicalize().unwrap_or(path);
+  println!("{}", path.display());
   Ok(())
 }
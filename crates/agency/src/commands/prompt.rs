@@ -0,0 +1,121 @@
+use std::collections::{BTreeMap, HashMap};
+
+use anyhow::Result;
+use serde::Serialize;
+
+use crate::config::AppContext;
+use crate::utils::command::as_shell_command;
+use crate::utils::git::{open_main_repo, repo_workdir_or};
+use crate::utils::session::{InvocationOptions, preview_agent_invocation};
+use crate::utils::task::{read_task_content, resolve_id_or_slug};
+
+/// Shown instead of env values whose names look like credentials.
+const REDACTED: &str = "<redacted>";
+
+/// Name fragments that mark an env var as secret.
+const SECRET_MARKERS: &[&str] = &[
+  "TOKEN",
+  "SECRET",
+  "PASSWORD",
+  "PASSWD",
+  "API_KEY",
+  "CREDENTIAL",
+];
+
+#[derive(Serialize)]
+struct PromptJson {
+  agent: String,
+  argv: Vec<String>,
+  env: BTreeMap<String, String>,
+  prompt: String,
+}
+
+/// Print the agent command, env and prompt `start` would use for a task,
+/// without creating a worktree or spawning anything.
+///
+/// Only env vars Agency sets or changes are listed; inherited ones are left out.
+///
+/// # Errors
+/// Returns an error if the task cannot be resolved or read, or its agent
+/// cannot be resolved.
+pub fn run(ctx: &AppContext, ident: &str, json: bool) -> Result<()> {
+  let task = resolve_id_or_slug(&ctx.paths, ident)?;
+  let content = read_task_content(&ctx.paths, &task)?;
+  let description = content.body.trim().to_string();
+  let repo = open_main_repo(ctx.paths.root())?;
+  let repo_root = repo_workdir_or(&repo, ctx.paths.root());
+  let invocation = preview_agent_invocation(
+    ctx,
+    &task,
+    &InvocationOptions {
+      frontmatter: content.frontmatter.as_ref(),
+      description: &description,
+      repo_root: &repo_root,
+      agent_override: None,
+    },
+  )?;
+
+  let prompt = invocation
+    .env_map
+    .get("AGENCY_TASK")
+    .cloned()
+    .unwrap_or_default();
+  let env = agency_env(&invocation.env_map);
+  if json {
+    let mut argv = vec![invocation.program];
+    argv.extend(invocation.args);
+    let out = PromptJson {
+      agent: invocation.agent,
+      argv,
+      env,
+      prompt,
+    };
+    println!("{}", serde_json::to_string_pretty(&out)?);
+    return Ok(());
+  }
+
+  println!("Agent: {}", invocation.agent);
+  println!(
+    "Command: {}",
+    as_shell_command(&invocation.program, &invocation.args)
+  );
+  println!("Env:");
+  for (key, value) in &env {
+    println!("  {key}={value}");
+  }
+  println!("Prompt:");
+  println!("{prompt}");
+  Ok(())
+}
+
+/// Env vars that differ from the inherited environment, with secrets redacted.
+fn agency_env(env_map: &HashMap<String, String>) -> BTreeMap<String, String> {
+  let inherited: HashMap<String, String> = std::env::vars().collect();
+  env_map
+    .iter()
+    .filter(|(key, value)| inherited.get(*key) != Some(*value))
+    .map(|(key, value)| {
+      let shown = if is_secret(key) { REDACTED } else { value };
+      (key.clone(), shown.to_string())
+    })
+    .collect()
+}
+
+fn is_secret(key: &str) -> bool {
+  let upper = key.to_ascii_uppercase();
+  SECRET_MARKERS.iter().any(|marker| upper.contains(marker))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::is_secret;
+
+  #[test]
+  fn secret_env_names_are_detected() {
+    assert!(is_secret("ANTHROPIC_API_KEY"));
+    assert!(is_secret("github_token"));
+    assert!(is_secret("DB_PASSWORD"));
+    assert!(!is_secret("AGENCY_TASK"));
+    assert!(!is_secret("KEYBOARD_LAYOUT"));
+  }
+}
@@ -0,0 +1,99 @@
+use anyhow::{Result, bail};
+
+use crate::config::AppContext;
+use crate::utils::daemon::notify_after_task_change;
+use crate::utils::git::{conflicted_files_at, rebase_abort_at, rebase_onto, stash_pop};
+use crate::utils::git_backend::GitBackend;
+use crate::utils::log::t;
+use crate::utils::task::{
+  TaskFrontmatterExt, read_task_frontmatter, resolve_id_or_slug, task_branch, worktree_dir,
+};
+use crate::{log_info, log_success, log_warn};
+
+/// Rebase a task branch onto the current tip of its base branch inside the task worktree.
+/// Uncommitted changes are stashed before and restored after the rebase. The
+/// fast-forward check, stash and commit count go through `git`.
+///
+/// # Errors
+/// Returns an error if the task or its worktree is missing, the rebase hits conflicts,
+/// or stashed changes cannot be restored.
+pub fn run(
+  ctx: &AppContext,
+  git: &dyn GitBackend,
+  ident: &str,
+  base_override: Option<&str>,
+) -> Result<()> {
+  let task = resolve_id_or_slug(&ctx.paths, ident)?;
+  let branch = task_branch(&ctx.paths, &task);
+  let wt_dir = worktree_dir(&ctx.paths, &task);
+  if !wt_dir.exists() {
+    bail!(
+      "Worktree not found at {}. Run `agency bootstrap {}` first",
+      wt_dir.display(),
+      task.id
+    );
+  }
+  let base_branch = match base_override {
+    Some(base) => base.to_string(),
+    None => read_task_frontmatter(&ctx.paths, &task).base_branch(ctx),
+  };
+
+  if git.is_fast_forward(&wt_dir, &base_branch, "HEAD")? {
+    log_info!(
+      "Task {} already up to date with {}",
+      t::id(task.id),
+      base_branch
+    );
+    return Ok(());
+  }
+
+  notify_after_task_change(ctx, || {
+    let message = format!("agency auto-stash before rebase {branch}");
+    let stash = git.stash(&wt_dir, &message)?;
+    if stash.is_some() {
+      log_warn!("Auto-stashed uncommitted changes in {}", wt_dir.display());
+    }
+
+    log_warn!("Rebase {} onto {}", branch, base_branch);
+    if let Err(err) = rebase_onto(&wt_dir, &base_branch) {
+      let conflicts = conflicted_files_at(&wt_dir).unwrap_or_default();
+      // Best-effort: the rebase may have failed before it started
+      let _ = rebase_abort_at(&wt_dir);
+      if let Some(stash_ref) = &stash
+        && let Err(pop_err) = stash_pop(&wt_dir, stash_ref)
+      {
+        log_warn!(
+          "Auto-stash {} failed to reapply: {}. Restore it with `git stash pop {}`",
+          stash_ref,
+          pop_err,
+          stash_ref
+        );
+      }
+      if conflicts.is_empty() {
+        bail!("Rebase failed and was aborted: {err}");
+      }
+      bail!(
+        "Rebase aborted due to conflicts in:\n  {}\nResolve them manually in {}",
+        conflicts.join("\n  "),
+        wt_dir.display()
+      );
+    }
+
+    if let Some(stash_ref) = &stash
+      && let Err(err) = stash_pop(&wt_dir, stash_ref)
+    {
+      bail!(
+        "Rebase succeeded but auto-stash {stash_ref} failed to reapply: {err}. Resolve manually with `git stash pop {stash_ref}`"
+      );
+    }
+
+    let replayed = git.commits_ahead(&wt_dir, &base_branch, "HEAD")?;
+    log_success!(
+      "Rebased {} onto {} ({} commits replayed)",
+      branch,
+      base_branch,
+      replayed
+    );
+    Ok(())
+  })
+}
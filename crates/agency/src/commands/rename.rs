@@ -0,0 +1,56 @@
+use anyhow::{Result, bail};
+
+use crate::config::AppContext;
+use crate::utils::daemon::{notify_after_task_change, task_has_session};
+use crate::utils::git::git_workdir;
+use crate::utils::log::t;
+use crate::utils::task::{
+  TaskRef, normalize_and_validate_slug, rename_task_artifacts, resolve_id_or_slug,
+};
+use crate::{log_info, log_success};
+
+/// Rename a task's slug across its file, branch, worktree and files directory.
+///
+/// # Errors
+/// Returns an error if the slug is invalid or taken, the task is running, or a rename step fails.
+pub fn run(ctx: &AppContext, ident: &str, new_slug: &str) -> Result<()> {
+  let task = resolve_id_or_slug(&ctx.paths, ident)?;
+  let new_slug = normalize_and_validate_slug(new_slug)?;
+  if new_slug == task.slug {
+    log_info!(
+      "Task {} already named {}",
+      t::id(task.id),
+      t::slug(&task.slug)
+    );
+    return Ok(());
+  }
+  if resolve_id_or_slug(&ctx.paths, &new_slug).is_ok() {
+    bail!("A task with slug {new_slug} already exists");
+  }
+
+  // Sessions are bound to the old slug, so renaming a running task would orphan them
+  if task_has_session(ctx, &task) {
+    bail!(
+      "Task {}-{} is running. Stop it with `agency stop {}` before renaming",
+      task.id,
+      task.slug,
+      task.id
+    );
+  }
+
+  notify_after_task_change(ctx, || {
+    let repo_workdir = git_workdir(ctx.paths.root())?;
+    let renamed = TaskRef {
+      id: task.id,
+      slug: new_slug,
+    };
+    rename_task_artifacts(&ctx.paths, &task, &renamed, &repo_workdir)?;
+    log_success!(
+      "Renamed task {} {} to {}",
+      t::id(task.id),
+      t::slug(&task.slug),
+      t::slug(&renamed.slug)
+    );
+    Ok(())
+  })
+}
@@ -0,0 +1,63 @@
+use anyhow::{Result, bail};
+
+use crate::config::AppContext;
+use crate::utils::daemon::{notify_after_task_change, task_has_session};
+use crate::utils::git::git_workdir;
+use crate::utils::log::t;
+use crate::utils::task::{TaskRef, list_tasks, rename_task_artifacts, resolve_id_or_slug};
+use crate::{log_info, log_success};
+
+/// Move a task to a different id, e.g. to resolve collisions after importing tasks.
+///
+/// # Errors
+/// Returns an error if the id is invalid or taken, the task is running, or a rename step fails.
+pub fn run(ctx: &AppContext, ident: &str, new_id: u32) -> Result<()> {
+  let task = resolve_id_or_slug(&ctx.paths, ident)?;
+  if new_id == 0 {
+    bail!("Task id must be greater than 0");
+  }
+  if new_id == task.id {
+    log_info!(
+      "Task {} already has id {}",
+      t::slug(&task.slug),
+      t::id(task.id)
+    );
+    return Ok(());
+  }
+  if let Some(other) = list_tasks(&ctx.paths)?
+    .into_iter()
+    .find(|other| other.id == new_id)
+  {
+    bail!(
+      "Task id {new_id} is already used by {}-{}",
+      other.id,
+      other.slug
+    );
+  }
+
+  // Sessions are bound to the old id, so renumbering a running task would orphan them
+  if task_has_session(ctx, &task) {
+    bail!(
+      "Task {}-{} is running. Stop it with `agency stop {}` before renumbering",
+      task.id,
+      task.slug,
+      task.id
+    );
+  }
+
+  notify_after_task_change(ctx, || {
+    let repo_workdir = git_workdir(ctx.paths.root())?;
+    let renumbered = TaskRef {
+      id: new_id,
+      slug: task.slug.clone(),
+    };
+    rename_task_artifacts(&ctx.paths, &task, &renumbered, &repo_workdir)?;
+    log_success!(
+      "Moved task {} from id {} to {}",
+      t::slug(&task.slug),
+      t::id(task.id),
+      t::id(renumbered.id)
+    );
+    Ok(())
+  })
+}
@@ -1,15 +1,21 @@
-use anyhow::Result;
+use anyhow::{Result, bail};
 
 use crate::config::AppContext;
-use crate::log_info;
-use crate::log_success;
 use crate::utils::daemon::{notify_after_task_change, stop_sessions_of_task};
-use crate::utils::git::{delete_branch_if_exists, open_main_repo, prune_worktree_if_exists};
+use crate::utils::git::{
+  delete_branch_if_exists, open_main_repo, prune_worktree_if_exists, rev_parse,
+  worktree_is_pristine_at,
+};
+use crate::utils::git_backend::GitBackend;
 use crate::utils::log::t;
-use crate::utils::task::{branch_name, resolve_id_or_slug, worktree_dir};
+use crate::utils::task::{
+  TaskFrontmatterExt, adopted_branch, branch_name, read_task_frontmatter, resolve_id_or_slug,
+  task_branch, worktree_dir,
+};
+use crate::{log_info, log_success, log_warn};
 
 /// Reset a task's workspace by pruning its worktree and deleting its branch.
-/// Keeps the markdown file intact. Best-effort stop of running sessions first.
+/// Keeps the markdown file and an adopted branch intact. Best-effort stop of running sessions first.
 pub fn run(ctx: &AppContext, ident: &str) -> Result<()> {
   let tref = resolve_id_or_slug(&ctx.paths, ident)?;
 
@@ -29,10 +35,74 @@ pub fn run(ctx: &AppContext, ident: &str) -> Result<()> {
     if prune_worktree_if_exists(&repo, &wt_dir).is_ok() {
       log_success!("Pruned worktree {}", t::path(wt_dir.display()));
     }
-    if delete_branch_if_exists(&repo, &branch).is_ok() {
+    if let Some(adopted) = adopted_branch(&ctx.paths, &tref) {
+      log_info!("Kept adopted branch {}", adopted);
+    } else if delete_branch_if_exists(&repo, &branch).is_ok() {
       log_success!("Deleted branch {}", branch);
     }
 
     Ok(())
   })
 }
+
+/// Reset a task's branch and worktree to the tip of its base branch, keeping the worktree.
+/// Refuses when the worktree has uncommitted changes or untracked files unless `force`
+/// is set, which discards them. Asks for confirmation when commits not on the base
+/// branch would be lost, unless `yes` is set.
+///
+/// # Errors
+/// Returns an error if the task or its worktree is missing, the worktree is dirty
+/// without `force`, or the reset fails.
+pub fn run_to_base(
+  ctx: &AppContext,
+  git: &dyn GitBackend,
+  ident: &str,
+  yes: bool,
+  force: bool,
+) -> Result<()> {
+  let task = resolve_id_or_slug(&ctx.paths, ident)?;
+  let branch = task_branch(&ctx.paths, &task);
+  let wt_dir = worktree_dir(&ctx.paths, &task);
+  if !wt_dir.exists() {
+    bail!(
+      "Worktree not found at {}. Run `agency bootstrap {}` first",
+      wt_dir.display(),
+      task.id
+    );
+  }
+  if !force && !worktree_is_pristine_at(&wt_dir)? {
+    bail!(
+      "Worktree of {}-{} has uncommitted changes. Commit or stash them, or pass --force to discard them",
+      task.id,
+      task.slug
+    );
+  }
+  let base_branch = read_task_frontmatter(&ctx.paths, &task).base_branch(ctx);
+
+  let ahead = git.commits_ahead(&wt_dir, &base_branch, "HEAD")?;
+  if ahead > 0 {
+    log_warn!(
+      "Resetting {} to {} discards {} commit(s) not on {}",
+      branch,
+      base_branch,
+      ahead,
+      base_branch
+    );
+    if !ctx.tty.confirm("Proceed?", false, yes)? {
+      log_warn!("Cancelled");
+      return Ok(());
+    }
+  }
+
+  notify_after_task_change(ctx, || {
+    git.reset(&wt_dir, &base_branch)?;
+    let head = rev_parse(&wt_dir, "HEAD")?;
+    log_success!(
+      "Reset {} to {} ({})",
+      branch,
+      base_branch,
+      &head[..head.len().min(7)]
+    );
+    Ok(())
+  })
+}
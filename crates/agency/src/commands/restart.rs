@@ -0,0 +1,34 @@
+use anyhow::{Result, bail};
+
+use crate::config::AppContext;
+use crate::log_success;
+use crate::utils::daemon::{get_project_state, notify_after_task_change};
+use crate::utils::session::{build_session_plan, start_session_for_task};
+use crate::utils::task::resolve_id_or_slug;
+use crate::utils::tmux;
+
+/// Restart a running task's session: kill it and start a fresh shell and agent
+/// in the same worktree, without attaching. The new session is planned before
+/// the old one is killed, so a broken config or agent leaves it running.
+///
+/// # Errors
+/// Returns an error if the task has no session, the new session cannot be
+/// planned or it fails to start.
+pub fn run(ctx: &AppContext, ident: &str) -> Result<()> {
+  let task = resolve_id_or_slug(&ctx.paths, ident)?;
+  let Some(session) = get_project_state(ctx)?
+    .sessions
+    .into_iter()
+    .find(|session| session.task.id == task.id && session.task.slug == task.slug)
+  else {
+    bail!("Task {}-{} is not running", task.id, task.slug);
+  };
+
+  notify_after_task_change(ctx, || {
+    let plan = build_session_plan(ctx, &task, None)?;
+    tmux::kill_session_keeping_prompt(&ctx.config, ctx.paths.root(), &session.task)?;
+    start_session_for_task(ctx, &plan, false)?;
+    log_success!("Restarted session for {}-{}", task.id, task.slug);
+    Ok(())
+  })
+}
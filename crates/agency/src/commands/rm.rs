@@ -4,13 +4,20 @@ use crate::config::AppContext;
 use crate::utils::daemon::notify_after_task_change;
 use crate::utils::git::git_workdir;
 use crate::utils::log::t;
-use crate::utils::task::{cleanup_task_artifacts, resolve_id_or_slug};
-use crate::{log_success, log_warn};
+use crate::utils::task::{
+  KeepArtifacts, TaskRef, cleanup_task_artifacts, resolve_id_or_slug, task_branch, worktree_dir,
+};
+use crate::{log_info, log_success, log_warn};
 
-pub fn run(ctx: &AppContext, ident: &str, yes: bool) -> Result<()> {
+pub fn run(ctx: &AppContext, ident: &str, yes: bool, keep: KeepArtifacts) -> Result<()> {
   let task = resolve_id_or_slug(&ctx.paths, ident)?;
 
   log_warn!("Remove task {} {}", t::id(task.id), t::slug(&task.slug));
+  let (removed, kept) = summarize(ctx, &task, keep);
+  log_info!("Removes: {}", removed.join(", "));
+  if !kept.is_empty() {
+    log_info!("Keeps: {}", kept.join(", "));
+  }
 
   if !ctx.tty.confirm("Proceed?", true, yes)? {
     log_warn!("Cancelled");
@@ -19,7 +26,7 @@ pub fn run(ctx: &AppContext, ident: &str, yes: bool) -> Result<()> {
 
   notify_after_task_change(ctx, || {
     let repo_workdir = git_workdir(ctx.paths.root())?;
-    cleanup_task_artifacts(ctx, &task, &repo_workdir)?;
+    cleanup_task_artifacts(ctx, &task, &repo_workdir, keep)?;
     log_success!(
       "Task {} {} removed",
       t::id(task.id),
@@ -31,5 +38,24 @@ pub fn run(ctx: &AppContext, ident: &str, yes: bool) -> Result<()> {
 
 /// Remove without interactive confirmation. Intended for non-interactive TUI use.
 pub fn run_force(ctx: &AppContext, ident: &str) -> Result<()> {
-  run(ctx, ident, true)
+  run(ctx, ident, true, KeepArtifacts::default())
+}
+
+/// Split the task's artifacts into those `rm` deletes and those it keeps.
+fn summarize(ctx: &AppContext, task: &TaskRef, keep: KeepArtifacts) -> (Vec<String>, Vec<String>) {
+  let mut removed = vec!["task file".to_string(), "attached files".to_string()];
+  let mut kept = Vec::new();
+  let worktree = format!("worktree {}", worktree_dir(&ctx.paths, task).display());
+  let branch = format!("branch {}", task_branch(&ctx.paths, task));
+  if keep.worktree {
+    kept.push(worktree);
+  } else {
+    removed.push(worktree);
+  }
+  if keep.keeps_branch() {
+    kept.push(branch);
+  } else {
+    removed.push(branch);
+  }
+  (removed, kept)
 }
@@ -1,25 +1,122 @@
-use anyhow::Result;
+use std::io::Write as _;
+use std::time::Duration;
+
+use anstream::print;
+use anyhow::{Context, Result, bail};
+use crossbeam_channel::RecvTimeoutError;
+use serde::Serialize;
 
 use crate::config::AppContext;
-use crate::utils::daemon::get_project_state;
+use crate::daemon_protocol::SessionInfo;
+use crate::utils::daemon::{ProjectEvent, get_project_state, subscribe_project_events};
 use crate::utils::term::print_table;
+use crate::utils::time::{format_relative, now_ms};
+
+/// How often `--watch` redraws without a daemon update, to keep STARTED current.
+const WATCH_TICK: Duration = Duration::from_secs(1);
+
+/// One session as emitted by `agency sessions --json`.
+#[derive(Debug, Serialize)]
+struct SessionJson {
+  session_id: u64,
+  task_id: u32,
+  slug: String,
+  status: String,
+  clients: u32,
+  cwd: String,
+  pid: u32,
+  created_at_ms: u64,
+  elapsed_ms: u64,
+  /// Approximate: derived from the size of the tmux pipe-pane activity stamp.
+  bytes_out: u64,
+  log_dropped_bytes: u64,
+}
+
+impl SessionJson {
+  fn new(info: SessionInfo, now_ms: u64) -> Self {
+    Self {
+      session_id: info.session_id,
+      task_id: info.task.id,
+      slug: info.task.slug,
+      status: info.status,
+      clients: info.clients,
+      cwd: info.cwd,
+      pid: info.pid,
+      created_at_ms: info.created_at_ms,
+      elapsed_ms: now_ms.saturating_sub(info.created_at_ms),
+      bytes_out: info.bytes_out,
+      log_dropped_bytes: info.log_dropped_bytes,
+    }
+  }
+}
 
-pub fn run(ctx: &AppContext) -> Result<()> {
+/// List running sessions of the project, as a table or as JSON.
+///
+/// # Errors
+/// Returns an error if the daemon cannot be reached or JSON serialization fails.
+pub fn run(ctx: &AppContext, json: bool) -> Result<()> {
   let state = get_project_state(ctx)?;
-  let headers = ["SESSION", "TASK", "CLIENTS", "STATUS", "CWD"];
-  let rows: Vec<Vec<String>> = state
-    .sessions
-    .into_iter()
+  if json {
+    let now_ms = now_ms();
+    let items: Vec<SessionJson> = state
+      .sessions
+      .into_iter()
+      .map(|info| SessionJson::new(info, now_ms))
+      .collect();
+    let out = serde_json::to_string_pretty(&items).context("failed to serialize sessions")?;
+    println!("{out}");
+    return Ok(());
+  }
+  print_sessions(&state.sessions);
+  Ok(())
+}
+
+/// Keep the session list on screen and redraw it in place on every daemon update.
+/// Runs until interrupted with Ctrl-C.
+///
+/// # Errors
+/// Returns an error if the daemon cannot be reached or the connection is lost.
+pub fn watch(ctx: &AppContext) -> Result<()> {
+  let events = subscribe_project_events(ctx, "sessions-watch")?;
+  let mut sessions: Option<Vec<SessionInfo>> = None;
+  let mut drawn_lines = 0;
+  loop {
+    match events.recv_timeout(WATCH_TICK) {
+      Ok(ProjectEvent::State(state)) => sessions = Some(state.sessions),
+      Ok(ProjectEvent::Disconnected(err)) => bail!("Daemon connection lost: {err}"),
+      Err(RecvTimeoutError::Timeout) => {}
+      Err(RecvTimeoutError::Disconnected) => bail!("Daemon connection lost"),
+    }
+    let Some(sessions) = &sessions else {
+      continue;
+    };
+    if drawn_lines > 0 {
+      // Move to the start of the previous list and clear everything below
+      print!("\x1b[{drawn_lines}F\x1b[J");
+    }
+    drawn_lines = print_sessions(sessions);
+    std::io::stdout()
+      .flush()
+      .context("failed to flush stdout")?;
+  }
+}
+
+/// Print the session table and return the number of lines written.
+fn print_sessions(sessions: &[SessionInfo]) -> usize {
+  let headers = ["SESSION", "TASK", "CLIENTS", "STATUS", "STARTED", "CWD"];
+  let rows: Vec<Vec<String>> = sessions
+    .iter()
     .map(|e| {
       vec![
         e.session_id.to_string(),
         format!("{}-{}", e.task.id, e.task.slug),
         e.clients.to_string(),
-        e.status,
-        e.cwd,
+        e.status.clone(),
+        format_relative(e.created_at_ms),
+        e.cwd.clone(),
       ]
     })
     .collect();
   print_table(&headers, &rows);
-  Ok(())
+  rows.len() + 1
 }
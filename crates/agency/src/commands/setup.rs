@@ -1,17 +1,32 @@
 use std::collections::BTreeMap;
 use std::fs;
 
-use anyhow::{Context, Result};
+use anyhow::{Context, Result, bail};
 use toml::value::Table as TomlTable;
 
 use crate::config::{self, AgencyConfig, AppContext};
 use crate::log_success;
 use crate::log_warn;
 use crate::texts;
+use crate::utils::fs::atomic_write;
 use crate::utils::which;
 use crate::utils::wizard::{Choice, Wizard};
 
-pub fn run(ctx: &AppContext) -> Result<()> {
+/// Run the setup wizard, or write the config straight from the flags when
+/// `non_interactive` is set.
+///
+/// # Errors
+/// Returns an error if a prompt fails, a non-interactive run lacks an agent or
+/// names an unknown one, or the global config cannot be read or written.
+pub fn run(
+  ctx: &AppContext,
+  agent: Option<&str>,
+  shell: Option<&str>,
+  non_interactive: bool,
+) -> Result<()> {
+  if non_interactive {
+    return run_non_interactive(ctx, agent, shell);
+  }
   let mut global = load_global_config()?;
   let existing_agent = global.file.agent.clone();
 
@@ -50,16 +65,53 @@ pub fn run(ctx: &AppContext) -> Result<()> {
   apply_shell_choice(&mut global.file, &shell_defaults, &shell_argv);
   apply_editor_choice(&mut global.file, &editor_defaults, &editor_argv);
   write_global_config(&global)?;
+  log_written(&global);
+
+  let summary = texts::setup::summary_lines();
+  Wizard::info_lines(&summary);
+  Ok(())
+}
+
+/// Write the global config without prompting. The agent falls back to the one
+/// already configured; shell and editor take the defaults the wizard offers.
+fn run_non_interactive(ctx: &AppContext, agent: Option<&str>, shell: Option<&str>) -> Result<()> {
+  let mut global = load_global_config()?;
+  let agent = agent
+    .map(str::to_string)
+    .or_else(|| global.file.agent.clone());
+  let Some(agent) = agent else {
+    bail!("--agent is required with --non-interactive");
+  };
+  ctx.config.get_agent(&agent)?;
+
+  let shell_defaults = shell_defaults(&ctx.config);
+  let shell_argv = match shell {
+    Some(cmd) => shell_words::split(cmd).context("invalid --shell command")?,
+    None => shell_defaults.prompt_default.clone(),
+  };
+  if shell_argv.is_empty() {
+    bail!("--shell must not be empty");
+  }
+  let editor_defaults = editor_defaults(&ctx.config);
+
+  apply_agent_choice(&mut global.file, &agent);
+  apply_shell_choice(&mut global.file, &shell_defaults, &shell_argv);
+  apply_editor_choice(
+    &mut global.file,
+    &editor_defaults,
+    &editor_defaults.prompt_default,
+  );
+  write_global_config(&global)?;
+  log_written(&global);
+  Ok(())
+}
 
+fn log_written(global: &GlobalConfigState) {
   if global.existed {
     log_warn!("Updated existing config {}", global.path.display());
   } else {
     log_success!("Created global config {}", global.path.display());
   }
-
-  let summary = texts::setup::summary_lines();
-  Wizard::info_lines(&summary);
-  Ok(())
 }
 
 #[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
@@ -124,9 +176,7 @@ fn write_global_config(state: &GlobalConfigState) -> Result<()> {
     format!("{}\n{}", serialized.trim_end(), config::config_template())
   };
 
-  fs::write(&state.path, content)
-    .with_context(|| format!("failed to write {}", state.path.display()))?;
-  Ok(())
+  atomic_write(&state.path, content)
 }
 
 #[derive(Debug)]
@@ -0,0 +1,52 @@
+use anyhow::{Result, bail};
+
+use crate::config::{AppContext, compute_socket_path};
+use crate::daemon_protocol::{
+  C2D, C2DControl, D2C, D2CControl, ProjectKey, SessionSignal, read_frame, write_frame,
+};
+use crate::log_success;
+use crate::utils::daemon::connect_daemon_socket;
+use crate::utils::git::{open_main_repo, repo_workdir_or};
+use crate::utils::task::resolve_id_or_slug;
+
+/// Forward a signal to the agent process of a running task session.
+///
+/// # Errors
+/// Returns an error if the task is not running or the signal cannot be delivered.
+pub fn run(ctx: &AppContext, ident: &str, signal: SessionSignal) -> Result<()> {
+  let task = resolve_id_or_slug(&ctx.paths, ident)?;
+  let repo = open_main_repo(ctx.paths.root())?;
+  let repo_root = repo_workdir_or(&repo, ctx.paths.root());
+  let project = ProjectKey {
+    repo_root: repo_root.display().to_string(),
+  };
+
+  let socket = compute_socket_path(&ctx.config);
+  let mut stream = connect_daemon_socket(&socket)?;
+  write_frame(
+    &mut stream,
+    &C2D::Control(C2DControl::SignalTask {
+      project,
+      task_id: task.id,
+      slug: task.slug.clone(),
+      signal,
+    }),
+  )?;
+  match read_frame::<_, D2C>(&mut stream)? {
+    D2C::Control(D2CControl::Signaled { pid }) => {
+      log_success!(
+        "Sent SIG{} to {}-{} (pid {})",
+        signal.as_str(),
+        task.id,
+        task.slug,
+        pid
+      );
+      Ok(())
+    }
+    D2C::Control(D2CControl::TaskNotRunning { task_id, slug }) => {
+      bail!("Task {task_id}-{slug} is not running")
+    }
+    D2C::Control(D2CControl::Error { code, message }) => bail!("Daemon {code}: {message}"),
+    D2C::Control(other) => bail!("Unexpected daemon reply: {other:?}"),
+  }
+}
@@ -8,9 +8,18 @@ use anyhow::Result;
 ///
 /// Performs the same preparation as `attach` (ensure branch/worktree, compute agent cmd),
 /// then optionally attaches to the daemon sending `OpenSession` with the real terminal size.
-pub fn run_with_attach(ctx: &AppContext, ident: &str, attach: bool) -> Result<()> {
+/// `agent` overrides the task's agent for this session without changing its front matter.
+pub fn run_with_attach(
+  ctx: &AppContext,
+  ident: &str,
+  attach: bool,
+  agent: Option<&str>,
+) -> Result<()> {
   // Resolve task
   let task = resolve_id_or_slug(&ctx.paths, ident)?;
+  if let Some(name) = agent {
+    ctx.config.get_agent(name)?;
+  }
   // Fail when a session is already running for this task
   let existing = get_project_state(ctx)?
     .sessions
@@ -19,7 +28,7 @@ pub fn run_with_attach(ctx: &AppContext, ident: &str, attach: bool) -> Result<()
   if existing {
     anyhow::bail!("Already started. Use attach");
   }
-  let plan = build_session_plan(ctx, &task)?;
+  let plan = build_session_plan(ctx, &task, agent)?;
 
   crate::utils::daemon::notify_after_task_change(ctx, || {
     start_session_for_task(ctx, &plan, false)?;
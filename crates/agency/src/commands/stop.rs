@@ -1,3 +1,5 @@
+use std::os::unix::net::UnixStream;
+
 use anyhow::Result;
 
 use crate::config::{AppContext, compute_socket_path};
@@ -10,10 +12,19 @@ use crate::utils::task::resolve_id_or_slug;
 use crate::{log_info, log_success};
 // Use macros via module path
 
-pub fn run(ctx: &AppContext, ident: Option<&str>, session_id: Option<u64>) -> Result<()> {
+pub fn run(
+  ctx: &AppContext,
+  ident: Option<&str>,
+  session_id: Option<u64>,
+  all: bool,
+) -> Result<()> {
   let socket = compute_socket_path(&ctx.config);
   let mut stream = connect_daemon_socket(&socket)?;
 
+  if all {
+    return stop_all(ctx, &mut stream);
+  }
+
   if let Some(sid) = session_id {
     write_frame(
       &mut stream,
@@ -24,8 +35,8 @@ pub fn run(ctx: &AppContext, ident: Option<&str>, session_id: Option<u64>) -> Re
       Ok(D2C::Control(D2CControl::Goodbye)) => {
         log_success!("Stopped session {}", sid);
       }
-      Ok(D2C::Control(D2CControl::Error { message })) => {
-        anyhow::bail!("Daemon error: {message}");
+      Ok(D2C::Control(D2CControl::Error { code, message })) => {
+        anyhow::bail!("Daemon {code}: {message}");
       }
       _ => {
         // Silent success if protocol differs; keep user informed
@@ -60,8 +71,11 @@ pub fn run(ctx: &AppContext, ident: Option<&str>, session_id: Option<u64>) -> Re
           task.slug
         );
       }
-      Ok(D2C::Control(D2CControl::Error { message })) => {
-        anyhow::bail!("Daemon error: {message}");
+      Ok(D2C::Control(D2CControl::TaskNotRunning { task_id, slug })) => {
+        anyhow::bail!("Task {task_id}-{slug} is not running");
+      }
+      Ok(D2C::Control(D2CControl::Error { code, message })) => {
+        anyhow::bail!("Daemon {code}: {message}");
       }
       _ => {
         log_info!("Requested stop for task {}-{}", task.id, task.slug);
@@ -70,5 +84,30 @@ pub fn run(ctx: &AppContext, ident: Option<&str>, session_id: Option<u64>) -> Re
     return Ok(());
   }
 
-  anyhow::bail!("Must specify --session <id> or task ident")
+  anyhow::bail!("Must specify --session <id>, --all or task ident")
+}
+
+fn stop_all(ctx: &AppContext, stream: &mut UnixStream) -> Result<()> {
+  let repo = open_main_repo(ctx.paths.root())?;
+  let repo_root = repo_workdir_or(&repo, ctx.paths.root());
+  let project = ProjectKey {
+    repo_root: repo_root.display().to_string(),
+  };
+  write_frame(
+    &mut *stream,
+    &C2D::Control(C2DControl::StopProject { project }),
+  )?;
+  match read_frame::<_, D2C>(&mut *stream)? {
+    D2C::Control(D2CControl::Ack { stopped: 0 }) => {
+      log_info!("No running sessions to stop");
+    }
+    D2C::Control(D2CControl::Ack { stopped }) => {
+      log_success!("Stopped {} session(s)", stopped);
+    }
+    D2C::Control(D2CControl::Error { code, message }) => {
+      anyhow::bail!("Daemon {code}: {message}");
+    }
+    other => anyhow::bail!("Unexpected reply: {other:?}"),
+  }
+  Ok(())
 }
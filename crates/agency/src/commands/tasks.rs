@@ -1,52 +1,309 @@
 use std::collections::HashMap;
+use std::io::Write as _;
+use std::time::{Duration, Instant};
 
-use anyhow::Result;
+use anstream::{print, println};
+use anyhow::{Context, Result};
+use crossbeam_channel::RecvTimeoutError;
+use crossterm::terminal;
+use owo_colors::OwoColorize as _;
+use serde::Serialize;
 
 use crate::config::AppContext;
-use crate::utils::daemon::get_project_state;
+use crate::utils::daemon::{
+  ProjectEvent, ProjectState, get_filtered_project_state, subscribe_project_events,
+};
+use crate::utils::fs::dir_size;
 use crate::utils::sessions::latest_sessions_by_task;
-use crate::utils::task::{TaskRef, list_tasks};
-use crate::utils::task_columns::{GitMetrics, TaskColumn, TaskRow};
+use crate::utils::status::{TaskStatus, derive_status};
+use crate::utils::task::{Priority, TaskRef, agent_for_task, list_tasks, worktree_dir};
+use crate::utils::task_columns::{GitMetrics, SortColumn, TaskColumn, TaskRow};
 use crate::utils::term::print_table;
 
-pub fn run(ctx: &AppContext) -> Result<()> {
-  let mut tasks = list_tasks(&ctx.paths)?;
-  tasks.sort_by_key(|t| t.id);
+/// One task as emitted by `agency tasks --json`.
+#[derive(Debug, Serialize)]
+struct TaskJson {
+  id: u32,
+  slug: String,
+  status: String,
+  uncommitted_add: u64,
+  uncommitted_del: u64,
+  commits_ahead: u64,
+  base: String,
+  agent: Option<String>,
+  labels: Vec<String>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  priority: Option<Priority>,
+  /// Worktree size in bytes, only with `--size`.
+  #[serde(skip_serializing_if = "Option::is_none")]
+  size_bytes: Option<u64>,
+}
 
-  // Query project state (sessions + metrics); fallback gracefully when daemon unavailable
-  let (sessions, git_metrics_map) = match get_project_state(ctx) {
-    Ok(state) => {
-      let m: HashMap<TaskRef, GitMetrics> = state
-        .metrics
-        .into_iter()
-        .map(|m| {
-          (
-            TaskRef::from(m.task),
-            GitMetrics {
-              uncommitted_add: m.uncommitted_add,
-              uncommitted_del: m.uncommitted_del,
-              commits_ahead: m.commits_ahead,
-            },
-          )
-        })
-        .collect();
-      (state.sessions, m)
+impl From<&TaskRow> for TaskJson {
+  fn from(row: &TaskRow) -> Self {
+    Self {
+      id: row.task.id,
+      slug: row.task.slug.clone(),
+      status: derive_status(row.session.as_ref(), row.wt_exists)
+        .label()
+        .to_string(),
+      uncommitted_add: row.git_metrics.uncommitted_add,
+      uncommitted_del: row.git_metrics.uncommitted_del,
+      commits_ahead: row.git_metrics.commits_ahead,
+      base: row.base_branch.clone(),
+      agent: agent_for_task(&row.config, row.frontmatter.as_ref()),
+      labels: row.labels().to_vec(),
+      priority: row.priority(),
+      size_bytes: row.size_bytes,
     }
-    Err(_) => (Vec::new(), HashMap::new()),
-  };
-  let latest = latest_sessions_by_task(&sessions);
+  }
+}
 
-  // Build TaskRow structs using the shared constructor
-  let task_rows: Vec<TaskRow> = tasks
-    .iter()
-    .map(|t| {
-      let git_metrics = git_metrics_map.get(t).cloned().unwrap_or_default();
-      TaskRow::new(ctx, t.clone(), latest.get(t), git_metrics)
+/// How often `--watch` checks for terminal resizes between daemon updates.
+const WATCH_TICK: Duration = Duration::from_millis(250);
+
+/// How often `--watch` reloads the tasks while the daemon is unavailable.
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Filters and ordering shared by `agency tasks` and `agency tasks --watch`,
+/// as given on the command line. `Default` lists every task by id.
+#[derive(Debug, Default)]
+pub struct TasksOptions<'a> {
+  /// Add the worktree size column.
+  pub size: bool,
+  /// Keep only tasks whose derived status matches one of these names.
+  pub statuses: &'a [String],
+  /// Keep only tasks that carry every one of these labels.
+  pub labels: &'a [String],
+  /// Column to order rows by; id when unset.
+  pub sort: Option<&'a str>,
+  pub reverse: bool,
+}
+
+/// [`TasksOptions`] with the status names and sort column parsed.
+struct TaskQuery<'a> {
+  size: bool,
+  status_names: &'a [String],
+  statuses: Vec<TaskStatus>,
+  labels: &'a [String],
+  sort: SortColumn,
+  reverse: bool,
+}
+
+impl<'a> TaskQuery<'a> {
+  fn parse(opts: &TasksOptions<'a>) -> Result<Self> {
+    let TasksOptions {
+      size,
+      statuses,
+      labels,
+      sort,
+      reverse,
+    } = *opts;
+    Ok(Self {
+      size,
+      status_names: statuses,
+      statuses: statuses
+        .iter()
+        .map(|name| TaskStatus::parse(name))
+        .collect::<Result<Vec<_>>>()?,
+      labels,
+      sort: sort.map(SortColumn::parse).transpose()?.unwrap_or_default(),
+      reverse,
+    })
+  }
+
+  /// Ask the daemon for the project state, filtered by the query's statuses
+  /// and labels. Returns `None` when the daemon is unavailable.
+  fn fetch_state(&self, ctx: &AppContext) -> Option<ProjectState> {
+    get_filtered_project_state(ctx, self.status_names, self.labels).ok()
+  }
+
+  /// Rows for the tasks matching the query, in display order. A daemon `state`
+  /// from [`Self::fetch_state`] is already filtered; without one, tasks are
+  /// listed and filtered locally, without sessions and metrics.
+  fn rows(&self, ctx: &AppContext, state: Option<ProjectState>) -> Result<Vec<TaskRow>> {
+    let mut task_rows: Vec<TaskRow> = match state {
+      Some(state) => daemon_rows(ctx, state),
+      None => list_tasks(&ctx.paths)?
+        .into_iter()
+        .map(|task| TaskRow::new(ctx, task, None, GitMetrics::default()))
+        .filter(|row| self.matches(row))
+        .collect(),
+    };
+    task_rows.sort_by_key(|row| row.task.id);
+    // Stable sort on id-ordered rows, so ties stay in id order
+    task_rows.sort_by(|left, right| {
+      let order = self.sort.compare(left, right);
+      if self.reverse { order.reverse() } else { order }
+    });
+    Ok(task_rows)
+  }
+
+  fn matches(&self, row: &TaskRow) -> bool {
+    let status = derive_status(row.session.as_ref(), row.wt_exists);
+    (self.statuses.is_empty() || self.statuses.contains(&status))
+      && self
+        .labels
+        .iter()
+        .all(|label| row.labels().iter().any(|own| own == label.trim()))
+  }
+}
+
+/// One row per task in the daemon's `state`, with its latest session and metrics.
+fn daemon_rows(ctx: &AppContext, state: ProjectState) -> Vec<TaskRow> {
+  let git_metrics_map: HashMap<TaskRef, GitMetrics> = state
+    .metrics
+    .into_iter()
+    .map(|m| {
+      (
+        TaskRef::from(m.task),
+        GitMetrics {
+          uncommitted_add: m.uncommitted_add,
+          uncommitted_del: m.uncommitted_del,
+          commits_ahead: m.commits_ahead,
+        },
+      )
     })
     .collect();
+  let latest = latest_sessions_by_task(&state.sessions);
+  state
+    .tasks
+    .iter()
+    .map(|info| {
+      let task = TaskRef::from(info);
+      let git_metrics = git_metrics_map.get(&task).cloned().unwrap_or_default();
+      let session = latest.get(&task);
+      TaskRow::new(ctx, task, session, git_metrics)
+    })
+    .collect()
+}
+
+/// List the tasks selected by `opts` as a table, or as a JSON array when
+/// `json` is set.
+///
+/// The table shows the `[tasks] columns` from the config in order, or the
+/// default set when unset. With `size` (or a configured `size` column), each
+/// worktree is walked to report its disk usage, shown as `-` for tasks without
+/// a worktree. A non-empty `statuses` keeps only tasks whose
+/// derived status matches one of the given names, and `labels` keeps only tasks
+/// that carry every given label. Rows are ordered by `sort` (id when unset),
+/// descending with `reverse`.
+///
+/// # Errors
+/// Returns an error if a status name or sort field is unknown, the tasks
+/// directory cannot be read or JSON serialization fails.
+pub fn run(ctx: &AppContext, opts: &TasksOptions, json: bool) -> Result<()> {
+  let query = TaskQuery::parse(opts)?;
+  // Query project state (sessions + metrics); fallback gracefully when daemon unavailable
+  let mut task_rows = query.rows(ctx, query.fetch_state(ctx))?;
+
+  if json {
+    if query.size {
+      measure_sizes(ctx, &mut task_rows);
+    }
+    let items: Vec<TaskJson> = task_rows.iter().map(TaskJson::from).collect();
+    let out = serde_json::to_string_pretty(&items).context("failed to serialize tasks")?;
+    println!("{out}");
+    return Ok(());
+  }
+
+  print_tasks(ctx, &query, task_rows);
+  Ok(())
+}
+
+/// Keep the table of the tasks selected by `opts` on screen and redraw it on
+/// every daemon update or terminal resize. Runs until interrupted with Ctrl-C.
+///
+/// Without a daemon the tasks are reloaded every [`POLL_INTERVAL`], and the
+/// subscription is retried on each reload.
+///
+/// # Errors
+/// Returns an error if a status name or sort field is unknown, or the tasks
+/// directory cannot be read.
+pub fn watch(ctx: &AppContext, opts: &TasksOptions) -> Result<()> {
+  let query = TaskQuery::parse(opts)?;
+  let mut events = subscribe_project_events(ctx, "tasks-watch").ok();
+  let mut state: Option<ProjectState> = None;
+  let mut next_poll = Instant::now();
+  let mut term_size = terminal::size().ok();
+  loop {
+    let mut redraw = false;
+    if let Some(receiver) = &events {
+      match receiver.recv_timeout(WATCH_TICK) {
+        Ok(ProjectEvent::State(_)) => {
+          // Pushed states are unfiltered, so fetch one narrowed to the query
+          state = query.fetch_state(ctx);
+          redraw = true;
+        }
+        Ok(ProjectEvent::Disconnected(_)) | Err(RecvTimeoutError::Disconnected) => {
+          events = None;
+          state = None;
+          next_poll = Instant::now();
+        }
+        Err(RecvTimeoutError::Timeout) => {}
+      }
+    } else if Instant::now() >= next_poll {
+      events = subscribe_project_events(ctx, "tasks-watch").ok();
+      next_poll = Instant::now() + POLL_INTERVAL;
+      redraw = events.is_none();
+    } else {
+      std::thread::sleep(WATCH_TICK);
+    }
+
+    let current_size = terminal::size().ok();
+    if current_size != term_size {
+      term_size = current_size;
+      // A fresh subscription has nothing to draw until its first state arrives
+      redraw = redraw || state.is_some() || events.is_none();
+    }
+    if !redraw {
+      continue;
+    }
+
+    let task_rows = query.rows(ctx, state.clone())?;
+    print!("\x1b[H\x1b[2J");
+    print_tasks(ctx, &query, task_rows);
+    if events.is_none() {
+      println!(
+        "{}",
+        format!(
+          "Daemon unavailable, refreshing every {}s",
+          POLL_INTERVAL.as_secs()
+        )
+        .dimmed()
+      );
+    }
+    std::io::stdout()
+      .flush()
+      .context("failed to flush stdout")?;
+  }
+}
+
+fn measure_sizes(ctx: &AppContext, task_rows: &mut [TaskRow]) {
+  for row in task_rows {
+    row.size_bytes = dir_size(&worktree_dir(&ctx.paths, &row.task));
+  }
+}
+
+/// Print the task table with the configured columns.
+fn print_tasks(ctx: &AppContext, query: &TaskQuery<'_>, mut task_rows: Vec<TaskRow>) {
+  let configured = ctx
+    .config
+    .tasks
+    .as_ref()
+    .and_then(|tasks| tasks.columns.clone());
+  let mut columns = match configured {
+    Some(columns) => columns,
+    None => TaskColumn::visible_columns(&task_rows),
+  };
+  if query.size && !columns.contains(&TaskColumn::Size) {
+    columns.push(TaskColumn::Size);
+  }
+  if columns.contains(&TaskColumn::Size) {
+    measure_sizes(ctx, &mut task_rows);
+  }
 
   // Use TaskColumn to generate headers and cell values
-  let columns = TaskColumn::visible_columns(&task_rows);
   let headers: Vec<&str> = columns.iter().copied().map(TaskColumn::header).collect();
   let rows: Vec<Vec<String>> = task_rows
     .iter()
@@ -54,8 +311,6 @@ pub fn run(ctx: &AppContext) -> Result<()> {
     .collect();
 
   print_table(&headers, &rows);
-
-  Ok(())
 }
 
 #[cfg(test)]
@@ -0,0 +1,34 @@
+use anyhow::{Context, Result};
+
+use crate::config::AppContext;
+use crate::utils::daemon::tui_list;
+use crate::utils::term::print_table;
+
+/// List the TUIs connected to the daemon for this project and the task each
+/// one is focused on, as a table or as JSON.
+///
+/// # Errors
+/// Returns an error if the daemon cannot be reached or JSON serialization fails.
+pub fn list(ctx: &AppContext, json: bool) -> Result<()> {
+  let items = tui_list(ctx)?;
+  if json {
+    let out = serde_json::to_string_pretty(&items).context("failed to serialize TUI list")?;
+    println!("{out}");
+    return Ok(());
+  }
+  let headers = ["TUI", "PID", "FOCUSED"];
+  let rows: Vec<Vec<String>> = items
+    .iter()
+    .map(|item| {
+      vec![
+        item.tui_id.to_string(),
+        item.pid.to_string(),
+        item
+          .focused_task_id
+          .map_or_else(|| "-".to_string(), |id| id.to_string()),
+      ]
+    })
+    .collect();
+  print_table(&headers, &rows);
+  Ok(())
+}
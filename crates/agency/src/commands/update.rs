@@ -0,0 +1,31 @@
+use anyhow::{Result, bail};
+
+use crate::config::AppContext;
+use crate::log_success;
+use crate::utils::daemon::notify_after_task_change;
+use crate::utils::log::t;
+use crate::utils::task::{Priority, read_task_content, resolve_id_or_slug, write_task_content};
+
+/// Change metadata in a task's front matter, keeping the description as is.
+///
+/// # Errors
+/// Returns an error if nothing to change was given, the task cannot be
+/// resolved or its file cannot be read or written.
+pub fn run(ctx: &AppContext, ident: &str, priority: Option<Priority>) -> Result<()> {
+  let Some(priority) = priority else {
+    bail!("Nothing to update, pass --priority");
+  };
+  let task = resolve_id_or_slug(&ctx.paths, ident)?;
+  notify_after_task_change(ctx, || {
+    let mut content = read_task_content(&ctx.paths, &task)?;
+    content.frontmatter.get_or_insert_default().priority = Some(priority);
+    write_task_content(&ctx.paths, &task, &content)?;
+    log_success!(
+      "Set priority of task {} {} to {}",
+      t::id(task.id),
+      t::slug(&task.slug),
+      priority.label()
+    );
+    Ok(())
+  })
+}
@@ -0,0 +1,27 @@
+use anyhow::Result;
+
+use crate::config::AppContext;
+use crate::utils::git::{git_workdir, prune_stale_worktrees_at};
+use crate::utils::log::t;
+use crate::{log_info, log_success};
+
+/// Drop git's administrative entries for worktrees whose directory is gone.
+///
+/// Unlike `agency gc` this never deletes branches or worktree directories, so
+/// tasks with a live worktree are left alone.
+///
+/// # Errors
+/// Returns an error if the repository cannot be found or `git worktree prune` fails.
+pub fn prune(ctx: &AppContext) -> Result<()> {
+  let repo_workdir = git_workdir(ctx.paths.root())?;
+  let pruned = prune_stale_worktrees_at(&repo_workdir)?;
+  if pruned.is_empty() {
+    log_info!("No stale worktree entries");
+    return Ok(());
+  }
+  for name in &pruned {
+    log_info!("Pruned {}", t::path(name));
+  }
+  log_success!("Pruned stale worktree entries: {}", pruned.len());
+  Ok(())
+}
@@ -2,23 +2,76 @@ use std::fs;
 use std::path::{Path, PathBuf};
 
 use crate::utils::command::Command;
-use anyhow::{Context, Result};
+use crate::utils::task_columns::TaskColumn;
+use anyhow::{Context, Result, bail};
 use owo_colors::OwoColorize as _;
 use serde::Deserialize;
 use std::collections::BTreeMap;
 use toml::Value as TomlValue;
 
 /// Known top-level config keys.
-const KNOWN_TOP_LEVEL_KEYS: &[&str] = &["agent", "agents", "daemon", "bootstrap", "shell", "editor"];
+const KNOWN_TOP_LEVEL_KEYS: &[&str] = &[
+  "agent",
+  "agents",
+  "daemon",
+  "bootstrap",
+  "shell",
+  "editor",
+  "open_cmd",
+  "default_base_branch",
+  "templates",
+  "tui",
+  "concurrency",
+  "worktree_root",
+  "tasks",
+  "commit_template",
+  "tmux",
+  "extends",
+];
 
 /// Known keys within `[daemon]` section.
-const KNOWN_DAEMON_KEYS: &[&str] = &["socket_path", "tmux_socket_path"];
+const KNOWN_DAEMON_KEYS: &[&str] = &[
+  "socket_path",
+  "tmux_socket_path",
+  "scrollback_lines",
+  "record_output",
+  "record_max_bytes",
+  "dwell_secs",
+  "detach_keys",
+  "session_reap_secs",
+];
+
+/// Numeric settings overridable from the environment as `(variable, section, key)`.
+/// An empty section names a top-level key.
+const ENV_OVERRIDES: &[(&str, &str, &str)] = &[
+  ("AGENCY_CONCURRENCY", "", "concurrency"),
+  ("AGENCY_DWELL_SECS", "daemon", "dwell_secs"),
+  ("AGENCY_SCROLLBACK_LINES", "daemon", "scrollback_lines"),
+  ("AGENCY_RECORD_MAX_BYTES", "daemon", "record_max_bytes"),
+];
+
+/// Known keys within `[tui]` section.
+const KNOWN_TUI_KEYS: &[&str] = &["theme"];
+
+/// Known keys within `[tasks]` section.
+const KNOWN_TASKS_KEYS: &[&str] = &["columns"];
+
+/// Known keys within `[tmux]` section.
+const KNOWN_TMUX_KEYS: &[&str] = &["status", "status_left", "window_format"];
 
 /// Known keys within `[bootstrap]` section.
-const KNOWN_BOOTSTRAP_KEYS: &[&str] = &["include", "exclude", "cmd"];
+const KNOWN_BOOTSTRAP_KEYS: &[&str] = &["include", "exclude", "cmd", "cmds"];
 
 /// Known keys within each `[agents.<name>]` section.
-const KNOWN_AGENT_KEYS: &[&str] = &["cmd"];
+const KNOWN_AGENT_KEYS: &[&str] = &[
+  "cmd",
+  "env",
+  "prompt_mode",
+  "open_cmd",
+  "startup_timeout_secs",
+  "transcript_file",
+  "transcript_delay_ms",
+];
 
 // Embed repository defaults
 const DEFAULT_TOML: &str =
@@ -58,6 +111,38 @@ pub fn global_config_exists() -> bool {
 pub struct AgentConfig {
   #[serde(default)]
   pub cmd: Vec<String>,
+  /// Extra environment variables for the agent. Values support `$VAR` expansion.
+  #[serde(default)]
+  pub env: BTreeMap<String, String>,
+  /// How the task prompt is handed to the agent.
+  #[serde(default)]
+  pub prompt_mode: PromptMode,
+  /// Program argv for `agency open` on this agent's tasks. Overrides the global `open_cmd`.
+  #[serde(default)]
+  pub open_cmd: Option<Vec<String>>,
+  /// Report the session as Failed when the agent exits with a non-zero status
+  /// within this many seconds of starting. Disabled when unset.
+  #[serde(default)]
+  pub startup_timeout_secs: Option<u64>,
+  /// Replay this file line by line instead of running `cmd`, as a scripted
+  /// stand-in for a real agent. Supports `<root>` and `$VAR` expansion.
+  #[serde(default)]
+  pub transcript_file: Option<String>,
+  /// Pause after each replayed transcript line. Defaults to no pause.
+  #[serde(default)]
+  pub transcript_delay_ms: Option<u64>,
+}
+
+/// How the task prompt reaches the agent command.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PromptMode {
+  /// Expand `$AGENCY_TASK` inline in the command arguments.
+  #[default]
+  Arg,
+  /// Write the prompt to a file exposed as `$AGENCY_TASK_FILE` and leave
+  /// `$AGENCY_TASK` in the arguments unexpanded. Avoids argument length limits.
+  File,
 }
 
 impl AgentConfig {
@@ -76,6 +161,56 @@ pub struct DaemonConfig {
   pub socket_path: Option<String>,
   #[serde(default)]
   pub tmux_socket_path: Option<String>,
+  /// Scrollback lines kept per session (tmux `history-limit`). Uses tmux default when unset.
+  #[serde(default)]
+  pub scrollback_lines: Option<u32>,
+  /// Append raw session output to `.agency/state/logs/<id>-<slug>.log`.
+  #[serde(default)]
+  pub record_output: bool,
+  /// Size in bytes at which a session log is rotated. Defaults to 10 MiB.
+  #[serde(default)]
+  pub record_max_bytes: Option<u64>,
+  /// Seconds without output before a running session is shown as idle. Defaults to 1.
+  #[serde(default)]
+  pub dwell_secs: Option<u64>,
+  /// Keys that detach from an attached session, e.g. `ctrl-q` or `ctrl-p ctrl-q`.
+  #[serde(default)]
+  pub detach_keys: Option<String>,
+  /// Seconds a session's pane may stay dead before the daemon kills the session.
+  /// Disabled when unset or 0.
+  #[serde(default)]
+  pub session_reap_secs: Option<u64>,
+  /// Bytes sent by `detach_keys`, filled in by `load_config`.
+  #[serde(skip)]
+  pub detach_sequence: Vec<u8>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct TuiConfig {
+  /// Colors by element (`running`, `idle`, `stopped`, `exited`, `draft`, `help`).
+  /// Values are color names, `#rrggbb`, or 256-color indexes.
+  #[serde(default)]
+  pub theme: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct TasksConfig {
+  /// Columns shown by `agency tasks`, in order. Unknown names fail to load.
+  #[serde(default)]
+  pub columns: Option<Vec<TaskColumn>>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct TmuxConfig {
+  /// Show the status bar in task sessions. Defaults to true.
+  #[serde(default)]
+  pub status: Option<bool>,
+  /// Left side of the status bar; `$SLUG` and `$ID` are replaced. Defaults to ` Agency `.
+  #[serde(default)]
+  pub status_left: Option<String>,
+  /// Centered task title; `$SLUG` and `$ID` are replaced. Defaults to ` Task $SLUG (Id: $ID) `.
+  #[serde(default)]
+  pub window_format: Option<String>,
 }
 
 #[derive(Debug, Clone, Default, Deserialize)]
@@ -85,9 +220,33 @@ pub struct AgencyConfig {
   /// Default agent name when task lacks front matter
   #[serde(default)]
   pub agent: Option<String>,
+  /// Base branch for tasks when the current branch cannot be determined.
+  /// Defaults to the `origin/HEAD` branch, then the local HEAD branch, then "main".
+  #[serde(default)]
+  pub default_base_branch: Option<String>,
+  /// Task templates by name, as paths relative to `.agency/`.
+  /// The `default` template applies when `agency new` has no `--template`.
+  #[serde(default)]
+  pub templates: BTreeMap<String, String>,
+  /// Maximum number of live sessions per project. Unlimited when unset.
+  #[serde(default)]
+  pub concurrency: Option<u32>,
+  /// Directory holding task worktrees, absolute or relative to the project root.
+  /// Defaults to `.agency/worktrees`.
+  #[serde(default)]
+  pub worktree_root: Option<String>,
+  /// Message template for `agency commit`; `$SLUG`, `$ID` and `$DATE` are replaced.
+  #[serde(default)]
+  pub commit_template: Option<String>,
   #[serde(default)]
   pub daemon: Option<DaemonConfig>,
   #[serde(default)]
+  pub tui: Option<TuiConfig>,
+  #[serde(default)]
+  pub tasks: Option<TasksConfig>,
+  #[serde(default)]
+  pub tmux: Option<TmuxConfig>,
+  #[serde(default)]
   pub bootstrap: Option<BootstrapConfig>,
   /// Command to launch when opening a shell. Defaults to user's shell.
   #[serde(default)]
@@ -95,6 +254,12 @@ pub struct AgencyConfig {
   /// Preferred editor command argv. Falls back to $EDITOR or `vi` when unset.
   #[serde(default)]
   pub editor: Option<Vec<String>>,
+  /// Editor argv from the `--editor` flag, which beats every other source.
+  #[serde(skip)]
+  pub editor_override: Option<Vec<String>>,
+  /// Program argv for `agency open`. Falls back to the editor when unset.
+  #[serde(default)]
+  pub open_cmd: Option<Vec<String>>,
 }
 
 impl AgencyConfig {
@@ -124,10 +289,17 @@ impl AgencyConfig {
     cfg
   }
 
-  /// Resolve the editor argv with precedence: config.editor -> $EDITOR -> [`vi`].
-  /// Splits the env var via shell-words to support composite commands.
+  /// Resolve the editor argv with precedence:
+  /// `--editor` -> $AGENCY_EDITOR -> config.editor -> $EDITOR -> [`vi`].
+  /// Splits the env vars via shell-words to support composite commands.
   #[must_use]
   pub fn editor_argv(&self) -> Vec<String> {
+    if let Some(argv) = &self.editor_override {
+      return argv.clone();
+    }
+    if let Some(tokens) = env_argv("AGENCY_EDITOR") {
+      return tokens;
+    }
     if let Some(v) = &self.editor
       && !v.is_empty()
     {
@@ -138,11 +310,30 @@ impl AgencyConfig {
     }
     vec!["vi".to_string()]
   }
+
+  /// Resolve the argv `agency open` launches with precedence:
+  /// `agents.<agent>.open_cmd` -> `open_cmd` -> editor.
+  #[must_use]
+  pub fn open_argv(&self, agent: Option<&str>) -> Vec<String> {
+    agent
+      .and_then(|name| self.agents.get(name))
+      .and_then(|agent| agent.open_cmd.as_ref())
+      .into_iter()
+      .chain(self.open_cmd.as_ref())
+      .find(|argv| !argv.is_empty())
+      .cloned()
+      .unwrap_or_else(|| self.editor_argv())
+  }
 }
 
 #[must_use]
 pub(crate) fn editor_env_argv() -> Option<Vec<String>> {
-  let raw = std::env::var("EDITOR").ok()?;
+  env_argv("EDITOR")
+}
+
+/// Shell-words split of the env var `name`, or `None` when unset or blank.
+fn env_argv(name: &str) -> Option<Vec<String>> {
+  let raw = std::env::var(name).ok()?;
   let trimmed = raw.trim();
   if trimmed.is_empty() {
     return None;
@@ -154,6 +345,24 @@ pub(crate) fn editor_env_argv() -> Option<Vec<String>> {
   Some(tokens)
 }
 
+/// Directory name for `root` below a shared `worktree_root`: the project
+/// directory name plus a stable FNV-1a hash of the canonical root path.
+fn project_dir_name(root: &Path) -> String {
+  let root = root.canonicalize().unwrap_or_else(|_| root.to_path_buf());
+  let hash = root
+    .as_os_str()
+    .as_encoded_bytes()
+    .iter()
+    .fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+      (hash ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01b3)
+    });
+  let name = root.file_name().map_or_else(
+    || "project".to_string(),
+    |name| name.to_string_lossy().into_owned(),
+  );
+  format!("{name}-{:08x}", hash >> 32)
+}
+
 // Helper to deduplicate string vectors while preserving the first occurrence
 fn dedup_keep_first(items: &mut Vec<String>) {
   let mut seen = std::collections::BTreeSet::new();
@@ -164,6 +373,7 @@ fn dedup_keep_first(items: &mut Vec<String>) {
 pub struct AgencyPaths {
   root: PathBuf,
   cwd: PathBuf,
+  worktree_root: Option<PathBuf>,
 }
 
 impl AgencyPaths {
@@ -171,9 +381,21 @@ impl AgencyPaths {
     Self {
       root: root.into(),
       cwd: cwd.into(),
+      worktree_root: None,
     }
   }
 
+  /// Place worktrees under `worktree_root` (from the `worktree_root` config)
+  /// instead of `.agency/worktrees`. Relative paths resolve against the project root.
+  /// Each project gets its own subdirectory so a shared root cannot mix up
+  /// tasks of different projects.
+  #[must_use]
+  pub fn with_worktree_root(mut self, worktree_root: Option<&str>) -> Self {
+    self.worktree_root =
+      worktree_root.map(|dir| self.root.join(dir).join(project_dir_name(&self.root)));
+    self
+  }
+
   #[must_use]
   pub fn root(&self) -> &PathBuf {
     &self.root
@@ -191,7 +413,10 @@ impl AgencyPaths {
 
   #[must_use]
   pub fn worktrees_dir(&self) -> PathBuf {
-    self.root.join(".agency").join("worktrees")
+    match &self.worktree_root {
+      Some(dir) => dir.clone(),
+      None => self.root.join(".agency").join("worktrees"),
+    }
   }
 
   #[must_use]
@@ -224,6 +449,20 @@ pub struct BootstrapConfig {
   /// Empty means disabled. Defaults come from embedded `defaults/agency.toml`.
   #[serde(default)]
   pub cmd: Vec<String>,
+  /// Commands run in order, stopping at the first failure. Takes precedence over `cmd`.
+  #[serde(default)]
+  pub cmds: Vec<Vec<String>>,
+}
+
+impl BootstrapConfig {
+  /// Bootstrap commands to run in order: `cmds` when set, otherwise `cmd`.
+  #[must_use]
+  pub fn commands(&self) -> Vec<&[String]> {
+    if self.cmds.is_empty() {
+      return vec![self.cmd.as_slice()];
+    }
+    self.cmds.iter().map(Vec::as_slice).collect()
+  }
 }
 
 fn merge_values(base: &mut TomlValue, overlay: TomlValue, path: &str) {
@@ -272,7 +511,7 @@ fn warn_unknown_keys(val: &TomlValue, file_path: &Path) {
 
   for key in table.keys() {
     if !KNOWN_TOP_LEVEL_KEYS.contains(&key.as_str()) {
-      eprintln!(
+      anstream::eprintln!(
         "{}: unknown config key '{}' in {} (did you mean one of: {}?)",
         "warning".yellow(),
         key,
@@ -285,7 +524,7 @@ fn warn_unknown_keys(val: &TomlValue, file_path: &Path) {
   if let Some(TomlValue::Table(daemon)) = table.get("daemon") {
     for key in daemon.keys() {
       if !KNOWN_DAEMON_KEYS.contains(&key.as_str()) {
-        eprintln!(
+        anstream::eprintln!(
           "{}: unknown config key 'daemon.{}' in {} (known keys: {})",
           "warning".yellow(),
           key,
@@ -296,10 +535,52 @@ fn warn_unknown_keys(val: &TomlValue, file_path: &Path) {
     }
   }
 
+  if let Some(TomlValue::Table(tui)) = table.get("tui") {
+    for key in tui.keys() {
+      if !KNOWN_TUI_KEYS.contains(&key.as_str()) {
+        anstream::eprintln!(
+          "{}: unknown config key 'tui.{}' in {} (known keys: {})",
+          "warning".yellow(),
+          key,
+          file_path.display(),
+          KNOWN_TUI_KEYS.join(", ")
+        );
+      }
+    }
+  }
+
+  if let Some(TomlValue::Table(tasks)) = table.get("tasks") {
+    for key in tasks.keys() {
+      if !KNOWN_TASKS_KEYS.contains(&key.as_str()) {
+        anstream::eprintln!(
+          "{}: unknown config key 'tasks.{}' in {} (known keys: {})",
+          "warning".yellow(),
+          key,
+          file_path.display(),
+          KNOWN_TASKS_KEYS.join(", ")
+        );
+      }
+    }
+  }
+
+  if let Some(TomlValue::Table(tmux)) = table.get("tmux") {
+    for key in tmux.keys() {
+      if !KNOWN_TMUX_KEYS.contains(&key.as_str()) {
+        anstream::eprintln!(
+          "{}: unknown config key 'tmux.{}' in {} (known keys: {})",
+          "warning".yellow(),
+          key,
+          file_path.display(),
+          KNOWN_TMUX_KEYS.join(", ")
+        );
+      }
+    }
+  }
+
   if let Some(TomlValue::Table(bootstrap)) = table.get("bootstrap") {
     for key in bootstrap.keys() {
       if !KNOWN_BOOTSTRAP_KEYS.contains(&key.as_str()) {
-        eprintln!(
+        anstream::eprintln!(
           "{}: unknown config key 'bootstrap.{}' in {} (known keys: {})",
           "warning".yellow(),
           key,
@@ -317,7 +598,7 @@ fn warn_unknown_keys(val: &TomlValue, file_path: &Path) {
       };
       for key in agent_table.keys() {
         if !KNOWN_AGENT_KEYS.contains(&key.as_str()) {
-          eprintln!(
+          anstream::eprintln!(
             "{}: unknown config key 'agents.{}.{}' in {} (known keys: {})",
             "warning".yellow(),
             agent_name,
@@ -331,42 +612,219 @@ fn warn_unknown_keys(val: &TomlValue, file_path: &Path) {
   }
 }
 
+/// Config files that apply to `cwd`, lowest precedence first: the global XDG
+/// config, then the project `.agency/agency.toml`. Missing files are skipped.
+#[must_use]
+pub fn config_files(cwd: &Path) -> Vec<PathBuf> {
+  let mut files = Vec::new();
+  let xdg = xdg::BaseDirectories::with_prefix("agency");
+  if let Some(global_path) = xdg.find_config_file("agency.toml") {
+    files.push(global_path);
+  }
+  let project_cfg = cwd.join(".agency").join("agency.toml");
+  if project_cfg.exists() {
+    files.push(project_cfg);
+  }
+  files
+}
+
 /// Load and merge configuration from defaults, global, and project files.
 ///
 /// # Errors
 /// Returns an error if any of the config files cannot be read or parsed
 /// as valid TOML, or if serialization of the merged config fails.
 pub fn load_config(cwd: &Path) -> Result<AgencyConfig> {
+  let mut cfg = load_config_files(&config_files(cwd))?;
+  validate(&mut cfg)?;
+  Ok(cfg)
+}
+
+/// Merge `files` in order over the embedded defaults without validating values.
+/// The environment overrides listed in `ENV_OVERRIDES` are applied last.
+///
+/// # Errors
+/// Returns an error if a file cannot be read or parsed as valid TOML, an
+/// override variable is not a non-negative integer, or the merged config does
+/// not match the expected shape.
+pub fn load_config_files(files: &[PathBuf]) -> Result<AgencyConfig> {
   // Start with embedded defaults
   let mut merged: TomlValue =
     toml::from_str(DEFAULT_TOML).context("invalid embedded default config")?;
 
-  // Merge global XDG config if present
-  let xdg = xdg::BaseDirectories::with_prefix("agency");
-  if let Some(global_path) = xdg.find_config_file("agency.toml") {
-    let data = fs::read_to_string(&global_path)
-      .with_context(|| format!("failed to read {}", global_path.display()))?;
-    let val: TomlValue = toml::from_str(&data)
-      .with_context(|| format!("invalid TOML in {}", global_path.display()))?;
-    warn_unknown_keys(&val, &global_path);
-    merge_values(&mut merged, val, "");
-  }
-
-  // Merge project config if present
-  let project_cfg = cwd.join(".agency").join("agency.toml");
-  if project_cfg.exists() {
-    let data = fs::read_to_string(&project_cfg)
-      .with_context(|| format!("failed to read {}", project_cfg.display()))?;
-    let val: TomlValue = toml::from_str(&data)
-      .with_context(|| format!("invalid TOML in {}", project_cfg.display()))?;
-    warn_unknown_keys(&val, &project_cfg);
+  for path in files {
+    let val = read_config_file(path, &mut Vec::new())?;
     merge_values(&mut merged, val, "");
   }
+  merge_values(&mut merged, env_overrides()?, "");
 
   // Deserialize into strongly typed config
   let merged_str = toml::to_string(&merged).context("failed to serialize merged config")?;
-  let cfg: AgencyConfig = toml::from_str(&merged_str).context("failed to parse merged config")?;
-  Ok(cfg)
+  toml::from_str(&merged_str).context("failed to parse merged config")
+}
+
+/// Read `path` merged over the files it `extends`.
+///
+/// `extends` is a path or a list of paths relative to the file's directory,
+/// later entries overriding earlier ones. `chain` holds the files currently
+/// being read and is used to detect cycles.
+fn read_config_file(path: &Path, chain: &mut Vec<PathBuf>) -> Result<TomlValue> {
+  let data =
+    fs::read_to_string(path).with_context(|| format!("failed to read {}", path.display()))?;
+  let mut val: TomlValue =
+    toml::from_str(&data).with_context(|| format!("invalid TOML in {}", path.display()))?;
+  let extends = match val.as_table_mut().and_then(|table| table.remove("extends")) {
+    None => Vec::new(),
+    Some(TomlValue::String(target)) => vec![target],
+    Some(TomlValue::Array(items)) => items
+      .into_iter()
+      .map(|item| match item {
+        TomlValue::String(target) => Ok(target),
+        _ => bail!("invalid extends in {}: expected paths", path.display()),
+      })
+      .collect::<Result<Vec<_>>>()?,
+    Some(_) => bail!(
+      "invalid extends in {}: expected a path or a list of paths",
+      path.display()
+    ),
+  };
+  warn_unknown_keys(&val, path);
+
+  let canonical = fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+  if chain.contains(&canonical) {
+    let cycle: Vec<String> = chain
+      .iter()
+      .chain([&canonical])
+      .map(|file| file.display().to_string())
+      .collect();
+    bail!("config extends cycle: {}", cycle.join(" -> "));
+  }
+  let dir = path.parent().unwrap_or_else(|| Path::new("."));
+  let mut merged = TomlValue::Table(toml::Table::new());
+  chain.push(canonical);
+  for target in extends {
+    let target_path = dir.join(&target);
+    if !target_path.is_file() {
+      bail!(
+        "{} extends {}, which does not exist",
+        path.display(),
+        target_path.display()
+      );
+    }
+    merge_values(&mut merged, read_config_file(&target_path, chain)?, "");
+  }
+  chain.pop();
+  merge_values(&mut merged, val, "");
+  Ok(merged)
+}
+
+/// Config overlay built from the `ENV_OVERRIDES` variables that are set.
+fn env_overrides() -> Result<TomlValue> {
+  let mut overlay = toml::Table::new();
+  for (var, section, key) in ENV_OVERRIDES {
+    let Ok(raw) = std::env::var(var) else {
+      continue;
+    };
+    let Some(value) = raw.trim().parse::<i64>().ok().filter(|value| *value >= 0) else {
+      bail!("invalid {var} '{raw}': expected a non-negative integer");
+    };
+    let target = if section.is_empty() {
+      &mut overlay
+    } else {
+      let entry = overlay
+        .entry(*section)
+        .or_insert_with(|| TomlValue::Table(toml::Table::new()));
+      let Some(table) = entry.as_table_mut() else {
+        continue;
+      };
+      table
+    };
+    target.insert((*key).to_string(), TomlValue::Integer(value));
+  }
+  Ok(TomlValue::Table(overlay))
+}
+
+/// List problems in a merged config that would break sessions later on:
+/// an unknown default agent, agents with an empty `cmd`, empty bootstrap
+/// commands, and unparseable `daemon.detach_keys`.
+#[must_use]
+pub fn config_problems(cfg: &AgencyConfig) -> Vec<String> {
+  let mut problems = Vec::new();
+  if let Some(agent) = cfg.agent.as_deref()
+    && !cfg.agents.contains_key(agent)
+  {
+    let known: Vec<&str> = cfg.agents.keys().map(String::as_str).collect();
+    problems.push(format!(
+      "agent '{agent}' is not defined (known agents: {})",
+      known.join(", ")
+    ));
+  }
+  for (name, agent) in &cfg.agents {
+    if agent.cmd.is_empty() && agent.transcript_file.is_none() {
+      problems.push(format!("agents.{name}.cmd is empty"));
+    }
+  }
+  if let Some(bootstrap) = cfg.bootstrap.as_ref() {
+    for (index, cmd) in bootstrap.cmds.iter().enumerate() {
+      if cmd.is_empty() {
+        problems.push(format!("bootstrap.cmds[{index}] is empty"));
+      }
+    }
+  }
+  if let Some(spec) = cfg
+    .daemon
+    .as_ref()
+    .and_then(|daemon| daemon.detach_keys.as_deref())
+    && let Err(err) = parse_detach_keys(spec)
+  {
+    problems.push(format!("invalid daemon.detach_keys '{spec}': {err}"));
+  }
+  problems
+}
+
+/// Check values that serde cannot and store their parsed forms.
+fn validate(cfg: &mut AgencyConfig) -> Result<()> {
+  if let Some(daemon) = cfg.daemon.as_mut()
+    && let Some(spec) = daemon.detach_keys.as_deref()
+  {
+    daemon.detach_sequence =
+      parse_detach_keys(spec).with_context(|| format!("invalid daemon.detach_keys '{spec}'"))?;
+  }
+  Ok(())
+}
+
+/// Parse a space-separated detach key spec like `ctrl-q` or `ctrl-p ctrl-q`
+/// into the bytes a terminal sends for it.
+///
+/// Each key is `ctrl-` followed by a letter or one of `[ \ ] ^ _`.
+///
+/// # Errors
+/// Returns an error if the spec is empty or contains a key that is not a control key.
+pub fn parse_detach_keys(spec: &str) -> Result<Vec<u8>> {
+  let keys: Vec<&str> = spec.split_whitespace().collect();
+  if keys.is_empty() {
+    anyhow::bail!("expected at least one key");
+  }
+  keys
+    .into_iter()
+    .map(|key| {
+      let lower = key.to_ascii_lowercase();
+      let Some(rest) = lower.strip_prefix("ctrl-") else {
+        anyhow::bail!("'{key}' is not a ctrl- key");
+      };
+      match rest.as_bytes() {
+        [letter @ b'a'..=b'z'] => Ok(letter - b'a' + 1),
+        [symbol @ (b'[' | b'\\' | b']' | b'^' | b'_')] => Ok(symbol - b'@'),
+        _ => anyhow::bail!("'{key}' is not a supported key"),
+      }
+    })
+    .collect()
+}
+
+/// Compute the daemon log file path. It lives next to the daemon socket so
+/// each socket gets its own log.
+#[must_use]
+pub fn compute_log_path(cfg: &AgencyConfig) -> PathBuf {
+  compute_socket_path(cfg).with_extension("logs.jsonl")
 }
 
 /// Compute the daemon socket path based on config and environment.
@@ -433,6 +891,64 @@ mod tests {
   use super::*;
   use temp_env::with_vars;
 
+  #[test]
+  fn shared_worktree_root_separates_projects() {
+    let first = AgencyPaths::new("/work/api", "/work/api").with_worktree_root(Some("/tmp/wt"));
+    let second = AgencyPaths::new("/other/api", "/other/api").with_worktree_root(Some("/tmp/wt"));
+    let first_dir = first.worktrees_dir();
+    assert!(first_dir.starts_with("/tmp/wt"));
+    assert!(
+      first_dir
+        .file_name()
+        .is_some_and(|name| name.to_string_lossy().starts_with("api-"))
+    );
+    assert_ne!(first_dir, second.worktrees_dir());
+  }
+
+  #[test]
+  fn parse_detach_keys_single_key() {
+    assert_eq!(parse_detach_keys("ctrl-q").expect("valid spec"), vec![0x11]);
+  }
+
+  #[test]
+  fn parse_detach_keys_two_key_sequence() {
+    assert_eq!(
+      parse_detach_keys("ctrl-p Ctrl-Q").expect("valid spec"),
+      vec![0x10, 0x11]
+    );
+    assert_eq!(parse_detach_keys("ctrl-]").expect("valid spec"), vec![0x1d]);
+  }
+
+  #[test]
+  fn parse_detach_keys_rejects_invalid_spec() {
+    assert!(parse_detach_keys("").is_err());
+    assert!(parse_detach_keys("q").is_err());
+    assert!(parse_detach_keys("ctrl-p alt-q").is_err());
+    assert!(parse_detach_keys("ctrl-qq").is_err());
+  }
+
+  #[test]
+  fn open_argv_prefers_agent_then_global_then_editor() {
+    let argv = |items: &[&str]| items.iter().map(ToString::to_string).collect::<Vec<_>>();
+    let mut cfg = AgencyConfig {
+      editor: Some(argv(&["nvim"])),
+      ..AgencyConfig::default()
+    };
+    assert_eq!(cfg.open_argv(Some("claude")), argv(&["nvim"]));
+
+    cfg.open_cmd = Some(argv(&["zed"]));
+    cfg.agents.insert(
+      "claude".to_string(),
+      AgentConfig {
+        open_cmd: Some(argv(&["code", "-n"])),
+        ..AgentConfig::default()
+      },
+    );
+    assert_eq!(cfg.open_argv(Some("claude")), argv(&["code", "-n"]));
+    assert_eq!(cfg.open_argv(Some("codex")), argv(&["zed"]));
+    assert_eq!(cfg.open_argv(None), argv(&["zed"]));
+  }
+
   #[test]
   fn compute_prefers_env_over_config_and_xdg() {
     let env_dir = tempfile::tempdir().expect("temp dir env");
@@ -454,6 +970,13 @@ mod tests {
           daemon: Some(DaemonConfig {
             socket_path: Some(cfg_sock.display().to_string()),
             tmux_socket_path: None,
+            scrollback_lines: None,
+            record_output: false,
+            record_max_bytes: None,
+            dwell_secs: None,
+            detach_keys: None,
+            session_reap_secs: None,
+            detach_sequence: Vec::new(),
           }),
           ..Default::default()
         };
@@ -483,6 +1006,13 @@ mod tests {
           daemon: Some(DaemonConfig {
             socket_path: Some(cfg_sock.display().to_string()),
             tmux_socket_path: None,
+            scrollback_lines: None,
+            record_output: false,
+            record_max_bytes: None,
+            dwell_secs: None,
+            detach_keys: None,
+            session_reap_secs: None,
+            detach_sequence: Vec::new(),
           }),
           ..Default::default()
         };
@@ -493,6 +1023,80 @@ mod tests {
     );
   }
 
+  #[test]
+  fn compute_reads_socket_path_from_config_file() {
+    let xdg_dir = tempfile::tempdir().expect("temp dir xdg");
+    let cfg_dir = tempfile::tempdir().expect("temp dir cfg");
+    let cfg_sock = cfg_dir.path().join("run").join("file.sock");
+    let cfg_file = cfg_dir.path().join("agency.toml");
+    fs::write(
+      &cfg_file,
+      format!("[daemon]\nsocket_path = \"{}\"\n", cfg_sock.display()),
+    )
+    .expect("write config");
+    let cfg = load_config_files(&[cfg_file]).expect("load config");
+
+    with_vars(
+      [
+        ("AGENCY_SOCKET_PATH", None),
+        (
+          "XDG_RUNTIME_DIR",
+          Some(xdg_dir.path().display().to_string()),
+        ),
+      ],
+      || {
+        let path = compute_socket_path(&cfg);
+        assert_eq!(path, cfg_sock);
+        assert!(path.parent().unwrap().is_dir());
+      },
+    );
+  }
+
+  #[test]
+  fn extends_merges_shared_config_below_the_project_file() {
+    let dir = tempfile::tempdir().expect("temp dir");
+    let shared = dir.path().join("shared");
+    let project = dir.path().join("app").join(".agency");
+    fs::create_dir_all(&shared).expect("mkdir shared");
+    fs::create_dir_all(&project).expect("mkdir project");
+    fs::write(
+      shared.join("agency.toml"),
+      "agent = \"team\"\n[agents.team]\ncmd = [\"team-agent\"]\n[agents.review]\ncmd = [\"shared-review\"]\n",
+    )
+    .expect("write shared");
+    let project_cfg = project.join("agency.toml");
+    fs::write(
+      &project_cfg,
+      "extends = \"../../shared/agency.toml\"\n[agents.review]\ncmd = [\"local-review\"]\n",
+    )
+    .expect("write project");
+
+    let cfg = load_config_files(&[project_cfg]).expect("load config");
+
+    assert_eq!(cfg.agent.as_deref(), Some("team"));
+    assert_eq!(cfg.get_agent("team").expect("team").cmd, ["team-agent"]);
+    assert_eq!(
+      cfg.get_agent("review").expect("review").cmd,
+      ["local-review"]
+    );
+    assert!(cfg.agents.contains_key("claude"), "defaults still apply");
+  }
+
+  #[test]
+  fn extends_rejects_cycles_and_missing_files() {
+    let dir = tempfile::tempdir().expect("temp dir");
+    let first = dir.path().join("a.toml");
+    fs::write(&first, "extends = [\"b.toml\"]\n").expect("write a");
+    fs::write(dir.path().join("b.toml"), "extends = \"a.toml\"\n").expect("write b");
+    let err = load_config_files(std::slice::from_ref(&first)).expect_err("cycle");
+    assert!(err.to_string().contains("extends cycle"), "{err:#}");
+
+    let missing = dir.path().join("c.toml");
+    fs::write(&missing, "extends = \"nope.toml\"\n").expect("write c");
+    let err = load_config_files(&[missing]).expect_err("missing");
+    assert!(err.to_string().contains("which does not exist"), "{err:#}");
+  }
+
   #[test]
   fn compute_uses_xdg_when_no_env_or_config() {
     let xdg_dir = tempfile::tempdir().expect("temp dir xdg");
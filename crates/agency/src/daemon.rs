@@ -1,23 +1,45 @@
-use crate::config::AgencyConfig;
+use crate::config::{AgencyConfig, AgencyPaths, compute_log_path};
 use crate::daemon_protocol::{
-  C2D, C2DControl, D2C, D2CControl, ProjectKey, SessionInfo, TaskInfo, TaskMeta, TaskMetrics,
-  read_frame, write_frame,
+  C2D, C2DControl, D2C, D2CControl, DaemonErrorCode, LogLevel, LogLine, ProjectKey, SessionInfo,
+  SessionSignal, TaskInfo, TaskMeta, TaskMetrics, read_frame, write_frame,
 };
-use crate::utils::git::{
-  commits_ahead_at, current_branch_name_at, git_workdir, uncommitted_numstat_at,
+use crate::utils::events::{
+  STATUS_COMPLETED, STATUS_DRAFT, STATUS_STOPPED, TaskEvent, append_events, events_path,
+  last_statuses, read_events,
+};
+use crate::utils::git::{default_base_branch, git_workdir};
+use crate::utils::git_backend::{GitBackend, GitCli};
+use crate::utils::sessions::latest_sessions_by_task;
+use crate::utils::status::{TaskStatus, derive_status};
+use crate::utils::task::{
+  TaskRef, list_tasks, read_task_frontmatter, resolve_id_or_slug, task_branch,
+  update_task_frontmatter, worktree_dir,
+};
+use crate::utils::time::now_ms;
+use crate::utils::tmux::{
+  count_sessions_and_clients, list_attachments_for_project, list_sessions_for_project as tmux_list,
+  reap_dead_sessions, reset_window_size, resize_window, set_session_paused, signal_session,
+  sweep_stale_activity_files,
 };
-use crate::utils::task::{TaskRef, branch_name, list_tasks, read_task_frontmatter, worktree_dir};
-use crate::utils::tmux::list_sessions_for_project as tmux_list;
 use anyhow::Result;
+use crossbeam_channel::{Sender, unbounded};
 use log::{error, info, warn};
 use parking_lot::Mutex;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::os::unix::fs::PermissionsExt;
 use std::os::unix::net::{UnixListener, UnixStream};
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant, SystemTime};
+
+/// Upper bound for delivering `Goodbye` to subscribers when the daemon stops.
+const SHUTDOWN_DRAIN_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Longest time the poller reuses a task's Git metrics while its worktree mtime
+/// is unchanged. Bounds staleness for in-place edits and commits, which do not
+/// touch the worktree directory itself.
+const MAX_METRICS_AGE: Duration = Duration::from_secs(10);
 
 pub fn run_daemon(socket_path: &Path, cfg: &AgencyConfig) -> Result<()> {
   info!("Starting daemon. Socket path: {}", socket_path.display());
@@ -42,6 +64,10 @@ pub struct SlimDaemon {
   socket_path: PathBuf,
   // Per-project TUI registry: id -> entry
   tui_registry: Arc<Mutex<HashMap<String, HashMap<u32, TuiEntry>>>>,
+  started_at: Instant,
+  recorder: StatusRecorder,
+  // Projects whose stale activity files were already swept
+  swept_projects: Mutex<HashSet<String>>,
 }
 
 struct Subscriber {
@@ -60,12 +86,43 @@ impl SlimDaemon {
       last_snapshot: Arc::new(Mutex::new(HashMap::new())),
       socket_path,
       tui_registry: Arc::new(Mutex::new(HashMap::new())),
+      started_at: Instant::now(),
+      recorder: StatusRecorder::spawn(),
+      swept_projects: Mutex::new(HashSet::new()),
     }
   }
 
   fn snapshot_for(&self, project: &ProjectKey) -> ProjectSnapshot {
-    let prev = self.last_snapshot.lock().get(&project.repo_root).cloned();
-    build_project_snapshot(&self.cfg, project, prev.as_ref())
+    self.sweep_once(project);
+    reap_sessions(&self.cfg, project);
+    let mut prev = self.last_snapshot.lock().get(&project.repo_root).cloned();
+    // Task changes (merge, rebase, ...) move branches without touching the worktree
+    if let Some(snap) = prev.as_mut() {
+      snap.metric_stamps.clear();
+    }
+    let snap = build_project_snapshot(&self.cfg, project, prev.as_ref());
+    self.recorder.record(project, &snap);
+    snap
+  }
+
+  /// Remove activity files of dead sessions the first time a project is seen,
+  /// so idle detection never reads stamps left over from before the daemon started.
+  fn sweep_once(&self, project: &ProjectKey) {
+    if !self.swept_projects.lock().insert(project.repo_root.clone()) {
+      return;
+    }
+    let removed = sweep_stale_activity_files(&self.cfg, Path::new(&project.repo_root));
+    if removed > 0 {
+      info!(
+        "Removed {removed} stale activity files in {}",
+        project.repo_root
+      );
+    }
+  }
+
+  fn refresh_project(&self, project: &ProjectKey) {
+    let snap = self.snapshot_for(project);
+    self.update_cache_and_broadcast(project, &snap);
   }
 
   fn update_cache_and_broadcast(&self, project: &ProjectKey, snap: &ProjectSnapshot) {
@@ -82,6 +139,7 @@ impl SlimDaemon {
     let cfg = self.cfg.clone();
     let cache = self.last_snapshot.clone();
     let registry = self.tui_registry.clone();
+    let recorder = self.recorder.clone();
     std::thread::Builder::new()
       .name("daemon-poller".to_string())
       .spawn(move || {
@@ -91,8 +149,10 @@ impl SlimDaemon {
 
           let targets: Vec<ProjectKey> = subs.lock().iter().map(|s| s.project.clone()).collect();
           for pk in targets {
+            reap_sessions(&cfg, &pk);
             let prev = cache.lock().get(&pk.repo_root).cloned();
             let new_snap = build_project_snapshot(&cfg, &pk, prev.as_ref());
+            recorder.record(&pk, &new_snap);
             let mut cache_guard = cache.lock();
             let changed = cache_guard.get(&pk.repo_root) != Some(&new_snap);
             if changed {
@@ -123,102 +183,199 @@ impl SlimDaemon {
       }
     }
 
-    // Cleanup socket on shutdown
-    let _ = fs::remove_file(&self.socket_path);
+    self.shutdown_graceful(SHUTDOWN_DRAIN_TIMEOUT);
     Ok(())
   }
 
+  /// Send `Goodbye` to every subscriber and close their streams, then remove the socket.
+  ///
+  /// Writes share a total budget of `timeout`, so a stuck client cannot hold up the
+  /// shutdown. Subscribers left over once it is spent are closed without a goodbye.
+  fn shutdown_graceful(&self, timeout: Duration) {
+    let deadline = Instant::now() + timeout;
+    let subscribers = std::mem::take(&mut *self.subscribers.lock());
+    info!("Shutting down, notifying {} subscribers", subscribers.len());
+    for mut sub in subscribers {
+      let remaining = deadline.saturating_duration_since(Instant::now());
+      if !remaining.is_zero() {
+        let _ = sub.stream.set_write_timeout(Some(remaining));
+        let _ = write_frame(&mut sub.stream, &D2C::Control(D2CControl::Goodbye));
+      }
+      let _ = sub.stream.shutdown(std::net::Shutdown::Both);
+    }
+    let _ = fs::remove_file(&self.socket_path);
+  }
+
   fn handle_connection(&self, stream: &mut UnixStream) {
     // Set stream to blocking mode for reliable reads (listener is non-blocking)
     let _ = stream.set_nonblocking(false);
     match read_frame::<_, C2D>(&mut *stream) {
-      Ok(C2D::Control(C2DControl::ListProjectState { project })) => {
-        self.write_project_state(stream, &project);
-      }
-      Ok(C2D::Control(C2DControl::GetVersion)) => {
-        Self::write_version(stream);
-      }
       Ok(C2D::Control(C2DControl::SubscribeEvents { project })) => {
         self.handle_subscribe(stream, &project);
       }
-      Ok(C2D::Control(C2DControl::TuiRegister { project, pid })) => {
-        self.handle_tui_register(stream, &project, pid);
+      Ok(C2D::Control(C2DControl::TuiFollow { project, tui_id })) => {
+        self.handle_tui_follow(stream, &project, tui_id);
       }
-      Ok(C2D::Control(C2DControl::TuiUnregister { project, pid })) => {
-        unregister_tui(&self.tui_registry, &project.repo_root, pid);
-        let _ = write_frame(&mut *stream, &D2C::Control(D2CControl::Ack { stopped: 0 }));
+      Ok(C2D::Control(C2DControl::Batch { requests })) => {
+        let replies = requests
+          .into_iter()
+          .map(|request| self.reply_for(request))
+          .collect();
+        let _ = write_frame(&mut *stream, &D2C::Control(D2CControl::Batch { replies }));
       }
-      Ok(C2D::Control(C2DControl::TuiList { project })) => {
-        let items = list_tuis(&self.tui_registry, &project.repo_root);
-        let _ = write_frame(&mut *stream, &D2C::Control(D2CControl::TuiList { items }));
+      Ok(C2D::Control(request)) => {
+        let reply = self.reply_for(request);
+        let _ = write_frame(&mut *stream, &D2C::Control(reply));
       }
-      Ok(C2D::Control(C2DControl::TuiFollow { project, tui_id })) => {
-        self.handle_tui_follow(stream, &project, tui_id);
+      Err(err) => {
+        let _ = write_frame(
+          stream,
+          &D2C::Control(error_reply(
+            DaemonErrorCode::InvalidRequest,
+            format!("Read error: {err}"),
+          )),
+        );
+      }
+    }
+  }
+
+  /// Handle a one-shot request and return its single reply. Streaming requests
+  /// and nested batches are rejected, so this also answers each `Batch` entry.
+  fn reply_for(&self, request: C2DControl) -> D2CControl {
+    match request {
+      C2DControl::ListProjectState {
+        project,
+        status_filter,
+        label_filter,
+      } => self.project_state_reply(
+        &project,
+        status_filter.as_deref().unwrap_or_default(),
+        label_filter.as_deref().unwrap_or_default(),
+      ),
+      C2DControl::GetVersion => D2CControl::Version {
+        version: crate::utils::version::get_version().to_string(),
+      },
+      C2DControl::TuiRegister { project, pid } => D2CControl::TuiRegistered {
+        tui_id: assign_tui_id(&self.tui_registry, &project.repo_root, pid),
+      },
+      C2DControl::TuiUnregister { project, pid } => {
+        unregister_tui(&self.tui_registry, &project.repo_root, pid);
+        D2CControl::Ack { stopped: 0 }
       }
-      Ok(C2D::Control(C2DControl::TuiFocusTaskChange {
+      C2DControl::TuiList { project } => D2CControl::TuiList {
+        items: list_tuis(&self.tui_registry, &project.repo_root),
+      },
+      C2DControl::TuiFocusTaskChange {
         project,
         tui_id,
         task_id,
-      })) => {
+      } => {
         update_tui_focus(&self.tui_registry, &project.repo_root, tui_id, task_id);
         broadcast_tui_focus(&self.subscribers, &project, tui_id, task_id);
-        let _ = write_frame(&mut *stream, &D2C::Control(D2CControl::Ack { stopped: 0 }));
+        D2CControl::Ack { stopped: 0 }
       }
-      Ok(C2D::Control(C2DControl::NotifyTasksChanged { project })) => {
-        let snap = self.snapshot_for(&project);
-        self.update_cache_and_broadcast(&project, &snap);
-        let _ = write_frame(&mut *stream, &D2C::Control(D2CControl::Ack { stopped: 0 }));
+      C2DControl::NotifyTasksChanged { project } => {
+        self.refresh_project(&project);
+        D2CControl::Ack { stopped: 0 }
       }
-      Ok(C2D::Control(C2DControl::StopSession { session_id })) => {
-        self.handle_stop_session(stream, session_id);
-      }
-      Ok(C2D::Control(C2DControl::StopTask {
+      C2DControl::StopSession { session_id } => self.stop_session(session_id),
+      C2DControl::StopTask {
         project,
         task_id,
         slug,
-      })) => {
-        self.handle_stop_task(stream, &project, task_id, &slug);
-      }
-      Ok(C2D::Control(C2DControl::Shutdown)) => {
+      } => self.stop_task(&project, task_id, &slug),
+      C2DControl::StopProject { project } => self.stop_project(&project),
+      C2DControl::Shutdown => {
         self
           .shutdown
           .store(true, std::sync::atomic::Ordering::Relaxed);
-        let _ = write_frame(&mut *stream, &D2C::Control(D2CControl::Goodbye));
-      }
-      Ok(C2D::Control(C2DControl::Ping { nonce })) => {
-        let _ = write_frame(&mut *stream, &D2C::Control(D2CControl::Pong { nonce }));
+        D2CControl::Goodbye
       }
-
-      Err(err) => {
-        let _ = write_frame(
-          stream,
-          &D2C::Control(D2CControl::Error {
-            message: format!("Read error: {err}"),
-          }),
-        );
+      C2DControl::Ping { nonce } => D2CControl::Pong { nonce },
+      C2DControl::Logs {
+        max_lines,
+        level_filter,
+      } => D2CControl::Logs {
+        lines: read_log_tail(&compute_log_path(&self.cfg), max_lines, level_filter),
+      },
+      C2DControl::Health => self.health_reply(),
+      C2DControl::ListAttachments { project } => {
+        match list_attachments_for_project(&self.cfg, Path::new(&project.repo_root)) {
+          Ok(items) => D2CControl::Attachments { items },
+          Err(err) => error_reply(DaemonErrorCode::Internal, format!("{err:#}")),
+        }
       }
+      C2DControl::ResizeAll {
+        project,
+        attachment_id,
+        rows,
+        cols,
+      } => self.resize_all(&project, &attachment_id, rows, cols),
+      C2DControl::SignalTask {
+        project,
+        task_id,
+        slug,
+        signal,
+      } => self.signal_task(&project, task_id, &slug, signal),
+      C2DControl::PauseTask {
+        project,
+        task_id,
+        slug,
+        paused,
+      } => self.pause_task(&project, task_id, &slug, paused),
+      C2DControl::UpdateTask {
+        project,
+        task,
+        agent,
+        base_branch,
+      } => self.update_task(&project, &task, agent, base_branch),
+      C2DControl::SubscribeEvents { .. }
+      | C2DControl::TuiFollow { .. }
+      | C2DControl::Batch { .. } => error_reply(
+        DaemonErrorCode::InvalidRequest,
+        "Request needs a dedicated connection",
+      ),
     }
   }
 
-  fn write_project_state(&self, stream: &mut UnixStream, project: &ProjectKey) {
-    let new_snap = self.snapshot_for(project);
-    let _ = write_frame(
-      &mut *stream,
-      &D2C::Control(D2CControl::ProjectState {
-        project: project.clone(),
-        tasks: new_snap.tasks,
-        sessions: new_snap.sessions,
-        metrics: new_snap.metrics,
-      }),
-    );
+  fn project_state_reply(
+    &self,
+    project: &ProjectKey,
+    status_filter: &[String],
+    label_filter: &[String],
+  ) -> D2CControl {
+    let statuses = match status_filter
+      .iter()
+      .map(|name| TaskStatus::parse(name))
+      .collect::<Result<Vec<_>>>()
+    {
+      Ok(statuses) => statuses,
+      Err(err) => return error_reply(DaemonErrorCode::InvalidRequest, format!("{err:#}")),
+    };
+    let mut new_snap = self.snapshot_for(project);
+    let root = Path::new(&project.repo_root);
+    let paths = AgencyPaths::new(root, root).with_worktree_root(self.cfg.worktree_root.as_deref());
+    filter_snapshot(&mut new_snap, &statuses, label_filter, |task| {
+      worktree_dir(&paths, &TaskRef::from(task)).exists()
+    });
+    D2CControl::ProjectState {
+      project: project.clone(),
+      tasks: new_snap.tasks,
+      sessions: new_snap.sessions,
+      metrics: new_snap.metrics,
+    }
   }
 
-  fn write_version(stream: &mut UnixStream) {
-    let ver = crate::utils::version::get_version().to_string();
-    let _ = write_frame(
-      &mut *stream,
-      &D2C::Control(D2CControl::Version { version: ver }),
-    );
+  fn health_reply(&self) -> D2CControl {
+    let (session_count, attached_client_count) = count_sessions_and_clients(&self.cfg);
+    let subscriber_count = u32::try_from(self.subscribers.lock().len()).unwrap_or(u32::MAX);
+    D2CControl::Health {
+      uptime_secs: self.started_at.elapsed().as_secs(),
+      pid: std::process::id(),
+      session_count,
+      attached_client_count,
+      subscriber_count,
+    }
   }
 
   fn handle_subscribe(&self, stream: &mut UnixStream, project: &ProjectKey) {
@@ -239,14 +396,6 @@ impl SlimDaemon {
     });
   }
 
-  fn handle_tui_register(&self, stream: &mut UnixStream, project: &ProjectKey, pid: u32) {
-    let tui_id = assign_tui_id(&self.tui_registry, &project.repo_root, pid);
-    let _ = write_frame(
-      &mut *stream,
-      &D2C::Control(D2CControl::TuiRegistered { tui_id }),
-    );
-  }
-
   fn handle_tui_follow(&self, stream: &mut UnixStream, project: &ProjectKey, tui_id: u32) {
     if let Some(entry) = get_tui(&self.tui_registry, &project.repo_root, tui_id) {
       let _ = write_frame(
@@ -272,7 +421,7 @@ impl SlimDaemon {
     }
   }
 
-  fn handle_stop_session(&self, stream: &mut UnixStream, session_id: u64) {
+  fn stop_session(&self, session_id: u64) -> D2CControl {
     let all_projects: Vec<ProjectKey> = self
       .subscribers
       .lock()
@@ -283,32 +432,194 @@ impl SlimDaemon {
     for pk in all_projects {
       let list = tmux_list(&self.cfg, Path::new(&pk.repo_root)).unwrap_or_default();
       if let Some(si) = list.iter().find(|s| s.session_id == session_id) {
-        let _ = crate::utils::tmux::kill_session(&self.cfg, &si.task);
+        let _ = crate::utils::tmux::kill_session(&self.cfg, Path::new(&pk.repo_root), &si.task);
         stopped = 1;
+        self.refresh_project(&pk);
         break;
       }
     }
-    let _ = write_frame(&mut *stream, &D2C::Control(D2CControl::Ack { stopped }));
+    D2CControl::Ack { stopped }
   }
 
-  fn handle_stop_task(
+  fn signal_task(
     &self,
-    stream: &mut UnixStream,
     project: &ProjectKey,
     task_id: u32,
     slug: &str,
-  ) {
+    signal: SessionSignal,
+  ) -> D2CControl {
+    let list = tmux_list(&self.cfg, Path::new(&project.repo_root)).unwrap_or_default();
+    let Some(session) = list
+      .into_iter()
+      .find(|si| si.task.id == task_id && si.task.slug == slug)
+    else {
+      return D2CControl::TaskNotRunning {
+        task_id,
+        slug: slug.to_string(),
+      };
+    };
+    match signal_session(&self.cfg, &session.task, signal_number(signal)) {
+      Ok(pid) => {
+        info!(
+          "Sent SIG{} to {task_id}-{slug} (pid {pid})",
+          signal.as_str()
+        );
+        D2CControl::Signaled { pid }
+      }
+      Err(err) => error_reply(DaemonErrorCode::Internal, format!("{err:#}")),
+    }
+  }
+
+  fn pause_task(&self, project: &ProjectKey, task_id: u32, slug: &str, paused: bool) -> D2CControl {
     let list = tmux_list(&self.cfg, Path::new(&project.repo_root)).unwrap_or_default();
+    let Some(session) = list
+      .into_iter()
+      .find(|si| si.task.id == task_id && si.task.slug == slug)
+    else {
+      return D2CControl::TaskNotRunning {
+        task_id,
+        slug: slug.to_string(),
+      };
+    };
+    match set_session_paused(&self.cfg, &session.task, paused) {
+      Ok(pid) => {
+        let action = if paused { "Paused" } else { "Resumed" };
+        info!("{action} {task_id}-{slug} (pid {pid})");
+        self.refresh_project(project);
+        D2CControl::Signaled { pid }
+      }
+      Err(err) => error_reply(DaemonErrorCode::Internal, format!("{err:#}")),
+    }
+  }
+
+  fn resize_all(
+    &self,
+    project: &ProjectKey,
+    attachment_id: &str,
+    rows: u16,
+    cols: u16,
+  ) -> D2CControl {
+    let attachments = match list_attachments_for_project(&self.cfg, Path::new(&project.repo_root)) {
+      Ok(items) => items,
+      Err(err) => return error_reply(DaemonErrorCode::Internal, format!("{err:#}")),
+    };
+    let Some(driver) = attachments
+      .iter()
+      .find(|item| item.attachment_id == attachment_id)
+    else {
+      return error_reply(
+        DaemonErrorCode::AttachmentNotFound,
+        format!("No attachment {attachment_id} in this project"),
+      );
+    };
+    let auto = rows == 0 && cols == 0;
+    let resized = if auto {
+      reset_window_size(&self.cfg, &driver.task)
+    } else {
+      resize_window(&self.cfg, &driver.task, cols, rows)
+    };
+    if let Err(err) = resized {
+      return error_reply(DaemonErrorCode::Internal, format!("{err:#}"));
+    }
+    let clients = attachments
+      .iter()
+      .filter(|item| item.session_id == driver.session_id)
+      .count();
+    if auto {
+      info!(
+        "Restored automatic size of session {} for {clients} client(s)",
+        driver.session_id
+      );
+    } else {
+      info!(
+        "Resized session {} to {cols}x{rows} for {clients} client(s)",
+        driver.session_id
+      );
+    }
+    D2CControl::Resized {
+      session_id: driver.session_id,
+      clients: u32::try_from(clients).unwrap_or(u32::MAX),
+    }
+  }
+
+  fn update_task(
+    &self,
+    project: &ProjectKey,
+    ident: &str,
+    agent: Option<String>,
+    base_branch: Option<String>,
+  ) -> D2CControl {
+    let paths = AgencyPaths::new(&project.repo_root, &project.repo_root);
+    let task = match resolve_id_or_slug(&paths, ident) {
+      Ok(task) => task,
+      Err(err) => return error_reply(DaemonErrorCode::TaskNotFound, format!("{err:#}")),
+    };
+    let frontmatter = match update_task_frontmatter(&paths, &task, agent, base_branch) {
+      Ok(frontmatter) => frontmatter,
+      Err(err) => return error_reply(DaemonErrorCode::Internal, format!("{err:#}")),
+    };
+    info!("Updated front matter of {}-{}", task.id, task.slug);
+    self.refresh_project(project);
+    D2CControl::TaskUpdated {
+      task: TaskInfo {
+        id: task.id,
+        slug: task.slug,
+        base_branch: frontmatter.base_branch,
+        labels: frontmatter.labels,
+      },
+    }
+  }
+
+  fn stop_project(&self, project: &ProjectKey) -> D2CControl {
+    let root = Path::new(&project.repo_root);
+    let list = tmux_list(&self.cfg, root).unwrap_or_default();
+    let mut stopped = 0usize;
+    for si in list {
+      if crate::utils::tmux::kill_session(&self.cfg, root, &si.task).is_ok() {
+        stopped += 1;
+      }
+    }
+    if stopped > 0 {
+      info!("Stopped {stopped} sessions in {}", project.repo_root);
+      self.refresh_project(project);
+    }
+    D2CControl::Ack { stopped }
+  }
+
+  fn stop_task(&self, project: &ProjectKey, task_id: u32, slug: &str) -> D2CControl {
+    let root = Path::new(&project.repo_root);
+    let list = tmux_list(&self.cfg, root).unwrap_or_default();
     let mut stopped = 0usize;
     for si in list {
       if si.task.id == task_id && si.task.slug == slug {
-        let _ = crate::utils::tmux::kill_session(&self.cfg, &si.task);
+        let _ = crate::utils::tmux::kill_session(&self.cfg, root, &si.task);
         stopped += 1;
       }
     }
-    let _ = write_frame(&mut *stream, &D2C::Control(D2CControl::Ack { stopped }));
+    if stopped == 0 {
+      return D2CControl::TaskNotRunning {
+        task_id,
+        slug: slug.to_string(),
+      };
+    }
+    self.refresh_project(project);
+    D2CControl::Ack { stopped }
   }
+}
 
+fn error_reply(code: DaemonErrorCode, message: impl Into<String>) -> D2CControl {
+  D2CControl::Error {
+    code,
+    message: message.into(),
+  }
+}
+
+fn signal_number(signal: SessionSignal) -> i32 {
+  match signal {
+    SessionSignal::Int => libc::SIGINT,
+    SessionSignal::Term => libc::SIGTERM,
+    SessionSignal::Kill => libc::SIGKILL,
+  }
 }
 
 pub fn ensure_socket_dir_and_bind(path: &Path) -> anyhow::Result<UnixListener> {
@@ -324,19 +635,255 @@ pub fn ensure_socket_dir_and_bind(path: &Path) -> anyhow::Result<UnixListener> {
   Ok(listener)
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+/// Turns session status changes seen in snapshots into entries of the project's
+/// `.agency/state/events.jsonl`. Appends happen on a background thread.
+#[derive(Clone)]
+struct StatusRecorder {
+  /// Last recorded status per project root and task
+  known: Arc<Mutex<HashMap<String, HashMap<TaskRef, String>>>>,
+  tx: Sender<(PathBuf, Vec<TaskEvent>)>,
+}
+
+impl StatusRecorder {
+  fn spawn() -> Self {
+    let (tx, rx) = unbounded::<(PathBuf, Vec<TaskEvent>)>();
+    let spawned = std::thread::Builder::new()
+      .name("daemon-events".to_string())
+      .spawn(move || {
+        for (path, events) in rx {
+          if let Err(err) = append_events(&path, &events) {
+            warn!("Failed to record task events: {err:#}");
+          }
+        }
+      });
+    if let Err(err) = spawned {
+      warn!("Failed to start event writer: {err}");
+    }
+    Self {
+      known: Arc::new(Mutex::new(HashMap::new())),
+      tx,
+    }
+  }
+
+  fn record(&self, project: &ProjectKey, snap: &ProjectSnapshot) {
+    let paths = AgencyPaths::new(&project.repo_root, &project.repo_root);
+    let path = events_path(&paths);
+    let mut known_by_project = self.known.lock();
+    let known = known_by_project
+      .entry(project.repo_root.clone())
+      .or_insert_with(|| last_statuses(&read_events(&path)));
+
+    let mut events = Vec::new();
+    for task in &snap.tasks {
+      let current = snap
+        .sessions
+        .iter()
+        .find(|session| session.task.id == task.id && session.task.slug == task.slug)
+        .map(|session| session.status.as_str());
+      let previous = known.get(&TaskRef::from(task)).map(String::as_str);
+      let next = match (current, previous) {
+        (Some(status), _) if previous != Some(status) => status,
+        (None, Some(status)) if status != STATUS_STOPPED && status != STATUS_COMPLETED => {
+          STATUS_STOPPED
+        }
+        _ => continue,
+      };
+      let from = previous.unwrap_or(STATUS_DRAFT);
+      events.push(TaskEvent::now(task.id, &task.slug, from, next));
+    }
+    for event in &events {
+      let task = TaskRef {
+        id: event.task_id,
+        slug: event.slug.clone(),
+      };
+      known.insert(task, event.to.clone());
+    }
+    known.retain(|known_task, _| {
+      snap
+        .tasks
+        .iter()
+        .any(|task| TaskRef::from(task) == *known_task)
+    });
+    if !events.is_empty() {
+      let _ = self.tx.send((path, events));
+    }
+  }
+}
+
+#[derive(Debug, Clone)]
 struct ProjectSnapshot {
   tasks: Vec<TaskInfo>,
   sessions: Vec<SessionInfo>,
   metrics: Vec<TaskMetrics>,
+  /// When each task's metrics were computed; poller bookkeeping, not published.
+  metric_stamps: HashMap<(u32, String), MetricsStamp>,
+}
+
+impl PartialEq for ProjectSnapshot {
+  fn eq(&self, other: &Self) -> bool {
+    self.tasks == other.tasks
+      && same_sessions(&self.sessions, &other.sessions)
+      && self.metrics == other.metrics
+  }
+}
+
+/// Compare sessions without `bytes_out`, which grows with every output chunk
+/// and would otherwise trigger a broadcast on each poll. Clients that need the
+/// current count get it from a fresh `ListProjectState`.
+fn same_sessions(left: &[SessionInfo], right: &[SessionInfo]) -> bool {
+  left.len() == right.len()
+    && left.iter().zip(right).all(|(left, right)| {
+      SessionInfo {
+        bytes_out: right.bytes_out,
+        ..left.clone()
+      } == *right
+    })
+}
+
+impl Eq for ProjectSnapshot {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct MetricsStamp {
+  worktree_mtime: Option<SystemTime>,
+  computed_at: Instant,
 }
 
-fn now_ms() -> u64 {
-  use std::time::{SystemTime, UNIX_EPOCH};
-  let dur = SystemTime::now()
-    .duration_since(UNIX_EPOCH)
-    .unwrap_or_else(|_| Duration::from_secs(0));
-  u64::try_from(dur.as_millis()).unwrap_or(u64::MAX)
+/// Where to read one task's metrics from.
+struct MetricsTarget<'a> {
+  task: TaskMeta,
+  worktree: &'a Path,
+  repo_root: &'a Path,
+  base: &'a str,
+  branch: &'a str,
+}
+
+/// Compute a task's metrics, reusing `prev` while the worktree mtime matches
+/// `prev_stamp` and the previous values are younger than [`MAX_METRICS_AGE`].
+fn compute_task_metrics(
+  git: &impl GitBackend,
+  target: MetricsTarget<'_>,
+  prev: Option<&TaskMetrics>,
+  prev_stamp: Option<&MetricsStamp>,
+) -> (TaskMetrics, MetricsStamp) {
+  let worktree_mtime = fs::metadata(target.worktree)
+    .and_then(|meta| meta.modified())
+    .ok();
+  if let Some(prev_metrics) = prev
+    && let Some(prev_stamp) = prev_stamp
+    && worktree_mtime.is_some()
+    && prev_stamp.worktree_mtime == worktree_mtime
+    && prev_stamp.computed_at.elapsed() < MAX_METRICS_AGE
+  {
+    return (prev_metrics.clone(), *prev_stamp);
+  }
+
+  let (add, del) = if worktree_mtime.is_some() {
+    git.numstat(target.worktree).unwrap_or((0, 0))
+  } else {
+    (0, 0)
+  };
+  let ahead = git
+    .commits_ahead(target.repo_root, target.base, target.branch)
+    .unwrap_or(0);
+  let updated_at_ms = match prev {
+    Some(prev_metrics)
+      if prev_metrics.uncommitted_add == add
+        && prev_metrics.uncommitted_del == del
+        && prev_metrics.commits_ahead == ahead =>
+    {
+      prev_metrics.updated_at_ms
+    }
+    _ => now_ms(),
+  };
+  let metrics = TaskMetrics {
+    task: target.task,
+    uncommitted_add: add,
+    uncommitted_del: del,
+    commits_ahead: ahead,
+    updated_at_ms,
+  };
+  let stamp = MetricsStamp {
+    worktree_mtime,
+    computed_at: Instant::now(),
+  };
+  (metrics, stamp)
+}
+
+/// Read the last `max_lines` parsed lines of the JSON log at `path`.
+///
+/// A missing file yields no lines. Lines that fail to parse (e.g. partially written)
+/// are skipped, and lines below `level_filter` are dropped.
+pub fn read_log_tail(
+  path: &Path,
+  max_lines: Option<usize>,
+  level_filter: Option<LogLevel>,
+) -> Vec<LogLine> {
+  let Ok(data) = fs::read_to_string(path) else {
+    return Vec::new();
+  };
+  let mut lines = parse_log_lines(&data, level_filter);
+  if let Some(max) = max_lines {
+    let skip = lines.len().saturating_sub(max);
+    lines.drain(..skip);
+  }
+  lines
+}
+
+/// Parse the JSON log lines in `data`, skipping unparseable ones and lines
+/// below `level_filter`.
+pub fn parse_log_lines(data: &str, level_filter: Option<LogLevel>) -> Vec<LogLine> {
+  data
+    .lines()
+    .filter_map(|line| serde_json::from_str::<LogLine>(line).ok())
+    .filter(|line| level_filter.is_none_or(|min| line.level >= min))
+    .collect()
+}
+
+/// Keep the tasks whose derived status is one of `statuses` and that carry
+/// every label in `labels`, along with their sessions and metrics. Empty
+/// filters keep everything.
+fn filter_snapshot(
+  snap: &mut ProjectSnapshot,
+  statuses: &[TaskStatus],
+  labels: &[String],
+  worktree_exists: impl Fn(&TaskInfo) -> bool,
+) {
+  if statuses.is_empty() && labels.is_empty() {
+    return;
+  }
+  let latest = latest_sessions_by_task(&snap.sessions);
+  snap.tasks.retain(|task| {
+    let status = derive_status(latest.get(&TaskRef::from(task)), worktree_exists(task));
+    (statuses.is_empty() || statuses.contains(&status))
+      && labels
+        .iter()
+        .all(|label| task.labels.iter().any(|own| own == label.trim()))
+  });
+  let kept = |meta: &TaskMeta| {
+    snap
+      .tasks
+      .iter()
+      .any(|task| task.id == meta.id && task.slug == meta.slug)
+  };
+  snap.sessions.retain(|session| kept(&session.task));
+  snap.metrics.retain(|metric| kept(&metric.task));
+}
+
+/// Kill sessions whose pane has been dead longer than `daemon.session_reap_secs`.
+fn reap_sessions(cfg: &AgencyConfig, project: &ProjectKey) {
+  let Some(secs) = cfg
+    .daemon
+    .as_ref()
+    .and_then(|daemon| daemon.session_reap_secs)
+    .filter(|secs| *secs > 0)
+  else {
+    return;
+  };
+  let root = Path::new(&project.repo_root);
+  let reaped = reap_dead_sessions(cfg, root, Duration::from_secs(secs));
+  if reaped > 0 {
+    info!("Reaped {reaped} dead sessions in {}", project.repo_root);
+  }
 }
 
 fn build_project_snapshot(
@@ -349,82 +896,52 @@ fn build_project_snapshot(
   let sessions = tmux_list(cfg, root).unwrap_or_default();
 
   // Task index
-  let paths = crate::config::AgencyPaths::new(root, root);
+  let paths = AgencyPaths::new(root, root).with_worktree_root(cfg.worktree_root.as_deref());
   let task_refs = list_tasks(&paths).unwrap_or_default();
 
-  // Determine base branch default from repo HEAD
+  // Determine base branch default from the repo's default branch
   let repo_root = git_workdir(root).unwrap_or_else(|_| root.to_path_buf());
-  let head = current_branch_name_at(&repo_root)
-    .unwrap_or(Some("main".to_string()))
-    .unwrap_or("main".to_string());
+  let head = default_base_branch(cfg, &repo_root);
 
   let mut tasks_info: Vec<TaskInfo> = Vec::new();
   for tref in &task_refs {
-    let fm = read_task_frontmatter(&paths, tref);
+    let fm = read_task_frontmatter(&paths, tref).unwrap_or_default();
     tasks_info.push(TaskInfo {
       id: tref.id,
       slug: tref.slug.clone(),
-      base_branch: fm.and_then(|f| f.base_branch),
+      base_branch: fm.base_branch,
+      labels: fm.labels,
     });
   }
 
   // Determine candidate tasks for metrics: tasks with active sessions
-  let candidates: std::collections::HashSet<(u32, String)> = sessions
+  let candidates: HashSet<(u32, String)> = sessions
     .iter()
-    .filter(|s| s.status == "Running" || s.status == "Idle" || s.status == "Exited")
+    .filter(|s| {
+      matches!(
+        s.status.as_str(),
+        "Running" | "Idle" | "Paused" | "Exited" | "Failed"
+      )
+    })
     .map(|s| (s.task.id, s.task.slug.clone()))
     .collect();
 
-  // Compute metrics
-  let mut metrics: Vec<TaskMetrics> = Vec::new();
-  for (id, slug) in candidates {
-    let tref = TaskRef {
-      id,
-      slug: slug.clone(),
-    };
-    let task = TaskMeta {
-      id,
-      slug: slug.clone(),
-    };
-    let wt = worktree_dir(&paths, &tref);
-    let (add, del) = if wt.exists() {
-      uncommitted_numstat_at(&wt).unwrap_or((0, 0))
-    } else {
-      (0, 0)
-    };
-    // Resolve base for this task
-    let base = tasks_info
-      .iter()
-      .find(|ti| ti.id == id && ti.slug == slug)
-      .and_then(|ti| ti.base_branch.clone())
-      .unwrap_or_else(|| head.clone());
-    let branch = branch_name(&tref);
-    let ahead = commits_ahead_at(&repo_root, &base, &branch).unwrap_or(0);
-    let mut updated_at_ms = now_ms();
-    if let Some(prev_snap) = prev
-      && let Some(prev_m) = prev_snap
-        .metrics
-        .iter()
-        .find(|m| m.task.id == id && m.task.slug == slug)
-      && prev_m.uncommitted_add == add
-      && prev_m.uncommitted_del == del
-      && prev_m.commits_ahead == ahead
-    {
-      updated_at_ms = prev_m.updated_at_ms;
-    }
-    metrics.push(TaskMetrics {
-      task,
-      uncommitted_add: add,
-      uncommitted_del: del,
-      commits_ahead: ahead,
-      updated_at_ms,
-    });
-  }
+  let (mut metrics, metric_stamps) = collect_metrics(
+    &GitCli,
+    &MetricsScope {
+      paths: &paths,
+      repo_root: &repo_root,
+      head: &head,
+      tasks: &tasks_info,
+    },
+    candidates,
+    prev,
+  );
 
   // Sort for stable equality
   tasks_info.sort_by(|a, b| a.id.cmp(&b.id).then_with(|| a.slug.cmp(&b.slug)));
   let mut sessions_sorted = sessions.clone();
-  sessions_sorted.sort_by(|a, b| a.session_id.cmp(&b.session_id));
+  sessions_sorted.sort_by_key(|a| a.session_id);
   metrics.sort_by(|a, b| {
     a.task
       .id
@@ -436,7 +953,63 @@ fn build_project_snapshot(
     tasks: tasks_info,
     sessions: sessions_sorted,
     metrics,
+    metric_stamps,
+  }
+}
+
+/// Where a project's task metrics are read from.
+struct MetricsScope<'a> {
+  paths: &'a AgencyPaths,
+  repo_root: &'a Path,
+  /// Base branch for tasks that don't set their own.
+  head: &'a str,
+  tasks: &'a [TaskInfo],
+}
+
+/// Compute metrics for the `candidates` tasks, reusing values from `prev`
+/// where [`compute_task_metrics`] allows it.
+fn collect_metrics(
+  git: &impl GitBackend,
+  scope: &MetricsScope<'_>,
+  candidates: HashSet<(u32, String)>,
+  prev: Option<&ProjectSnapshot>,
+) -> (Vec<TaskMetrics>, HashMap<(u32, String), MetricsStamp>) {
+  let mut metrics: Vec<TaskMetrics> = Vec::new();
+  let mut metric_stamps = HashMap::new();
+  for (id, slug) in candidates {
+    let tref = TaskRef {
+      id,
+      slug: slug.clone(),
+    };
+    let wt = worktree_dir(scope.paths, &tref);
+    // Resolve base for this task
+    let base = scope
+      .tasks
+      .iter()
+      .find(|ti| ti.id == id && ti.slug == slug)
+      .and_then(|ti| ti.base_branch.clone())
+      .unwrap_or_else(|| scope.head.to_string());
+    let branch = task_branch(scope.paths, &tref);
+    let key = (id, slug.clone());
+    let prev_m = prev.and_then(|prev_snap| {
+      prev_snap
+        .metrics
+        .iter()
+        .find(|m| m.task.id == id && m.task.slug == slug)
+    });
+    let prev_stamp = prev.and_then(|prev_snap| prev_snap.metric_stamps.get(&key));
+    let target = MetricsTarget {
+      task: TaskMeta { id, slug },
+      worktree: &wt,
+      repo_root: scope.repo_root,
+      base: &base,
+      branch: &branch,
+    };
+    let (task_metrics, stamp) = compute_task_metrics(git, target, prev_m, prev_stamp);
+    metrics.push(task_metrics);
+    metric_stamps.insert(key, stamp);
   }
+  (metrics, metric_stamps)
 }
 
 // Helper for the poller: broadcast snapshot to all subscribers of a project.
@@ -553,7 +1126,7 @@ fn list_tuis(
         focused_task_id: e.focused_task_id,
       });
     }
-    out.sort_by(|a, b| a.tui_id.cmp(&b.tui_id));
+    out.sort_by_key(|a| a.tui_id);
   }
   out
 }
@@ -610,6 +1183,35 @@ fn broadcast_tui_focus(
 mod tests {
   use super::*;
 
+  #[test]
+  fn read_log_tail_filters_levels_and_skips_partial_lines() {
+    let dir = tempfile::tempdir().expect("tmp");
+    let path = dir.path().join("agency.logs.jsonl");
+    assert!(read_log_tail(&path, None, None).is_empty());
+
+    let body = concat!(
+      r#"{"ts":"t1","level":"INFO","target":"agency","message":"one"}"#,
+      "\n",
+      r#"{"ts":"t2","level":"WARN","target":"agency","message":"two"}"#,
+      "\n",
+      r#"{"ts":"t3","level":"ERROR","target":"agency","message":"three"}"#,
+      "\n",
+      r#"{"ts":"t4","level":"ERR"#,
+    );
+    fs::write(&path, body).expect("write log");
+
+    let all = read_log_tail(&path, None, None);
+    assert_eq!(all.len(), 3);
+
+    let last = read_log_tail(&path, Some(1), None);
+    assert_eq!(last.len(), 1);
+    assert_eq!(last[0].message, "three");
+
+    let warn = read_log_tail(&path, None, Some(LogLevel::Warn));
+    let messages: Vec<&str> = warn.iter().map(|line| line.message.as_str()).collect();
+    assert_eq!(messages, vec!["two", "three"]);
+  }
+
   #[test]
   fn assign_and_reuse_ids_and_list_sorting() {
     let reg: Arc<Mutex<HashMap<String, HashMap<u32, TuiEntry>>>> =
@@ -701,4 +1303,185 @@ mod tests {
       other @ D2C::Control(_) => panic!("unexpected: {other:?}"),
     }
   }
+
+  /// Fake repository where each branch is a fixed number of commits ahead of
+  /// the base it is compared with and every worktree has the same diff. Counts
+  /// the metric calls; the other operations succeed without doing anything.
+  #[derive(Default)]
+  struct FakeGit {
+    ahead: HashMap<(String, String), u64>,
+    numstat: (u64, u64),
+    numstat_calls: std::cell::Cell<u32>,
+    ahead_calls: std::cell::Cell<u32>,
+  }
+
+  impl FakeGit {
+    fn new(ahead: &[(&str, &str, u64)], numstat: (u64, u64)) -> Self {
+      Self {
+        ahead: ahead
+          .iter()
+          .map(|(base, branch, count)| ((base.to_string(), branch.to_string()), *count))
+          .collect(),
+        numstat,
+        ..Self::default()
+      }
+    }
+  }
+
+  impl GitBackend for FakeGit {
+    fn current_branch(&self, _cwd: &Path) -> Result<Option<String>> {
+      Ok(Some("main".to_string()))
+    }
+
+    fn commits_ahead(&self, _repo_root: &Path, base: &str, branch: &str) -> Result<u64> {
+      self.ahead_calls.set(self.ahead_calls.get() + 1);
+      self
+        .ahead
+        .get(&(base.to_string(), branch.to_string()))
+        .copied()
+        .ok_or_else(|| anyhow::anyhow!("unknown range {base}..{branch}"))
+    }
+
+    fn numstat(&self, _workdir: &Path) -> Result<(u64, u64)> {
+      self.numstat_calls.set(self.numstat_calls.get() + 1);
+      Ok(self.numstat)
+    }
+
+    fn is_fast_forward(&self, _cwd: &Path, _base: &str, _branch: &str) -> Result<bool> {
+      Ok(true)
+    }
+
+    fn reset(&self, _cwd: &Path, _rev: &str) -> Result<()> {
+      Ok(())
+    }
+
+    fn stash(&self, _workdir: &Path, _message: &str) -> Result<Option<String>> {
+      Ok(None)
+    }
+  }
+
+  fn metrics_target(worktree: &Path) -> MetricsTarget<'_> {
+    MetricsTarget {
+      task: TaskMeta {
+        id: 1,
+        slug: "alpha".to_string(),
+      },
+      worktree,
+      repo_root: worktree,
+      base: "main",
+      branch: "agency/1-alpha",
+    }
+  }
+
+  #[test]
+  fn task_metrics_reuse_previous_values_until_worktree_changes() {
+    let dir = tempfile::tempdir().expect("tmp");
+    let source = FakeGit::new(&[("main", "agency/1-alpha", 2)], (3, 1));
+
+    let (first, stamp) = compute_task_metrics(&source, metrics_target(dir.path()), None, None);
+    assert_eq!((first.uncommitted_add, first.commits_ahead), (3, 2));
+    assert_eq!(source.numstat_calls.get(), 1);
+
+    let (second, second_stamp) = compute_task_metrics(
+      &source,
+      metrics_target(dir.path()),
+      Some(&first),
+      Some(&stamp),
+    );
+    assert_eq!(second, first);
+    assert_eq!(second_stamp, stamp);
+    assert_eq!(source.numstat_calls.get(), 1);
+    assert_eq!(source.ahead_calls.get(), 1);
+
+    // Stale values are recomputed even without a worktree change
+    let old_stamp = MetricsStamp {
+      computed_at: Instant::now() - MAX_METRICS_AGE,
+      ..stamp
+    };
+    compute_task_metrics(
+      &source,
+      metrics_target(dir.path()),
+      Some(&first),
+      Some(&old_stamp),
+    );
+    assert_eq!(source.numstat_calls.get(), 2);
+
+    let changed_stamp = MetricsStamp {
+      worktree_mtime: Some(SystemTime::UNIX_EPOCH),
+      ..stamp
+    };
+    let (third, _) = compute_task_metrics(
+      &source,
+      metrics_target(dir.path()),
+      Some(&first),
+      Some(&changed_stamp),
+    );
+    assert_eq!(source.numstat_calls.get(), 3);
+    assert_eq!(third.updated_at_ms, first.updated_at_ms);
+  }
+
+  #[test]
+  fn collect_metrics_uses_task_base_and_defaults_on_git_errors() {
+    let dir = tempfile::tempdir().expect("tmp");
+    let paths = AgencyPaths::new(dir.path(), dir.path());
+    fs::create_dir_all(paths.worktrees_dir().join("1-alpha")).expect("mkdir worktree");
+    let tasks = [
+      TaskInfo {
+        id: 1,
+        slug: "alpha".to_string(),
+        base_branch: Some("develop".to_string()),
+        labels: Vec::new(),
+      },
+      TaskInfo {
+        id: 2,
+        slug: "beta".to_string(),
+        base_branch: None,
+        labels: Vec::new(),
+      },
+    ];
+    let git = FakeGit::new(&[("develop", "agency/1-alpha", 4)], (5, 2));
+    let scope = MetricsScope {
+      paths: &paths,
+      repo_root: dir.path(),
+      head: "main",
+      tasks: &tasks,
+    };
+    let candidates = HashSet::from([(1, "alpha".to_string()), (2, "beta".to_string())]);
+
+    let (mut metrics, stamps) = collect_metrics(&git, &scope, candidates, None);
+    metrics.sort_by_key(|metric| metric.task.id);
+
+    let values: Vec<_> = metrics
+      .iter()
+      .map(|metric| {
+        (
+          metric.task.id,
+          metric.uncommitted_add,
+          metric.uncommitted_del,
+          metric.commits_ahead,
+        )
+      })
+      .collect();
+    // Task 2 has no worktree and its range against `main` is unknown
+    assert_eq!(values, vec![(1, 5, 2, 4), (2, 0, 0, 0)]);
+    assert_eq!(stamps.len(), 2);
+  }
+
+  #[test]
+  fn snapshot_equality_ignores_output_counter() {
+    let snapshot = |bytes_out, status: &str| ProjectSnapshot {
+      tasks: Vec::new(),
+      sessions: vec![SessionInfo {
+        session_id: 1,
+        status: status.to_string(),
+        bytes_out,
+        ..SessionInfo::default()
+      }],
+      metrics: Vec::new(),
+      metric_stamps: HashMap::new(),
+    };
+
+    assert_eq!(snapshot(10, "Running"), snapshot(4096, "Running"));
+    assert_ne!(snapshot(10, "Running"), snapshot(10, "Idle"));
+  }
 }
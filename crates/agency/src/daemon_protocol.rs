@@ -3,6 +3,96 @@ use bincode::{Decode, Encode};
 use serde::{Deserialize, Serialize};
 use std::io::{Read, Write};
 
+/// Severity of a daemon log line, ordered from least to most severe
+#[derive(
+  Debug,
+  Clone,
+  Copy,
+  PartialEq,
+  Eq,
+  PartialOrd,
+  Ord,
+  Serialize,
+  Deserialize,
+  Encode,
+  Decode,
+  clap::ValueEnum,
+)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum LogLevel {
+  Trace,
+  Debug,
+  Info,
+  Warn,
+  Error,
+}
+
+impl LogLevel {
+  #[must_use]
+  pub fn as_str(self) -> &'static str {
+    match self {
+      LogLevel::Trace => "TRACE",
+      LogLevel::Debug => "DEBUG",
+      LogLevel::Info => "INFO",
+      LogLevel::Warn => "WARN",
+      LogLevel::Error => "ERROR",
+    }
+  }
+}
+
+impl From<log::Level> for LogLevel {
+  fn from(level: log::Level) -> Self {
+    match level {
+      log::Level::Trace => LogLevel::Trace,
+      log::Level::Debug => LogLevel::Debug,
+      log::Level::Info => LogLevel::Info,
+      log::Level::Warn => LogLevel::Warn,
+      log::Level::Error => LogLevel::Error,
+    }
+  }
+}
+
+/// Signal that can be forwarded to the process running in a task session
+#[derive(
+  Debug,
+  Clone,
+  Copy,
+  PartialEq,
+  Eq,
+  Serialize,
+  Deserialize,
+  Encode,
+  Decode,
+  clap::ValueEnum,
+)]
+#[serde(rename_all = "UPPERCASE")]
+#[value(rename_all = "UPPER")]
+pub enum SessionSignal {
+  Int,
+  Term,
+  Kill,
+}
+
+impl SessionSignal {
+  #[must_use]
+  pub fn as_str(self) -> &'static str {
+    match self {
+      SessionSignal::Int => "INT",
+      SessionSignal::Term => "TERM",
+      SessionSignal::Kill => "KILL",
+    }
+  }
+}
+
+/// One parsed line of the daemon's JSON log file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Encode, Decode)]
+pub struct LogLine {
+  pub ts: String,
+  pub level: LogLevel,
+  pub target: String,
+  pub message: String,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Encode, Decode)]
 pub struct ProjectKey {
   pub repo_root: String,
@@ -20,6 +110,7 @@ pub struct TaskInfo {
   pub id: u32,
   pub slug: String,
   pub base_branch: Option<String>,
+  pub labels: Vec<String>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default, Encode, Decode)]
@@ -30,6 +121,27 @@ pub struct SessionInfo {
   pub status: String,
   pub clients: u32,
   pub cwd: String,
+  /// Pid of the process running in the session's pane
+  pub pid: u32,
+  /// Output bytes seen since the session started. Approximate, as it is derived
+  /// from the size of the tmux pipe-pane activity stamp. Changes to it alone are
+  /// not broadcast, so subscribers may see a stale value.
+  pub bytes_out: u64,
+  /// Output bytes dropped from the session log because writing it fell behind
+  pub log_dropped_bytes: u64,
+}
+
+/// A tmux client attached to a task session
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default, Encode, Decode)]
+pub struct AttachmentInfo {
+  /// tmux client name, e.g. `/dev/pts/3`
+  pub attachment_id: String,
+  pub session_id: u64,
+  pub task: TaskMeta,
+  pub rows: u16,
+  pub cols: u16,
+  /// Milliseconds since the client last sent input
+  pub idle_ms: u64,
 }
 
 /// Live Git metrics per task
@@ -44,9 +156,14 @@ pub struct TaskMetrics {
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Encode, Decode)]
 pub enum C2DControl {
-  /// One-shot snapshot of the full project state
+  /// One-shot snapshot of the full project state. A non-empty `status_filter`
+  /// keeps only tasks whose status matches one of the names (e.g. `draft`,
+  /// `running`) and `label_filter` only tasks carrying every label; sessions
+  /// and metrics of dropped tasks are left out too.
   ListProjectState {
     project: ProjectKey,
+    status_filter: Option<Vec<String>>,
+    label_filter: Option<Vec<String>>,
   },
   SubscribeEvents {
     project: ProjectKey,
@@ -89,10 +206,62 @@ pub enum C2DControl {
     task_id: u32,
     slug: String,
   },
+  /// Stop every session of the project, answered with `Ack { stopped }`
+  StopProject {
+    project: ProjectKey,
+  },
   Shutdown,
   Ping {
     nonce: u64,
   },
+  /// Tail the daemon log file, keeping lines at or above `level_filter`
+  Logs {
+    max_lines: Option<usize>,
+    level_filter: Option<LogLevel>,
+  },
+  /// Request uptime and load counters of the daemon
+  Health,
+  /// Forward a signal to the process running in the task's session
+  SignalTask {
+    project: ProjectKey,
+    task_id: u32,
+    slug: String,
+    signal: SessionSignal,
+  },
+  /// Stop (`paused`) or continue the processes of the task's session
+  PauseTask {
+    project: ProjectKey,
+    task_id: u32,
+    slug: String,
+    paused: bool,
+  },
+  /// List clients attached to the project's task sessions
+  ListAttachments {
+    project: ProjectKey,
+  },
+  /// Resize the session an attachment views, so every client attached to that
+  /// session sees the same size. Larger clients show the window letterboxed.
+  /// Zero `rows` and `cols` restore automatic sizing.
+  ResizeAll {
+    project: ProjectKey,
+    attachment_id: String,
+    rows: u16,
+    cols: u16,
+  },
+  /// Overwrite front matter fields of a task. `None` fields are left untouched.
+  UpdateTask {
+    project: ProjectKey,
+    /// Task id or slug
+    task: String,
+    agent: Option<String>,
+    base_branch: Option<String>,
+  },
+  /// Run several one-shot requests over one connection, answered by a single
+  /// `Batch` reply in request order. Streaming requests and nested batches are
+  /// answered with `Error` entries.
+  Batch {
+    requests: Vec<C2DControl>,
+  },
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Encode, Decode)]
@@ -129,7 +298,13 @@ pub enum D2CControl {
   Ack {
     stopped: usize,
   },
+  /// Reply to `StopTask` when no session exists for the task
+  TaskNotRunning {
+    task_id: u32,
+    slug: String,
+  },
   Error {
+    code: DaemonErrorCode,
     message: String,
   },
   Goodbye,
@@ -140,6 +315,71 @@ pub enum D2CControl {
   Version {
     version: String,
   },
+  /// Reply with the last daemon log lines, oldest first
+  Logs {
+    lines: Vec<LogLine>,
+  },
+  /// Reply with daemon uptime, pid and counts across all projects
+  Health {
+    uptime_secs: u64,
+    pid: u32,
+    session_count: u32,
+    attached_client_count: u32,
+    subscriber_count: u32,
+  },
+  /// Reply to `SignalTask` and `PauseTask` with the pid that received the signal
+  Signaled {
+    pid: u32,
+  },
+  Attachments {
+    items: Vec<AttachmentInfo>,
+  },
+  /// Reply to `ResizeAll` with the resized session and its attached client count
+  Resized {
+    session_id: u64,
+    clients: u32,
+  },
+  /// Reply to `UpdateTask` with the task as stored afterwards
+  TaskUpdated {
+    task: TaskInfo,
+  },
+  /// Replies to a `Batch` request, one per request and in the same order
+  Batch {
+    replies: Vec<D2CControl>,
+  },
+}
+
+/// Kind of a daemon `Error` reply, so clients can branch without parsing messages.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Encode, Decode)]
+pub enum DaemonErrorCode {
+  /// The request could not be read or is not allowed on this connection
+  InvalidRequest,
+  /// The daemon failed while handling a valid request
+  Internal,
+  /// No task matches the given id or slug
+  TaskNotFound,
+  /// No client with the given attachment id is attached in the project
+  AttachmentNotFound,
+}
+
+impl DaemonErrorCode {
+  /// Stable numeric code in the JSON-RPC error range.
+  #[must_use]
+  pub fn code(self) -> i32 {
+    match self {
+      DaemonErrorCode::InvalidRequest => -32600,
+      DaemonErrorCode::Internal => -32603,
+      DaemonErrorCode::TaskNotFound => -32001,
+      DaemonErrorCode::AttachmentNotFound => -32002,
+    }
+  }
+}
+
+/// Shown in CLI errors, e.g. `Daemon error[-32001]: Task not found`.
+impl std::fmt::Display for DaemonErrorCode {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(f, "error[{}]", self.code())
+  }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Encode, Decode)]
@@ -1,6 +1,8 @@
 use std::io::{IsTerminal as _, Read as _};
+use std::path::PathBuf;
+use std::time::Duration;
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use clap::{Parser, Subcommand};
 
 mod commands;
@@ -11,10 +13,21 @@ mod texts;
 pub mod tui;
 mod utils;
 
+use crate::commands::gc::GcScope;
+use crate::commands::merge::MergeMode;
+use crate::commands::complete::CompleteOptions;
+use crate::commands::new::NewOptions;
+use crate::commands::tasks::TasksOptions;
+use crate::commands::path::PathKind;
 use crate::config::{AgencyPaths, AppContext, global_config_exists, load_config};
+use crate::daemon_protocol::{LogLevel, SessionSignal};
+use crate::utils::color::{self, ColorMode};
 use crate::utils::daemon::ensure_running_and_latest_version;
 use crate::utils::git::resolve_main_workdir;
+use crate::utils::git_backend::GitCli;
+use crate::utils::task::{KeepArtifacts, Priority};
 use crate::utils::tmux::ensure_server as ensure_tmux_server;
+use crate::utils::transcript;
 use crate::utils::tty::Tty;
 
 /// Categorizes how a command uses the daemon/tmux.
@@ -32,6 +45,12 @@ enum DaemonRequirement {
 #[derive(Debug, Parser)]
 #[command(author, version, about, long_about = None)]
 pub struct Cli {
+  /// Editor command for this run, overriding $AGENCY_EDITOR, config and $EDITOR
+  #[arg(long = "editor", value_name = "CMD", global = true)]
+  editor: Option<String>,
+  /// When to use colors; `auto` also honors `NO_COLOR`
+  #[arg(long, value_enum, value_name = "WHEN", default_value_t, global = true)]
+  color: ColorMode,
   #[command(subcommand)]
   command: Option<Commands>,
 }
@@ -39,7 +58,17 @@ pub struct Cli {
 #[derive(Debug, Subcommand)]
 enum Commands {
   /// Run the setup wizard to configure Agency
-  Setup {},
+  Setup {
+    /// Default agent to write (requires --non-interactive)
+    #[arg(short = 'a', long = "agent", requires = "non_interactive")]
+    agent: Option<String>,
+    /// Shell command to write (requires --non-interactive)
+    #[arg(long = "shell", value_name = "CMD", requires = "non_interactive")]
+    shell: Option<String>,
+    /// Write the global config from flags and defaults without prompting
+    #[arg(short = 'y', long = "non-interactive", visible_alias = "yes")]
+    non_interactive: bool,
+  },
   /// Scaffold a .agency/ directory with starter files
   Init {
     /// Set the default agent for the project
@@ -50,7 +79,10 @@ enum Commands {
     yes: bool,
   },
   /// Interactive terminal UI
-  Tui {},
+  Tui {
+    #[command(subcommand)]
+    cmd: Option<TuiCmd>,
+  },
   /// Create a new task under .agency/tasks
   New {
     slug: String,
@@ -71,17 +103,77 @@ enum Commands {
     /// Open editor for description (even without --draft)
     #[arg(short = 'e', long = "edit")]
     edit: bool,
+    /// Bootstrap the worktree and open it with `open_cmd` or the editor (does not attach)
+    #[arg(long = "open")]
+    open: bool,
+    /// Read the whole description from stdin, e.g. `echo ... | agency new slug --stdin`
+    #[arg(long = "stdin", conflicts_with_all = ["desc", "description", "edit"])]
+    stdin: bool,
     /// Attach file(s) to the task (can be repeated)
     #[arg(short = 'f', long = "file")]
     files: Vec<String>,
+    /// Prepend a template from `[templates]` (uses `default` when omitted)
+    #[arg(short = 't', long = "template")]
+    template: Option<String>,
+    /// Adopt an existing branch instead of creating `agency/<id>-<slug>`
+    #[arg(long = "from-branch", value_name = "BRANCH")]
+    from_branch: Option<String>,
+    /// Branch to start from and merge back into (defaults to the current branch)
+    #[arg(long = "base", value_name = "BRANCH")]
+    base: Option<String>,
+    /// Label the task (can be repeated or comma-separated)
+    #[arg(
+      short = 'l',
+      long = "label",
+      value_name = "LABEL",
+      value_delimiter = ','
+    )]
+    labels: Vec<String>,
+    /// Triage priority of the task
+    #[arg(long = "priority", value_enum)]
+    priority: Option<Priority>,
+    /// Return the task created earlier with this key instead of creating a duplicate
+    #[arg(long = "idempotency-key", value_name = "KEY")]
+    idempotency_key: Option<String>,
   },
   /// Open the task's markdown in $EDITOR
   Edit { ident: String },
+  /// Rename a task's slug (file, branch, and worktree)
+  Rename { ident: String, new_slug: String },
+  /// Change a task's metadata, e.g. `agency update my-task --priority high`
+  Update {
+    ident: String,
+    /// Triage priority of the task
+    #[arg(long = "priority", value_enum)]
+    priority: Option<Priority>,
+  },
+  /// Move a task to a free id (file, branch, and worktree)
+  #[command(alias = "move")]
+  Renumber { ident: String, new_id: u32 },
+  /// Rebase a task branch onto the latest tip of its base branch
+  Rebase {
+    ident: String,
+    /// Override the base branch stored in the task
+    #[arg(long = "base")]
+    base: Option<String>,
+  },
+  /// Bundle a task's markdown, files, and branch patch into a tar.gz archive
+  Export {
+    ident: String,
+    /// Archive path (defaults to `<id>-<slug>.tar.gz` in the current directory)
+    #[arg(short = 'o', long = "output")]
+    output: Option<String>,
+  },
+  /// Restore a task archive from `agency export` as a new task
+  Import { archive: String },
   /// Start a task session; attach by default
   Start {
     ident: String,
     #[arg(long = "no-attach")]
     no_attach: bool,
+    /// Use a different agent for this session only (front matter is unchanged)
+    #[arg(short = 'a', long = "agent")]
+    agent: Option<String>,
   },
   /// Attach to an already running task session via PTY daemon
   Attach {
@@ -92,12 +184,49 @@ enum Commands {
     /// Use without an id to auto-pick when exactly one TUI is open.
     #[arg(long = "follow", num_args(0..=1), conflicts_with = "task", conflicts_with = "session")]
     follow: Option<Option<u32>>,
+    /// Observe without sending input or resizing the session
+    #[arg(long = "read-only", conflicts_with = "follow")]
+    read_only: bool,
+    /// Re-attach when the session exits and a new one starts for the task
+    #[arg(long)]
+    reconnect: bool,
+    /// Wait up to this long for a session that is still starting before giving up
+    #[arg(long = "wait-ready-ms", value_name = "MS", default_value_t = 0, conflicts_with = "follow")]
+    wait_ready_ms: u64,
   },
   /// Stop a task's sessions or a specific session
   Stop {
     task: Option<String>,
     #[arg(long)]
     session: Option<u64>,
+    /// Stop every session in this project
+    #[arg(long, conflicts_with = "task", conflicts_with = "session")]
+    all: bool,
+  },
+  /// Kill a running task's session and start a fresh shell and agent
+  Restart { ident: String },
+  /// Send a signal to the agent process of a running task
+  Signal {
+    ident: String,
+    /// Signal to send
+    #[arg(short = 's', long = "signal", value_enum, ignore_case = true, default_value = "INT")]
+    signal: SessionSignal,
+  },
+  /// Stop the processes of a running task until `agency resume`
+  Pause { ident: String },
+  /// Continue a task paused with `agency pause`
+  Resume { ident: String },
+  /// Type input into a running task and wait for output matching a regex
+  Expect {
+    ident: String,
+    /// Regex the output produced after the input must match
+    pattern: String,
+    /// Text to type into the session, followed by Enter
+    #[arg(short = 'i', long = "input")]
+    input: Option<String>,
+    /// Give up after this many milliseconds
+    #[arg(short = 't', long = "timeout-ms", default_value_t = 10_000)]
+    timeout_ms: u64,
   },
   /// Fast-forward merge task back to base
   Merge {
@@ -105,6 +234,22 @@ enum Commands {
     /// Override base branch
     #[arg(short = 'b', long = "branch")]
     base: Option<String>,
+    /// Collapse all task commits into a single commit on base
+    #[arg(long)]
+    squash: bool,
+    /// Always create a merge commit, even when a fast-forward is possible
+    #[arg(long = "no-ff", conflicts_with = "squash")]
+    no_ff: bool,
+    /// Skip editing the squash or merge commit message
+    #[arg(short = 'y', long = "yes")]
+    yes: bool,
+  },
+  /// Stage all changes in a task's worktree and commit them
+  Commit {
+    ident: String,
+    /// Commit message (defaults to the rendered `commit_template`)
+    #[arg(short = 'm', long = "message")]
+    message: Option<String>,
   },
   /// Merge task into base and clean up (branch, worktree, file)
   Complete {
@@ -115,13 +260,68 @@ enum Commands {
     /// Skip confirmation prompt
     #[arg(short = 'y', long = "yes")]
     yes: bool,
+    /// Push the merged base branch to REMOTE (default: origin) before cleanup
+    #[arg(long, value_name = "REMOTE", num_args = 0..=1, default_missing_value = "origin")]
+    push: Option<String>,
+    /// Merge and mark completed, but keep the branch, worktree, and file
+    #[arg(long = "no-delete")]
+    no_delete: bool,
+    /// Squash the task branch into a single commit on base
+    #[arg(long)]
+    squash: bool,
   },
   /// List tasks (ID and SLUG)
-  Tasks {},
+  Tasks {
+    /// Print tasks as a JSON array for scripting
+    #[arg(long, conflicts_with = "watch")]
+    json: bool,
+    /// Add a SIZE column with each worktree's disk usage (walks every worktree)
+    #[arg(long)]
+    size: bool,
+    /// Only show tasks with these statuses (comma-separated, e.g. `draft,running`)
+    #[arg(long = "status", value_delimiter = ',')]
+    status: Vec<String>,
+    /// Only show tasks that have all of these labels (can be repeated or comma-separated)
+    #[arg(short = 'l', long = "label", value_delimiter = ',')]
+    label: Vec<String>,
+    /// Sort rows by `id` (default), `slug`, `status`, `uncommitted` or `commits`
+    #[arg(long, value_name = "FIELD")]
+    sort: Option<String>,
+    /// Reverse the sort order
+    #[arg(long)]
+    reverse: bool,
+    /// Keep the table on screen and redraw it whenever tasks change
+    #[arg(long)]
+    watch: bool,
+  },
+  /// Print the daemon log, optionally following new lines
+  Logs {
+    /// Number of lines to show
+    #[arg(short = 'n', long = "lines", default_value_t = 50)]
+    lines: usize,
+    /// Only show lines at or above this level
+    #[arg(long = "level", value_enum)]
+    level: Option<LogLevel>,
+    /// Keep streaming new lines as they are written
+    #[arg(short = 'f', long = "follow")]
+    follow: bool,
+  },
   /// List running sessions in this project
-  Sessions {},
-  /// Open the task's worktree directory in $EDITOR
-  Open { ident: String },
+  Sessions {
+    /// Print sessions with pid and output stats as a JSON array
+    #[arg(long, conflicts_with = "watch")]
+    json: bool,
+    /// Keep the list on screen and redraw it whenever sessions change
+    #[arg(long)]
+    watch: bool,
+  },
+  /// Open the task's worktree directory in $EDITOR (or the configured `open_cmd`)
+  Open {
+    ident: String,
+    /// Program to open the worktree with instead of the configured one
+    #[arg(long = "cmd")]
+    cmd: Option<String>,
+  },
   /// Open a shell with the worktree as cwd
   Shell { ident: String },
   /// Execute a command in a task's worktree
@@ -130,22 +330,84 @@ enum Commands {
     /// Command and arguments to execute
     #[arg(trailing_var_arg = true, allow_hyphen_values = true, required = true)]
     cmd: Vec<String>,
+    /// Kill the command after this many seconds and exit with code 124
+    #[arg(long, value_name = "SECS")]
+    timeout: Option<u64>,
   },
   /// Select a task with fzf and output its ID
-  Fzf {},
+  Fzf {
+    /// Show the highlighted task's description in a preview window
+    #[arg(long)]
+    preview: bool,
+  },
   /// Print the absolute worktree path
-  Path { ident: String },
+  Path {
+    ident: String,
+    /// Print the task's files directory instead
+    #[arg(long, conflicts_with = "task_file")]
+    files: bool,
+    /// Print the task's markdown file instead
+    #[arg(long = "task-file")]
+    task_file: bool,
+  },
+  /// Print the agent command, env and prompt `start` would use, without running it
+  Prompt {
+    ident: String,
+    /// Print the agent, argv, env and prompt as a JSON object
+    #[arg(long)]
+    json: bool,
+  },
   /// Print the branch name
-  Branch { ident: String },
+  Branch {
+    ident: String,
+    /// Print the branch ref on REMOTE (default: origin) instead
+    #[arg(long, value_name = "REMOTE", num_args = 0..=1, default_missing_value = "origin")]
+    remote: Option<String>,
+    /// Push the branch to REMOTE (default: origin) with upstream tracking and print its ref
+    #[arg(
+      long,
+      value_name = "REMOTE",
+      num_args = 0..=1,
+      default_missing_value = "origin",
+      conflicts_with = "remote"
+    )]
+    push: Option<String>,
+  },
+  /// Show the recorded status changes of a task
+  History { ident: String },
+  /// Show the task's changes against its base branch
+  Diff {
+    ident: String,
+    /// Print a diffstat summary instead of the full diff
+    #[arg(long)]
+    stat: bool,
+  },
   /// Remove task file, worktree, and branch
   Rm {
     ident: String,
     /// Skip confirmation prompt
     #[arg(short = 'y', long = "yes")]
     yes: bool,
+    /// Keep the task branch
+    #[arg(long = "keep-branch")]
+    keep_branch: bool,
+    /// Keep the worktree (and the branch checked out in it)
+    #[arg(long = "keep-worktree")]
+    keep_worktree: bool,
   },
   /// Reset a task's worktree and branch (keep markdown)
-  Reset { ident: String },
+  Reset {
+    ident: String,
+    /// Keep the worktree and reset it to the tip of the base branch instead
+    #[arg(long = "to-base")]
+    to_base: bool,
+    /// Skip the confirmation when committed work would be discarded
+    #[arg(short = 'y', long = "yes", requires = "to_base")]
+    yes: bool,
+    /// Discard uncommitted changes and untracked files in the worktree
+    #[arg(long, requires = "to_base")]
+    force: bool,
+  },
   /// Prepare branch/worktree and run bootstrap (no PTY)
   Bootstrap {
     #[command(subcommand)]
@@ -154,11 +416,28 @@ enum Commands {
     ident: Option<String>,
   },
   /// Open the global config in the configured editor
-  Config {},
+  Config {
+    #[command(subcommand)]
+    cmd: Option<ConfigCmd>,
+  },
   /// Print embedded defaults for inspection
   Defaults {},
+  /// Check the environment for common setup problems
+  Doctor {},
   /// Garbage-collect orphaned branches/worktrees (no task)
-  Gc {},
+  Gc {
+    /// Only prune orphaned worktrees, keep all branches
+    #[arg(long = "worktrees-only", conflicts_with = "branches_only")]
+    worktrees_only: bool,
+    /// Only delete orphaned branches, keep all worktrees
+    #[arg(long = "branches-only")]
+    branches_only: bool,
+  },
+  /// Manage git worktrees of tasks
+  Worktree {
+    #[command(subcommand)]
+    cmd: WorktreeCmd,
+  },
   /// Manage external CLI skills
   Skill {
     #[command(subcommand)]
@@ -175,7 +454,31 @@ enum Commands {
     cmd: FilesCmd,
   },
   /// Show current task context and attached files
-  Info {},
+  Info {
+    /// Print only `<id>-<slug>` of the task worktree containing the current directory
+    #[arg(long = "task-id")]
+    task_id: bool,
+    /// Print the task, worktree, branch, files and session as JSON
+    #[arg(long, conflicts_with = "task_id")]
+    json: bool,
+  },
+  /// Print a task for the `fzf --preview` window (internal)
+  #[command(name = "__fzf-preview", hide = true)]
+  FzfPreview { ident: String },
+  /// Replay a transcript file as a scripted agent (internal)
+  #[command(hide = true)]
+  Transcript {
+    #[arg(long = "file")]
+    file: PathBuf,
+    #[arg(long = "delay-ms", default_value_t = 0)]
+    delay_ms: u64,
+  },
+}
+
+#[derive(Debug, Subcommand)]
+enum WorktreeCmd {
+  /// Remove git's entries for worktrees whose directory was deleted
+  Prune {},
 }
 
 #[derive(Debug, Subcommand)]
@@ -184,6 +487,26 @@ enum SkillCmd {
   Install {},
 }
 
+#[derive(Debug, Subcommand)]
+enum ConfigCmd {
+  /// Check the merged config (or a single file) and report errors
+  Validate {
+    /// Validate only this file, merged over the embedded defaults
+    #[arg(long = "path")]
+    path: Option<PathBuf>,
+  },
+}
+
+#[derive(Debug, Subcommand)]
+enum TuiCmd {
+  /// List TUIs connected to the daemon and the task each one is focused on
+  List {
+    /// Print `tui_id`, `pid` and `focused_task_id` as a JSON array
+    #[arg(long)]
+    json: bool,
+  },
+}
+
 #[derive(Debug, Subcommand)]
 enum DaemonCmd {
   /// Start the daemon as a background service
@@ -199,12 +522,57 @@ enum DaemonCmd {
     /// Skip confirmation prompt when restarting tmux server
     #[arg(short = 'y', long = "yes")]
     yes: bool,
+    /// Kill all sessions and the tmux server, then start both fresh
+    #[arg(long = "hard")]
+    hard: bool,
   },
   /// Show daemon and tmux server status
-  Status {},
+  Status {
+    /// Output daemon status and health counts as JSON
+    #[arg(long)]
+    json: bool,
+  },
+  /// Show the last lines of the daemon log
+  Logs {
+    /// Number of lines to show
+    #[arg(short = 'n', long = "lines", default_value_t = 50)]
+    lines: usize,
+    /// Only show lines at or above this level
+    #[arg(long = "level", value_enum)]
+    level: Option<LogLevel>,
+  },
+  /// Show daemon uptime and session, client and subscriber counts
+  Health {},
+  /// List clients attached to this project's task sessions
+  Attachments {},
+  /// Resize an attachment's session for every client attached to it
+  Resize {
+    /// Attachment id as shown by `agency daemon attachments`
+    attachment_id: String,
+    #[arg(long, required_unless_present = "auto")]
+    rows: Option<u16>,
+    #[arg(long, required_unless_present = "auto")]
+    cols: Option<u16>,
+    /// Drop a previous fixed size so the window follows its clients again
+    #[arg(long, conflicts_with_all = ["rows", "cols"])]
+    auto: bool,
+  },
   /// Run the daemon in the foreground (internal)
   #[command(hide = true)]
   Run {},
+  /// Record session output piped from tmux (internal)
+  #[command(hide = true)]
+  Record {
+    #[arg(long = "stamp")]
+    stamp: PathBuf,
+    #[arg(long = "log")]
+    log: PathBuf,
+    #[arg(long = "max-bytes")]
+    max_bytes: u64,
+    /// Daemon log file that receives the recorder's warnings
+    #[arg(long = "daemon-log")]
+    daemon_log: Option<PathBuf>,
+  },
 }
 
 #[derive(Debug, Subcommand)]
@@ -230,6 +598,9 @@ enum FilesCmd {
     /// Read image from clipboard (optionally specify filename)
     #[arg(long = "from-clipboard", num_args = 0..=1, default_missing_value = "clipboard.png")]
     from_clipboard: Option<String>,
+    /// Download the file from an http(s) URL
+    #[arg(long = "from-url", conflicts_with_all = ["source", "from_clipboard"])]
+    from_url: Option<String>,
   },
   /// Remove a file from a task
   Rm {
@@ -291,18 +662,50 @@ fn read_description_from_stdin() -> Option<String> {
   None
 }
 
+/// Read all of stdin for `agency new --stdin`, waiting for EOF even on a terminal.
+///
+/// # Errors
+/// Returns an error if stdin cannot be read or holds only whitespace.
+fn read_body_from_stdin() -> Result<String> {
+  let mut buf = String::new();
+  std::io::stdin()
+    .lock()
+    .read_to_string(&mut buf)
+    .context("failed to read task description from stdin")?;
+  if buf.trim().is_empty() {
+    anyhow::bail!("No task description on stdin");
+  }
+  Ok(buf)
+}
+
 pub fn run() -> Result<()> {
   let cli = parse();
-  let ctx = build_context()?;
+  color::apply(cli.color);
+  // Validation has to report configs that `build_context` would reject
+  if let Some(Commands::Config {
+    cmd: Some(ConfigCmd::Validate { path }),
+  }) = &cli.command
+  {
+    return commands::config::validate(path.as_deref());
+  }
+  let ctx = build_context(cli.editor.as_deref())?;
   autostart_daemon(&ctx, cli.command.as_ref())?;
   run_command(&ctx, cli)
 }
 
-fn build_context() -> Result<AppContext> {
+fn build_context(editor: Option<&str>) -> Result<AppContext> {
   let cwd = std::env::current_dir()?;
   let project_root = resolve_main_workdir(&cwd);
-  let paths = AgencyPaths::new(project_root.clone(), cwd);
-  let config = load_config(&project_root)?;
+  let mut config = load_config(&project_root)?;
+  if let Some(editor) = editor {
+    let argv = shell_words::split(editor).context("invalid --editor command")?;
+    if argv.is_empty() {
+      anyhow::bail!("--editor must not be empty");
+    }
+    config.editor_override = Some(argv);
+  }
+  let paths =
+    AgencyPaths::new(project_root.clone(), cwd).with_worktree_root(config.worktree_root.as_deref());
   let tty = Tty::new();
   Ok(AppContext { paths, config, tty })
 }
@@ -314,12 +717,17 @@ fn daemon_required(cmd: Option<&Commands>) -> DaemonRequirement {
     // No command (run_default handles its own daemon logic)
     None => DaemonRequirement::None,
     // Commands that require daemon/tmux
-    Some(Commands::Tui {}) => DaemonRequirement::Required,
+    Some(Commands::Tui { .. }) => DaemonRequirement::Required,
     Some(Commands::Start { .. }) => DaemonRequirement::Required,
     Some(Commands::Attach { .. }) => DaemonRequirement::Required,
     Some(Commands::Stop { .. }) => DaemonRequirement::Required,
-    Some(Commands::Sessions {}) => DaemonRequirement::Required,
+    Some(Commands::Restart { .. }) => DaemonRequirement::Required,
+    Some(Commands::Signal { .. }) => DaemonRequirement::Required,
+    Some(Commands::Pause { .. }) => DaemonRequirement::Required,
+    Some(Commands::Resume { .. }) => DaemonRequirement::Required,
+    Some(Commands::Sessions { .. }) => DaemonRequirement::Required,
     Some(Commands::Merge { .. }) => DaemonRequirement::Required,
+    Some(Commands::Commit { .. }) => DaemonRequirement::None,
     Some(Commands::Complete { .. }) => DaemonRequirement::Required,
     Some(Commands::Reset { .. }) => DaemonRequirement::Required,
     Some(Commands::Rm { .. }) => DaemonRequirement::Required,
@@ -327,24 +735,39 @@ fn daemon_required(cmd: Option<&Commands>) -> DaemonRequirement {
     Some(Commands::New { draft: false, .. }) => DaemonRequirement::Required,
     Some(Commands::New { draft: true, .. }) => DaemonRequirement::None,
     // Commands with fallback logic
-    Some(Commands::Tasks {}) => DaemonRequirement::Optional,
-    Some(Commands::Fzf {}) => DaemonRequirement::Optional,
+    Some(Commands::Tasks { .. }) => DaemonRequirement::Optional,
+    Some(Commands::Fzf { .. }) => DaemonRequirement::Optional,
+    Some(Commands::Rename { .. }) => DaemonRequirement::Optional,
+    Some(Commands::Update { .. }) => DaemonRequirement::Optional,
+    Some(Commands::Renumber { .. }) => DaemonRequirement::Optional,
+    Some(Commands::Rebase { .. }) => DaemonRequirement::Optional,
+    Some(Commands::Import { .. }) => DaemonRequirement::Optional,
     // Commands that don't need daemon
-    Some(Commands::Setup {}) => DaemonRequirement::None,
+    Some(Commands::Setup { .. }) => DaemonRequirement::None,
     Some(Commands::Init { .. }) => DaemonRequirement::None,
     Some(Commands::Edit { .. }) => DaemonRequirement::None,
     Some(Commands::Open { .. }) => DaemonRequirement::None,
     Some(Commands::Shell { .. }) => DaemonRequirement::None,
     Some(Commands::Exec { .. }) => DaemonRequirement::None,
+    Some(Commands::Expect { .. }) => DaemonRequirement::None,
     Some(Commands::Path { .. }) => DaemonRequirement::None,
+    Some(Commands::Prompt { .. }) => DaemonRequirement::None,
     Some(Commands::Branch { .. }) => DaemonRequirement::None,
+    Some(Commands::Diff { .. }) => DaemonRequirement::None,
+    Some(Commands::History { .. }) => DaemonRequirement::None,
+    Some(Commands::Export { .. }) => DaemonRequirement::None,
     Some(Commands::Bootstrap { .. }) => DaemonRequirement::None,
-    Some(Commands::Config {}) => DaemonRequirement::None,
+    Some(Commands::Config { .. }) => DaemonRequirement::None,
     Some(Commands::Defaults {}) => DaemonRequirement::None,
-    Some(Commands::Gc {}) => DaemonRequirement::None,
+    Some(Commands::Doctor {}) => DaemonRequirement::None,
+    Some(Commands::Gc { .. }) => DaemonRequirement::None,
     Some(Commands::Daemon { .. }) => DaemonRequirement::None,
+    Some(Commands::Logs { .. }) => DaemonRequirement::None,
     Some(Commands::Files { .. }) => DaemonRequirement::None,
-    Some(Commands::Info {}) => DaemonRequirement::None,
+    Some(Commands::Info { .. }) => DaemonRequirement::None,
+    Some(Commands::FzfPreview { .. }) => DaemonRequirement::None,
+    Some(Commands::Transcript { .. }) => DaemonRequirement::None,
+    Some(Commands::Worktree { .. }) => DaemonRequirement::None,
     Some(Commands::Skill { .. }) => DaemonRequirement::None,
   }
 }
@@ -377,9 +800,16 @@ fn autostart_daemon(ctx: &AppContext, cmd: Option<&Commands>) -> Result<()> {
 #[allow(clippy::too_many_lines)]
 fn run_command(ctx: &AppContext, cli: Cli) -> Result<()> {
   match cli.command {
-    Some(Commands::Setup {}) => commands::setup::run(ctx),
+    Some(Commands::Setup {
+      agent,
+      shell,
+      non_interactive,
+    }) => commands::setup::run(ctx, agent.as_deref(), shell.as_deref(), non_interactive),
     Some(Commands::Init { agent, yes }) => commands::init::run(ctx, agent.as_deref(), yes),
-    Some(Commands::Tui {}) => tui::run(ctx),
+    Some(Commands::Tui { cmd }) => match cmd {
+      None => tui::run(ctx),
+      Some(TuiCmd::List { json }) => commands::tui::list(ctx, json),
+    },
     Some(Commands::New {
       slug,
       desc,
@@ -388,79 +818,281 @@ fn run_command(ctx: &AppContext, cli: Cli) -> Result<()> {
       description,
       no_attach,
       edit,
+      open,
+      stdin,
       files,
+      template,
+      from_branch,
+      base,
+      labels,
+      priority,
+      idempotency_key,
     }) => {
-      // Priority: positional arg > --description flag > stdin
-      let desc = desc.or(description).or_else(read_description_from_stdin);
+      // Priority: --stdin > positional arg > --description flag > piped stdin
+      let desc = if stdin {
+        Some(read_body_from_stdin()?)
+      } else {
+        desc.or(description).or_else(read_description_from_stdin)
+      };
       let desc = if draft || edit {
         desc
       } else {
         Some(desc.unwrap_or_default())
       };
-      let created =
-        commands::new::run(ctx, &slug, agent.as_deref(), desc.as_deref(), edit, &files)?;
+      let opts = NewOptions {
+        agent: agent.as_deref(),
+        desc: desc.as_deref(),
+        edit,
+        files: &files,
+        template: template.as_deref(),
+        from_branch: from_branch.as_deref(),
+        base: base.as_deref(),
+        labels: &labels,
+        priority,
+      };
+      let created = match idempotency_key.as_deref() {
+        Some(key) => match commands::new::run_with_key(ctx, &GitCli, &slug, &opts, key)? {
+          Some(task) => task,
+          None => return Ok(()),
+        },
+        None => commands::new::run(ctx, &GitCli, &slug, &opts)?,
+      };
+      let ident = created.id.to_string();
       if !draft {
-        let ident = created.id.to_string();
-        // Only attach in interactive mode; non-interactive defaults to no-attach
-        let should_attach = !no_attach && ctx.tty.is_interactive();
-        commands::start::run_with_attach(ctx, &ident, should_attach)?;
+        // Only attach in interactive mode; non-interactive defaults to no-attach.
+        // With --open the terminal goes to the opened program instead
+        let should_attach = !no_attach && !open && ctx.tty.is_interactive();
+        commands::start::run_with_attach(ctx, &ident, should_attach, None)?;
+      } else if open {
+        commands::bootstrap::run(ctx, &ident)?;
+      }
+      if open {
+        commands::open::run(ctx, &ident, None)?;
       }
       Ok(())
     }
     Some(Commands::Edit { ident }) => commands::edit::run(ctx, &ident),
-    Some(Commands::Start { ident, no_attach }) => {
+    Some(Commands::Rename { ident, new_slug }) => commands::rename::run(ctx, &ident, &new_slug),
+    Some(Commands::Update { ident, priority }) => commands::update::run(ctx, &ident, priority),
+    Some(Commands::Renumber { ident, new_id }) => commands::renumber::run(ctx, &ident, new_id),
+    Some(Commands::Rebase { ident, base }) => commands::rebase::run(ctx, &GitCli, &ident, base.as_deref()),
+    Some(Commands::Export { ident, output }) => {
+      commands::export::run(ctx, &ident, output.as_deref())
+    }
+    Some(Commands::Import { archive }) => commands::import::run(ctx, &archive).map(|_| ()),
+    Some(Commands::Start {
+      ident,
+      no_attach,
+      agent,
+    }) => {
       // Only attach in interactive mode; non-interactive defaults to no-attach
       let should_attach = !no_attach && ctx.tty.is_interactive();
-      commands::start::run_with_attach(ctx, &ident, should_attach)
+      commands::start::run_with_attach(ctx, &ident, should_attach, agent.as_deref())
     }
     Some(Commands::Attach {
       task,
       session,
       follow,
+      read_only,
+      reconnect,
+      wait_ready_ms,
     }) => {
+      let wait_ready = Duration::from_millis(wait_ready_ms);
       if let Some(f) = follow {
-        commands::attach::run_follow(ctx, f)
+        commands::attach::run_follow(ctx, f, reconnect)
       } else if let Some(t) = task {
-        commands::attach::run_with_task(ctx, &t)
+        commands::attach::run_with_task(ctx, &t, read_only, reconnect, wait_ready)
       } else if let Some(sid) = session {
-        commands::attach::run_join_session(ctx, sid)
+        commands::attach::run_join_session(ctx, sid, read_only, reconnect, wait_ready)
       } else {
         anyhow::bail!("Attach requires either a task, --session <id>, or --follow [<tui-id>]")
       }
     }
-    Some(Commands::Stop { task, session }) => commands::stop::run(ctx, task.as_deref(), session),
-    Some(Commands::Merge { ident, base }) => commands::merge::run(ctx, &ident, base.as_deref()),
-    Some(Commands::Complete { ident, base, yes }) => {
-      commands::complete::run(ctx, ident.as_deref(), base.as_deref(), yes)
+    Some(Commands::Stop { task, session, all }) => {
+      commands::stop::run(ctx, task.as_deref(), session, all)
+    }
+    Some(Commands::Restart { ident }) => commands::restart::run(ctx, &ident),
+    Some(Commands::Signal { ident, signal }) => commands::signal::run(ctx, &ident, signal),
+    Some(Commands::Pause { ident }) => commands::pause::run(ctx, &ident, true),
+    Some(Commands::Resume { ident }) => commands::pause::run(ctx, &ident, false),
+    Some(Commands::Merge {
+      ident,
+      base,
+      squash,
+      no_ff,
+      yes,
+    }) => {
+      let mode = if squash {
+        MergeMode::Squash
+      } else if no_ff {
+        MergeMode::NoFastForward
+      } else {
+        MergeMode::FastForward
+      };
+      commands::merge::run(ctx, &ident, base.as_deref(), mode, yes)
+    }
+    Some(Commands::Commit { ident, message }) => {
+      commands::commit::run(ctx, &ident, message.as_deref())
+    }
+    Some(Commands::Complete {
+      ident,
+      base,
+      yes,
+      push,
+      no_delete,
+      squash,
+    }) => commands::complete::run(
+      ctx,
+      ident.as_deref(),
+      &CompleteOptions {
+        base: base.as_deref(),
+        yes,
+        push: push.as_deref(),
+        keep: no_delete,
+        squash,
+      },
+    ),
+    Some(Commands::Tasks {
+      json,
+      size,
+      status,
+      label,
+      sort,
+      reverse,
+      watch,
+    }) => {
+      let opts = TasksOptions {
+        size,
+        statuses: &status,
+        labels: &label,
+        sort: sort.as_deref(),
+        reverse,
+      };
+      if watch {
+        commands::tasks::watch(ctx, &opts)
+      } else {
+        commands::tasks::run(ctx, &opts, json)
+      }
     }
-    Some(Commands::Tasks {}) => commands::tasks::run(ctx),
-    Some(Commands::Sessions {}) => commands::sessions::run(ctx),
-    Some(Commands::Open { ident }) => commands::open::run(ctx, &ident),
+    Some(Commands::Sessions { watch: true, .. }) => commands::sessions::watch(ctx),
+    Some(Commands::Sessions { json, .. }) => commands::sessions::run(ctx, json),
+    Some(Commands::Logs {
+      lines,
+      level,
+      follow,
+    }) => commands::logs::run(ctx, lines, level, follow),
+    Some(Commands::Open { ident, cmd }) => commands::open::run(ctx, &ident, cmd.as_deref()),
     Some(Commands::Shell { ident }) => commands::shell::run(ctx, &ident),
-    Some(Commands::Exec { ident, cmd }) => {
-      let code = commands::exec::run(ctx, &ident, &cmd)?;
+    Some(Commands::Exec {
+      ident,
+      cmd,
+      timeout,
+    }) => {
+      let code = commands::exec::run(ctx, &ident, &cmd, timeout.map(Duration::from_secs))?;
       std::process::exit(code);
     }
-    Some(Commands::Fzf {}) => commands::fzf::run(ctx),
-    Some(Commands::Path { ident }) => commands::path::run(ctx, &ident),
-    Some(Commands::Branch { ident }) => commands::branch::run(ctx, &ident),
-    Some(Commands::Rm { ident, yes }) => commands::rm::run(ctx, &ident, yes),
-    Some(Commands::Reset { ident }) => commands::reset::run(ctx, &ident),
+    Some(Commands::Expect {
+      ident,
+      pattern,
+      input,
+      timeout_ms,
+    }) => {
+      let timeout = Duration::from_millis(timeout_ms);
+      commands::expect::run(ctx, &ident, input.as_deref(), &pattern, timeout)
+    }
+    Some(Commands::Fzf { preview }) => commands::fzf::run(ctx, preview),
+    Some(Commands::FzfPreview { ident }) => commands::fzf::preview(ctx, &ident),
+    Some(Commands::Prompt { ident, json }) => commands::prompt::run(ctx, &ident, json),
+    Some(Commands::Path {
+      ident,
+      files,
+      task_file,
+    }) => {
+      let kind = if files {
+        PathKind::Files
+      } else if task_file {
+        PathKind::TaskFile
+      } else {
+        PathKind::Worktree
+      };
+      commands::path::run(ctx, &ident, kind)
+    }
+    Some(Commands::Branch {
+      ident,
+      remote,
+      push,
+    }) => commands::branch::run(ctx, &ident, remote.as_deref(), push.as_deref()),
+    Some(Commands::Diff { ident, stat }) => commands::diff::run(ctx, &ident, stat),
+    Some(Commands::History { ident }) => commands::history::run(ctx, &ident),
+    Some(Commands::Rm {
+      ident,
+      yes,
+      keep_branch,
+      keep_worktree,
+    }) => {
+      let keep = KeepArtifacts {
+        branch: keep_branch,
+        worktree: keep_worktree,
+      };
+      commands::rm::run(ctx, &ident, yes, keep)
+    }
+    Some(Commands::Reset {
+      ident,
+      to_base: true,
+      yes,
+      force,
+    }) => commands::reset::run_to_base(ctx, &GitCli, &ident, yes, force),
+    Some(Commands::Reset { ident, .. }) => commands::reset::run(ctx, &ident),
     Some(Commands::Bootstrap { cmd, ident }) => match (cmd, ident) {
       (Some(BootstrapCmd::Task { ident }), _) | (None, Some(ident)) => {
         commands::bootstrap::run(ctx, &ident)
       }
       (None, None) => anyhow::bail!("Bootstrap requires a task ID or slug"),
     },
-    Some(Commands::Config {}) => commands::config::run(ctx),
+    Some(Commands::Config { cmd }) => match cmd {
+      None => commands::config::run(ctx),
+      Some(ConfigCmd::Validate { path }) => commands::config::validate(path.as_deref()),
+    },
     Some(Commands::Defaults {}) => commands::defaults::run(),
-    Some(Commands::Gc {}) => commands::gc::run(ctx),
+    Some(Commands::Doctor {}) => commands::doctor::run(ctx),
+    Some(Commands::Gc {
+      worktrees_only,
+      branches_only,
+    }) => {
+      let scope = if worktrees_only {
+        GcScope::WorktreesOnly
+      } else if branches_only {
+        GcScope::BranchesOnly
+      } else {
+        GcScope::All
+      };
+      commands::gc::run(ctx, scope)
+    }
     Some(Commands::Daemon { cmd }) => match cmd {
       DaemonCmd::Start {} => commands::daemon::start(),
       DaemonCmd::Stop { yes } => commands::daemon::stop(ctx, yes),
-      DaemonCmd::Restart { yes } => commands::daemon::restart(ctx, yes),
-      DaemonCmd::Status {} => commands::daemon::status(ctx),
+      DaemonCmd::Restart { yes, hard: false } => commands::daemon::restart(ctx, yes),
+      DaemonCmd::Restart { yes, hard: true } => commands::daemon::restart_hard(ctx, yes),
+      DaemonCmd::Status { json } => commands::daemon::status(ctx, json),
+      DaemonCmd::Logs { lines, level } => commands::daemon::logs(ctx, lines, level),
+      DaemonCmd::Health {} => commands::daemon::health(ctx),
+      DaemonCmd::Attachments {} => commands::daemon::attachments(ctx),
+      DaemonCmd::Resize {
+        attachment_id,
+        rows,
+        cols,
+        auto,
+      } => {
+        let size = rows.zip(cols).filter(|_| !auto);
+        commands::daemon::resize(ctx, &attachment_id, size)
+      }
       DaemonCmd::Run {} => commands::daemon::run_blocking(),
+      DaemonCmd::Record {
+        stamp,
+        log,
+        max_bytes,
+        daemon_log,
+      } => commands::daemon::record(&stamp, &log, max_bytes, daemon_log.as_deref()),
     },
     Some(Commands::Files { cmd }) => match cmd {
       FilesCmd::List { task } => commands::files::list::run(ctx, &task),
@@ -468,14 +1100,27 @@ fn run_command(ctx: &AppContext, cli: Cli) -> Result<()> {
         task,
         source,
         from_clipboard,
-      } => commands::files::add::run(ctx, &task, source.as_deref(), from_clipboard.as_deref()),
+        from_url,
+      } => commands::files::add::run(
+        ctx,
+        &task,
+        source.as_deref(),
+        from_clipboard.as_deref(),
+        from_url.as_deref(),
+      ),
       FilesCmd::Rm { task, file, yes } => commands::files::rm::run(ctx, &task, &file, yes),
       FilesCmd::Path { task, file } => commands::files::path::run(ctx, &task, file.as_deref()),
       FilesCmd::Fzf { task } => commands::files::fzf::run(ctx, &task),
       FilesCmd::Open { task, file } => commands::files::open::run(ctx, &task, file.as_deref()),
       FilesCmd::Edit { task, file } => commands::files::edit::run(ctx, &task, &file),
     },
-    Some(Commands::Info {}) => commands::info::run(ctx),
+    Some(Commands::Info { task_id, json }) => commands::info::run(ctx, task_id, json),
+    Some(Commands::Transcript { file, delay_ms }) => {
+      transcript::replay(&file, Duration::from_millis(delay_ms))
+    }
+    Some(Commands::Worktree { cmd }) => match cmd {
+      WorktreeCmd::Prune {} => commands::worktree::prune(ctx),
+    },
     Some(Commands::Skill { cmd }) => match cmd {
       SkillCmd::Install {} => commands::skill::install::run(ctx),
     },
@@ -486,7 +1131,7 @@ fn run_command(ctx: &AppContext, cli: Cli) -> Result<()> {
 fn run_default(ctx: &AppContext) -> Result<()> {
   if !global_config_exists() {
     if ctx.tty.is_interactive() {
-      commands::setup::run(ctx)?;
+      commands::setup::run(ctx, None, None, false)?;
     } else {
       log_warn!("Global config missing: run `agency setup` in a terminal");
     }
@@ -19,28 +19,33 @@ use super::command_log::CommandLogState;
 use super::confirm_dialog::{ConfirmAction, ConfirmDialogState, ConfirmOutcome};
 use super::file_input_overlay::{FileInputAction, FileInputState};
 use super::files_overlay::{FilesOutcome, FilesOverlayState};
+use super::filter_input_overlay::{FilterInputAction, FilterInputState};
 use super::help_bar::{
-  self, HELP_ITEMS, HELP_ITEMS_FILES, HELP_ITEMS_FILE_INPUT, HELP_ITEMS_INPUT, HELP_ITEMS_LOG,
+  self, HELP_ITEMS, HELP_ITEMS_FILES, HELP_ITEMS_FILE_INPUT, HELP_ITEMS_FILTER, HELP_ITEMS_INPUT,
+  HELP_ITEMS_LOG,
 };
 use super::task_input_overlay::{self, InputOverlayState};
 use super::select_menu::{MenuOutcome, SelectMenuState};
 use super::task_table::{self, TaskTableState};
+use super::theme::Theme;
 use crate::commands::{attach, complete, edit, merge, new, open, reset, rm, shell, start, stop};
+use crate::commands::new::NewOptions;
 use crate::utils::files::{FileRef, add_file, add_file_from_bytes, files_dir_for_task};
 use crate::utils::opener::open_with_default;
 use crate::config::{AppContext, compute_socket_path};
-use crate::daemon_protocol::{
-  C2D, C2DControl, D2C, D2CControl, ProjectKey, read_frame, write_frame,
-};
+use crate::daemon_protocol::{C2DControl, ProjectKey};
 use crate::utils::daemon::{
-  connect_daemon, get_project_state, send_message_to_daemon, tui_register, tui_unregister,
+  ProjectEvent, connect_daemon, get_project_state, send_message_to_daemon,
+  subscribe_project_events, tui_register, tui_unregister,
 };
 use crate::utils::git::{open_main_repo, repo_workdir_or};
+use crate::utils::git_backend::GitCli;
 use crate::utils::interactive::{InteractiveReq, register_sender as register_interactive_sender};
 use crate::utils::log::{LogEvent, clear_log_sink, set_log_sink};
 use crate::utils::task::TaskRef;
 use crate::utils::task_columns::{GitMetrics, TaskRow};
 use crate::utils::term::restore_terminal_state;
+use crate::utils::time::now_ms;
 use crate::{log_error, log_info};
 
 /// Which pane is focused.
@@ -59,16 +64,11 @@ pub enum Mode {
   InputSlug,
   FilesOverlay(FilesOverlayState),
   FileInput(FileInputState),
+  FilterInput(FilterInputState),
   SelectMenu(SelectMenuState),
   ConfirmDialog(ConfirmDialogState),
 }
 
-/// Daemon events for the UI.
-enum UiEvent {
-  ProjectState,
-  Disconnected(Error),
-}
-
 /// Connection status for daemon subscription.
 enum SubscriptionStatus {
   Connected,
@@ -78,6 +78,7 @@ enum SubscriptionStatus {
 /// Main application state composing all component states.
 struct AppState {
   task_table: TaskTableState,
+  theme: Theme,
   command_log: CommandLogState,
   input_overlay: Option<InputOverlayState>,
   focus: Focus,
@@ -85,13 +86,14 @@ struct AppState {
   paused: bool,
   sent_initial_focus: bool,
   subscription_status: SubscriptionStatus,
-  events_rx: Option<Receiver<UiEvent>>,
+  events_rx: Option<Receiver<ProjectEvent>>,
 }
 
 impl Default for AppState {
   fn default() -> Self {
     Self {
       task_table: TaskTableState::new(),
+      theme: Theme::default(),
       command_log: CommandLogState::new(),
       input_overlay: None,
       focus: Focus::Tasks,
@@ -110,6 +112,7 @@ impl AppState {
       Mode::InputSlug | Mode::SelectMenu(_) => HELP_ITEMS_INPUT,
       Mode::FilesOverlay(_) => HELP_ITEMS_FILES,
       Mode::FileInput(_) => HELP_ITEMS_FILE_INPUT,
+      Mode::FilterInput(_) => HELP_ITEMS_FILTER,
       Mode::List | Mode::ConfirmDialog(_) => match self.focus {
         Focus::Log => HELP_ITEMS_LOG,
         Focus::Tasks => HELP_ITEMS,
@@ -148,9 +151,9 @@ impl AppState {
     Ok(())
   }
 
-  fn handle_daemon_event(&mut self, ctx: &AppContext, ev: UiEvent) -> Result<(), Error> {
+  fn handle_daemon_event(&mut self, ctx: &AppContext, ev: ProjectEvent) -> Result<(), Error> {
     match ev {
-      UiEvent::ProjectState => {
+      ProjectEvent::State(_) => {
         let prev_sel_id = self.task_table.selected_row().map(TaskRow::id);
         self.refresh(ctx).map_err(|err| {
           log_error!("{}", err);
@@ -162,7 +165,7 @@ impl AppState {
           emit_focus_change(ctx, self.task_table.tui_id, cur_sel_id);
         }
       }
-      UiEvent::Disconnected(err) => {
+      ProjectEvent::Disconnected(err) => {
         log_info!("Daemon connection lost: {}", err);
         self.subscription_status = SubscriptionStatus::Disconnected { since: Instant::now() };
         self.events_rx = None;
@@ -191,11 +194,11 @@ impl AppState {
 
     self
       .task_table
-      .draw(f, rects[0], self.focus == Focus::Tasks);
+      .draw(f, rects[0], self.focus == Focus::Tasks, &self.theme);
     if log_height > 0 {
       self.command_log.draw(f, rects[1], self.focus == Focus::Log);
     }
-    help_bar::draw_with_items(f, rects[2], help_items);
+    help_bar::draw_with_items(f, rects[2], help_items, self.theme.help);
 
     if let Some(ref overlay) = self.input_overlay {
       overlay.draw(f, rects[0]);
@@ -209,6 +212,10 @@ impl AppState {
       input.draw(f, rects[0]);
     }
 
+    if let Mode::FilterInput(ref input) = self.mode {
+      input.draw(f, rects[0]);
+    }
+
     if let Mode::SelectMenu(ref menu) = self.mode {
       menu.draw(f, rects[0]);
     }
@@ -249,7 +256,7 @@ impl AppState {
           "agency start --no-attach {id_str}"
         )));
         spawn_cmd(ctx, move |ctx| {
-          if let Err(err) = start::run_with_attach(&ctx, &id_str, false) {
+          if let Err(err) = start::run_with_attach(&ctx, &id_str, false, None) {
             log_error!("Start failed: {}", err);
           }
         });
@@ -260,7 +267,7 @@ impl AppState {
           .command_log
           .push(LogEvent::Command(format!("agency stop --task {id}")));
         spawn_cmd(ctx, move |ctx| {
-          if let Err(err) = stop::run(&ctx, Some(&id.to_string()), None) {
+          if let Err(err) = stop::run(&ctx, Some(&id.to_string()), None, false) {
             log_error!("Stop failed: {}", err);
           }
         });
@@ -271,7 +278,7 @@ impl AppState {
           .command_log
           .push(LogEvent::Command(format!("agency merge {id_str}")));
         spawn_cmd(ctx, move |ctx| {
-          if let Err(err) = merge::run(&ctx, &id_str, None) {
+          if let Err(err) = merge::run(&ctx, &id_str, None, merge::MergeMode::FastForward, true) {
             log_error!("Merge failed: {}", err);
           }
         });
@@ -287,7 +294,7 @@ impl AppState {
       task_table::Action::OpenTask { id } => {
         let id = *id;
         spawn_cmd(ctx, move |ctx| {
-          let _ = open::run(&ctx, &id.to_string());
+          let _ = open::run(&ctx, &id.to_string(), None);
         });
       }
       task_table::Action::ShellTask { id } => {
@@ -403,7 +410,7 @@ fn ui_loop(
     state.sent_initial_focus = true;
   }
 
-  state.events_rx = subscribe_events(ctx)
+  state.events_rx = subscribe_project_events(ctx, "tui-subscribe")
     .map_err(|err| {
       log_error!("{}", err);
       err
@@ -412,6 +419,7 @@ fn ui_loop(
 
   let (log_tx, log_rx) = unbounded::<LogEvent>();
   set_log_sink(log_tx);
+  state.theme = Theme::from_config(&ctx.config);
 
   let (itx, irx) = unbounded::<InteractiveReq>();
   register_interactive_sender(itx);
@@ -451,7 +459,7 @@ fn ui_loop(
     if let SubscriptionStatus::Disconnected { since } = state.subscription_status
       && since.elapsed() > Duration::from_secs(2)
     {
-      if let Ok(rx) = subscribe_events(ctx) {
+      if let Ok(rx) = subscribe_project_events(ctx, "tui-subscribe") {
         state.events_rx = Some(rx);
         state.subscription_status = SubscriptionStatus::Connected;
         state.refresh(ctx).ok();
@@ -489,6 +497,9 @@ fn ui_loop(
             Mode::FileInput(input) => {
               handle_file_input_mode(&mut state, ctx, input, key);
             }
+            Mode::FilterInput(input) => {
+              handle_filter_input_mode(&mut state, ctx, input, key);
+            }
             Mode::SelectMenu(menu) => {
               handle_menu_mode(&mut state, menu, key);
             }
@@ -534,6 +545,13 @@ fn handle_list_mode(state: &mut AppState, ctx: &AppContext, key: crossterm::even
   }
 
   match state.focus {
+    Focus::Tasks if key.code == KeyCode::Char('/') => {
+      let input = FilterInputState::new(state.task_table.filter());
+      state.mode = Mode::FilterInput(input);
+    }
+    Focus::Tasks if key.code == KeyCode::Esc && state.task_table.filter().is_some() => {
+      update_filter(state, ctx, None);
+    }
     Focus::Tasks => {
       let action = state.task_table.handle_key(key);
       state.dispatch_action(ctx, &action);
@@ -579,10 +597,19 @@ fn handle_input_mode(state: &mut AppState, ctx: &AppContext, key: crossterm::eve
           .push(LogEvent::Command(format!("agency new {slug} + start")));
         std::thread::spawn({
           let ctx = ctx.clone();
-          move || match new::run(&ctx, &slug, agent.as_deref(), Some(""), false, &[]) {
+          move || match new::run(
+            &ctx,
+            &GitCli,
+            &slug,
+            &NewOptions {
+              agent: agent.as_deref(),
+              desc: Some(""),
+              ..NewOptions::default()
+            },
+          ) {
             Ok(created) => {
               let id_str = created.id.to_string();
-              if let Err(err) = start::run_with_attach(&ctx, &id_str, true) {
+              if let Err(err) = start::run_with_attach(&ctx, &id_str, true, None) {
                 log_error!("Start+attach failed: {}", err);
               }
             }
@@ -595,7 +622,15 @@ fn handle_input_mode(state: &mut AppState, ctx: &AppContext, key: crossterm::eve
         std::thread::spawn({
           let ctx = ctx.clone();
           move || {
-            let _ = new::run(&ctx, &slug, agent.as_deref(), None, false, &[]);
+            let _ = new::run(
+              &ctx,
+              &GitCli,
+              &slug,
+              &NewOptions {
+                agent: agent.as_deref(),
+                ..NewOptions::default()
+              },
+            );
           }
         });
       }
@@ -752,6 +787,40 @@ fn handle_file_input_mode(
   }
 }
 
+fn handle_filter_input_mode(
+  state: &mut AppState,
+  ctx: &AppContext,
+  mut input: FilterInputState,
+  key: crossterm::event::KeyEvent,
+) {
+  match input.handle_key(key) {
+    FilterInputAction::None => {
+      state.mode = Mode::FilterInput(input);
+    }
+    FilterInputAction::Changed(text) => {
+      update_filter(state, ctx, Some(text));
+      state.mode = Mode::FilterInput(input);
+    }
+    FilterInputAction::Apply => {
+      state.mode = Mode::List;
+    }
+    FilterInputAction::Clear => {
+      update_filter(state, ctx, None);
+      state.mode = Mode::List;
+    }
+  }
+}
+
+/// Apply a task filter and emit a focus event when the selected task changes.
+fn update_filter(state: &mut AppState, ctx: &AppContext, filter: Option<String>) {
+  let prev_sel_id = state.task_table.selected_row().map(TaskRow::id);
+  state.task_table.set_filter(filter);
+  let cur_sel_id = state.task_table.selected_row().map(TaskRow::id);
+  if prev_sel_id != cur_sel_id {
+    emit_focus_change(ctx, state.task_table.tui_id, cur_sel_id);
+  }
+}
+
 fn execute_confirm_action(ctx: &AppContext, action: &ConfirmAction) {
   match *action {
     ConfirmAction::CompleteTask { id } => {
@@ -783,11 +852,7 @@ fn spawn_paste_clipboard(ctx: &AppContext, task: TaskRef) {
         return;
       }
     };
-    let timestamp = std::time::SystemTime::now()
-      .duration_since(std::time::UNIX_EPOCH)
-      .map(|d| d.as_millis())
-      .unwrap_or(0);
-    let name = format!("screenshot-{timestamp}.png");
+    let name = format!("screenshot-{}.png", now_ms());
     match add_file_from_bytes(&ctx.paths, &task, &name, &data) {
       Ok(file_ref) => {
         log_info!("Added file {} {}", file_ref.id, file_ref.name);
@@ -804,7 +869,7 @@ fn spawn_edit_or_attach(ctx: &AppContext, id: u32, session: Option<u64>) {
   let ctx = ctx.clone();
   std::thread::spawn(move || {
     if let Some(sid) = session {
-      let _ = attach::run_join_session(&ctx, sid);
+      let _ = attach::run_join_session(&ctx, sid, false, false, Duration::ZERO);
     } else {
       let _ = edit::run(&ctx, &id.to_string());
     }
@@ -841,43 +906,6 @@ fn reinit_terminal(terminal: &mut Terminal<CrosstermBackend<std::io::Stdout>>) -
   Ok(())
 }
 
-fn subscribe_events(ctx: &AppContext) -> Result<Receiver<UiEvent>> {
-  let (tx, rx) = unbounded::<UiEvent>();
-  let repo = open_main_repo(ctx.paths.root())?;
-  let repo_root = repo_workdir_or(&repo, ctx.paths.root());
-  let project = ProjectKey {
-    repo_root: repo_root.display().to_string(),
-  };
-  let stream = connect_daemon(ctx)?;
-  std::thread::Builder::new()
-    .name("tui-subscribe".to_string())
-    .spawn(move || {
-      let tx_events = tx;
-      let mut stream = stream;
-      if let Err(err) = write_frame(
-        &mut stream,
-        &C2D::Control(C2DControl::SubscribeEvents { project }),
-      ) {
-        let _ = tx_events.send(UiEvent::Disconnected(err));
-        return;
-      }
-      loop {
-        let msg: Result<D2C> = read_frame(&mut stream);
-        match msg {
-          Ok(D2C::Control(D2CControl::ProjectState { .. })) => {
-            let _ = tx_events.send(UiEvent::ProjectState);
-          }
-          Ok(D2C::Control(_)) => {}
-          Err(err) => {
-            let _ = tx_events.send(UiEvent::Disconnected(err));
-            break;
-          }
-        }
-      }
-    })?;
-  Ok(rx)
-}
-
 fn emit_focus_change(ctx: &AppContext, tui_id: Option<u32>, task_id: Option<u32>) {
   let Some(tid) = tui_id else { return };
   let Ok(repo) = open_main_repo(ctx.paths.root()) else {
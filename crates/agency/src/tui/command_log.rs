@@ -135,20 +135,16 @@ impl CommandLogState {
   /// Handle mouse events for border dragging.
   pub fn handle_mouse_event(&mut self, mouse: MouseEvent) {
     match mouse.kind {
-      MouseEventKind::Down(MouseButton::Left) => {
-        // Check if click is on the command log border (top row of command log area)
-        if mouse.row == self.border_y && self.visible {
-          self.dragging = true;
-        }
+      // Check if click is on the command log border (top row of command log area)
+      MouseEventKind::Down(MouseButton::Left) if mouse.row == self.border_y && self.visible => {
+        self.dragging = true;
       }
-      MouseEventKind::Drag(MouseButton::Left) => {
-        if self.dragging {
-          // Calculate new height based on mouse Y position
-          let delta = i32::from(self.border_y) - i32::from(mouse.row);
-          let new_height = i32::from(self.height) + delta;
-          self.height =
-            u16::try_from(new_height.max(i32::from(MIN_LOG_HEIGHT))).unwrap_or(u16::MAX);
-        }
+      MouseEventKind::Drag(MouseButton::Left) if self.dragging => {
+        // Calculate new height based on mouse Y position
+        let delta = i32::from(self.border_y) - i32::from(mouse.row);
+        let new_height = i32::from(self.height) + delta;
+        self.height =
+          u16::try_from(new_height.max(i32::from(MIN_LOG_HEIGHT))).unwrap_or(u16::MAX);
       }
       MouseEventKind::Up(MouseButton::Left) => {
         self.dragging = false;
@@ -0,0 +1,78 @@
+use crossterm::event::KeyEvent;
+use ratatui::layout::Rect;
+
+use super::text_input::{TextInputConfig, TextInputOutcome, TextInputState};
+
+/// Actions from the task filter overlay.
+#[derive(Clone, Debug)]
+pub enum FilterInputAction {
+  None,
+  /// Filter text changed; the table should update live.
+  Changed(String),
+  /// Keep the current filter and close the overlay.
+  Apply,
+  /// Clear the filter and close the overlay.
+  Clear,
+}
+
+/// State for the task filter input overlay.
+#[derive(Clone, Debug)]
+pub struct FilterInputState {
+  text_input: TextInputState,
+}
+
+impl FilterInputState {
+  pub fn new(initial: Option<&str>) -> Self {
+    let config = TextInputConfig::new("Filter Tasks", "slug or status");
+    Self {
+      text_input: TextInputState::with_initial(config, initial.unwrap_or_default()),
+    }
+  }
+
+  /// Draw the input overlay centered in the parent area.
+  pub fn draw(&self, f: &mut ratatui::Frame, parent: Rect) {
+    self.text_input.draw(f, parent);
+  }
+
+  /// Handle key events. Returns a `FilterInputAction` describing what happened.
+  pub fn handle_key(&mut self, key: KeyEvent) -> FilterInputAction {
+    let before = self.text_input.input.clone();
+    match self.text_input.handle_key(key) {
+      TextInputOutcome::Continue if self.text_input.input != before => {
+        FilterInputAction::Changed(self.text_input.input.clone())
+      }
+      TextInputOutcome::Continue => FilterInputAction::None,
+      TextInputOutcome::Canceled => FilterInputAction::Clear,
+      TextInputOutcome::Submit(_) => FilterInputAction::Apply,
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crossterm::event::KeyCode;
+
+  #[test]
+  fn typing_reports_changed_filter() {
+    let mut state = FilterInputState::new(Some("al"));
+    let action = state.handle_key(KeyEvent::from(KeyCode::Char('p')));
+    assert!(
+      matches!(&action, FilterInputAction::Changed(text) if text == "alp"),
+      "expected Changed(\"alp\"), got {action:?}"
+    );
+  }
+
+  #[test]
+  fn esc_clears_and_enter_applies() {
+    let mut state = FilterInputState::new(None);
+    assert!(matches!(
+      state.handle_key(KeyEvent::from(KeyCode::Esc)),
+      FilterInputAction::Clear
+    ));
+    assert!(matches!(
+      state.handle_key(KeyEvent::from(KeyCode::Enter)),
+      FilterInputAction::Apply
+    ));
+  }
+}
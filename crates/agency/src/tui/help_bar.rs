@@ -12,6 +12,8 @@ pub const HELP_ITEMS: &[&str] = &[
   "Start: s",
   "Stop: S",
   "Files: f",
+  "Filter: /",
+  "Sort: t/T",
   "Merge: m",
   "Complete: C",
   "Shell: o",
@@ -36,6 +38,9 @@ pub const HELP_ITEMS_FILES: &[&str] = &[
 /// Help items for file input overlay.
 pub const HELP_ITEMS_FILE_INPUT: &[&str] = &["Type path", "Submit: ⏎", "Cancel: Esc"];
 
+/// Help items for the task filter overlay.
+pub const HELP_ITEMS_FILTER: &[&str] = &["Type slug or status", "Apply: ⏎", "Clear: Esc"];
+
 /// Help items for input overlay.
 pub const HELP_ITEMS_INPUT: &[&str] = &[
   "Type slug",
@@ -47,10 +52,10 @@ pub const HELP_ITEMS_INPUT: &[&str] = &[
 /// Help items for command log pane.
 pub const HELP_ITEMS_LOG: &[&str] = &["Scroll: j/k"];
 
-/// Draw the help bar with custom items.
-pub fn draw_with_items(f: &mut ratatui::Frame, area: Rect, items: &[&str]) {
+/// Draw the help bar with custom items in the given color.
+pub fn draw_with_items(f: &mut ratatui::Frame, area: Rect, items: &[&str], color: Color) {
   let mut lines = layout_lines(items, area.width);
-  lines = lines.into_iter().map(|ln| ln.fg(Color::Blue)).collect();
+  lines = lines.into_iter().map(|ln| ln.fg(color)).collect();
   f.render_widget(Paragraph::new(lines).alignment(Alignment::Center), area);
 }
 
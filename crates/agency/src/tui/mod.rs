@@ -4,11 +4,13 @@ mod command_log;
 mod confirm_dialog;
 mod file_input_overlay;
 mod files_overlay;
+mod filter_input_overlay;
 mod help_bar;
 mod layout;
 mod select_menu;
 mod task_input_overlay;
 mod task_table;
 mod text_input;
+mod theme;
 
 pub use app::run;
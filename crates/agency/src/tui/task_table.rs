@@ -4,16 +4,18 @@ use std::time::{Duration, Instant};
 use crossterm::event::{KeyCode, KeyEvent};
 use ratatui::layout::{Constraint, Rect};
 use ratatui::prelude::Stylize;
-use ratatui::style::{Color, Style};
+use ratatui::style::{Color, Modifier, Style};
 use ratatui::text::{Line, Span};
 use ratatui::widgets::{Block, Borders, Cell, Row, Table, TableState};
 
 use crate::config::AppContext;
 use crate::daemon_protocol::SessionInfo;
 use crate::tui::colors::ansi_to_spans;
+use crate::tui::theme::Theme;
 use crate::utils::sessions::latest_sessions_by_task;
-use crate::utils::task::{TaskRef, list_tasks};
-use crate::utils::task_columns::{GitMetrics, TaskColumn, TaskRow};
+use crate::utils::status::derive_status;
+use crate::utils::task::{Priority, TaskRef, list_tasks};
+use crate::utils::task_columns::{GitMetrics, SortColumn, TaskColumn, TaskRow};
 
 /// Actions that can be triggered from the task table.
 #[derive(Clone, Debug)]
@@ -62,8 +64,15 @@ pub enum Action {
 
 /// State for the task table component.
 pub struct TaskTableState {
+  /// Rows matching the active filter, in display order.
   pub rows: Vec<TaskRow>,
   pub selected: usize,
+  /// All rows from the last refresh, before filtering.
+  all_rows: Vec<TaskRow>,
+  /// Case-insensitive substring matched against slug or status.
+  filter: Option<String>,
+  sort: SortColumn,
+  sort_descending: bool,
   /// Tasks being deleted to show immediate "Loading" feedback.
   pending_delete: HashMap<u32, Instant>,
   /// TUI id for focus events (set externally).
@@ -81,6 +90,10 @@ impl TaskTableState {
     Self {
       rows: Vec::new(),
       selected: 0,
+      all_rows: Vec::new(),
+      filter: None,
+      sort: SortColumn::Id,
+      sort_descending: false,
       pending_delete: HashMap::new(),
       tui_id: None,
     }
@@ -98,23 +111,66 @@ impl TaskTableState {
 
     let latest = latest_sessions_by_task(sessions);
 
-    let rows: Vec<TaskRow> = tasks
+    self.all_rows = tasks
       .iter()
       .map(|t| {
         let metrics = git_metrics.get(t).cloned().unwrap_or_default();
         TaskRow::new(ctx, t.clone(), latest.get(t), metrics)
       })
       .collect();
-
-    self.selected = if rows.is_empty() {
-      0
-    } else {
-      self.selected.min(rows.len().saturating_sub(1))
-    };
-    self.rows = rows;
+    self.apply_filter();
     Ok(())
   }
 
+  /// Active filter text, if any.
+  pub fn filter(&self) -> Option<&str> {
+    self.filter.as_deref()
+  }
+
+  /// Set or clear (`None` or empty) the row filter and re-filter the rows.
+  pub fn set_filter(&mut self, filter: Option<String>) {
+    self.filter = filter.filter(|text| !text.is_empty());
+    self.apply_filter();
+  }
+
+  /// Sort by the next column, keeping the selected task selected.
+  pub fn cycle_sort(&mut self) {
+    self.sort = self.sort.next();
+    self.apply_filter();
+  }
+
+  /// Toggle between ascending and descending order.
+  pub fn toggle_sort_direction(&mut self) {
+    self.sort_descending = !self.sort_descending;
+    self.apply_filter();
+  }
+
+  /// Rebuild visible rows from `all_rows` in sort order, keeping the selected task
+  /// when it still matches and clamping the selection otherwise.
+  fn apply_filter(&mut self) {
+    let selected_id = self.selected_row().map(TaskRow::id);
+    let needle = self.filter.as_deref().map(str::to_lowercase);
+    self.rows = self
+      .all_rows
+      .iter()
+      .filter(|row| {
+        needle
+          .as_deref()
+          .is_none_or(|needle| row_matches(row, needle))
+      })
+      .cloned()
+      .collect();
+    // Stable sort on id-ordered rows, so ties stay in id order
+    let (sort, descending) = (self.sort, self.sort_descending);
+    self.rows.sort_by(|left, right| {
+      let order = sort.compare(left, right);
+      if descending { order.reverse() } else { order }
+    });
+    self.selected = selected_id
+      .and_then(|id| self.rows.iter().position(|row| row.id() == id))
+      .unwrap_or_else(|| self.selected.min(self.rows.len().saturating_sub(1)));
+  }
+
   /// Mark a task as pending delete for immediate UI feedback.
   pub fn mark_pending_delete(&mut self, id: u32) {
     self
@@ -132,25 +188,49 @@ impl TaskTableState {
   }
 
   /// Draw the task table.
-  pub fn draw(&self, f: &mut ratatui::Frame, area: Rect, focused: bool) {
+  pub fn draw(&self, f: &mut ratatui::Frame, area: Rect, focused: bool, theme: &Theme) {
     let columns = TaskColumn::visible_columns(&self.rows);
 
-    let header_cells: Vec<Cell> = columns.iter().map(|col| Cell::from(col.header())).collect();
+    let sort_column = self.sort.column();
+    let header_cells: Vec<Cell> = columns
+      .iter()
+      .map(|col| {
+        if *col != sort_column {
+          return Cell::from(col.header());
+        }
+        let arrow = if self.sort_descending { "▼" } else { "▲" };
+        Cell::from(format!("{} {arrow}", col.header()))
+      })
+      .collect();
     let header = Row::new(header_cells).style(Style::default().fg(Color::Gray));
 
     let rows = self.rows.iter().map(|r| {
       let pending = self.pending_delete.contains_key(&r.id());
       let cells: Vec<Cell> = columns
         .iter()
-        .map(|col| Cell::from(Line::from(ansi_to_spans(&col.cell(r, pending)))))
+        .map(|col| match col {
+          TaskColumn::Status if !pending => {
+            let status = derive_status(r.session.as_ref(), r.wt_exists);
+            let style = theme.status_style(&status);
+            Cell::from(Span::styled(status.label().to_string(), style))
+          }
+          _ => Cell::from(Line::from(ansi_to_spans(&col.cell(r, pending)))),
+        })
         .collect();
+      if r.priority() == Some(Priority::High) {
+        return Row::new(cells).style(Style::default().add_modifier(Modifier::BOLD));
+      }
       Row::new(cells)
     });
 
+    let title = match self.filter() {
+      Some(filter) => format!("[1] Tasks (/{filter})"),
+      None => "[1] Tasks".to_string(),
+    };
     let left_title = if focused {
-      Line::from("[1] Tasks").fg(Color::Cyan)
+      Line::from(title).fg(Color::Cyan)
     } else {
-      Line::from("[1] Tasks")
+      Line::from(title)
     };
     let mut table_block = Block::default().borders(Borders::ALL).title(left_title);
     if let Some(id) = self.tui_id {
@@ -197,6 +277,14 @@ impl TaskTableState {
       KeyCode::Char('X') => self.action_for_id(|id| Action::DeleteTask { id }),
       KeyCode::Char('R') => self.action_for_id(|id| Action::ResetTask { id }),
       KeyCode::Char('f') => self.action_for_task(|task| Action::OpenFilesOverlay { task }),
+      KeyCode::Char('t') => {
+        self.cycle_sort();
+        Action::None
+      }
+      KeyCode::Char('T') => {
+        self.toggle_sort_direction();
+        Action::None
+      }
       _ => Action::None,
     }
   }
@@ -248,13 +336,20 @@ impl TaskTableState {
   }
 }
 
+/// Whether `row` matches the lowercase `needle` by slug or status label.
+fn row_matches(row: &TaskRow, needle: &str) -> bool {
+  let status = derive_status(row.session.as_ref(), row.wt_exists);
+  row.task.slug.to_lowercase().contains(needle) || status.label().to_lowercase().contains(needle)
+}
+
 #[cfg(test)]
 mod tests {
   use super::*;
-  use crate::config::AgencyPaths;
+  use crate::config::{AgencyConfig, AgencyPaths};
   use crate::daemon_protocol::TaskMeta;
-  use crate::utils::task::TaskRef;
+  use crate::utils::task::{TaskFrontmatter, TaskRef};
   use crate::utils::term::strip_ansi_control_codes;
+  use crate::utils::tty::Tty;
 
   fn make_task(id: u32, slug: &str) -> TaskRef {
     TaskRef {
@@ -280,6 +375,7 @@ mod tests {
       status: status.to_string(),
       clients: 1,
       created_at_ms,
+      ..Default::default()
     }
   }
 
@@ -288,8 +384,8 @@ mod tests {
     let dir = tempfile::TempDir::new().expect("tmp");
     let ctx = AppContext {
       paths: AgencyPaths::new(dir.path(), dir.path()),
-      config: crate::config::AgencyConfig::default(),
-      tty: crate::utils::tty::Tty::new(),
+      config: AgencyConfig::default(),
+      tty: Tty::new(),
     };
     let task = make_task(1, "alpha");
     let session = make_session(9, 1, "alpha", "Running", 900);
@@ -308,8 +404,8 @@ mod tests {
     let dir = tempfile::TempDir::new().expect("tmp");
     let ctx = AppContext {
       paths: AgencyPaths::new(dir.path(), dir.path()),
-      config: crate::config::AgencyConfig::default(),
-      tty: crate::utils::tty::Tty::new(),
+      config: AgencyConfig::default(),
+      tty: Tty::new(),
     };
     let task = make_task(1, "alpha");
     let row = TaskRow::new(&ctx, task, None, GitMetrics::default());
@@ -325,8 +421,8 @@ mod tests {
     let dir = tempfile::TempDir::new().expect("tmp");
     let ctx = AppContext {
       paths: AgencyPaths::new(dir.path(), dir.path()),
-      config: crate::config::AgencyConfig::default(),
-      tty: crate::utils::tty::Tty::new(),
+      config: AgencyConfig::default(),
+      tty: Tty::new(),
     };
     let task = make_task(2, "beta");
     let session = make_session(11, 2, "beta", "Exited", 1100);
@@ -336,13 +432,130 @@ mod tests {
     assert_eq!(strip_ansi_control_codes(&status_cell), "Exited");
   }
 
+  #[test]
+  fn filter_narrows_rows_and_clamps_selection() {
+    let dir = tempfile::TempDir::new().expect("tmp");
+    let ctx = AppContext {
+      paths: AgencyPaths::new(dir.path(), dir.path()),
+      config: AgencyConfig::default(),
+      tty: Tty::new(),
+    };
+    let running = make_session(5, 2, "beta", "Running", 500);
+    let mut state = TaskTableState::new();
+    state.all_rows = vec![
+      TaskRow::new(&ctx, make_task(1, "alpha"), None, GitMetrics::default()),
+      TaskRow::new(
+        &ctx,
+        make_task(2, "beta"),
+        Some(&running),
+        GitMetrics::default(),
+      ),
+      TaskRow::new(&ctx, make_task(3, "gamma"), None, GitMetrics::default()),
+    ];
+    state.set_filter(None);
+    state.selected = 2;
+
+    state.set_filter(Some("RUN".to_string()));
+    let ids: Vec<u32> = state.rows.iter().map(TaskRow::id).collect();
+    assert_eq!(ids, vec![2]);
+    assert_eq!(state.selected, 0);
+
+    state.set_filter(Some("ta".to_string()));
+    let ids: Vec<u32> = state.rows.iter().map(TaskRow::id).collect();
+    assert_eq!(ids, vec![2]);
+
+    state.set_filter(Some(String::new()));
+    assert_eq!(state.filter(), None);
+    assert_eq!(state.rows.len(), 3);
+    assert_eq!(state.selected_row().map(TaskRow::id), Some(2));
+  }
+
+  #[test]
+  fn sort_keeps_selected_task_and_is_stable() {
+    let dir = tempfile::TempDir::new().expect("tmp");
+    let ctx = AppContext {
+      paths: AgencyPaths::new(dir.path(), dir.path()),
+      config: AgencyConfig::default(),
+      tty: Tty::new(),
+    };
+    let metrics = |commits_ahead| GitMetrics {
+      commits_ahead,
+      ..GitMetrics::default()
+    };
+    let mut state = TaskTableState::new();
+    state.all_rows = vec![
+      TaskRow::new(&ctx, make_task(1, "alpha"), None, metrics(2)),
+      TaskRow::new(&ctx, make_task(2, "beta"), None, metrics(5)),
+      TaskRow::new(&ctx, make_task(3, "gamma"), None, metrics(2)),
+    ];
+    state.set_filter(None);
+    state.selected = 0;
+
+    state.cycle_sort();
+    state.cycle_sort();
+    state.cycle_sort();
+    state.toggle_sort_direction();
+    let ids: Vec<u32> = state.rows.iter().map(TaskRow::id).collect();
+    assert_eq!(ids, vec![2, 1, 3]);
+    assert_eq!(state.selected_row().map(TaskRow::id), Some(1));
+
+    // Priority ties keep id order, then the cycle wraps around to id
+    state.cycle_sort();
+    state.cycle_sort();
+    let ids: Vec<u32> = state.rows.iter().map(TaskRow::id).collect();
+    assert_eq!(ids, vec![3, 2, 1]);
+    assert_eq!(state.selected_row().map(TaskRow::id), Some(1));
+  }
+
+  #[test]
+  fn sort_by_priority_puts_high_first_and_unset_with_normal() {
+    let dir = tempfile::TempDir::new().expect("tmp");
+    let ctx = AppContext {
+      paths: AgencyPaths::new(dir.path(), dir.path()),
+      config: AgencyConfig::default(),
+      tty: Tty::new(),
+    };
+    let row = |id, slug, priority| TaskRow {
+      frontmatter: Some(TaskFrontmatter {
+        priority,
+        ..TaskFrontmatter::default()
+      }),
+      ..TaskRow::new(&ctx, make_task(id, slug), None, GitMetrics::default())
+    };
+    let mut state = TaskTableState::new();
+    state.all_rows = vec![
+      row(1, "alpha", Some(Priority::Low)),
+      row(2, "beta", None),
+      row(3, "gamma", Some(Priority::High)),
+      row(4, "delta", Some(Priority::Normal)),
+    ];
+    state.sort = SortColumn::Priority;
+    state.set_filter(None);
+
+    let ids: Vec<u32> = state.rows.iter().map(TaskRow::id).collect();
+    assert_eq!(ids, vec![3, 2, 4, 1]);
+    assert!(TaskColumn::visible_columns(&state.rows).contains(&TaskColumn::Priority));
+    assert_eq!(
+      strip_ansi_control_codes(&TaskColumn::Priority.cell(&state.rows[0], false)),
+      "high"
+    );
+    assert_eq!(
+      strip_ansi_control_codes(&TaskColumn::Priority.cell(&state.rows[1], false)),
+      "-"
+    );
+
+    state.toggle_sort_direction();
+    let ids: Vec<u32> = state.rows.iter().map(TaskRow::id).collect();
+    assert_eq!(ids, vec![1, 2, 4, 3]);
+  }
+
   #[test]
   fn task_row_with_idle_session() {
     let dir = tempfile::TempDir::new().expect("tmp");
     let ctx = AppContext {
       paths: AgencyPaths::new(dir.path(), dir.path()),
-      config: crate::config::AgencyConfig::default(),
-      tty: crate::utils::tty::Tty::new(),
+      config: AgencyConfig::default(),
+      tty: Tty::new(),
     };
     let task = make_task(1, "alpha");
     let session = make_session(10, 1, "alpha", "Idle", 1000);
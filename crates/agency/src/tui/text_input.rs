@@ -67,7 +67,6 @@ impl TextInputState {
   }
 
   /// Create with an initial value.
-  #[allow(dead_code)]
   pub fn with_initial(config: TextInputConfig, initial: impl Into<String>) -> Self {
     Self {
       input: initial.into(),
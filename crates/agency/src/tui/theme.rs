@@ -0,0 +1,138 @@
+use ratatui::style::{Color, Style};
+
+use crate::config::AgencyConfig;
+use crate::log_warn;
+use crate::utils::status::TaskStatus;
+
+/// Element names accepted in `[tui.theme]`.
+const THEME_KEYS: &[&str] = &[
+  "running", "idle", "paused", "stopped", "exited", "failed", "draft", "help",
+];
+
+/// Colors used by the TUI, configurable via `[tui.theme]`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Theme {
+  pub running: Color,
+  pub idle: Color,
+  pub paused: Color,
+  pub stopped: Color,
+  pub exited: Color,
+  pub failed: Color,
+  pub draft: Color,
+  pub help: Color,
+}
+
+impl Default for Theme {
+  fn default() -> Self {
+    Self {
+      running: Color::Green,
+      idle: Color::Blue,
+      paused: Color::Magenta,
+      stopped: Color::Red,
+      exited: Color::Red,
+      failed: Color::LightRed,
+      draft: Color::Yellow,
+      help: Color::Blue,
+    }
+  }
+}
+
+impl Theme {
+  /// Build the theme from `[tui.theme]`, warning about and skipping invalid entries.
+  #[must_use]
+  pub fn from_config(cfg: &AgencyConfig) -> Self {
+    let mut theme = Self::default();
+    let Some(tui) = cfg.tui.as_ref() else {
+      return theme;
+    };
+    for (key, value) in &tui.theme {
+      let Some(slot) = theme.slot_mut(key) else {
+        log_warn!(
+          "Unknown tui.theme key '{}' (known keys: {})",
+          key,
+          THEME_KEYS.join(", ")
+        );
+        continue;
+      };
+      match parse_color(value) {
+        Some(color) => *slot = color,
+        None => log_warn!(
+          "Invalid color '{}' for tui.theme.{}; using default",
+          value,
+          key
+        ),
+      }
+    }
+    theme
+  }
+
+  /// Style for a task status label.
+  #[must_use]
+  pub fn status_style(&self, status: &TaskStatus) -> Style {
+    let color = match status {
+      TaskStatus::Running => self.running,
+      TaskStatus::Idle => self.idle,
+      TaskStatus::Paused => self.paused,
+      TaskStatus::Stopped => self.stopped,
+      TaskStatus::Exited => self.exited,
+      TaskStatus::Failed => self.failed,
+      TaskStatus::Draft => self.draft,
+      TaskStatus::Other(_) => return Style::default(),
+    };
+    Style::default().fg(color)
+  }
+
+  fn slot_mut(&mut self, key: &str) -> Option<&mut Color> {
+    match key {
+      "running" => Some(&mut self.running),
+      "idle" => Some(&mut self.idle),
+      "paused" => Some(&mut self.paused),
+      "stopped" => Some(&mut self.stopped),
+      "exited" => Some(&mut self.exited),
+      "failed" => Some(&mut self.failed),
+      "draft" => Some(&mut self.draft),
+      "help" => Some(&mut self.help),
+      _ => None,
+    }
+  }
+}
+
+/// Parse a color name (`light-red`), `#rrggbb`, or a 256-color index (`208`).
+#[must_use]
+pub fn parse_color(value: &str) -> Option<Color> {
+  value.trim().parse::<Color>().ok()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{Theme, parse_color};
+  use crate::config::{AgencyConfig, TuiConfig};
+  use ratatui::style::Color;
+
+  #[test]
+  fn parse_color_supports_names_hex_and_indexes() {
+    assert_eq!(parse_color("green"), Some(Color::Green));
+    assert_eq!(parse_color("light-blue"), Some(Color::LightBlue));
+    assert_eq!(parse_color("#1e90ff"), Some(Color::Rgb(30, 144, 255)));
+    assert_eq!(parse_color("208"), Some(Color::Indexed(208)));
+    assert_eq!(parse_color("not-a-color"), None);
+  }
+
+  #[test]
+  fn theme_falls_back_to_defaults_for_invalid_colors() {
+    let mut tui = TuiConfig::default();
+    tui
+      .theme
+      .insert("running".to_string(), "#00aa00".to_string());
+    tui.theme.insert("draft".to_string(), "sparkly".to_string());
+    let cfg = AgencyConfig {
+      tui: Some(tui),
+      ..AgencyConfig::default()
+    };
+
+    let theme = Theme::from_config(&cfg);
+
+    assert_eq!(theme.running, Color::Rgb(0, 170, 0));
+    assert_eq!(theme.draft, Theme::default().draft);
+  }
+}
@@ -0,0 +1,165 @@
+use std::fs::File;
+use std::io::Read as _;
+use std::path::{Component, Path};
+
+use anyhow::{Context, Result, bail};
+use flate2::Compression;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+
+/// Name of the task markdown inside an export archive.
+const TASK_ENTRY: &str = "task.md";
+/// Directory holding the attached files inside an export archive.
+const FILES_DIR: &str = "files";
+/// Name of the branch patch inside an export archive.
+const PATCH_ENTRY: &str = "changes.patch";
+
+/// Portable bundle of a task, stored as `<slug>/task.md`, `<slug>/files/*`
+/// and `<slug>/changes.patch` inside a tar.gz archive.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TaskArchive {
+  pub slug: String,
+  pub task_md: String,
+  /// Attached files as (stored filename, content), e.g. `("1-notes.txt", ..)`.
+  pub files: Vec<(String, Vec<u8>)>,
+  /// Diff of the task branch against its base, kept as raw bytes since it may hold
+  /// content that is not UTF-8; `None` when the branch does not exist.
+  pub patch: Option<Vec<u8>>,
+}
+
+/// Write `archive` as a gzip-compressed tarball to `out`.
+///
+/// # Errors
+/// Returns an error if the output file cannot be created or written.
+pub fn write_task_archive(out: &Path, archive: &TaskArchive) -> Result<()> {
+  let file = File::create(out).with_context(|| format!("failed to create {}", out.display()))?;
+  let mut builder = tar::Builder::new(GzEncoder::new(file, Compression::default()));
+  let root = Path::new(&archive.slug);
+
+  append_entry(
+    &mut builder,
+    &root.join(TASK_ENTRY),
+    archive.task_md.as_bytes(),
+  )?;
+  for (name, data) in &archive.files {
+    append_entry(&mut builder, &root.join(FILES_DIR).join(name), data)?;
+  }
+  if let Some(patch) = &archive.patch {
+    append_entry(&mut builder, &root.join(PATCH_ENTRY), patch)?;
+  }
+
+  let encoder = builder
+    .into_inner()
+    .with_context(|| format!("failed to finish {}", out.display()))?;
+  encoder
+    .finish()
+    .with_context(|| format!("failed to finish {}", out.display()))?;
+  Ok(())
+}
+
+/// Read a task archive produced by [`write_task_archive`].
+///
+/// Entries outside the expected layout are ignored.
+///
+/// # Errors
+/// Returns an error if the archive cannot be read or has no `task.md`.
+pub fn read_task_archive(path: &Path) -> Result<TaskArchive> {
+  let file = File::open(path).with_context(|| format!("failed to open {}", path.display()))?;
+  let mut tar = tar::Archive::new(GzDecoder::new(file));
+  let mut slug: Option<String> = None;
+  let mut task_md: Option<String> = None;
+  let mut files = Vec::new();
+  let mut patch = None;
+
+  let entries = tar
+    .entries()
+    .with_context(|| format!("failed to read {}", path.display()))?;
+  for entry in entries {
+    let mut entry = entry.with_context(|| format!("failed to read {}", path.display()))?;
+    if !entry.header().entry_type().is_file() {
+      continue;
+    }
+    let entry_path = entry.path()?.into_owned();
+    let Some(parts) = normal_components(&entry_path) else {
+      continue;
+    };
+    let mut data = Vec::new();
+    entry
+      .read_to_end(&mut data)
+      .with_context(|| format!("failed to read {}", entry_path.display()))?;
+    match parts.as_slice() {
+      [root, name] if name == TASK_ENTRY => {
+        slug = Some(root.clone());
+        task_md = Some(String::from_utf8_lossy(&data).into_owned());
+      }
+      [_, name] if name == PATCH_ENTRY => {
+        patch = Some(data);
+      }
+      [_, dir, name] if dir == FILES_DIR => files.push((name.clone(), data)),
+      _ => {}
+    }
+  }
+
+  let (Some(slug), Some(task_md)) = (slug, task_md) else {
+    bail!(
+      "{} is not a task archive (missing {TASK_ENTRY})",
+      path.display()
+    );
+  };
+  files.sort_by(|left, right| left.0.cmp(&right.0));
+  Ok(TaskArchive {
+    slug,
+    task_md,
+    files,
+    patch,
+  })
+}
+
+fn append_entry<W: std::io::Write>(
+  builder: &mut tar::Builder<W>,
+  path: &Path,
+  data: &[u8],
+) -> Result<()> {
+  let mut header = tar::Header::new_gnu();
+  header.set_size(u64::try_from(data.len()).context("archive entry is too large")?);
+  header.set_mode(0o644);
+  builder
+    .append_data(&mut header, path, data)
+    .with_context(|| format!("failed to add {} to archive", path.display()))
+}
+
+/// Split an entry path into plain components, rejecting absolute or `..` paths.
+fn normal_components(path: &Path) -> Option<Vec<String>> {
+  path
+    .components()
+    .map(|component| match component {
+      Component::Normal(part) => part.to_str().map(str::to_string),
+      _ => None,
+    })
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{TaskArchive, read_task_archive, write_task_archive};
+
+  #[test]
+  fn task_archive_round_trips() {
+    let dir = tempfile::tempdir().expect("temp dir");
+    let path = dir.path().join("task.tar.gz");
+    let archive = TaskArchive {
+      slug: "fix-login".to_string(),
+      task_md: "---\nagent: sh\n---\n\nFix it\n".to_string(),
+      files: vec![
+        ("1-notes.txt".to_string(), b"notes".to_vec()),
+        ("2-shot.png".to_string(), vec![0, 159, 146, 150]),
+      ],
+      patch: Some(b"diff --git a/a b/a\n\xff\n".to_vec()),
+    };
+
+    write_task_archive(&path, &archive).expect("write archive");
+    let restored = read_task_archive(&path).expect("read archive");
+
+    assert_eq!(restored, archive);
+  }
+}
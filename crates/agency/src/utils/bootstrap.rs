@@ -3,6 +3,7 @@ use std::path::{Path, PathBuf};
 
 use anyhow::{Context, Result};
 use glob::glob;
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
 use reflink_copy::reflink_or_copy;
 
 use crate::config::{AppContext, BootstrapConfig};
@@ -17,13 +18,19 @@ use gix as git;
 /// Maximum file size for any bootstrap file copying (10MB)
 const MAX_BOOTSTRAP_FILE_BYTES: u64 = 10 * 1024 * 1024;
 
+/// Repo-root file with gitignore-style patterns excluded from bootstrap copying.
+const AGENCYIGNORE_FILE: &str = ".agencyignore";
+
 /// Bootstrap a worktree by copying gitignored root files and included directories.
+///
+/// Entries matched by config excludes or by `.agencyignore` are skipped.
 pub fn bootstrap_worktree(
   root_workdir: &Path,
   dst_worktree: &Path,
   cfg: &BootstrapConfig,
 ) -> Result<()> {
-  copy_gitignored_root_files(root_workdir, dst_worktree, cfg)?;
+  let agencyignore = load_agencyignore(root_workdir);
+  copy_gitignored_root_files(root_workdir, dst_worktree, cfg, &agencyignore)?;
 
   // Copy explicitly included files and directories using glob patterns
   for pattern in &cfg.include {
@@ -52,13 +59,16 @@ pub fn bootstrap_worktree(
 
       let metadata = fs::metadata(&path)
         .with_context(|| format!("failed to read metadata for {}", path.display()))?;
+      if is_agencyignored(&agencyignore, Path::new(&name), metadata.is_dir()) {
+        continue;
+      }
 
       if metadata.is_file() {
         let dst_file = dst_worktree.join(&name);
         copy_included_file(&path, &dst_file)?;
       } else if metadata.is_dir() {
         let dst_dir = dst_worktree.join(&name);
-        copy_dir_tree(&path, &dst_dir)?;
+        copy_dir_tree(&path, &dst_dir, Path::new(&name), &agencyignore)?;
       }
     }
 
@@ -77,6 +87,7 @@ fn copy_gitignored_root_files(
   root_workdir: &Path,
   dst_worktree: &Path,
   cfg: &BootstrapConfig,
+  agencyignore: &Gitignore,
 ) -> Result<()> {
   let entries = discover_root_entries(root_workdir)?;
 
@@ -91,7 +102,7 @@ fn copy_gitignored_root_files(
       continue;
     }
     let name = entry.file_name().to_string_lossy().to_string();
-    if is_excluded(&name, cfg) {
+    if is_excluded(&name, cfg) || is_agencyignored(agencyignore, Path::new(&name), false) {
       continue;
     }
     file_names.push(name);
@@ -217,14 +228,22 @@ pub fn create_worktree_for_task(
   })
 }
 
-/// Run the configured bootstrap command with custom environment variables.
+/// Run the configured bootstrap commands in order with custom environment variables.
+///
+/// Stops at the first command that fails to start or exits non-zero and warns
+/// which step failed.
 pub fn run_bootstrap_cmd_with_env(
   repo_root: &Path,
   worktree_dir: &Path,
   cfg: &BootstrapConfig,
   env_vars: &std::collections::HashMap<String, String>,
 ) {
-  if cfg.cmd.is_empty() {
+  let commands: Vec<&[String]> = cfg
+    .commands()
+    .into_iter()
+    .filter(|cmd| !cmd.is_empty())
+    .collect();
+  if commands.is_empty() {
     return;
   }
 
@@ -234,34 +253,46 @@ pub fn run_bootstrap_cmd_with_env(
     .display()
     .to_string();
   let ctx = CmdCtx::with_env(root_abs.clone(), env_vars.clone());
-  let argv = expand_argv(&cfg.cmd, &ctx);
-
-  // Special-case: default path missing should be a silent skip
-  if cfg.cmd.len() == 1 && cfg.cmd[0] == "<root>/.agency/setup.sh" {
-    let candidate = PathBuf::from(&argv[0]);
-    if !candidate.exists() {
-      return;
-    }
-  }
-
-  log_info!("Run bootstrap {}", argv.join(" "));
   let env_overrides: Vec<(String, String)> = env_vars
     .iter()
     .map(|(k, v)| (k.clone(), v.clone()))
     .collect();
-  match run_child_process(&argv[0], &argv[1..], worktree_dir, &env_overrides) {
-    Ok(status) => {
-      if !status.success() {
-        log_warn!("Bootstrap exited with status {}", status);
+  let total = commands.len();
+
+  for (index, cmd) in commands.into_iter().enumerate() {
+    let argv = expand_argv(cmd, &ctx);
+
+    // Special-case: default path missing should be a silent skip
+    if cmd.len() == 1 && cmd[0] == "<root>/.agency/setup.sh" {
+      let candidate = PathBuf::from(&argv[0]);
+      if !candidate.exists() {
+        continue;
       }
     }
-    Err(err) => {
-      log_warn!("Bootstrap failed to start: {}", err);
+
+    let step = if total > 1 {
+      format!(" step {}/{total}", index + 1)
+    } else {
+      String::new()
+    };
+    log_info!("Run bootstrap{} {}", step, argv.join(" "));
+    match run_child_process(&argv[0], &argv[1..], worktree_dir, &env_overrides) {
+      Ok(status) if status.success() => {}
+      Ok(status) => {
+        log_warn!("Bootstrap{} exited with status {}", step, status);
+        return;
+      }
+      Err(err) => {
+        log_warn!("Bootstrap{} failed to start: {}", step, err);
+        return;
+      }
     }
   }
 }
 
-fn create_files_symlink(paths: &crate::config::AgencyPaths, task: &TaskRef, worktree: &Path) {
+/// Link `.agency/local/files` in the worktree to the task's files directory.
+/// Skips when the link already exists and only warns on failure.
+pub fn create_files_symlink(paths: &crate::config::AgencyPaths, task: &TaskRef, worktree: &Path) {
   let files_dir = files_dir_for_task(paths, task);
   let local_path = local_files_path(worktree);
 
@@ -347,6 +378,29 @@ fn is_excluded(entry_name: &str, cfg: &BootstrapConfig) -> bool {
   matches!(entry_name, ".git" | ".agency") || cfg.exclude.iter().any(|e| e == entry_name)
 }
 
+/// Load `.agencyignore` from the repo root. Missing files yield an empty matcher;
+/// invalid lines are skipped with a warning.
+fn load_agencyignore(root_workdir: &Path) -> Gitignore {
+  let path = root_workdir.join(AGENCYIGNORE_FILE);
+  let mut builder = GitignoreBuilder::new(root_workdir);
+  if path.is_file()
+    && let Some(err) = builder.add(&path)
+  {
+    log_warn!("Ignoring invalid patterns in {}: {}", path.display(), err);
+  }
+  builder.build().unwrap_or_else(|err| {
+    log_warn!("Failed to load {}: {}", path.display(), err);
+    Gitignore::empty()
+  })
+}
+
+/// Whether `relative` (from the repo root) matches `.agencyignore`, directly or via a parent.
+fn is_agencyignored(agencyignore: &Gitignore, relative: &Path, is_dir: bool) -> bool {
+  agencyignore
+    .matched_path_or_any_parents(relative, is_dir)
+    .is_ignore()
+}
+
 fn copy_file(src: &Path, dst: &Path) -> Result<()> {
   if let Some(parent) = dst.parent() {
     fs::create_dir_all(parent).with_context(|| format!("failed to create {}", parent.display()))?;
@@ -357,7 +411,12 @@ fn copy_file(src: &Path, dst: &Path) -> Result<()> {
     .with_context(|| format!("failed to copy {} -> {}", src.display(), dst.display()))
 }
 
-fn copy_dir_tree(src_dir: &Path, dst_dir: &Path) -> Result<()> {
+fn copy_dir_tree(
+  src_dir: &Path,
+  dst_dir: &Path,
+  relative: &Path,
+  agencyignore: &Gitignore,
+) -> Result<()> {
   if dst_dir.exists() && !dst_dir.is_dir() {
     anyhow::bail!("destination exists and is not a dir: {}", dst_dir.display());
   }
@@ -369,6 +428,10 @@ fn copy_dir_tree(src_dir: &Path, dst_dir: &Path) -> Result<()> {
     let name = name.to_string_lossy().to_string();
     let src = entry.path();
     let dst = dst_dir.join(&name);
+    let entry_relative = relative.join(&name);
+    if is_agencyignored(agencyignore, &entry_relative, file_type.is_dir()) {
+      continue;
+    }
     if file_type.is_file() {
       if dst.exists() {
         continue;
@@ -379,7 +442,7 @@ fn copy_dir_tree(src_dir: &Path, dst_dir: &Path) -> Result<()> {
       // For included directories, copy regardless of ignore status within the dir
       copy_file(&src, &dst)?;
     } else if file_type.is_dir() {
-      copy_dir_tree(&src, &dst)?;
+      copy_dir_tree(&src, &dst, &entry_relative, agencyignore)?;
     } else if file_type.is_symlink() {
       // Skip symlinks
       // no-op; fall through to next entry
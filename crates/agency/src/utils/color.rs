@@ -0,0 +1,40 @@
+use anstream::ColorChoice;
+
+/// When to emit ANSI colors, selected with the global `--color` flag.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum ColorMode {
+  /// Color only on an interactive terminal and when `NO_COLOR` is unset
+  #[default]
+  Auto,
+  Always,
+  Never,
+}
+
+/// Apply `mode` to everything printed through `anstream` by this process.
+///
+/// `auto` leaves the decision to `anstream`, which checks `NO_COLOR` and
+/// whether each stream is a terminal; `always` and `never` ignore both.
+pub fn apply(mode: ColorMode) {
+  resolve(mode).write_global();
+}
+
+fn resolve(mode: ColorMode) -> ColorChoice {
+  match mode {
+    ColorMode::Auto => ColorChoice::Auto,
+    ColorMode::Always => ColorChoice::Always,
+    ColorMode::Never => ColorChoice::Never,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{ColorMode, resolve};
+  use anstream::ColorChoice;
+
+  #[test]
+  fn modes_map_to_anstream_choices() {
+    assert_eq!(resolve(ColorMode::Auto), ColorChoice::Auto);
+    assert_eq!(resolve(ColorMode::Always), ColorChoice::Always);
+    assert_eq!(resolve(ColorMode::Never), ColorChoice::Never);
+  }
+}
@@ -123,6 +123,7 @@ mod tests {
   fn agent_config_get_cmd_success() -> Result<()> {
     let ac = AgentConfig {
       cmd: vec!["echo".to_string(), "hello".to_string()],
+      ..Default::default()
     };
     let cmd = ac.get_cmd("echo-agent")?;
     assert_eq!(cmd.program, "echo");
@@ -132,7 +133,10 @@ mod tests {
 
   #[test]
   fn agent_config_get_cmd_errors_on_empty() {
-    let ac = AgentConfig { cmd: vec![] };
+    let ac = AgentConfig {
+      cmd: vec![],
+      ..Default::default()
+    };
     let err = ac.get_cmd("x").expect_err("should fail");
     let msg = err.to_string();
     assert!(msg.contains("not defined"));
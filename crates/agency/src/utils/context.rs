@@ -2,7 +2,7 @@ use anyhow::Result;
 
 use crate::config::AgencyPaths;
 use crate::utils::files::local_files_path;
-use crate::utils::task::{TaskRef, resolve_id_or_slug};
+use crate::utils::task::{TaskRef, list_tasks, resolve_id_or_slug, worktree_dir};
 
 /// Detect the current task from the `AGENCY_TASK_ID` environment variable.
 ///
@@ -14,6 +14,24 @@ pub fn detect_task_from_env(paths: &AgencyPaths) -> Result<TaskRef> {
   resolve_id_or_slug(paths, &task_id)
 }
 
+/// Find the task whose worktree contains the current working directory.
+///
+/// Paths are canonicalized, so symlinks to a worktree (or into it) resolve to their task.
+///
+/// # Errors
+/// Returns an error if the tasks directory cannot be read.
+pub fn task_for_cwd(paths: &AgencyPaths) -> Result<Option<TaskRef>> {
+  let Ok(cwd) = paths.cwd().canonicalize() else {
+    return Ok(None);
+  };
+  let task = list_tasks(paths)?.into_iter().find(|task| {
+    worktree_dir(paths, task)
+      .canonicalize()
+      .is_ok_and(|wt_dir| cwd.starts_with(wt_dir))
+  });
+  Ok(task)
+}
+
 /// Check if the current working directory is inside an agency worktree.
 ///
 /// Detects this by checking if `.agency/local/files` exists (symlink or directory)
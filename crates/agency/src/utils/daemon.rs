@@ -1,15 +1,17 @@
 use crate::config::{AppContext, compute_socket_path};
 use crate::daemon_protocol::{
-  C2D, C2DControl, D2C, D2CControl, ProjectKey, SessionInfo, TaskInfo, TaskMetrics, TuiListItem,
-  read_frame, write_frame,
+  AttachmentInfo, C2D, C2DControl, D2C, D2CControl, ProjectKey, SessionInfo, TaskInfo, TaskMetrics,
+  TuiListItem, read_frame, write_frame,
 };
 use crate::log_warn;
 use crate::utils::git::{open_main_repo, repo_workdir_or};
 use crate::utils::task::TaskRef;
 use anyhow::{Context, Result, anyhow, bail};
+use crossbeam_channel::{Receiver, unbounded};
 use std::os::unix::net::UnixStream;
 use std::path::Path;
-use std::time::Duration;
+use std::thread;
+use std::time::{Duration, Instant};
 
 #[cfg(test)]
 use std::sync::atomic::{AtomicU64, Ordering};
@@ -32,6 +34,15 @@ pub fn task_notify_count() -> u64 {
 const DAEMON_NOT_RUNNING_MSG: &str =
   "Daemon not running. Please start it with `agency daemon start`";
 
+/// Delays between round-trip attempts while a freshly started daemon comes up.
+const READY_BACKOFF_MS: [u64; 5] = [50, 100, 200, 400, 800];
+
+/// Upper bound for waiting on a freshly started daemon, so a dead one fails fast.
+const READY_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Read timeout for a single `GetVersion` round-trip.
+const VERSION_REPLY_TIMEOUT: Duration = Duration::from_millis(250);
+
 /// Connect to the daemon socket for the current context and bail with guidance on failure.
 pub fn connect_daemon(ctx: &AppContext) -> anyhow::Result<UnixStream> {
   let socket = compute_socket_path(&ctx.config);
@@ -119,7 +130,6 @@ pub fn notify_tasks_changed(ctx: &AppContext) -> anyhow::Result<()> {
 /// Returns an error with guidance if the daemon is unavailable.
 #[derive(Debug, Clone)]
 pub struct ProjectState {
-  #[allow(dead_code)]
   pub tasks: Vec<TaskInfo>,
   pub sessions: Vec<SessionInfo>,
   pub metrics: Vec<TaskMetrics>,
@@ -127,6 +137,17 @@ pub struct ProjectState {
 
 /// Best-effort helper to fetch a one-shot project state snapshot.
 pub fn get_project_state(ctx: &AppContext) -> anyhow::Result<ProjectState> {
+  get_filtered_project_state(ctx, &[], &[])
+}
+
+/// Like [`get_project_state`], but the daemon keeps only tasks whose status
+/// matches one of `statuses` and that carry every label in `labels`. An empty
+/// slice disables its filter.
+pub fn get_filtered_project_state(
+  ctx: &AppContext,
+  statuses: &[String],
+  labels: &[String],
+) -> anyhow::Result<ProjectState> {
   let socket = compute_socket_path(&ctx.config);
   let repo = open_main_repo(ctx.paths.root())?;
   let repo_root = repo_workdir_or(&repo, ctx.paths.root());
@@ -137,7 +158,11 @@ pub fn get_project_state(ctx: &AppContext) -> anyhow::Result<ProjectState> {
   let mut stream = connect_daemon_socket(&socket)?;
   write_frame(
     &mut stream,
-    &C2D::Control(C2DControl::ListProjectState { project }),
+    &C2D::Control(C2DControl::ListProjectState {
+      project,
+      status_filter: (!statuses.is_empty()).then(|| statuses.to_vec()),
+      label_filter: (!labels.is_empty()).then(|| labels.to_vec()),
+    }),
   )
   .context("failed to write ListProjectState frame")?;
 
@@ -153,12 +178,156 @@ pub fn get_project_state(ctx: &AppContext) -> anyhow::Result<ProjectState> {
       sessions,
       metrics,
     }),
-    Ok(D2C::Control(D2CControl::Error { message })) => bail!("{message}"),
+    Ok(D2C::Control(D2CControl::Error { code, message })) => bail!("Daemon {code}: {message}"),
     Ok(_) => bail!("Protocol error: Expected ProjectState reply"),
     Err(err) => Err(err),
   }
 }
 
+/// Update delivered by [`subscribe_project_events`].
+pub enum ProjectEvent {
+  /// The daemon broadcast the project's current state
+  State(ProjectState),
+  /// The subscription ended; no further events follow
+  Disconnected(anyhow::Error),
+}
+
+/// Subscribe to the project's state broadcasts on a background thread named `thread_name`.
+///
+/// The daemon sends the current state right away and then on every change.
+///
+/// # Errors
+/// Returns an error if the daemon cannot be reached or the thread cannot be spawned.
+pub fn subscribe_project_events(
+  ctx: &AppContext,
+  thread_name: &str,
+) -> Result<Receiver<ProjectEvent>> {
+  let (tx, rx) = unbounded::<ProjectEvent>();
+  let repo = open_main_repo(ctx.paths.root())?;
+  let repo_root = repo_workdir_or(&repo, ctx.paths.root());
+  let project = ProjectKey {
+    repo_root: repo_root.display().to_string(),
+  };
+  let mut stream = connect_daemon(ctx)?;
+  thread::Builder::new()
+    .name(thread_name.to_string())
+    .spawn(move || {
+      if let Err(err) = write_frame(
+        &mut stream,
+        &C2D::Control(C2DControl::SubscribeEvents { project }),
+      ) {
+        let _ = tx.send(ProjectEvent::Disconnected(err));
+        return;
+      }
+      loop {
+        match read_frame::<_, D2C>(&mut stream) {
+          Ok(D2C::Control(D2CControl::ProjectState {
+            project: _,
+            tasks,
+            sessions,
+            metrics,
+          })) => {
+            let state = ProjectState {
+              tasks,
+              sessions,
+              metrics,
+            };
+            if tx.send(ProjectEvent::State(state)).is_err() {
+              break;
+            }
+          }
+          Ok(D2C::Control(D2CControl::Goodbye)) => {
+            let _ = tx.send(ProjectEvent::Disconnected(anyhow!("daemon stopped")));
+            break;
+          }
+          Ok(D2C::Control(_)) => {}
+          Err(err) => {
+            let _ = tx.send(ProjectEvent::Disconnected(err));
+            break;
+          }
+        }
+      }
+    })?;
+  Ok(rx)
+}
+
+/// Fetch the clients attached to the project's task sessions.
+///
+/// # Errors
+/// Returns an error if the daemon cannot be reached or replies unexpectedly.
+pub fn list_attachments(ctx: &AppContext) -> Result<Vec<AttachmentInfo>> {
+  let socket = compute_socket_path(&ctx.config);
+  let repo = open_main_repo(ctx.paths.root())?;
+  let repo_root = repo_workdir_or(&repo, ctx.paths.root());
+  let project = ProjectKey {
+    repo_root: repo_root.display().to_string(),
+  };
+
+  let mut stream = connect_daemon_socket(&socket)?;
+  write_frame(
+    &mut stream,
+    &C2D::Control(C2DControl::ListAttachments { project }),
+  )
+  .context("failed to write ListAttachments frame")?;
+
+  match read_frame(&mut stream)? {
+    D2C::Control(D2CControl::Attachments { items }) => Ok(items),
+    D2C::Control(D2CControl::Error { code, message }) => bail!("Daemon {code}: {message}"),
+    _ => bail!("Protocol error: Expected Attachments reply"),
+  }
+}
+
+/// Resize the session viewed by `attachment_id` for all of its attached clients.
+/// Returns the session id and how many clients now share the size.
+///
+/// # Errors
+/// Returns an error if the daemon is unreachable, the attachment is unknown or
+/// tmux fails to resize.
+pub fn resize_all(
+  ctx: &AppContext,
+  attachment_id: &str,
+  rows: u16,
+  cols: u16,
+) -> Result<(u64, u32)> {
+  let socket = compute_socket_path(&ctx.config);
+  let repo = open_main_repo(ctx.paths.root())?;
+  let repo_root = repo_workdir_or(&repo, ctx.paths.root());
+  let project = ProjectKey {
+    repo_root: repo_root.display().to_string(),
+  };
+
+  let mut stream = connect_daemon_socket(&socket)?;
+  write_frame(
+    &mut stream,
+    &C2D::Control(C2DControl::ResizeAll {
+      project,
+      attachment_id: attachment_id.to_string(),
+      rows,
+      cols,
+    }),
+  )
+  .context("failed to write ResizeAll frame")?;
+
+  match read_frame(&mut stream)? {
+    D2C::Control(D2CControl::Resized {
+      session_id,
+      clients,
+    }) => Ok((session_id, clients)),
+    D2C::Control(D2CControl::Error { code, message }) => bail!("Daemon {code}: {message}"),
+    _ => bail!("Protocol error: Expected Resized reply"),
+  }
+}
+
+/// Best-effort check whether the daemon reports a session for `task`.
+/// Returns false when the daemon is unreachable.
+pub fn task_has_session(ctx: &AppContext, task: &TaskRef) -> bool {
+  get_project_state(ctx)
+    .map(|state| state.sessions)
+    .unwrap_or_default()
+    .iter()
+    .any(|s| s.task.id == task.id && s.task.slug == task.slug)
+}
+
 /// Register a running TUI instance and obtain a numeric id.
 pub fn tui_register(ctx: &AppContext, pid: u32) -> anyhow::Result<u32> {
   let socket = compute_socket_path(&ctx.config);
@@ -174,7 +343,7 @@ pub fn tui_register(ctx: &AppContext, pid: u32) -> anyhow::Result<u32> {
   )?;
   match read_frame::<_, D2C>(&mut stream)? {
     D2C::Control(D2CControl::TuiRegistered { tui_id }) => Ok(tui_id),
-    D2C::Control(D2CControl::Error { message }) => anyhow::bail!(message),
+    D2C::Control(D2CControl::Error { code, message }) => anyhow::bail!("Daemon {code}: {message}"),
     D2C::Control(_) => anyhow::bail!("Protocol error: expected TuiRegistered reply"),
   }
 }
@@ -200,7 +369,7 @@ pub fn tui_list(ctx: &AppContext) -> anyhow::Result<Vec<TuiListItem>> {
   write_frame(&mut stream, &C2D::Control(C2DControl::TuiList { project }))?;
   match read_frame::<_, D2C>(&mut stream)? {
     D2C::Control(D2CControl::TuiList { items }) => Ok(items),
-    D2C::Control(D2CControl::Error { message }) => anyhow::bail!(message),
+    D2C::Control(D2CControl::Error { code, message }) => anyhow::bail!("Daemon {code}: {message}"),
     D2C::Control(_) => anyhow::bail!("Protocol error: expected TuiList reply"),
   }
 }
@@ -208,36 +377,116 @@ pub fn tui_list(ctx: &AppContext) -> anyhow::Result<Vec<TuiListItem>> {
 /// Ensure the daemon is running and matches the current CLI version.
 ///
 /// - Skips when `AGENCY_NO_AUTOSTART=1` is set.
-/// - Starts the daemon if the socket connect fails.
+/// - Starts the daemon if the socket connect fails and waits until it answers.
 /// - If connect succeeds, queries version and restarts on mismatch or unexpected reply.
+///
+/// # Errors
+/// Returns an error if the daemon cannot be started or does not answer after starting.
 pub fn ensure_running_and_latest_version(ctx: &AppContext) -> anyhow::Result<()> {
   if std::env::var("AGENCY_NO_AUTOSTART").ok().as_deref() == Some("1") {
     return Ok(());
   }
 
   let socket = compute_socket_path(&ctx.config);
-  match UnixStream::connect(&socket) {
-    Err(_) => {
-      // Not running -> start
-      crate::commands::daemon::start()?;
-      return Ok(());
+  if UnixStream::connect(&socket).is_err() {
+    // Not running -> start
+    crate::commands::daemon::start()?;
+    return wait_until_responsive(&socket, READY_TIMEOUT);
+  }
+  let cli_ver = crate::utils::version::get_version();
+  let matches = query_version(&socket).is_ok_and(|version| version == cli_ver);
+  if !matches {
+    // Older daemon without version support or mismatched -> restart daemon only
+    crate::commands::daemon::restart_daemon_only()?;
+    return wait_until_responsive(&socket, READY_TIMEOUT);
+  }
+  Ok(())
+}
+
+/// Wait until the daemon at `socket` answers a `GetVersion` round-trip.
+///
+/// The socket file can appear before the daemon accepts connections, so this
+/// retries with exponential backoff and returns as soon as a reply arrives.
+///
+/// # Errors
+/// Returns an error if no round-trip succeeds before the backoff or `timeout` runs out.
+pub fn wait_until_responsive(socket: &Path, timeout: Duration) -> Result<()> {
+  let deadline = Instant::now() + timeout;
+  let mut last_err = anyhow!(DAEMON_NOT_RUNNING_MSG);
+  for delay_ms in READY_BACKOFF_MS {
+    match query_version(socket) {
+      Ok(_) => return Ok(()),
+      Err(err) => last_err = err,
     }
-    Ok(mut stream) => {
-      // Connected: query version with a short timeout
-      let _ = stream.set_read_timeout(Some(Duration::from_millis(250)));
-      write_frame(&mut stream, &C2D::Control(C2DControl::GetVersion))
-        .context("failed to write GetVersion frame")?;
-      let reply: Result<D2C> = read_frame(&mut stream);
-      let cli_ver = crate::utils::version::get_version();
-      let matches = match reply {
-        Ok(D2C::Control(D2CControl::Version { version })) => version == cli_ver,
-        _ => false,
-      };
-      if !matches {
-        // Older daemon without version support or mismatched -> restart daemon only
-        crate::commands::daemon::restart_daemon_only()?;
-      }
+    let remaining = deadline.saturating_duration_since(Instant::now());
+    if remaining.is_zero() {
+      break;
     }
+    thread::sleep(Duration::from_millis(delay_ms).min(remaining));
+  }
+  if query_version(socket).is_ok() {
+    return Ok(());
+  }
+  Err(last_err.context(format!(
+    "Daemon at {} did not respond within {}ms",
+    socket.display(),
+    timeout.as_millis()
+  )))
+}
+
+/// Ask the daemon for its version over a short-lived connection.
+///
+/// # Errors
+/// Returns an error if the daemon cannot be reached or replies unexpectedly.
+pub fn query_version(socket: &Path) -> Result<String> {
+  let mut stream = connect_daemon_socket(socket)?;
+  let _ = stream.set_read_timeout(Some(VERSION_REPLY_TIMEOUT));
+  write_frame(&mut stream, &C2D::Control(C2DControl::GetVersion))
+    .context("failed to write GetVersion frame")?;
+  match read_frame::<_, D2C>(&mut stream)? {
+    D2C::Control(D2CControl::Version { version }) => Ok(version),
+    D2C::Control(_) => bail!("Protocol error: expected Version reply"),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::wait_until_responsive;
+  use crate::daemon_protocol::{C2D, C2DControl, D2C, D2CControl, read_frame, write_frame};
+  use std::os::unix::net::UnixListener;
+  use std::thread;
+  use std::time::{Duration, Instant};
+
+  #[test]
+  fn wait_until_responsive_retries_until_daemon_binds() {
+    let dir = tempfile::tempdir().expect("temp dir");
+    let socket = dir.path().join("slow.sock");
+    let bind_path = socket.clone();
+    let server = thread::spawn(move || {
+      thread::sleep(Duration::from_millis(200));
+      let listener = UnixListener::bind(&bind_path).expect("bind");
+      let (mut stream, _) = listener.accept().expect("accept");
+      let request: C2D = read_frame(&mut stream).expect("read request");
+      assert!(matches!(request, C2D::Control(C2DControl::GetVersion)));
+      let reply = D2C::Control(D2CControl::Version {
+        version: "test".to_string(),
+      });
+      write_frame(&mut stream, &reply).expect("write reply");
+    });
+
+    wait_until_responsive(&socket, Duration::from_secs(2)).expect("daemon responds");
+    server.join().expect("server thread");
+  }
+
+  #[test]
+  fn wait_until_responsive_fails_fast_without_daemon() {
+    let dir = tempfile::tempdir().expect("temp dir");
+    let socket = dir.path().join("dead.sock");
+    let started = Instant::now();
+
+    let result = wait_until_responsive(&socket, Duration::from_millis(300));
+
+    assert!(result.is_err());
+    assert!(started.elapsed() < Duration::from_secs(1));
   }
-  Ok(())
 }
@@ -0,0 +1,193 @@
+use std::process::Command;
+
+use anyhow::{Context, Result, bail};
+
+use crate::utils::which::which;
+
+/// Largest download accepted by `agency files add --from-url`.
+pub const MAX_DOWNLOAD_BYTES: u64 = 25 * 1024 * 1024;
+
+/// Redirects followed before giving up.
+const MAX_REDIRECTS: u32 = 5;
+
+/// Content types that can be attached, matched against the media type only.
+const ALLOWED_CONTENT_TYPES: &[&str] = &[
+  "application/json",
+  "application/pdf",
+  "application/xml",
+  "application/yaml",
+  "application/zip",
+];
+
+/// Content type families that can be attached.
+const ALLOWED_CONTENT_PREFIXES: &[&str] = &["text/", "image/"];
+
+/// A downloaded resource and the filename it should be stored under.
+#[derive(Debug)]
+pub struct Download {
+  pub filename: String,
+  pub data: Vec<u8>,
+}
+
+/// Fetch `url` over HTTP(S) with `curl`, following redirects.
+///
+/// The filename comes from `Content-Disposition`, falling back to the last
+/// path segment of the final URL.
+///
+/// # Errors
+/// Returns an error if `curl` is missing, the request fails or ends with a
+/// non-200 status, the content type is not allowed, or the body exceeds
+/// `MAX_DOWNLOAD_BYTES`.
+pub fn download(url: &str) -> Result<Download> {
+  let Some(curl) = which("curl") else {
+    bail!("curl is required to download files but was not found in PATH");
+  };
+  // Private directory, removed on drop, so nobody can plant files at a known path
+  let scratch = tempfile::tempdir().context("failed to create a temporary directory")?;
+  let headers_path = scratch.path().join("headers");
+  let body_path = scratch.path().join("body");
+  let output = Command::new(curl)
+    .args(["--silent", "--show-error", "--location"])
+    .args(["--proto", "=http,https"])
+    .args(["--max-redirs", &MAX_REDIRECTS.to_string()])
+    .args(["--max-filesize", &MAX_DOWNLOAD_BYTES.to_string()])
+    .arg("--dump-header")
+    .arg(&headers_path)
+    .arg("--output")
+    .arg(&body_path)
+    .args(["--write-out", "%{http_code}\n%{url_effective}"])
+    .arg(url)
+    .output()
+    .context("failed to run curl")?;
+  match output.status.code() {
+    Some(0) => {}
+    Some(47) => bail!("Download of {url} failed: more than {MAX_REDIRECTS} redirects"),
+    Some(63) => bail!("Download of {url} exceeds the {MAX_DOWNLOAD_BYTES} byte limit"),
+    _ => bail!(
+      "Download of {url} failed: {}",
+      String::from_utf8_lossy(&output.stderr).trim()
+    ),
+  }
+
+  let write_out = String::from_utf8_lossy(&output.stdout);
+  let (status, effective_url) = write_out.split_once('\n').unwrap_or((&write_out, url));
+  if status != "200" {
+    bail!("Download of {url} failed with HTTP status {status}");
+  }
+
+  let headers = std::fs::read_to_string(&headers_path).unwrap_or_default();
+  let headers = last_header_block(&headers);
+  let content_type = header_value(headers, "content-type").unwrap_or_default();
+  if !is_allowed_content_type(content_type) {
+    bail!("Refusing to attach {url}: unsupported content type '{content_type}'");
+  }
+
+  let data = std::fs::read(&body_path).context("failed to read downloaded file")?;
+  if u64::try_from(data.len()).unwrap_or(u64::MAX) > MAX_DOWNLOAD_BYTES {
+    bail!("Download of {url} exceeds the {MAX_DOWNLOAD_BYTES} byte limit");
+  }
+  let filename = header_value(headers, "content-disposition")
+    .and_then(filename_from_disposition)
+    .or_else(|| filename_from_url(effective_url))
+    .unwrap_or_else(|| "download".to_string());
+  Ok(Download { filename, data })
+}
+
+/// Headers of the final response; curl writes one block per redirect hop.
+fn last_header_block(headers: &str) -> &str {
+  let trimmed = headers.trim_end();
+  trimmed
+    .rfind("\r\n\r\n")
+    .or_else(|| trimmed.rfind("\n\n"))
+    .map_or(trimmed, |pos| &trimmed[pos..])
+}
+
+/// Value of the header `name` (case-insensitive) in a raw header block.
+fn header_value<'a>(headers: &'a str, name: &str) -> Option<&'a str> {
+  headers.lines().find_map(|line| {
+    let (key, value) = line.split_once(':')?;
+    key.trim().eq_ignore_ascii_case(name).then(|| value.trim())
+  })
+}
+
+fn is_allowed_content_type(content_type: &str) -> bool {
+  let media_type = content_type
+    .split(';')
+    .next()
+    .unwrap_or_default()
+    .trim()
+    .to_ascii_lowercase();
+  ALLOWED_CONTENT_TYPES.contains(&media_type.as_str())
+    || ALLOWED_CONTENT_PREFIXES
+      .iter()
+      .any(|prefix| media_type.starts_with(prefix))
+}
+
+/// Filename from a `Content-Disposition` value like `attachment; filename="spec.pdf"`.
+fn filename_from_disposition(disposition: &str) -> Option<String> {
+  disposition.split(';').find_map(|part| {
+    let (key, value) = part.split_once('=')?;
+    if !key.trim().eq_ignore_ascii_case("filename") {
+      return None;
+    }
+    sanitize_filename(value.trim().trim_matches('"'))
+  })
+}
+
+/// Last path segment of `url`, ignoring query and fragment.
+fn filename_from_url(url: &str) -> Option<String> {
+  let without_query = url.split(['?', '#']).next().unwrap_or_default();
+  let (_, path) = without_query.split_once("://")?;
+  let (_, path) = path.split_once('/')?;
+  sanitize_filename(path.rsplit('/').next().unwrap_or_default())
+}
+
+/// Keep only the final path component and reject empty or dot-only names.
+fn sanitize_filename(name: &str) -> Option<String> {
+  let name = name.rsplit(['/', '\\']).next().unwrap_or_default().trim();
+  if name.is_empty() || name.chars().all(|ch| ch == '.') {
+    return None;
+  }
+  Some(name.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn filename_prefers_disposition_and_falls_back_to_url() {
+    assert_eq!(
+      filename_from_disposition(r#"attachment; filename="design spec.pdf""#).as_deref(),
+      Some("design spec.pdf")
+    );
+    assert_eq!(
+      filename_from_disposition("attachment; filename=../../etc/passwd").as_deref(),
+      Some("passwd")
+    );
+    assert_eq!(filename_from_disposition("inline"), None);
+    assert_eq!(
+      filename_from_url("https://example.com/docs/mock.png?v=2#top").as_deref(),
+      Some("mock.png")
+    );
+    assert_eq!(filename_from_url("https://example.com/"), None);
+    assert_eq!(filename_from_url("https://example.com"), None);
+  }
+
+  #[test]
+  fn content_type_allows_text_images_and_documents() {
+    assert!(is_allowed_content_type("text/markdown; charset=utf-8"));
+    assert!(is_allowed_content_type("image/png"));
+    assert!(is_allowed_content_type("Application/PDF"));
+    assert!(!is_allowed_content_type("application/x-msdownload"));
+    assert!(!is_allowed_content_type(""));
+  }
+
+  #[test]
+  fn headers_are_read_from_final_response() {
+    let raw = "HTTP/1.1 302 Found\r\nLocation: /b\r\nContent-Type: text/html\r\n\r\nHTTP/1.1 200 OK\r\nContent-Type: image/png\r\n\r\n";
+    let block = last_header_block(raw);
+    assert_eq!(header_value(block, "content-type"), Some("image/png"));
+    assert_eq!(header_value(block, "location"), None);
+  }
+}
@@ -2,6 +2,7 @@ use std::path::Path;
 use std::process::Command;
 
 use crate::utils::interactive;
+use crate::utils::which::which;
 use anyhow::{Context, Result, bail};
 
 use crate::config::AgencyConfig;
@@ -9,16 +10,27 @@ use crate::config::AgencyConfig;
 /// Open a file or directory path using the configured editor.
 /// Falls back to $EDITOR or `vi` when not configured.
 pub fn open_path(cfg: &AgencyConfig, path: &Path, cwd: &Path) -> Result<()> {
+  open_path_with(&cfg.editor_argv(), path, cwd)
+}
+
+/// Open a file or directory path with `argv`, appending the path as the last argument.
+///
+/// # Errors
+/// Returns an error if `argv` is empty, its program is not on PATH, or it exits
+/// with a non-zero status.
+pub fn open_path_with(argv: &[String], path: &Path, cwd: &Path) -> Result<()> {
   let target = path
     .canonicalize()
     .unwrap_or_else(|_| path.to_path_buf())
     .display()
     .to_string();
 
-  let editor_argv = cfg.editor_argv();
-  let (program, rest) = editor_argv
+  let (program, rest) = argv
     .split_first()
     .ok_or_else(|| anyhow::anyhow!("invalid editor argv: empty"))?;
+  if which(program).is_none() {
+    bail!("program not found in PATH: {program}");
+  }
   let mut launch_args: Vec<String> = rest.to_vec();
   launch_args.push(target);
 
@@ -29,7 +41,7 @@ pub fn open_path(cfg: &AgencyConfig, path: &Path, cwd: &Path) -> Result<()> {
       .status()
       .with_context(|| format!("failed to spawn editor program: {program}"))?;
     if !status.success() {
-      bail!("editor exited with non-zero status");
+      bail!("{program} exited with non-zero status");
     }
     Ok(())
   })
@@ -0,0 +1,189 @@
+use std::collections::HashMap;
+use std::fs::OpenOptions;
+use std::io::Write as _;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::config::AgencyPaths;
+use crate::utils::fs::atomic_write;
+use crate::utils::task::TaskRef;
+use crate::utils::template::civil_from_days;
+use crate::utils::time::now_ms;
+
+/// Status recorded for tasks that never had a session.
+pub const STATUS_DRAFT: &str = "Draft";
+/// Status recorded when a task's session disappears.
+pub const STATUS_STOPPED: &str = "Stopped";
+/// Status recorded when a task is completed.
+pub const STATUS_COMPLETED: &str = "Completed";
+
+/// One task status transition in `.agency/state/events.jsonl`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TaskEvent {
+  /// Milliseconds since the Unix epoch
+  pub ts_ms: u64,
+  pub task_id: u32,
+  pub slug: String,
+  pub from: String,
+  pub to: String,
+}
+
+impl TaskEvent {
+  #[must_use]
+  pub fn now(task_id: u32, slug: &str, from: &str, to: &str) -> Self {
+    Self {
+      ts_ms: now_ms(),
+      task_id,
+      slug: slug.to_string(),
+      from: from.to_string(),
+      to: to.to_string(),
+    }
+  }
+
+  /// Event time as `YYYY-MM-DD HH:MM:SS` in UTC.
+  #[must_use]
+  pub fn utc_time(&self) -> String {
+    let secs = self.ts_ms / 1000;
+    let days = i64::try_from(secs / 86_400).unwrap_or(0);
+    let (year, month, day) = civil_from_days(days);
+    let rest = secs % 86_400;
+    format!(
+      "{year:04}-{month:02}-{day:02} {:02}:{:02}:{:02}",
+      rest / 3600,
+      rest % 3600 / 60,
+      rest % 60
+    )
+  }
+}
+
+#[must_use]
+pub fn events_path(paths: &AgencyPaths) -> PathBuf {
+  paths.state_dir().join("events.jsonl")
+}
+
+/// Append `events` as JSON lines, creating the file and its directory if needed.
+///
+/// # Errors
+/// Returns an error if the file cannot be opened or written.
+pub fn append_events(path: &Path, events: &[TaskEvent]) -> Result<()> {
+  if let Some(dir) = path.parent() {
+    std::fs::create_dir_all(dir).with_context(|| format!("failed to create {}", dir.display()))?;
+  }
+  let out = to_json_lines(events)?;
+  let mut file = OpenOptions::new()
+    .create(true)
+    .append(true)
+    .open(path)
+    .with_context(|| format!("failed to open {}", path.display()))?;
+  file
+    .write_all(out.as_bytes())
+    .with_context(|| format!("failed to write {}", path.display()))
+}
+
+/// Read all events, oldest first. A missing file yields no events and
+/// unparsable lines are skipped.
+#[must_use]
+pub fn read_events(path: &Path) -> Vec<TaskEvent> {
+  let Ok(data) = std::fs::read_to_string(path) else {
+    return Vec::new();
+  };
+  data
+    .lines()
+    .filter_map(|line| serde_json::from_str(line).ok())
+    .collect()
+}
+
+/// Move the recorded events of `task` to the id and slug of `renamed`, so its
+/// history follows a rename or renumber.
+///
+/// # Errors
+/// Returns an error if the event log cannot be rewritten.
+pub fn rename_task_events(paths: &AgencyPaths, task: &TaskRef, renamed: &TaskRef) -> Result<()> {
+  let path = events_path(paths);
+  let mut events = read_events(&path);
+  let mut moved = false;
+  for event in &mut events {
+    if event.task_id == task.id && event.slug == task.slug {
+      event.task_id = renamed.id;
+      event.slug.clone_from(&renamed.slug);
+      moved = true;
+    }
+  }
+  if !moved {
+    return Ok(());
+  }
+  atomic_write(&path, to_json_lines(&events)?)
+}
+
+fn to_json_lines(events: &[TaskEvent]) -> Result<String> {
+  let mut out = String::new();
+  for event in events {
+    out.push_str(&serde_json::to_string(event).context("failed to serialize event")?);
+    out.push('\n');
+  }
+  Ok(out)
+}
+
+/// Record that `task` was completed, following its last recorded status.
+///
+/// # Errors
+/// Returns an error if the event log cannot be written.
+pub fn record_completed(paths: &AgencyPaths, task: &TaskRef) -> Result<()> {
+  let path = events_path(paths);
+  let previous = last_statuses(&read_events(&path))
+    .remove(task)
+    .unwrap_or_else(|| STATUS_DRAFT.to_string());
+  let event = TaskEvent::now(task.id, &task.slug, &previous, STATUS_COMPLETED);
+  append_events(&path, &[event])
+}
+
+/// Last recorded status per task. Keyed by id and slug, so a later task
+/// that reuses the id of a deleted one starts without history.
+#[must_use]
+pub fn last_statuses(events: &[TaskEvent]) -> HashMap<TaskRef, String> {
+  events
+    .iter()
+    .map(|event| {
+      let task = TaskRef {
+        id: event.task_id,
+        slug: event.slug.clone(),
+      };
+      (task, event.to.clone())
+    })
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{TaskEvent, append_events, last_statuses, read_events};
+  use crate::utils::task::TaskRef;
+
+  #[test]
+  fn events_round_trip_and_track_last_status() {
+    let dir = tempfile::tempdir().expect("tmp");
+    let path = dir.path().join("state").join("events.jsonl");
+    let mut first = TaskEvent::now(1, "alpha", "Draft", "Running");
+    first.ts_ms = 1_700_000_000_000;
+    let second = TaskEvent::now(1, "alpha", "Running", "Stopped");
+    let reused = TaskEvent::now(1, "beta", "Draft", "Running");
+
+    append_events(&path, std::slice::from_ref(&first)).expect("append");
+    append_events(&path, &[second.clone(), reused.clone()]).expect("append");
+
+    let events = read_events(&path);
+    assert_eq!(events, vec![first.clone(), second, reused]);
+    let last = last_statuses(&events);
+    let status = |slug: &str| {
+      let task = TaskRef {
+        id: 1,
+        slug: slug.to_string(),
+      };
+      last.get(&task).cloned()
+    };
+    assert_eq!(status("alpha").as_deref(), Some("Stopped"));
+    assert_eq!(status("beta").as_deref(), Some("Running"));
+    assert_eq!(first.utc_time(), "2023-11-14 22:13:20");
+  }
+}
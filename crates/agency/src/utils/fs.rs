@@ -0,0 +1,159 @@
+use std::fs;
+use std::io::Write as _;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+
+/// Units used by [`format_bytes`], each 1024 times the previous one.
+const UNITS: &[&str] = &["KiB", "MiB", "GiB", "TiB"];
+
+/// Total size in bytes of the regular files below `dir`, or `None` if `dir`
+/// does not exist.
+///
+/// Symlinks are not followed and the top-level `.git` entry (the pointer a
+/// linked worktree has to the main repository) is skipped. Unreadable entries
+/// are ignored.
+#[must_use]
+pub fn dir_size(dir: &Path) -> Option<u64> {
+  if !dir.is_dir() {
+    return None;
+  }
+  let mut total = 0;
+  let mut pending = vec![dir.to_path_buf()];
+  while let Some(current) = pending.pop() {
+    let Ok(entries) = std::fs::read_dir(&current) else {
+      continue;
+    };
+    for entry in entries.flatten() {
+      let path = entry.path();
+      if current == dir && entry.file_name() == ".git" {
+        continue;
+      }
+      let Ok(meta) = std::fs::symlink_metadata(&path) else {
+        continue;
+      };
+      if meta.is_dir() {
+        pending.push(path);
+      } else if meta.is_file() {
+        total += meta.len();
+      }
+    }
+  }
+  Some(total)
+}
+
+/// Replace `path` with `contents` so readers see either the old or the new
+/// file, never a partial one.
+///
+/// The data goes to a temporary file in the same directory, is flushed to
+/// disk and then renamed over the target. Existing permissions are kept and
+/// a symlinked target is written through the link.
+///
+/// # Errors
+/// Returns an error if the temporary file cannot be written or renamed.
+pub fn atomic_write(path: &Path, contents: impl AsRef<[u8]>) -> Result<()> {
+  let target = fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+  let temp = temp_path_for(&target);
+  let result = write_and_rename(&target, &temp, contents.as_ref());
+  if result.is_err() {
+    let _ = fs::remove_file(&temp);
+  }
+  result.with_context(|| format!("failed to write {}", path.display()))
+}
+
+fn write_and_rename(target: &Path, temp: &Path, contents: &[u8]) -> Result<()> {
+  let mut file = fs::File::create(temp)?;
+  file.write_all(contents)?;
+  if let Ok(meta) = fs::metadata(target) {
+    file.set_permissions(meta.permissions())?;
+  }
+  file.sync_all()?;
+  fs::rename(temp, target)?;
+  Ok(())
+}
+
+/// Hidden sibling of `target` unique to this process, e.g. `.1-task.md.4242.tmp`.
+fn temp_path_for(target: &Path) -> PathBuf {
+  let name = target
+    .file_name()
+    .map(|name| name.to_string_lossy().into_owned())
+    .unwrap_or_default();
+  target.with_file_name(format!(".{name}.{}.tmp", std::process::id()))
+}
+
+/// Render a byte count with binary units, e.g. `512 B` or `1.5 MiB`.
+#[must_use]
+#[allow(clippy::cast_precision_loss)] // One decimal is all we display
+pub fn format_bytes(bytes: u64) -> String {
+  if bytes < 1024 {
+    return format!("{bytes} B");
+  }
+  let mut value = bytes as f64 / 1024.0;
+  let mut unit = UNITS[0];
+  for next in &UNITS[1..] {
+    if value < 1024.0 {
+      break;
+    }
+    value /= 1024.0;
+    unit = next;
+  }
+  format!("{value:.1} {unit}")
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{atomic_write, dir_size, format_bytes};
+  use std::fs;
+
+  #[test]
+  fn format_bytes_uses_binary_units() {
+    assert_eq!(format_bytes(512), "512 B");
+    assert_eq!(format_bytes(1536), "1.5 KiB");
+    assert_eq!(format_bytes(5 * 1024 * 1024), "5.0 MiB");
+    assert_eq!(format_bytes(3 * 1024 * 1024 * 1024), "3.0 GiB");
+  }
+
+  #[test]
+  fn dir_size_sums_nested_files_and_skips_git_pointer() {
+    let dir = tempfile::tempdir().expect("tmp");
+    fs::write(dir.path().join(".git"), "gitdir: /elsewhere\n").expect("write .git");
+    fs::create_dir_all(dir.path().join("src")).expect("mkdir");
+    fs::write(dir.path().join("a.txt"), [0u8; 100]).expect("write a");
+    fs::write(dir.path().join("src").join("b.txt"), [0u8; 28]).expect("write b");
+
+    assert_eq!(dir_size(dir.path()), Some(128));
+    assert_eq!(dir_size(&dir.path().join("missing")), None);
+  }
+
+  #[test]
+  fn atomic_write_replaces_content_and_leaves_no_temp_files() {
+    let dir = tempfile::tempdir().expect("tmp");
+    let target = dir.path().join("1-task.md");
+
+    atomic_write(&target, "first\n").expect("create");
+    atomic_write(&target, "second\n").expect("replace");
+
+    assert_eq!(fs::read_to_string(&target).expect("read"), "second\n");
+    let names: Vec<_> = fs::read_dir(dir.path())
+      .expect("read dir")
+      .map(|entry| entry.expect("entry").file_name())
+      .collect();
+    assert_eq!(names, ["1-task.md"]);
+  }
+
+  #[test]
+  fn atomic_write_keeps_symlinks_and_fails_cleanly() {
+    let dir = tempfile::tempdir().expect("tmp");
+    let real = dir.path().join("real.toml");
+    let link = dir.path().join("agency.toml");
+    fs::write(&real, "old").expect("write real");
+    std::os::unix::fs::symlink(&real, &link).expect("symlink");
+
+    atomic_write(&link, "new").expect("write through link");
+
+    assert!(fs::symlink_metadata(&link).expect("meta").is_symlink());
+    assert_eq!(fs::read_to_string(&real).expect("read"), "new");
+    assert!(atomic_write(&dir.path().join("missing/file.md"), "x").is_err());
+    assert_eq!(fs::read_dir(dir.path()).expect("read dir").count(), 2);
+  }
+}
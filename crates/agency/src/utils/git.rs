@@ -1,3 +1,4 @@
+use std::io::Write as _;
 use std::path::{Path, PathBuf};
 
 use anyhow::{Context, Result, bail};
@@ -5,40 +6,36 @@ use anyhow::{Context, Result, bail};
 use gix as git;
 use gix::refs::transaction::PreviousValue;
 
-use crate::config::AppContext;
+use crate::config::{AgencyConfig, AppContext};
 use crate::utils::child::run_child_process;
 
 /// Resolve the main repository workdir for any given `cwd`.
 ///
+/// - When `$GIT_DIR` is set, the repository it names is used instead of discovery.
 /// - When `cwd` is inside a linked worktree, returns the main repo's workdir.
+/// - When `cwd` is inside a submodule or a nested repo, returns that repo's own
+///   workdir, not the superproject's.
 /// - When `cwd` is inside a regular repo, returns that repo's workdir.
 /// - When not inside any git repo, falls back to `cwd`.
 #[must_use]
 pub fn resolve_main_workdir(cwd: &Path) -> PathBuf {
-  match git::discover(cwd) {
-    Ok(repo) => {
-      // Get the workdir for the discovered repo
-      let workdir = match repo.workdir() {
-        Some(dir) => dir.to_path_buf(),
-        None => return cwd.to_path_buf(), // Bare repo fallback
-      };
-
-      // If in a linked worktree, navigate to main repo
-      match repo.kind() {
-        git::repository::Kind::WorkTree { is_linked } if is_linked => repo
-          .main_repo()
-          .ok()
-          .and_then(|r| r.workdir().map(std::path::Path::to_path_buf))
-          .unwrap_or(workdir),
-        _ => workdir, // Return repo workdir (not cwd!)
-      }
-    }
-    Err(_) => cwd.to_path_buf(),
+  let Ok(repo) = open_main_repo(cwd) else {
+    return cwd.to_path_buf();
+  };
+  match repo.workdir() {
+    Some(dir) => dir.to_path_buf(),
+    None => cwd.to_path_buf(), // Bare repo fallback
   }
 }
 
+/// Discover the repository for `cwd` like `git rev-parse --show-toplevel` does,
+/// honoring `$GIT_DIR`/`$GIT_WORK_TREE`, and step from a linked worktree to its
+/// main repository. Submodules stay on their own repository.
+///
+/// # Errors
+/// Returns an error if no repository is found or the main repo cannot be opened.
 pub fn open_main_repo(cwd: &Path) -> Result<git::Repository> {
-  let repo = git::discover(cwd)?;
+  let repo = git::discover_with_environment_overrides(cwd)?;
   match repo.kind() {
     git::repository::Kind::WorkTree { is_linked } if is_linked => {
       let main = repo.main_repo().context("failed to open main repo")?;
@@ -55,18 +52,56 @@ pub fn repo_workdir_or(repo: &git::Repository, fallback: &Path) -> PathBuf {
   )
 }
 
-/// Return the current HEAD branch name or "main" if unavailable.
+/// Return the current HEAD branch name or the default base branch if unavailable.
 ///
 /// Uses the main repository (not a linked worktree) and falls back to
-/// "main" when HEAD cannot be resolved to a named branch.
+/// `default_base_branch` when HEAD cannot be resolved to a named branch.
 pub fn head_branch(ctx: &AppContext) -> String {
   let Ok(repo) = open_main_repo(ctx.paths.root()) else {
-    return "main".to_string();
+    return default_base_branch(&ctx.config, ctx.paths.root());
   };
   match current_branch_name(&repo) {
     Ok(name) => name,
-    Err(_) => "main".to_string(),
+    Err(_) => default_base_branch(&ctx.config, ctx.paths.root()),
+  }
+}
+
+/// Determine the branch new work should be based on when none is given.
+///
+/// Prefers the `default_base_branch` config key, then the branch `origin/HEAD`
+/// points to, then the local HEAD branch, and finally "main".
+pub fn default_base_branch(cfg: &AgencyConfig, repo_root: &Path) -> String {
+  if let Some(name) = cfg.default_base_branch.as_deref()
+    && !name.trim().is_empty()
+  {
+    return name.trim().to_string();
+  }
+  if let Some(name) = remote_head_branch_at(repo_root, "origin") {
+    return name;
   }
+  if let Ok(Some(name)) = current_branch_name_at(repo_root) {
+    return name;
+  }
+  "main".to_string()
+}
+
+/// Return the branch `refs/remotes/<remote>/HEAD` points to, without the remote prefix.
+fn remote_head_branch_at(cwd: &Path, remote: &str) -> Option<String> {
+  let out = std::process::Command::new("git")
+    .current_dir(cwd)
+    .args(["symbolic-ref", "--quiet", &format!("refs/remotes/{remote}/HEAD")])
+    .stdout(std::process::Stdio::piped())
+    .stderr(std::process::Stdio::null())
+    .output()
+    .ok()?;
+  if !out.status.success() {
+    return None;
+  }
+  let full = String::from_utf8_lossy(&out.stdout).trim().to_string();
+  full
+    .strip_prefix(&format!("refs/remotes/{remote}/"))
+    .filter(|name| !name.is_empty())
+    .map(str::to_string)
 }
 
 /// Ensure a branch exists at the given starting point (rev or ref).
@@ -128,6 +163,32 @@ pub fn prune_worktree_if_exists(repo: &git::Repository, wt_path: &Path) -> Resul
   }
 }
 
+/// Run `git worktree prune` in `cwd` and return the names of the removed
+/// administrative entries, i.e. worktrees whose directory no longer exists.
+///
+/// # Errors
+/// Returns an error if git fails.
+pub fn prune_stale_worktrees_at(cwd: &Path) -> Result<Vec<String>> {
+  let output = std::process::Command::new("git")
+    .current_dir(cwd)
+    .args(["worktree", "prune", "--verbose"])
+    // The parsed messages are translated in other locales
+    .env("LC_ALL", "C")
+    .output()
+    .context("failed to run git worktree prune")?;
+  let stderr = String::from_utf8_lossy(&output.stderr);
+  if !output.status.success() {
+    bail!("git worktree prune failed: {}", stderr.trim());
+  }
+  // Each pruned entry is reported as `Removing worktrees/<name>: <reason>`
+  let names = stderr
+    .lines()
+    .filter_map(|line| line.strip_prefix("Removing worktrees/"))
+    .filter_map(|rest| rest.split_once(':').map(|(name, _)| name.to_string()))
+    .collect();
+  Ok(names)
+}
+
 pub fn add_worktree_for_branch(
   repo: &git::Repository,
   _wt_name: &str,
@@ -194,6 +255,37 @@ pub fn rebase_onto(worktree_dir: &Path, base: &str) -> Result<()> {
   git(&["rebase", base], worktree_dir)
 }
 
+/// Abort an in-progress rebase within `cwd`.
+///
+/// # Errors
+/// Returns an error if `git rebase --abort` fails.
+pub fn rebase_abort_at(cwd: &Path) -> Result<()> {
+  git(&["rebase", "--abort"], cwd)
+}
+
+/// List paths with unresolved merge conflicts within `cwd`.
+///
+/// # Errors
+/// Returns an error if git cannot be spawned or fails to list the conflicts.
+pub fn conflicted_files_at(cwd: &Path) -> Result<Vec<String>> {
+  let out = std::process::Command::new("git")
+    .current_dir(cwd)
+    .args(["diff", "--name-only", "--diff-filter=U"])
+    .stdout(std::process::Stdio::piped())
+    .stderr(std::process::Stdio::null())
+    .output()
+    .with_context(|| "failed to run git diff --diff-filter=U")?;
+  if !out.status.success() {
+    bail!("git diff --diff-filter=U failed: status={}", out.status);
+  }
+  Ok(
+    String::from_utf8_lossy(&out.stdout)
+      .lines()
+      .map(str::to_string)
+      .collect(),
+  )
+}
+
 /// Like `is_fast_forward` but operates directly on a working directory path.
 pub fn is_fast_forward_at(cwd: &Path, base: &str, task_branch: &str) -> Result<bool> {
   let status = std::process::Command::new("git")
@@ -212,6 +304,295 @@ pub fn is_fast_forward_at(cwd: &Path, base: &str, task_branch: &str) -> Result<b
   anyhow::bail!("git merge-base --is-ancestor failed: status={status}");
 }
 
+/// Returns true if there is no content difference between the trees of `from` and `to`.
+///
+/// # Errors
+/// Returns an error if git cannot be spawned or fails to compare the revisions.
+pub fn diff_is_empty_at(cwd: &Path, from: &str, to: &str) -> Result<bool> {
+  let status = std::process::Command::new("git")
+    .current_dir(cwd)
+    .args(["diff", "--quiet", from, to])
+    .stdout(std::process::Stdio::null())
+    .stderr(std::process::Stdio::null())
+    .status()
+    .with_context(|| "failed to run git diff --quiet")?;
+  if status.success() {
+    return Ok(true);
+  }
+  if status.code() == Some(1) {
+    return Ok(false);
+  }
+  anyhow::bail!("git diff --quiet failed: status={status}");
+}
+
+/// Push `branch` to `remote`, streaming git's output.
+///
+/// # Errors
+/// Returns an error if git cannot be spawned or the push is rejected.
+pub fn push_branch_at(cwd: &Path, remote: &str, branch: &str) -> Result<()> {
+  git(&["push", remote, branch], cwd)
+}
+
+/// Push `branch` to `remote` and set it as the branch's upstream.
+///
+/// Runs quietly so only errors reach the terminal.
+///
+/// # Errors
+/// Returns an error if git cannot be spawned or the push is rejected.
+pub fn push_branch_tracking_at(cwd: &Path, remote: &str, branch: &str) -> Result<()> {
+  git(&["push", "--quiet", "--set-upstream", remote, branch], cwd)
+}
+
+/// Binary-safe patch of the changes on `branch` since it diverged from `base`.
+///
+/// # Errors
+/// Returns an error if git cannot be spawned or fails to produce the diff.
+pub fn diff_patch_at(cwd: &Path, base: &str, branch: &str) -> Result<Vec<u8>> {
+  let range = format!("{base}...{branch}");
+  let out = std::process::Command::new("git")
+    .current_dir(cwd)
+    .args(["diff", "--binary", &range])
+    .stdout(std::process::Stdio::piped())
+    .stderr(std::process::Stdio::piped())
+    .output()
+    .with_context(|| "failed to run git diff --binary")?;
+  if !out.status.success() {
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    bail!("git diff {range} failed: {}", stderr.trim());
+  }
+  Ok(out.stdout)
+}
+
+/// Diff of the changes on `branch` since it diverged from `base`, as a patch or,
+/// with `stat`, as a diffstat summary. `color` forces colored output.
+///
+/// # Errors
+/// Returns an error if git cannot be spawned or fails to produce the diff.
+pub fn diff_range_at(
+  cwd: &Path,
+  base: &str,
+  branch: &str,
+  stat: bool,
+  color: bool,
+) -> Result<String> {
+  let range = format!("{base}...{branch}");
+  let color_arg = if color {
+    "--color=always"
+  } else {
+    "--color=never"
+  };
+  let mut args = vec!["diff", color_arg];
+  if stat {
+    args.push("--stat");
+  }
+  args.push(&range);
+  let out = std::process::Command::new("git")
+    .current_dir(cwd)
+    .args(&args)
+    .stdout(std::process::Stdio::piped())
+    .stderr(std::process::Stdio::piped())
+    .output()
+    .with_context(|| "failed to run git diff")?;
+  if !out.status.success() {
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    bail!("git diff {range} failed: {}", stderr.trim());
+  }
+  Ok(String::from_utf8_lossy(&out.stdout).into_owned())
+}
+
+/// Apply `patch` to the index and working tree of the checkout at `cwd`.
+///
+/// # Errors
+/// Returns an error if the patch does not apply cleanly.
+pub fn apply_patch_at(cwd: &Path, patch: &[u8]) -> Result<()> {
+  let mut child = std::process::Command::new("git")
+    .current_dir(cwd)
+    .args(["apply", "--index", "-"])
+    .stdin(std::process::Stdio::piped())
+    .stdout(std::process::Stdio::null())
+    .stderr(std::process::Stdio::piped())
+    .spawn()
+    .with_context(|| "failed to spawn git apply")?;
+  if let Some(mut stdin) = child.stdin.take() {
+    stdin
+      .write_all(patch)
+      .with_context(|| "failed to write patch to git apply")?;
+  }
+  let out = child
+    .wait_with_output()
+    .with_context(|| "failed to wait for git apply")?;
+  if !out.status.success() {
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    bail!("git apply failed: {}", stderr.trim());
+  }
+  Ok(())
+}
+
+/// Stage all changes, including untracked files, in the checkout at `cwd`,
+/// leaving out the paths in `exclude`.
+///
+/// # Errors
+/// Returns an error if git add fails.
+pub fn stage_all_at(cwd: &Path, exclude: &[&Path]) -> Result<()> {
+  let excludes: Vec<String> = exclude
+    .iter()
+    .map(|path| format!(":(exclude){}", path.display()))
+    .collect();
+  let mut args = vec!["add", "--all", "--", "."];
+  args.extend(excludes.iter().map(String::as_str));
+  run_git(&args, cwd)
+}
+
+/// Returns true if the index of the checkout at `cwd` differs from HEAD.
+///
+/// # Errors
+/// Returns an error if git diff cannot be run.
+pub fn has_staged_changes_at(cwd: &Path) -> Result<bool> {
+  let status = std::process::Command::new("git")
+    .current_dir(cwd)
+    .args(["diff", "--cached", "--quiet"])
+    .status()
+    .context("failed to run git diff --cached")?;
+  match status.code() {
+    Some(0) => Ok(false),
+    Some(1) => Ok(true),
+    _ => bail!("git diff --cached exited with status {status}"),
+  }
+}
+
+/// Commit the staged changes of the checkout at `cwd`.
+///
+/// # Errors
+/// Returns an error if git commit fails.
+pub fn commit_staged_at(cwd: &Path, message: &str) -> Result<()> {
+  run_git(&["commit", "--no-verify", "-m", message], cwd)
+}
+
+/// Create a single commit on top of `base` containing the tree of `task_branch`.
+///
+/// The task branch is expected to be rebased onto `base`, so the new commit holds
+/// exactly the diff between base and the branch HEAD. No refs are moved.
+///
+/// # Errors
+/// Returns an error if git fails to create the commit.
+pub fn squash_merge_at(cwd: &Path, base: &str, task_branch: &str, message: &str) -> Result<String> {
+  let tree = format!("{task_branch}^{{tree}}");
+  let mut child = std::process::Command::new("git")
+    .current_dir(cwd)
+    .args(["commit-tree", &tree, "-p", base, "-F", "-"])
+    .stdin(std::process::Stdio::piped())
+    .stdout(std::process::Stdio::piped())
+    .stderr(std::process::Stdio::piped())
+    .spawn()
+    .with_context(|| "failed to spawn git commit-tree")?;
+  if let Some(mut stdin) = child.stdin.take() {
+    stdin
+      .write_all(message.as_bytes())
+      .with_context(|| "failed to write commit message to git commit-tree")?;
+  }
+  let out = child
+    .wait_with_output()
+    .with_context(|| "failed to wait for git commit-tree")?;
+  if !out.status.success() {
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    bail!("git commit-tree failed: {}", stderr.trim());
+  }
+  Ok(String::from_utf8_lossy(&out.stdout).trim().to_string())
+}
+
+/// First git release whose `merge-tree` supports `--write-tree`.
+const MERGE_TREE_MIN_VERSION: (u32, u32) = (2, 38);
+
+/// Major and minor version of the installed git, if it can be determined.
+fn git_version(cwd: &Path) -> Option<(u32, u32)> {
+  let out = std::process::Command::new("git")
+    .current_dir(cwd)
+    .arg("--version")
+    .output()
+    .ok()?;
+  parse_git_version(&String::from_utf8_lossy(&out.stdout))
+}
+
+/// Parse `git version 2.39.2 (Apple Git-143)` into `(2, 39)`.
+fn parse_git_version(output: &str) -> Option<(u32, u32)> {
+  let version = output.trim().strip_prefix("git version ")?;
+  let mut parts = version.split(|c: char| !c.is_ascii_digit());
+  let major = parts.next()?.parse().ok()?;
+  let minor = parts.next()?.parse().ok()?;
+  Some((major, minor))
+}
+
+/// Create a merge commit of `task_branch` into `base` with both as parents.
+///
+/// The merge is computed with `git merge-tree`, so neither the index nor any
+/// working tree is touched and a conflict leaves the repository unchanged.
+/// No refs are moved.
+///
+/// # Errors
+/// Returns an error listing the conflicted paths if the branches conflict, if
+/// git is older than 2.38, or if git fails to compute the merge or create the commit.
+pub fn merge_commit_at(cwd: &Path, base: &str, task_branch: &str, message: &str) -> Result<String> {
+  let out = std::process::Command::new("git")
+    .current_dir(cwd)
+    .args(["merge-tree", "--write-tree", "--name-only"])
+    .args([base, task_branch])
+    .output()
+    .with_context(|| "failed to run git merge-tree")?;
+  let stdout = String::from_utf8_lossy(&out.stdout);
+  let mut lines = stdout.lines();
+  let tree = lines.next().unwrap_or_default().trim().to_string();
+  match out.status.code() {
+    Some(0) => {}
+    Some(1) => {
+      let conflicts: Vec<&str> = lines.take_while(|line| !line.is_empty()).collect();
+      bail!(
+        "Merge aborted due to conflicts in:\n  {}\nRebase the task with `agency rebase` and resolve them first",
+        conflicts.join("\n  ")
+      );
+    }
+    _ => {
+      if let Some((major, minor)) = git_version(cwd)
+        && (major, minor) < MERGE_TREE_MIN_VERSION
+      {
+        bail!("Merging with a merge commit needs git 2.38 or newer, found git {major}.{minor}");
+      }
+      let stderr = String::from_utf8_lossy(&out.stderr);
+      bail!("git merge-tree failed: {}", stderr.trim());
+    }
+  }
+
+  let mut child = std::process::Command::new("git")
+    .current_dir(cwd)
+    .args(["commit-tree", &tree, "-F", "-"])
+    .args(["-p", base, "-p", task_branch])
+    .stdin(std::process::Stdio::piped())
+    .stdout(std::process::Stdio::piped())
+    .stderr(std::process::Stdio::piped())
+    .spawn()
+    .with_context(|| "failed to spawn git commit-tree")?;
+  if let Some(mut stdin) = child.stdin.take() {
+    stdin
+      .write_all(message.as_bytes())
+      .with_context(|| "failed to write commit message to git commit-tree")?;
+  }
+  let out = child
+    .wait_with_output()
+    .with_context(|| "failed to wait for git commit-tree")?;
+  if !out.status.success() {
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    bail!("git commit-tree failed: {}", stderr.trim());
+  }
+  Ok(String::from_utf8_lossy(&out.stdout).trim().to_string())
+}
+
+/// Move HEAD of the checkout at `cwd` to `rev` while keeping index and files untouched.
+///
+/// # Errors
+/// Returns an error if git reset fails.
+pub fn soft_reset_to_at(cwd: &Path, rev: &str) -> Result<()> {
+  run_git(&["reset", "--soft", rev], cwd)
+}
+
 pub fn rev_parse(cwd: &Path, rev: &str) -> Result<String> {
   let out = std::process::Command::new("git")
     .current_dir(cwd)
@@ -321,11 +702,20 @@ pub fn stash_pop(workdir: &Path, stash_ref: &str) -> Result<()> {
 /// Returns true if the main worktree has no changes (including untracked files).
 /// Returns true if the working tree at `cwd` has no changes (including untracked files).
 pub fn worktree_is_clean_at(cwd: &Path) -> Result<bool> {
+  status_is_empty_at(cwd, "--untracked-files=no")
+}
+
+/// Returns true if the working tree at `cwd` has neither changes nor untracked files.
+pub fn worktree_is_pristine_at(cwd: &Path) -> Result<bool> {
+  status_is_empty_at(cwd, "--untracked-files=normal")
+}
+
+fn status_is_empty_at(cwd: &Path, untracked: &str) -> Result<bool> {
   let out = std::process::Command::new("git")
     .current_dir(cwd)
     .arg("status")
     .arg("--porcelain")
-    .arg("--untracked-files=no")
+    .arg(untracked)
     .stdout(std::process::Stdio::piped())
     .stderr(std::process::Stdio::null())
     .spawn()
@@ -344,8 +734,21 @@ pub fn hard_reset_to_head_at(cwd: &Path) -> Result<()> {
   git(&["reset", "--hard"], cwd)
 }
 
-/// Delete a branch if it exists; returns Ok(true) if deleted, Ok(false) if it didn't exist.
-pub fn delete_branch_if_exists_at(cwd: &Path, name: &str) -> Result<bool> {
+/// Hard resets the branch checked out within `cwd` to `rev` and removes
+/// untracked files (ignored files are kept).
+///
+/// # Errors
+/// Returns an error if git reset or git clean fails.
+pub fn hard_reset_to_at(cwd: &Path, rev: &str) -> Result<()> {
+  git(&["reset", "--hard", rev], cwd)?;
+  git(&["clean", "-fd"], cwd)
+}
+
+/// Returns true if a local branch named `name` exists within `cwd`.
+///
+/// # Errors
+/// Returns an error if git cannot be spawned or fails unexpectedly.
+pub fn branch_exists_at(cwd: &Path, name: &str) -> Result<bool> {
   let full = format!("refs/heads/{name}");
   let status = std::process::Command::new("git")
     .current_dir(cwd)
@@ -360,10 +763,47 @@ pub fn delete_branch_if_exists_at(cwd: &Path, name: &str) -> Result<bool> {
   if !status.success() {
     anyhow::bail!("git show-ref --verify failed: status={status}");
   }
+  Ok(true)
+}
+
+/// Delete a branch if it exists; returns Ok(true) if deleted, Ok(false) if it didn't exist.
+pub fn delete_branch_if_exists_at(cwd: &Path, name: &str) -> Result<bool> {
+  if !branch_exists_at(cwd, name)? {
+    return Ok(false);
+  }
   git(&["branch", "-D", name], cwd)?;
   Ok(true)
 }
 
+/// Rename a branch if it exists; returns Ok(true) if renamed, Ok(false) if it didn't exist.
+/// Worktrees that have the branch checked out follow the rename.
+///
+/// # Errors
+/// Returns an error if the target branch already exists or git fails.
+pub fn rename_branch_if_exists_at(cwd: &Path, old: &str, new: &str) -> Result<bool> {
+  if !branch_exists_at(cwd, old)? {
+    return Ok(false);
+  }
+  run_git(&["branch", "-m", old, new], cwd)?;
+  Ok(true)
+}
+
+/// Move a linked worktree to `to`, updating git's worktree metadata.
+///
+/// # Errors
+/// Returns an error if git refuses to move the worktree.
+pub fn move_worktree_at(cwd: &Path, from: &Path, to: &Path) -> Result<()> {
+  run_git(
+    &[
+      "worktree",
+      "move",
+      from.to_string_lossy().as_ref(),
+      to.to_string_lossy().as_ref(),
+    ],
+    cwd,
+  )
+}
+
 // Tests moved to end of file to avoid items-after-test-module lint
 
 /// Remove a linked worktree directory if it exists; returns whether it existed beforehand.
@@ -438,10 +878,20 @@ pub fn commits_ahead_at(repo_root: &Path, base: &str, branch: &str) -> Result<u6
 
 #[cfg(test)]
 mod tests {
-  use super::{commits_ahead_at, resolve_main_workdir, uncommitted_numstat_at};
+  use super::{commits_ahead_at, parse_git_version, resolve_main_workdir, uncommitted_numstat_at};
   use std::fs;
   use std::path::PathBuf;
 
+  #[test]
+  fn parses_git_version_output() {
+    assert_eq!(parse_git_version("git version 2.39.2\n"), Some((2, 39)));
+    assert_eq!(
+      parse_git_version("git version 2.37.1 (Apple Git-137.1)"),
+      Some((2, 37))
+    );
+    assert_eq!(parse_git_version("not git"), None);
+  }
+
   fn run_git(cwd: &std::path::Path, args: &[&str]) {
     let status = std::process::Command::new("git")
       .current_dir(cwd)
@@ -547,6 +997,60 @@ mod tests {
     assert_eq!(got, want);
   }
 
+  fn init_repo_with_commit(root: &std::path::Path) {
+    run_git(root, &["init"]);
+    run_git(root, &["config", "user.email", "test@example.com"]);
+    run_git(root, &["config", "user.name", "Tester"]);
+    fs::write(root.join("README.md"), "ok\n").expect("write");
+    run_git(root, &["add", "."]);
+    run_git(root, &["commit", "-m", "init"]);
+  }
+
+  #[test]
+  fn resolves_inner_root_in_nested_repo() {
+    let dir = tempfile::tempdir().expect("tmp");
+    let outer = dir.path().canonicalize().expect("canonicalize");
+    init_repo_with_commit(&outer);
+    let inner = outer.join("vendor").join("inner");
+    fs::create_dir_all(&inner).expect("mkdir");
+    init_repo_with_commit(&inner);
+    let subdir = inner.join("src");
+    fs::create_dir_all(&subdir).expect("mkdir");
+
+    assert_eq!(resolve_main_workdir(&subdir), inner);
+    assert_eq!(resolve_main_workdir(&outer.join("vendor")), outer);
+  }
+
+  #[test]
+  fn resolves_submodule_root_not_superproject() {
+    let dir = tempfile::tempdir().expect("tmp");
+    let base = dir.path().canonicalize().expect("canonicalize");
+    let upstream = base.join("upstream");
+    let superproject = base.join("super");
+    fs::create_dir_all(&upstream).expect("mkdir");
+    fs::create_dir_all(&superproject).expect("mkdir");
+    init_repo_with_commit(&upstream);
+    init_repo_with_commit(&superproject);
+    run_git(
+      &superproject,
+      &[
+        "-c",
+        "protocol.file.allow=always",
+        "submodule",
+        "add",
+        &upstream.display().to_string(),
+        "libs/sub",
+      ],
+    );
+    let submodule = superproject.join("libs").join("sub");
+
+    assert_eq!(resolve_main_workdir(&submodule), submodule);
+    assert_eq!(
+      resolve_main_workdir(&superproject.join("libs")),
+      superproject
+    );
+  }
+
   #[test]
   fn uncommitted_numstat_reports_changes() {
     let dir = tempfile::tempdir().expect("tmp");
@@ -0,0 +1,78 @@
+use std::path::Path;
+
+use anyhow::Result;
+
+use crate::utils::git::{
+  commits_ahead_at, current_branch_name_at, hard_reset_to_at, is_fast_forward_at, stash_push,
+  uncommitted_numstat_at,
+};
+
+/// The git operations agency needs on a working directory, abstracted so the
+/// code built on them can be tested against a fake repository.
+pub trait GitBackend {
+  /// Branch checked out in `cwd`, or `None` for a detached HEAD.
+  ///
+  /// # Errors
+  /// Returns an error if `cwd` is not inside a git repository.
+  fn current_branch(&self, cwd: &Path) -> Result<Option<String>>;
+
+  /// Number of commits on `branch` that are not on `base`.
+  ///
+  /// # Errors
+  /// Returns an error if either revision cannot be resolved.
+  fn commits_ahead(&self, repo_root: &Path, base: &str, branch: &str) -> Result<u64>;
+
+  /// Added and deleted lines of the unstaged changes in `workdir`.
+  ///
+  /// # Errors
+  /// Returns an error if the diff cannot be computed.
+  fn numstat(&self, workdir: &Path) -> Result<(u64, u64)>;
+
+  /// Whether `branch` can be fast-forwarded from `base`.
+  ///
+  /// # Errors
+  /// Returns an error if the ancestry check fails.
+  fn is_fast_forward(&self, cwd: &Path, base: &str, branch: &str) -> Result<bool>;
+
+  /// Hard reset the branch checked out in `cwd` to `rev`.
+  ///
+  /// # Errors
+  /// Returns an error if the reset fails.
+  fn reset(&self, cwd: &Path, rev: &str) -> Result<()>;
+
+  /// Stash all changes in `workdir`, returning the stash ref or `None` when
+  /// there was nothing to stash.
+  ///
+  /// # Errors
+  /// Returns an error if the stash fails.
+  fn stash(&self, workdir: &Path, message: &str) -> Result<Option<String>>;
+}
+
+/// [`GitBackend`] running the `git` binary in the given directory.
+pub struct GitCli;
+
+impl GitBackend for GitCli {
+  fn current_branch(&self, cwd: &Path) -> Result<Option<String>> {
+    current_branch_name_at(cwd)
+  }
+
+  fn commits_ahead(&self, repo_root: &Path, base: &str, branch: &str) -> Result<u64> {
+    commits_ahead_at(repo_root, base, branch)
+  }
+
+  fn numstat(&self, workdir: &Path) -> Result<(u64, u64)> {
+    uncommitted_numstat_at(workdir)
+  }
+
+  fn is_fast_forward(&self, cwd: &Path, base: &str, branch: &str) -> Result<bool> {
+    is_fast_forward_at(cwd, base, branch)
+  }
+
+  fn reset(&self, cwd: &Path, rev: &str) -> Result<()> {
+    hard_reset_to_at(cwd, rev)
+  }
+
+  fn stash(&self, workdir: &Path, message: &str) -> Result<Option<String>> {
+    stash_push(workdir, message)
+  }
+}
@@ -0,0 +1,86 @@
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::config::AgencyPaths;
+use crate::utils::fs::atomic_write;
+use crate::utils::task::{TaskRef, task_file};
+
+/// Task created for an idempotency key.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct KeyedTask {
+  id: u32,
+  slug: String,
+}
+
+#[must_use]
+pub fn idempotency_path(paths: &AgencyPaths) -> PathBuf {
+  paths.state_dir().join("idempotency.json")
+}
+
+/// Take the exclusive lock on the key store, held until the returned file is
+/// dropped. Hold it from [`lookup`] to [`record`] so concurrent runs with the
+/// same key create one task.
+///
+/// # Errors
+/// Returns an error if the lock file cannot be created or locked.
+pub fn lock(paths: &AgencyPaths) -> Result<File> {
+  let dir = paths.state_dir();
+  std::fs::create_dir_all(&dir).with_context(|| format!("failed to create {}", dir.display()))?;
+  let path = dir.join("idempotency.lock");
+  let file = File::create(&path).with_context(|| format!("failed to open {}", path.display()))?;
+  file
+    .lock()
+    .with_context(|| format!("failed to lock {}", path.display()))?;
+  Ok(file)
+}
+
+/// Task previously created with `key`, if its task file still exists.
+///
+/// # Errors
+/// Returns an error if the key file exists but cannot be read or parsed.
+pub fn lookup(paths: &AgencyPaths, key: &str) -> Result<Option<TaskRef>> {
+  let keys = load(paths)?;
+  let Some(entry) = keys.get(key) else {
+    return Ok(None);
+  };
+  let task = TaskRef {
+    id: entry.id,
+    slug: entry.slug.clone(),
+  };
+  Ok(task_file(paths, &task).exists().then_some(task))
+}
+
+/// Remember that `key` created `task`.
+///
+/// # Errors
+/// Returns an error if the key file cannot be read, parsed, or written.
+pub fn record(paths: &AgencyPaths, key: &str, task: &TaskRef) -> Result<()> {
+  let mut keys = load(paths)?;
+  keys.insert(
+    key.to_string(),
+    KeyedTask {
+      id: task.id,
+      slug: task.slug.clone(),
+    },
+  );
+  let path = idempotency_path(paths);
+  if let Some(dir) = path.parent() {
+    std::fs::create_dir_all(dir).with_context(|| format!("failed to create {}", dir.display()))?;
+  }
+  let data = serde_json::to_string_pretty(&keys).context("failed to serialize idempotency keys")?;
+  atomic_write(&path, data)
+}
+
+fn load(paths: &AgencyPaths) -> Result<BTreeMap<String, KeyedTask>> {
+  let path = idempotency_path(paths);
+  if !path.exists() {
+    return Ok(BTreeMap::new());
+  }
+  let data =
+    std::fs::read_to_string(&path).with_context(|| format!("failed to read {}", path.display()))?;
+  serde_json::from_str(&data).with_context(|| format!("failed to parse {}", path.display()))
+}
@@ -1,23 +1,34 @@
+pub mod archive;
 pub mod bootstrap;
 pub mod child;
 pub mod clipboard;
 pub mod cmd;
+pub mod color;
 pub mod command;
 pub mod context;
 pub mod daemon;
+pub mod download;
 pub mod editor;
+pub mod events;
 pub mod files;
+pub mod fs;
 pub mod git;
+pub mod git_backend;
+pub mod idempotency;
 pub mod interactive;
 pub mod log;
 pub mod opener;
+pub mod recorder;
 pub mod session;
 pub mod sessions;
 pub mod status;
 pub mod task;
 pub mod task_columns;
+pub mod template;
 pub mod term;
+pub mod time;
 pub mod tmux;
+pub mod transcript;
 pub mod tty;
 pub mod version;
 pub mod which;
@@ -0,0 +1,272 @@
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Read as _, Write as _};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, SyncSender, TrySendError};
+use std::thread;
+
+use anyhow::{Context, Result};
+
+/// Default size at which a session log is rotated to `<name>.log.1`.
+pub const DEFAULT_MAX_BYTES: u64 = 10 * 1024 * 1024;
+
+/// Number of pending output chunks before new chunks are dropped.
+const CHANNEL_CAPACITY: usize = 256;
+
+/// Path of the recorded output log for a task session.
+#[must_use]
+pub fn session_log_path(project_root: &Path, task_id: u32, slug: &str) -> PathBuf {
+  project_root
+    .join(".agency")
+    .join("state")
+    .join("logs")
+    .join(format!("{task_id}-{slug}.log"))
+}
+
+/// The session log of a task, its rotated predecessor and its dropped byte
+/// counter, in that order.
+#[must_use]
+pub fn session_log_files(project_root: &Path, task_id: u32, slug: &str) -> [PathBuf; 3] {
+  let log = session_log_path(project_root, task_id, slug);
+  let rotated = rotated_path(&log);
+  let dropped = dropped_count_path(&log);
+  [log, rotated, dropped]
+}
+
+/// Bytes of output the recorder of a task session had to drop from its log.
+/// Returns 0 if nothing was dropped or the counter cannot be read.
+#[must_use]
+pub fn dropped_bytes(project_root: &Path, task_id: u32, slug: &str) -> u64 {
+  let log = session_log_path(project_root, task_id, slug);
+  fs::read_to_string(dropped_count_path(&log))
+    .ok()
+    .and_then(|text| text.trim().parse().ok())
+    .unwrap_or(0)
+}
+
+/// Where a full log is moved by rotation, e.g. `1-task.log.1`.
+fn rotated_path(log: &Path) -> PathBuf {
+  let mut rotated = log.to_path_buf().into_os_string();
+  rotated.push(".1");
+  PathBuf::from(rotated)
+}
+
+/// Where the recorder keeps its dropped byte count, e.g. `1-task.log.dropped`.
+fn dropped_count_path(log: &Path) -> PathBuf {
+  let mut path = log.to_path_buf().into_os_string();
+  path.push(".dropped");
+  PathBuf::from(path)
+}
+
+/// Copy stdin into the activity `stamp` file and append it to the rotating `log`.
+///
+/// Runs as the tmux `pipe-pane` consumer. Log writes happen on a separate thread
+/// behind a bounded channel, so a stalled disk drops log chunks instead of
+/// blocking the pane. Dropped bytes are noted in the log once writes resume and
+/// their total is kept next to the log for [`dropped_bytes`].
+///
+/// # Errors
+/// Returns an error if the stamp or log file cannot be opened.
+pub fn run(stamp: &Path, log: &Path, max_bytes: u64) -> Result<()> {
+  let mut stamp_file =
+    File::create(stamp).with_context(|| format!("failed to create {}", stamp.display()))?;
+  let rotating = RotatingLog::open(log, max_bytes)?;
+  let _ = fs::remove_file(dropped_count_path(log));
+  let (sender, receiver) = mpsc::sync_channel::<Vec<u8>>(CHANNEL_CAPACITY);
+  let writer = thread::spawn(move || write_loop(rotating, &receiver));
+
+  let mut forwarder = Forwarder::new(sender, log);
+  let mut stdin = io::stdin().lock();
+  let mut buf = [0u8; 8192];
+  loop {
+    let read = match stdin.read(&mut buf) {
+      Ok(0) => break,
+      Ok(read) => read,
+      Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+      Err(_) => break,
+    };
+    let chunk = &buf[..read];
+    let _ = stamp_file.write_all(chunk);
+    if !forwarder.forward(chunk) {
+      break;
+    }
+  }
+
+  forwarder.save_total();
+  drop(forwarder);
+  let _ = writer.join();
+  Ok(())
+}
+
+/// Hands output chunks to the log writer without blocking and counts the bytes
+/// it had to drop while the writer was behind.
+struct Forwarder<'a> {
+  sender: SyncSender<Vec<u8>>,
+  log: &'a Path,
+  /// Bytes dropped since the last marker was queued
+  dropped: u64,
+  /// Bytes dropped since the recorder started
+  total: u64,
+}
+
+impl<'a> Forwarder<'a> {
+  fn new(sender: SyncSender<Vec<u8>>, log: &'a Path) -> Self {
+    Self {
+      sender,
+      log,
+      dropped: 0,
+      total: 0,
+    }
+  }
+
+  /// Queue `chunk`, preceded by a marker for output dropped since the last
+  /// successful send. Returns `false` once the writer is gone.
+  fn forward(&mut self, chunk: &[u8]) -> bool {
+    if self.dropped > 0 {
+      let marker = dropped_marker(self.dropped);
+      if self.sender.try_send(marker.into_bytes()).is_ok() {
+        self.dropped = 0;
+        self.save_total();
+      }
+    }
+    match self.sender.try_send(chunk.to_vec()) {
+      Ok(()) => true,
+      Err(TrySendError::Full(_)) => {
+        if self.dropped == 0 {
+          log::warn!(
+            "Session log {} is falling behind; dropping output",
+            self.log.display()
+          );
+        }
+        let len = u64::try_from(chunk.len()).unwrap_or(u64::MAX);
+        self.dropped = self.dropped.saturating_add(len);
+        self.total = self.total.saturating_add(len);
+        true
+      }
+      Err(TrySendError::Disconnected(_)) => false,
+    }
+  }
+
+  /// Persist the total number of dropped bytes. Only called once the writer
+  /// keeps up again or at exit, so a stalled disk cannot block the pane here.
+  fn save_total(&self) {
+    if self.total == 0 {
+      return;
+    }
+    if let Err(err) = fs::write(dropped_count_path(self.log), self.total.to_string()) {
+      log::warn!("Failed to record dropped log output: {err}");
+    }
+  }
+}
+
+/// Line written to the log in place of output that was dropped.
+fn dropped_marker(bytes: u64) -> String {
+  format!("\r\n[agency: dropped {bytes} bytes of output]\r\n")
+}
+
+fn write_loop(mut rotating: RotatingLog, receiver: &Receiver<Vec<u8>>) {
+  while let Ok(chunk) = receiver.recv() {
+    if let Err(err) = rotating.write(&chunk) {
+      log::warn!("Failed to write session log: {err}");
+    }
+  }
+}
+
+/// Append-only log file that moves itself to `<path>.1` once it exceeds `max_bytes`.
+struct RotatingLog {
+  path: PathBuf,
+  file: File,
+  written: u64,
+  max_bytes: u64,
+}
+
+impl RotatingLog {
+  fn open(path: &Path, max_bytes: u64) -> Result<Self> {
+    if let Some(parent) = path.parent() {
+      fs::create_dir_all(parent)
+        .with_context(|| format!("failed to create {}", parent.display()))?;
+    }
+    let file = open_append(path)?;
+    let written = file.metadata().map(|meta| meta.len()).unwrap_or(0);
+    Ok(Self {
+      path: path.to_path_buf(),
+      file,
+      written,
+      max_bytes,
+    })
+  }
+
+  fn write(&mut self, chunk: &[u8]) -> Result<()> {
+    let len = u64::try_from(chunk.len()).unwrap_or(u64::MAX);
+    if self.written > 0 && self.written.saturating_add(len) > self.max_bytes {
+      self.rotate()?;
+    }
+    self.file.write_all(chunk)?;
+    self.written = self.written.saturating_add(len);
+    Ok(())
+  }
+
+  fn rotate(&mut self) -> Result<()> {
+    fs::rename(&self.path, rotated_path(&self.path))
+      .with_context(|| format!("failed to rotate {}", self.path.display()))?;
+    self.file = open_append(&self.path)?;
+    self.written = 0;
+    Ok(())
+  }
+}
+
+fn open_append(path: &Path) -> Result<File> {
+  OpenOptions::new()
+    .create(true)
+    .append(true)
+    .open(path)
+    .with_context(|| format!("failed to open {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{Forwarder, RotatingLog, dropped_bytes, dropped_marker, session_log_path};
+  use std::fs;
+  use std::sync::mpsc;
+
+  #[test]
+  fn forwarder_marks_output_dropped_while_writer_lags() {
+    let dir = tempfile::tempdir().expect("temp dir");
+    let log = session_log_path(dir.path(), 1, "task");
+    fs::create_dir_all(log.parent().expect("log has a parent")).expect("log dir");
+    let (sender, receiver) = mpsc::sync_channel(2);
+    let mut forwarder = Forwarder::new(sender, &log);
+
+    for chunk in [b"aaaa", b"bbbb", b"cccc", b"dddd", b"eeee"] {
+      assert!(forwarder.forward(chunk));
+    }
+    assert_eq!(forwarder.dropped, 12);
+    assert_eq!(dropped_bytes(dir.path(), 1, "task"), 0);
+
+    let queued: Vec<Vec<u8>> = receiver.try_iter().collect();
+    assert_eq!(queued, [b"aaaa".to_vec(), b"bbbb".to_vec()]);
+
+    assert!(forwarder.forward(b"ffff"));
+    assert_eq!(forwarder.dropped, 0);
+    let resumed: Vec<Vec<u8>> = receiver.try_iter().collect();
+    assert_eq!(resumed, [dropped_marker(12).into_bytes(), b"ffff".to_vec()]);
+    assert_eq!(dropped_bytes(dir.path(), 1, "task"), 12);
+
+    drop(receiver);
+    assert!(!forwarder.forward(b"gggg"));
+  }
+
+  #[test]
+  fn rotating_log_moves_full_file_aside() {
+    let dir = tempfile::tempdir().expect("temp dir");
+    let path = dir.path().join("logs").join("1-task.log");
+    let mut log = RotatingLog::open(&path, 8).expect("open log");
+
+    log.write(b"12345").expect("write");
+    log.write(b"6789").expect("write");
+    log.write(b"ab").expect("write");
+
+    let rotated = dir.path().join("logs").join("1-task.log.1");
+    assert_eq!(fs::read_to_string(rotated).expect("rotated"), "12345");
+    assert_eq!(fs::read_to_string(&path).expect("current"), "6789ab");
+  }
+}
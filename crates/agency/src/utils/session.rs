@@ -1,9 +1,10 @@
-use anyhow::Result;
-use std::collections::HashMap;
+use anyhow::{Context, Result, bail};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
 use std::path::{Path, PathBuf};
 
 use crate::commands::shell::resolve_shell_argv;
-use crate::config::AppContext;
+use crate::config::{AgencyPaths, AppContext, PromptMode};
 use crate::daemon_protocol::TaskMeta;
 use crate::utils::bootstrap::{create_worktree_for_task, run_bootstrap_cmd_with_env};
 use crate::utils::cmd::{CmdCtx, expand_argv};
@@ -12,7 +13,7 @@ use crate::utils::files::has_files;
 use crate::utils::git::{ensure_branch_at, open_main_repo, repo_workdir_or, rev_parse};
 use crate::utils::interactive;
 use crate::utils::task::{
-  TaskFrontmatterExt, TaskRef, agent_for_task, branch_name, read_task_content,
+  TaskFrontmatter, TaskFrontmatterExt, TaskRef, agent_for_task, read_task_content, task_branch,
 };
 use crate::utils::tmux;
 
@@ -44,6 +45,53 @@ pub fn build_task_env(
   env_map
 }
 
+/// Merge agent-specific env vars over the task env, after the built-in `AGENCY_*` vars.
+/// Values may reference `$VARS` from the parent environment or the built-ins.
+pub fn apply_agent_env(
+  env_map: &mut HashMap<String, String>,
+  agent_env: &BTreeMap<String, String>,
+  repo_root: &str,
+) {
+  let ctx_expand = CmdCtx::with_env(repo_root, env_map.clone());
+  for (key, value) in agent_env {
+    let expanded = expand_argv(std::slice::from_ref(value), &ctx_expand);
+    let expanded = expanded.into_iter().next().unwrap_or_default();
+    env_map.insert(key.clone(), expanded);
+  }
+}
+
+/// Write the task prompt (`AGENCY_TASK`) to `.agency/state/prompts/<id>-<slug>.md`
+/// and expose its path as `AGENCY_TASK_FILE`.
+///
+/// # Errors
+/// Returns an error if the prompt file cannot be written.
+pub fn write_prompt_file(
+  paths: &AgencyPaths,
+  task: &TaskRef,
+  env_map: &mut HashMap<String, String>,
+) -> Result<PathBuf> {
+  let path = prompt_file_path(paths, task);
+  let dir = paths.state_dir().join("prompts");
+  fs::create_dir_all(&dir).with_context(|| format!("failed to create {}", dir.display()))?;
+  let prompt = env_map.get("AGENCY_TASK").cloned().unwrap_or_default();
+  fs::write(&path, prompt).with_context(|| format!("failed to write {}", path.display()))?;
+  env_map.insert("AGENCY_TASK_FILE".to_string(), path.display().to_string());
+  Ok(path)
+}
+
+/// Location of the prompt file written by [`write_prompt_file`].
+#[must_use]
+pub fn prompt_file_path(paths: &AgencyPaths, task: &TaskRef) -> PathBuf {
+  paths
+    .state_dir()
+    .join("prompts")
+    .join(format!("{}-{}.md", task.id, task.slug))
+}
+
+/// Runs the agent (`$0` with its arguments) and records its exit status in
+/// `$AGENCY_EXIT_FILE` for startup failure detection.
+const AGENT_EXIT_SCRIPT: &str = r#""$0" "$@"; echo $? > "$AGENCY_EXIT_FILE""#;
+
 pub struct SessionPlan {
   pub task_meta: TaskMeta,
   pub repo_root: PathBuf,
@@ -52,9 +100,15 @@ pub struct SessionPlan {
   pub agent_args: Vec<String>,
   pub env_map: HashMap<String, String>,
   pub shell_argv: Vec<String>,
+  /// Seconds after start during which a failing agent marks the session Failed.
+  pub startup_timeout_secs: Option<u64>,
 }
 
-pub fn build_session_plan(ctx: &AppContext, task: &TaskRef) -> Result<SessionPlan> {
+pub fn build_session_plan(
+  ctx: &AppContext,
+  task: &TaskRef,
+  agent_override: Option<&str>,
+) -> Result<SessionPlan> {
   // Load content and front matter
   let content = read_task_content(&ctx.paths, task)?;
   let frontmatter = content.frontmatter.clone();
@@ -66,51 +120,29 @@ pub fn build_session_plan(ctx: &AppContext, task: &TaskRef) -> Result<SessionPla
   let base_branch = frontmatter.base_branch(ctx);
   // Ensure base branch resolves
   if rev_parse(&repo_root, &base_branch).is_err() {
-    anyhow::bail!(
+    bail!(
       "No worktree can be created as base branch has no commits. Please create an initial commit in your basebranch, e.g. by using `touch README.md; git add .; git commit -m 'init'`."
     );
   }
-  let branch = branch_name(task);
+  let branch = task_branch(&ctx.paths, task);
   let _ = ensure_branch_at(&repo, &branch, &base_branch)?;
 
   // Create worktree (fast, synchronous)
   let wt_result = create_worktree_for_task(ctx, &repo, task, &branch)?;
   let worktree_dir = wt_result.worktree_dir;
 
-  // Build env map
-  let task_has_files = has_files(&ctx.paths, task);
-  let env_map = build_task_env(task.id, &description, &repo_root, task_has_files);
-
-  // Select agent and expand argv
-  let agent_name = agent_for_task(&ctx.config, frontmatter.as_ref()).ok_or_else(|| {
-    let known: Vec<String> = ctx.config.agents.keys().cloned().collect();
-    anyhow::anyhow!(
-      "no agent selected. Set `agent` in config or add YAML front matter. Known agents: {}",
-      known.join(", ")
-    )
-  })?;
-  let agent_cfg = ctx.config.get_agent(&agent_name)?;
-  let argv_tmpl = agent_cfg.cmd.clone();
-  let ctx_expand = CmdCtx::with_env(
-    repo_root
-      .canonicalize()
-      .unwrap_or(repo_root.clone())
-      .display()
-      .to_string(),
-    env_map.clone(),
-  );
-  let argv = expand_argv(&argv_tmpl, &ctx_expand);
-  if argv.is_empty() {
-    anyhow::bail!("expanded agent argv is empty");
-  }
-  let agent_program = argv[0].clone();
-  let agent_args = if argv.len() > 1 {
-    argv[1..].to_vec()
-  } else {
-    Vec::new()
-  };
+  let invocation = resolve_agent_invocation(
+    ctx,
+    task,
+    &InvocationOptions {
+      frontmatter: frontmatter.as_ref(),
+      description: &description,
+      repo_root: &repo_root,
+      agent_override,
+    },
+  )?;
+  let env_map = invocation.env_map;
   let shell_argv = resolve_shell_argv(&ctx.config);
-
   let task_meta = TaskMeta {
     id: task.id,
     slug: task.slug.clone(),
@@ -127,14 +159,162 @@ pub fn build_session_plan(ctx: &AppContext, task: &TaskRef) -> Result<SessionPla
     task_meta,
     repo_root,
     worktree_dir,
-    agent_program,
-    agent_args,
+    agent_program: invocation.program,
+    agent_args: invocation.args,
     env_map,
     shell_argv,
+    startup_timeout_secs: invocation.startup_timeout_secs,
+  })
+}
+
+/// The agent command and environment for a task, resolved but not spawned.
+pub struct AgentInvocation {
+  pub agent: String,
+  pub program: String,
+  pub args: Vec<String>,
+  pub env_map: HashMap<String, String>,
+  pub startup_timeout_secs: Option<u64>,
+}
+
+/// What an agent invocation is resolved from, besides the task itself.
+#[derive(Debug)]
+pub struct InvocationOptions<'a> {
+  pub frontmatter: Option<&'a TaskFrontmatter>,
+  /// Trimmed task body, used as the prompt.
+  pub description: &'a str,
+  pub repo_root: &'a Path,
+  /// One-off agent that wins over the front matter and config.
+  pub agent_override: Option<&'a str>,
+}
+
+/// Select the task's agent and expand its argv and env the way `start` does.
+///
+/// In file mode the prompt is kept out of the environment: the prompt is
+/// written to a file, `AGENCY_TASK_FILE` holds its path and `$AGENCY_TASK` in
+/// argv expands to that path.
+///
+/// # Errors
+/// Returns an error if no agent is selected, the agent is unknown, its argv
+/// expands to nothing, or the prompt file cannot be written.
+pub fn resolve_agent_invocation(
+  ctx: &AppContext,
+  task: &TaskRef,
+  opts: &InvocationOptions,
+) -> Result<AgentInvocation> {
+  build_agent_invocation(ctx, task, opts, true)
+}
+
+/// Like [`resolve_agent_invocation`], but in file mode the prompt file path is
+/// only computed, so nothing touches disk.
+///
+/// # Errors
+/// Returns an error if no agent is selected, the agent is unknown, or its argv
+/// expands to nothing.
+pub fn preview_agent_invocation(
+  ctx: &AppContext,
+  task: &TaskRef,
+  opts: &InvocationOptions,
+) -> Result<AgentInvocation> {
+  build_agent_invocation(ctx, task, opts, false)
+}
+
+fn build_agent_invocation(
+  ctx: &AppContext,
+  task: &TaskRef,
+  opts: &InvocationOptions,
+  write_prompt: bool,
+) -> Result<AgentInvocation> {
+  let repo_root = opts.repo_root;
+  let task_has_files = has_files(&ctx.paths, task);
+  let mut env_map = build_task_env(task.id, opts.description, repo_root, task_has_files);
+
+  // Select agent (a one-off override wins over front matter) and expand argv
+  let agent_name = match opts.agent_override {
+    Some(name) => Some(name.to_string()),
+    None => agent_for_task(&ctx.config, opts.frontmatter),
+  };
+  let agent_name = agent_name.ok_or_else(|| {
+    let known: Vec<String> = ctx.config.agents.keys().cloned().collect();
+    anyhow::anyhow!(
+      "no agent selected. Set `agent` in config or add YAML front matter. Known agents: {}",
+      known.join(", ")
+    )
+  })?;
+  let agent_cfg = ctx.config.get_agent(&agent_name)?;
+  let root_str = repo_root
+    .canonicalize()
+    .unwrap_or_else(|_| repo_root.to_path_buf())
+    .display()
+    .to_string();
+  let prompt_file = if agent_cfg.prompt_mode == PromptMode::File {
+    let path = if write_prompt {
+      write_prompt_file(&ctx.paths, task, &mut env_map)?
+    } else {
+      let path = prompt_file_path(&ctx.paths, task);
+      env_map.insert("AGENCY_TASK_FILE".to_string(), path.display().to_string());
+      path
+    };
+    env_map.remove("AGENCY_TASK");
+    Some(path)
+  } else {
+    None
+  };
+  apply_agent_env(&mut env_map, &agent_cfg.env, &root_str);
+  let mut argv_env = env_map.clone();
+  if let Some(path) = prompt_file {
+    // tmux runs argv without a shell, so the file path goes in directly
+    argv_env.insert("AGENCY_TASK".to_string(), path.display().to_string());
+  }
+  let ctx_expand = CmdCtx::with_env(root_str, argv_env);
+  let mut argv = match agent_cfg.transcript_file.as_deref() {
+    Some(file) => transcript_argv(file, agent_cfg.transcript_delay_ms, &ctx_expand)?,
+    None => expand_argv(&agent_cfg.cmd, &ctx_expand),
+  };
+  if argv.is_empty() {
+    bail!("expanded agent argv is empty");
+  }
+  let program = argv.remove(0);
+
+  let startup_timeout_secs = agent_cfg.startup_timeout_secs;
+  if startup_timeout_secs.is_some() {
+    let name = tmux::session_name(task.id, &task.slug);
+    let exit_file = tmux::agent_exit_path(repo_root, &name);
+    env_map.insert(
+      "AGENCY_EXIT_FILE".to_string(),
+      exit_file.display().to_string(),
+    );
+  }
+
+  Ok(AgentInvocation {
+    agent: agent_name,
+    program,
+    args: argv,
+    env_map,
+    startup_timeout_secs,
   })
 }
 
+/// Argv running this binary's hidden `transcript` command to replay `file`.
+fn transcript_argv(file: &str, delay_ms: Option<u64>, ctx: &CmdCtx) -> Result<Vec<String>> {
+  let exe = std::env::current_exe().context("failed to get current exe")?;
+  let file = expand_argv(&[file.to_string()], ctx).remove(0);
+  Ok(vec![
+    exe.display().to_string(),
+    "transcript".to_string(),
+    "--file".to_string(),
+    file,
+    "--delay-ms".to_string(),
+    delay_ms.unwrap_or(0).to_string(),
+  ])
+}
+
+/// Start the tmux session for `plan`, send the agent command, and optionally attach.
+///
+/// # Errors
+/// Returns an error if the `concurrency` limit is reached or tmux fails.
 pub fn start_session_for_task(ctx: &AppContext, plan: &SessionPlan, attach: bool) -> Result<()> {
+  ensure_below_concurrency_limit(ctx, &plan.repo_root)?;
+
   // Start interactive shell as pane process
   let sh_prog = plan
     .shell_argv
@@ -160,7 +340,21 @@ pub fn start_session_for_task(ctx: &AppContext, plan: &SessionPlan, attach: bool
 
   // Send agent command into the shell using POSIX quoting.
   // Prefix with space to avoid adding to shell history (HISTCONTROL=ignorespace).
-  let run = format!(" {}", as_shell_command(&plan.agent_program, &plan.agent_args));
+  let run = match plan.startup_timeout_secs {
+    Some(secs) => {
+      tmux::set_startup_timeout(&ctx.config, &target, secs)?;
+      // Run through `sh` so the exit status is recorded whatever the user's shell is
+      let mut args = vec![
+        "-c".to_string(),
+        AGENT_EXIT_SCRIPT.to_string(),
+        plan.agent_program.clone(),
+      ];
+      args.extend(plan.agent_args.iter().cloned());
+      as_shell_command("sh", &args)
+    }
+    None => as_shell_command(&plan.agent_program, &plan.agent_args),
+  };
+  let run = format!(" {run}");
   tmux::send_keys(&ctx.config, &target, &run)?;
   tmux::send_keys_enter(&ctx.config, &target)?;
 
@@ -170,3 +364,114 @@ pub fn start_session_for_task(ctx: &AppContext, plan: &SessionPlan, attach: bool
     Ok(())
   }
 }
+
+/// Refuse a new session once `concurrency` sessions are live (Running or Idle)
+/// for the project.
+fn ensure_below_concurrency_limit(ctx: &AppContext, repo_root: &Path) -> Result<()> {
+  let Some(limit) = ctx.config.concurrency else {
+    return Ok(());
+  };
+  let live: Vec<String> = tmux::list_sessions_for_project(&ctx.config, repo_root)?
+    .into_iter()
+    .filter(|session| session.status != "Exited" && session.status != "Failed")
+    .map(|session| format!("{}-{}", session.task.id, session.task.slug))
+    .collect();
+  let limit_sessions = usize::try_from(limit).unwrap_or(usize::MAX);
+  if live.len() >= limit_sessions {
+    bail!(
+      "concurrency limit reached ({limit}). Running tasks: {}",
+      live.join(", ")
+    );
+  }
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::config::{AgencyConfig, AgentConfig};
+  use crate::utils::tty::Tty;
+
+  #[test]
+  fn agent_env_expands_vars_and_overrides_inherited() {
+    let mut env_map = HashMap::from([
+      ("HOME".to_string(), "/home/me".to_string()),
+      ("AGENCY_TASK_ID".to_string(), "7".to_string()),
+      ("SHARED".to_string(), "inherited".to_string()),
+    ]);
+    let agent_env = BTreeMap::from([
+      ("EXTRA_PATH".to_string(), "$HOME/bin".to_string()),
+      ("SHARED".to_string(), "agent-$AGENCY_TASK_ID".to_string()),
+    ]);
+
+    apply_agent_env(&mut env_map, &agent_env, "/repo");
+
+    assert_eq!(env_map["EXTRA_PATH"], "/home/me/bin");
+    assert_eq!(env_map["SHARED"], "agent-7");
+  }
+
+  #[test]
+  fn prompt_file_holds_task_body_and_is_exposed_in_env() {
+    let dir = tempfile::tempdir().expect("tmp");
+    let paths = AgencyPaths::new(dir.path(), dir.path());
+    let task = TaskRef {
+      id: 3,
+      slug: "long-prompt".to_string(),
+    };
+    let mut env_map = build_task_env(3, "Refactor the parser", dir.path(), false);
+
+    let path = write_prompt_file(&paths, &task, &mut env_map).expect("write prompt");
+
+    assert_eq!(
+      fs::read_to_string(&path).expect("read prompt"),
+      "Refactor the parser"
+    );
+    assert_eq!(env_map["AGENCY_TASK_FILE"], path.display().to_string());
+    assert!(path.starts_with(paths.state_dir()));
+  }
+
+  #[test]
+  fn file_mode_passes_the_prompt_file_instead_of_the_prompt() {
+    let dir = tempfile::tempdir().expect("tmp");
+    let mut config = AgencyConfig::default();
+    config.agents.insert(
+      "long".to_string(),
+      AgentConfig {
+        cmd: vec![
+          "agent".to_string(),
+          "--prompt".to_string(),
+          "$AGENCY_TASK".to_string(),
+        ],
+        prompt_mode: PromptMode::File,
+        ..AgentConfig::default()
+      },
+    );
+    let ctx = AppContext {
+      paths: AgencyPaths::new(dir.path(), dir.path()),
+      config,
+      tty: Tty::new(),
+    };
+    let task = TaskRef {
+      id: 4,
+      slug: "huge".to_string(),
+    };
+
+    let invocation = resolve_agent_invocation(
+      &ctx,
+      &task,
+      &InvocationOptions {
+        frontmatter: None,
+        description: "Refactor the parser",
+        repo_root: dir.path(),
+        agent_override: Some("long"),
+      },
+    )
+    .expect("resolve");
+
+    let path = prompt_file_path(&ctx.paths, &task).display().to_string();
+    assert_eq!(invocation.program, "agent");
+    assert_eq!(invocation.args, ["--prompt", path.as_str()]);
+    assert_eq!(invocation.env_map["AGENCY_TASK_FILE"], path);
+    assert!(!invocation.env_map.contains_key("AGENCY_TASK"));
+  }
+}
@@ -1,12 +1,29 @@
+use anyhow::{Result, bail};
+
 use crate::daemon_protocol::SessionInfo;
 
+/// Statuses a task can be filtered by, in display order.
+pub const FILTERABLE_STATUSES: &[TaskStatus] = &[
+  TaskStatus::Draft,
+  TaskStatus::Stopped,
+  TaskStatus::Running,
+  TaskStatus::Idle,
+  TaskStatus::Paused,
+  TaskStatus::Exited,
+  TaskStatus::Failed,
+];
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum TaskStatus {
   Draft,
   Stopped,
   Running,
   Idle,
+  /// The session's processes were stopped with `agency pause`.
+  Paused,
   Exited,
+  /// The agent exited with an error within its `startup_timeout_secs`.
+  Failed,
   Other(String),
 }
 
@@ -19,10 +36,31 @@ impl TaskStatus {
       Self::Stopped => "Stopped",
       Self::Running => "Running",
       Self::Idle => "Idle",
+      Self::Paused => "Paused",
       Self::Exited => "Exited",
+      Self::Failed => "Failed",
       Self::Other(s) => s,
     }
   }
+
+  /// Parse a status name case-insensitively, e.g. `draft` or `Running`.
+  ///
+  /// # Errors
+  /// Returns an error listing the valid names if `name` is not a known status.
+  pub fn parse(name: &str) -> Result<Self> {
+    let name = name.trim();
+    if let Some(status) = FILTERABLE_STATUSES
+      .iter()
+      .find(|status| status.label().eq_ignore_ascii_case(name))
+    {
+      return Ok(status.clone());
+    }
+    let valid: Vec<String> = FILTERABLE_STATUSES
+      .iter()
+      .map(|status| status.label().to_ascii_lowercase())
+      .collect();
+    bail!("Unknown status '{name}' (valid: {})", valid.join(", "))
+  }
 }
 
 pub fn derive_status(latest: Option<&SessionInfo>, worktree_exists: bool) -> TaskStatus {
@@ -30,7 +68,9 @@ pub fn derive_status(latest: Option<&SessionInfo>, worktree_exists: bool) -> Tas
     return match s.status.as_str() {
       "Running" => TaskStatus::Running,
       "Idle" => TaskStatus::Idle,
+      "Paused" => TaskStatus::Paused,
       "Exited" => TaskStatus::Exited,
+      "Failed" => TaskStatus::Failed,
       other => TaskStatus::Other(other.to_string()),
     };
   }
@@ -40,3 +80,20 @@ pub fn derive_status(latest: Option<&SessionInfo>, worktree_exists: bool) -> Tas
     TaskStatus::Draft
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::TaskStatus;
+
+  #[test]
+  fn parse_accepts_any_case_and_rejects_unknown() {
+    assert_eq!(TaskStatus::parse("draft").unwrap(), TaskStatus::Draft);
+    assert_eq!(TaskStatus::parse(" Running").unwrap(), TaskStatus::Running);
+    assert_eq!(TaskStatus::parse("paused").unwrap(), TaskStatus::Paused);
+    let err = TaskStatus::parse("sleeping").unwrap_err().to_string();
+    assert_eq!(
+      err,
+      "Unknown status 'sleeping' (valid: draft, stopped, running, idle, paused, exited, failed)"
+    );
+  }
+}
@@ -8,11 +8,19 @@ use regex::Regex;
 use serde::{Deserialize, Serialize};
 
 use crate::config::{AgencyConfig, AgencyPaths, AppContext};
-use crate::daemon_protocol::TaskMeta;
+use crate::daemon_protocol::{TaskInfo, TaskMeta};
+use crate::utils::bootstrap::create_files_symlink;
 use crate::utils::daemon::stop_sessions_of_task;
 use crate::utils::editor::open_path as open_editor;
-use crate::utils::files::files_dir_for_task;
-use crate::utils::git::{delete_branch_if_exists_at, head_branch, prune_worktree_if_exists_at};
+use crate::utils::events::rename_task_events;
+use crate::utils::files::{files_dir_for_task, local_files_path};
+use crate::utils::fs::atomic_write;
+use crate::utils::git::{
+  delete_branch_if_exists_at, head_branch, move_worktree_at, prune_worktree_if_exists_at,
+  rename_branch_if_exists_at,
+};
+use crate::utils::recorder::session_log_files;
+use crate::utils::session::prompt_file_path;
 
 static TASK_FILE_RE: OnceLock<Regex> = OnceLock::new();
 static TRAILING_NUM_RE: OnceLock<Regex> = OnceLock::new();
@@ -32,6 +40,15 @@ impl From<TaskMeta> for TaskRef {
   }
 }
 
+impl From<&TaskInfo> for TaskRef {
+  fn from(info: &TaskInfo) -> Self {
+    Self {
+      id: info.id,
+      slug: info.slug.clone(),
+    }
+  }
+}
+
 impl TaskRef {
   pub fn from_task_file(path: &Path) -> Option<Self> {
     let name = path.file_name()?.to_str()?;
@@ -201,6 +218,16 @@ pub fn branch_name(task: &TaskRef) -> String {
   format!("agency/{}-{}", task.id, task.slug)
 }
 
+/// Branch adopted with `agency new --from-branch`, as recorded in front matter.
+pub fn adopted_branch(paths: &AgencyPaths, task: &TaskRef) -> Option<String> {
+  read_task_frontmatter(paths, task).and_then(|fm| fm.branch)
+}
+
+/// Git branch of a task: the adopted branch if recorded, otherwise `agency/<id>-<slug>`.
+pub fn task_branch(paths: &AgencyPaths, task: &TaskRef) -> String {
+  adopted_branch(paths, task).unwrap_or_else(|| branch_name(task))
+}
+
 pub fn worktree_name(task: &TaskRef) -> String {
   format!("{}-{}", task.id, task.slug)
 }
@@ -232,25 +259,47 @@ pub fn list_tasks(paths: &AgencyPaths) -> Result<Vec<TaskRef>> {
   Ok(out)
 }
 
+/// Task artifacts that `cleanup_task_artifacts` should leave in place.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct KeepArtifacts {
+  pub branch: bool,
+  /// Also keeps the branch, as git refuses to delete a checked-out branch.
+  pub worktree: bool,
+}
+
+impl KeepArtifacts {
+  #[must_use]
+  pub fn keeps_branch(self) -> bool {
+    self.branch || self.worktree
+  }
+}
+
 /// Clean up task artifacts: stop sessions, prune worktree, delete branch, remove task file and files directory.
 ///
+/// The worktree and branch survive when requested via `keep`.
+///
 /// # Errors
 /// Returns an error if worktree pruning, branch deletion, or file removal fails.
 pub fn cleanup_task_artifacts(
   ctx: &AppContext,
   task: &TaskRef,
   repo_workdir: &Path,
+  keep: KeepArtifacts,
 ) -> Result<()> {
   // Best-effort stop of running sessions
   let _ = stop_sessions_of_task(ctx, task);
 
   let wt_dir = worktree_dir(&ctx.paths, task);
-  let branch = branch_name(task);
+  let branch = task_branch(&ctx.paths, task);
   let file_path = task_file(&ctx.paths, task);
   let files_dir = files_dir_for_task(&ctx.paths, task);
 
-  let _ = prune_worktree_if_exists_at(repo_workdir, &wt_dir);
-  let _ = delete_branch_if_exists_at(repo_workdir, &branch)?;
+  if !keep.worktree {
+    let _ = prune_worktree_if_exists_at(repo_workdir, &wt_dir);
+  }
+  if !keep.keeps_branch() {
+    let _ = delete_branch_if_exists_at(repo_workdir, &branch)?;
+  }
 
   if file_path.exists() {
     fs::remove_file(&file_path)
@@ -261,16 +310,158 @@ pub fn cleanup_task_artifacts(
     fs::remove_dir_all(&files_dir)
       .with_context(|| format!("failed to remove {}", files_dir.display()))?;
   }
+  let _ = fs::remove_file(prompt_file_path(&ctx.paths, task));
 
   Ok(())
 }
 
+/// Move task artifacts to a new id and/or slug: branch, worktree, files directory, session
+/// logs, task file and recorded events. Adopted branches keep their name.
+///
+/// Each step is undone in reverse order if a later step fails, so the task is left
+/// under its original name.
+///
+/// # Errors
+/// Returns an error if any artifact for the new name already exists or a rename step fails.
+pub fn rename_task_artifacts(
+  paths: &AgencyPaths,
+  task: &TaskRef,
+  renamed: &TaskRef,
+  repo_workdir: &Path,
+) -> Result<()> {
+  let adopted = adopted_branch(paths, task).is_some();
+  let old_branch = branch_name(task);
+  let new_branch = branch_name(renamed);
+  let old_wt = worktree_dir(paths, task);
+  let new_wt = worktree_dir(paths, renamed);
+  let old_files = files_dir_for_task(paths, task);
+  let new_files = files_dir_for_task(paths, renamed);
+  let old_file = task_file(paths, task);
+  let new_file = task_file(paths, renamed);
+
+  for target in [&new_wt, &new_files, &new_file] {
+    if target.exists() {
+      bail!("Cannot rename: {} already exists", target.display());
+    }
+  }
+
+  let mut rollback = RenameRollback::default();
+
+  if !adopted && rename_branch_if_exists_at(repo_workdir, &old_branch, &new_branch)? {
+    let cwd = repo_workdir.to_path_buf();
+    rollback.push(move || {
+      let _ = rename_branch_if_exists_at(&cwd, &new_branch, &old_branch);
+    });
+  }
+
+  if old_wt.exists() {
+    move_worktree_at(repo_workdir, &old_wt, &new_wt)?;
+    let cwd = repo_workdir.to_path_buf();
+    let (from, to) = (new_wt.clone(), old_wt.clone());
+    rollback.push(move || {
+      let _ = move_worktree_at(&cwd, &from, &to);
+    });
+  }
+
+  if old_files.exists() {
+    fs::rename(&old_files, &new_files)
+      .with_context(|| format!("failed to move {}", old_files.display()))?;
+    let (from, to) = (new_files.clone(), old_files.clone());
+    rollback.push(move || {
+      let _ = fs::rename(&from, &to);
+    });
+  }
+
+  let old_logs = session_log_files(paths.root(), task.id, &task.slug);
+  let new_logs = session_log_files(paths.root(), renamed.id, &renamed.slug);
+  for (old_log, new_log) in old_logs.into_iter().zip(new_logs) {
+    if !old_log.exists() {
+      continue;
+    }
+    fs::rename(&old_log, &new_log)
+      .with_context(|| format!("failed to move {}", old_log.display()))?;
+    rollback.push(move || {
+      let _ = fs::rename(&new_log, &old_log);
+    });
+  }
+
+  fs::rename(&old_file, &new_file)
+    .with_context(|| format!("failed to move {}", old_file.display()))?;
+  rollback.disarm();
+  rename_task_events(paths, task, renamed)?;
+
+  // The files symlink inside the worktree still points at the old files directory
+  let link = local_files_path(&new_wt);
+  if link.is_symlink() {
+    let _ = fs::remove_file(&link);
+    create_files_symlink(paths, renamed, &new_wt);
+  }
+
+  Ok(())
+}
+
+/// Undo steps for a partially applied rename, run in reverse order on drop unless disarmed.
+#[derive(Default)]
+struct RenameRollback {
+  steps: Vec<Box<dyn FnOnce()>>,
+}
+
+impl RenameRollback {
+  fn push(&mut self, step: impl FnOnce() + 'static) {
+    self.steps.push(Box::new(step));
+  }
+
+  fn disarm(&mut self) {
+    self.steps.clear();
+  }
+}
+
+impl Drop for RenameRollback {
+  fn drop(&mut self) {
+    while let Some(step) = self.steps.pop() {
+      step();
+    }
+  }
+}
+
 #[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
 pub struct TaskFrontmatter {
   #[serde(default, skip_serializing_if = "Option::is_none")]
   pub agent: Option<String>,
   #[serde(default)]
   pub base_branch: Option<String>,
+  /// Existing branch adopted with `agency new --from-branch` instead of `agency/<id>-<slug>`.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub branch: Option<String>,
+  /// Free-form labels set with `agency new --label`, used for filtering.
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  pub labels: Vec<String>,
+  /// Triage priority set with `agency new --priority` or `agency update --priority`.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub priority: Option<Priority>,
+}
+
+/// Task priority for triage, stored in lowercase in the front matter.
+#[derive(
+  Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, clap::ValueEnum,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum Priority {
+  Low,
+  Normal,
+  High,
+}
+
+impl Priority {
+  /// Lowercase name as stored in the front matter.
+  #[must_use]
+  pub fn label(self) -> &'static str {
+    match self {
+      Priority::Low => "low",
+      Priority::Normal => "normal",
+      Priority::High => "high",
+    }
+  }
 }
 
 /// Extension trait for `Option<TaskFrontmatter>` to extract base branch with fallback.
@@ -360,10 +551,34 @@ pub fn write_task_content(
     }
   }
 
-  std::fs::write(&tf, output).with_context(|| format!("failed to write {}", tf.display()))?;
+  atomic_write(&tf, output)?;
   Ok(())
 }
 
+/// Overwrite the given front matter fields of a task, keeping all other fields
+/// and the body as they are. Returns the updated front matter.
+///
+/// # Errors
+/// Returns an error if the task file cannot be read or written.
+pub fn update_task_frontmatter(
+  paths: &AgencyPaths,
+  task: &TaskRef,
+  agent: Option<String>,
+  base_branch: Option<String>,
+) -> Result<TaskFrontmatter> {
+  let mut content = read_task_content(paths, task)?;
+  let mut frontmatter = content.frontmatter.take().unwrap_or_default();
+  if let Some(agent) = agent {
+    frontmatter.agent = Some(agent);
+  }
+  if let Some(base_branch) = base_branch {
+    frontmatter.base_branch = Some(base_branch);
+  }
+  content.frontmatter = Some(frontmatter.clone());
+  write_task_content(paths, task, &content)?;
+  Ok(frontmatter)
+}
+
 pub fn edit_task_description(
   cfg: &AgencyConfig,
   paths: &AgencyPaths,
@@ -531,6 +746,9 @@ mod tests {
     let frontmatter = TaskFrontmatter {
       agent: Some("agent-one".to_string()),
       base_branch: Some("main".to_string()),
+      branch: None,
+      labels: Vec::new(),
+      priority: None,
     };
     let body = "Implement the feature\nwith bullet points\n".to_string();
     let content = TaskContent {
@@ -552,6 +770,54 @@ mod tests {
     assert_eq!(roundtrip.frontmatter, Some(frontmatter));
   }
 
+  #[test]
+  fn labels_roundtrip_and_default_to_empty() {
+    let frontmatter = TaskFrontmatter {
+      base_branch: Some("main".to_string()),
+      labels: vec!["backend".to_string(), "needs-review".to_string()],
+      ..Default::default()
+    };
+    let yaml = serde_yaml::to_string(&frontmatter).expect("serialize");
+    assert!(yaml.contains("labels:"));
+    let parsed: TaskFrontmatter = serde_yaml::from_str(&yaml).expect("parse");
+    assert_eq!(parsed, frontmatter);
+
+    let (legacy, _) = parse_task_markdown("---\nbase_branch: main\n---\n\nBody\n");
+    assert_eq!(legacy.expect("front matter").labels, Vec::<String>::new());
+    let unlabeled = TaskFrontmatter {
+      labels: Vec::new(),
+      ..frontmatter
+    };
+    let yaml = serde_yaml::to_string(&unlabeled).expect("serialize");
+    assert!(!yaml.contains("labels"));
+  }
+
+  #[test]
+  fn priority_roundtrips_in_lowercase_and_defaults_to_unset() {
+    let frontmatter = TaskFrontmatter {
+      base_branch: Some("main".to_string()),
+      priority: Some(Priority::High),
+      ..Default::default()
+    };
+    let markdown = format!(
+      "---\n{}---\n\nBody\n",
+      serde_yaml::to_string(&frontmatter).expect("serialize")
+    );
+    assert!(markdown.contains("priority: high"));
+    let (parsed, body) = parse_task_markdown(&markdown);
+    assert_eq!(parsed, Some(frontmatter.clone()));
+    assert_eq!(body.trim(), "Body");
+
+    let (legacy, _) = parse_task_markdown("---\nbase_branch: main\n---\n\nBody\n");
+    assert_eq!(legacy.expect("front matter").priority, None);
+    let unset = TaskFrontmatter {
+      priority: None,
+      ..frontmatter
+    };
+    let yaml = serde_yaml::to_string(&unset).expect("serialize");
+    assert!(!yaml.contains("priority"));
+  }
+
   #[test]
   fn write_task_content_preserves_trailing_newline() {
     let dir = TempDir::new().expect("tmp");
@@ -580,6 +846,9 @@ mod tests {
     let fm: Option<TaskFrontmatter> = Some(TaskFrontmatter {
       agent: None,
       base_branch: Some("feature-branch".to_string()),
+      branch: None,
+      labels: Vec::new(),
+      priority: None,
     });
     let result = fm.base_branch_or(|| "fallback".to_string());
     assert_eq!(result, "feature-branch");
@@ -590,6 +859,9 @@ mod tests {
     let fm: Option<TaskFrontmatter> = Some(TaskFrontmatter {
       agent: None,
       base_branch: None,
+      branch: None,
+      labels: Vec::new(),
+      priority: None,
     });
     let result = fm.base_branch_or(|| "fallback".to_string());
     assert_eq!(result, "fallback");
@@ -1,12 +1,22 @@
+use std::cmp::Ordering;
+
+use anyhow::{Result, bail};
 use owo_colors::OwoColorize as _;
+use serde::Deserialize;
 
 use crate::config::{AgencyConfig, AppContext};
 use crate::daemon_protocol::SessionInfo;
 use crate::utils::files::list_files;
+use crate::utils::fs::format_bytes;
 use crate::utils::status::{TaskStatus, derive_status};
 use crate::utils::task::{
-  TaskFrontmatter, TaskFrontmatterExt, TaskRef, agent_for_task, read_task_frontmatter, worktree_dir,
+  Priority, TaskFrontmatter, TaskFrontmatterExt, TaskRef, agent_for_task, read_task_frontmatter,
+  worktree_dir,
 };
+use crate::utils::time::format_relative;
+
+/// Widest LABELS cell in characters before it is cut off with `…`.
+const MAX_LABELS_WIDTH: usize = 24;
 
 /// Git metrics for a task (uncommitted changes, commits ahead).
 #[derive(Clone, Debug, Default)]
@@ -25,7 +35,11 @@ pub struct TaskRow {
   pub git_metrics: GitMetrics,
   pub wt_exists: bool,
   pub frontmatter: Option<TaskFrontmatter>,
+  /// Stored base branch, or the repository default when the task has none.
+  pub base_branch: String,
   pub file_count: usize,
+  /// Worktree size in bytes, only filled in when the SIZE column is shown.
+  pub size_bytes: Option<u64>,
 }
 
 impl TaskRow {
@@ -41,14 +55,17 @@ impl TaskRow {
     let file_count = list_files(&ctx.paths, &task)
       .map(|f| f.len())
       .unwrap_or(0);
+    let frontmatter = read_task_frontmatter(&ctx.paths, &task);
     Self {
       config: ctx.config.clone(),
       wt_exists: worktree_dir(&ctx.paths, &task).exists(),
-      frontmatter: read_task_frontmatter(&ctx.paths, &task),
+      base_branch: frontmatter.base_branch(ctx),
+      frontmatter,
       task,
       session: session.cloned(),
       git_metrics,
       file_count,
+      size_bytes: None,
     }
   }
 
@@ -61,42 +78,81 @@ impl TaskRow {
   pub fn session_id(&self) -> Option<u64> {
     self.session.as_ref().map(|s| s.session_id)
   }
+
+  /// Labels from the task's front matter.
+  #[must_use]
+  pub fn labels(&self) -> &[String] {
+    self
+      .frontmatter
+      .as_ref()
+      .map_or(&[], |frontmatter| frontmatter.labels.as_slice())
+  }
+
+  /// Priority from the task's front matter, if set.
+  #[must_use]
+  pub fn priority(&self) -> Option<Priority> {
+    self
+      .frontmatter
+      .as_ref()
+      .and_then(|frontmatter| frontmatter.priority)
+  }
 }
 
-/// Columns available for the task table.
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+/// Columns available for the task table, named in lowercase in `[tasks] columns`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum TaskColumn {
   Id,
   Slug,
   Status,
+  Priority,
+  #[serde(alias = "age")]
+  Started,
   Files,
   Uncommitted,
   Commits,
   Base,
   Agent,
+  Labels,
+  /// Worktree disk usage. Never shown by default as it walks every worktree.
+  Size,
 }
 
 impl TaskColumn {
-  /// All columns in display order.
+  /// Default columns in display order.
   pub const ALL: &[TaskColumn] = &[
     TaskColumn::Id,
     TaskColumn::Slug,
     TaskColumn::Status,
+    TaskColumn::Priority,
+    TaskColumn::Started,
     TaskColumn::Files,
     TaskColumn::Uncommitted,
     TaskColumn::Commits,
     TaskColumn::Base,
     TaskColumn::Agent,
+    TaskColumn::Labels,
   ];
 
   /// Returns visible columns based on the rows.
-  /// Hides the Files column if no task has any files.
+  /// Hides the Files column if no task has any files, the Started column
+  /// if no task has a session, the Labels column if no task has labels and
+  /// the Priority column if no task has a priority.
   #[must_use]
   pub fn visible_columns(rows: &[TaskRow]) -> Vec<TaskColumn> {
     let has_files = rows.iter().any(|r| r.file_count > 0);
+    let has_sessions = rows.iter().any(|r| r.session.is_some());
+    let has_labels = rows.iter().any(|r| !r.labels().is_empty());
+    let has_priority = rows.iter().any(|r| r.priority().is_some());
     Self::ALL
       .iter()
-      .filter(|col| **col != TaskColumn::Files || has_files)
+      .filter(|col| match col {
+        TaskColumn::Files => has_files,
+        TaskColumn::Started => has_sessions,
+        TaskColumn::Labels => has_labels,
+        TaskColumn::Priority => has_priority,
+        _ => true,
+      })
       .copied()
       .collect()
   }
@@ -108,11 +164,15 @@ impl TaskColumn {
       TaskColumn::Id => "ID",
       TaskColumn::Slug => "SLUG",
       TaskColumn::Status => "STATUS",
+      TaskColumn::Priority => "PRIORITY",
+      TaskColumn::Started => "STARTED",
       TaskColumn::Files => "FILES",
       TaskColumn::Uncommitted => "UNCOMMITTED",
       TaskColumn::Commits => "COMMITS",
       TaskColumn::Base => "BASE",
       TaskColumn::Agent => "AGENT",
+      TaskColumn::Labels => "LABELS",
+      TaskColumn::Size => "SIZE",
     }
   }
 
@@ -121,23 +181,32 @@ impl TaskColumn {
   #[must_use]
   pub fn weight(self) -> u8 {
     match self {
-      TaskColumn::Slug | TaskColumn::Agent | TaskColumn::Base => 2,
+      TaskColumn::Slug | TaskColumn::Agent | TaskColumn::Base | TaskColumn::Labels => 2,
       TaskColumn::Id
       | TaskColumn::Status
+      | TaskColumn::Priority
+      | TaskColumn::Started
       | TaskColumn::Files
       | TaskColumn::Uncommitted
-      | TaskColumn::Commits => 1,
+      | TaskColumn::Commits
+      | TaskColumn::Size => 1,
     }
   }
 
   /// Calculate percentage widths from weights for a given set of columns.
+  /// The rounding remainder goes to the first of the widest columns.
   #[must_use]
   pub fn width_percentages_for(columns: &[TaskColumn]) -> Vec<u16> {
     let total_weight: u16 = columns.iter().map(|c| u16::from(c.weight())).sum();
-    columns
+    let mut widths: Vec<u16> = columns
       .iter()
-      .map(|c| u16::from(c.weight()) * 100 / total_weight)
-      .collect()
+      .map(|c| u16::from(c.weight()) * 100 / total_weight.max(1))
+      .collect();
+    let max_weight = columns.iter().map(|c| c.weight()).max();
+    if let Some(widest) = columns.iter().position(|c| Some(c.weight()) == max_weight) {
+      widths[widest] += 100u16.saturating_sub(widths.iter().sum());
+    }
+    widths
   }
 
   /// Format cell value with ANSI colors.
@@ -150,6 +219,16 @@ impl TaskColumn {
       TaskColumn::Id => row.task.id.to_string(),
       TaskColumn::Slug => row.task.slug.clone(),
       TaskColumn::Status => Self::format_status(row, pending_delete),
+      TaskColumn::Priority => match row.priority() {
+        Some(Priority::High) => Priority::High.label().red().bold().to_string(),
+        Some(Priority::Normal) => Priority::Normal.label().to_string(),
+        Some(Priority::Low) => Priority::Low.label().dimmed().to_string(),
+        None => "-".dimmed().to_string(),
+      },
+      TaskColumn::Started => match &row.session {
+        Some(session) => format_relative(session.created_at_ms),
+        None => "-".dimmed().to_string(),
+      },
       TaskColumn::Files => {
         if row.file_count == 0 {
           "-".dimmed().to_string()
@@ -181,10 +260,18 @@ impl TaskColumn {
           row.git_metrics.commits_ahead.to_string().cyan().to_string()
         }
       }
-      TaskColumn::Base => row.frontmatter.base_branch_or(|| "main".to_string()),
+      TaskColumn::Base => row.base_branch.clone(),
       TaskColumn::Agent => {
         agent_for_task(&row.config, row.frontmatter.as_ref()).unwrap_or_else(|| "-".to_string())
       }
+      TaskColumn::Labels => {
+        if row.labels().is_empty() {
+          "-".dimmed().to_string()
+        } else {
+          truncate_labels(&row.labels().join(","))
+        }
+      }
+      TaskColumn::Size => row.size_bytes.map_or_else(|| "-".to_string(), format_bytes),
     }
   }
 
@@ -196,13 +283,147 @@ impl TaskColumn {
     match status {
       TaskStatus::Running => status.label().green().to_string(),
       TaskStatus::Idle => status.label().blue().to_string(),
+      TaskStatus::Paused => status.label().magenta().to_string(),
       TaskStatus::Exited | TaskStatus::Stopped => status.label().red().to_string(),
+      TaskStatus::Failed => status.label().bright_red().to_string(),
       TaskStatus::Draft => status.label().yellow().to_string(),
       TaskStatus::Other(s) => s,
     }
   }
 }
 
+/// Cut `labels` to [`MAX_LABELS_WIDTH`] characters, ending in `…` when shortened.
+fn truncate_labels(labels: &str) -> String {
+  if labels.chars().count() <= MAX_LABELS_WIDTH {
+    return labels.to_string();
+  }
+  let mut truncated: String = labels.chars().take(MAX_LABELS_WIDTH - 1).collect();
+  truncated.push('…');
+  truncated
+}
+
+/// Field task rows are sorted by, in the TUI table and `agency tasks --sort`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SortColumn {
+  #[default]
+  Id,
+  Slug,
+  Status,
+  Uncommitted,
+  Commits,
+  Priority,
+}
+
+impl SortColumn {
+  /// Fields accepted by `agency tasks --sort`.
+  pub const ALL: &[SortColumn] = &[
+    SortColumn::Id,
+    SortColumn::Slug,
+    SortColumn::Status,
+    SortColumn::Uncommitted,
+    SortColumn::Commits,
+    SortColumn::Priority,
+  ];
+
+  /// Lowercase name used on the command line.
+  #[must_use]
+  pub fn name(self) -> &'static str {
+    match self {
+      SortColumn::Id => "id",
+      SortColumn::Slug => "slug",
+      SortColumn::Status => "status",
+      SortColumn::Uncommitted => "uncommitted",
+      SortColumn::Commits => "commits",
+      SortColumn::Priority => "priority",
+    }
+  }
+
+  /// Parse a sort field name case-insensitively, e.g. `commits`.
+  ///
+  /// # Errors
+  /// Returns an error listing the valid names if `name` is not a known field.
+  pub fn parse(name: &str) -> Result<Self> {
+    let name = name.trim();
+    if let Some(column) = Self::ALL
+      .iter()
+      .find(|column| column.name().eq_ignore_ascii_case(name))
+    {
+      return Ok(*column);
+    }
+    let valid: Vec<&str> = Self::ALL.iter().map(|column| column.name()).collect();
+    bail!("Unknown sort field '{name}' (valid: {})", valid.join(", "))
+  }
+
+  /// Next column in the TUI's `t` key cycle, which skips `Slug`.
+  #[must_use]
+  pub fn next(self) -> Self {
+    match self {
+      SortColumn::Id => SortColumn::Status,
+      SortColumn::Status => SortColumn::Uncommitted,
+      SortColumn::Uncommitted => SortColumn::Commits,
+      SortColumn::Commits => SortColumn::Priority,
+      SortColumn::Priority | SortColumn::Slug => SortColumn::Id,
+    }
+  }
+
+  /// Table column that shows the sort indicator.
+  #[must_use]
+  pub fn column(self) -> TaskColumn {
+    match self {
+      SortColumn::Id => TaskColumn::Id,
+      SortColumn::Slug => TaskColumn::Slug,
+      SortColumn::Status => TaskColumn::Status,
+      SortColumn::Uncommitted => TaskColumn::Uncommitted,
+      SortColumn::Commits => TaskColumn::Commits,
+      SortColumn::Priority => TaskColumn::Priority,
+    }
+  }
+
+  /// Ascending order of two rows by this column.
+  #[must_use]
+  pub fn compare(self, left: &TaskRow, right: &TaskRow) -> Ordering {
+    match self {
+      SortColumn::Id => left.id().cmp(&right.id()),
+      SortColumn::Slug => left.task.slug.cmp(&right.task.slug),
+      SortColumn::Status => status_rank(left).cmp(&status_rank(right)),
+      SortColumn::Uncommitted => uncommitted_lines(left).cmp(&uncommitted_lines(right)),
+      SortColumn::Commits => left
+        .git_metrics
+        .commits_ahead
+        .cmp(&right.git_metrics.commits_ahead),
+      SortColumn::Priority => priority_rank(left).cmp(&priority_rank(right)),
+    }
+  }
+}
+
+/// Rank for sorting by priority: high first, unset counts as normal.
+fn priority_rank(row: &TaskRow) -> u8 {
+  match row.priority() {
+    Some(Priority::High) => 0,
+    Some(Priority::Normal) | None => 1,
+    Some(Priority::Low) => 2,
+  }
+}
+
+/// Rank for sorting by status: active sessions first, drafts last.
+fn status_rank(row: &TaskRow) -> u8 {
+  match derive_status(row.session.as_ref(), row.wt_exists) {
+    TaskStatus::Running => 0,
+    TaskStatus::Idle | TaskStatus::Paused => 1,
+    TaskStatus::Exited | TaskStatus::Failed => 2,
+    TaskStatus::Stopped => 3,
+    TaskStatus::Draft => 4,
+    TaskStatus::Other(_) => 5,
+  }
+}
+
+fn uncommitted_lines(row: &TaskRow) -> u64 {
+  row
+    .git_metrics
+    .uncommitted_add
+    .saturating_add(row.git_metrics.uncommitted_del)
+}
+
 #[cfg(test)]
 mod tests {
   use super::*;
@@ -244,11 +465,41 @@ mod tests {
     assert_eq!(TaskColumn::Id.header(), "ID");
     assert_eq!(TaskColumn::Slug.header(), "SLUG");
     assert_eq!(TaskColumn::Status.header(), "STATUS");
+    assert_eq!(TaskColumn::Priority.header(), "PRIORITY");
+    assert_eq!(TaskColumn::Started.header(), "STARTED");
     assert_eq!(TaskColumn::Files.header(), "FILES");
     assert_eq!(TaskColumn::Uncommitted.header(), "UNCOMMITTED");
     assert_eq!(TaskColumn::Commits.header(), "COMMITS");
     assert_eq!(TaskColumn::Base.header(), "BASE");
     assert_eq!(TaskColumn::Agent.header(), "AGENT");
+    assert_eq!(TaskColumn::Labels.header(), "LABELS");
+    assert_eq!(TaskColumn::Size.header(), "SIZE");
+  }
+
+  #[derive(Deserialize)]
+  struct Columns {
+    columns: Vec<TaskColumn>,
+  }
+
+  #[test]
+  fn columns_deserialize_from_lowercase_names() {
+    let parsed: Columns =
+      toml::from_str(r#"columns = ["id", "age", "size"]"#).expect("parse columns");
+    assert_eq!(
+      parsed.columns,
+      [TaskColumn::Id, TaskColumn::Started, TaskColumn::Size]
+    );
+    assert!(toml::from_str::<Columns>(r#"columns = ["owner"]"#).is_err());
+  }
+
+  #[test]
+  fn long_labels_are_truncated() {
+    assert_eq!(truncate_labels("backend,api"), "backend,api");
+    let long = "backend,needs-review,security,docs";
+    let cell = truncate_labels(long);
+    assert_eq!(cell.chars().count(), MAX_LABELS_WIDTH);
+    assert!(cell.ends_with('…'));
+    assert!(long.starts_with(cell.trim_end_matches('…')));
   }
 
   #[test]
@@ -320,4 +571,30 @@ mod tests {
     assert_eq!(strip_ansi_control_codes(&cell), "-");
     assert!(cell.contains("\x1b[")); // Has ANSI codes for dimmed
   }
+
+  #[test]
+  fn started_column_is_shown_only_with_sessions() {
+    let (_dir, ctx) = make_ctx();
+    let draft = TaskRow::new(&ctx, make_task(1, "draft"), None, GitMetrics::default());
+    assert!(
+      !TaskColumn::visible_columns(std::slice::from_ref(&draft)).contains(&TaskColumn::Started)
+    );
+    assert_eq!(
+      strip_ansi_control_codes(&TaskColumn::Started.cell(&draft, false)),
+      "-"
+    );
+
+    let session = SessionInfo {
+      created_at_ms: crate::utils::time::now_ms() - 120_000,
+      ..make_session(1, 2, "running", "Running")
+    };
+    let running = TaskRow::new(
+      &ctx,
+      make_task(2, "running"),
+      Some(&session),
+      GitMetrics::default(),
+    );
+    assert!(TaskColumn::visible_columns(&[draft, running.clone()]).contains(&TaskColumn::Started));
+    assert_eq!(TaskColumn::Started.cell(&running, false), "2m ago");
+  }
 }
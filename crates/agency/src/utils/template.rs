@@ -0,0 +1,103 @@
+use std::fs;
+
+use anyhow::{Context, Result, bail};
+
+use crate::config::AppContext;
+use crate::utils::task::TaskRef;
+use crate::utils::time::now_ms;
+
+/// Template name applied when `agency new` is run without `--template`.
+pub const DEFAULT_TEMPLATE: &str = "default";
+
+/// Load the body of the task template `name`, or of the `default` template when
+/// `name` is `None` and one is configured.
+///
+/// Template paths in `[templates]` are relative to the project's `.agency/` directory.
+///
+/// # Errors
+/// Returns an error if an explicitly requested template is not configured or a
+/// configured template file cannot be read.
+pub fn load_template(ctx: &AppContext, name: Option<&str>) -> Result<Option<String>> {
+  let key = name.unwrap_or(DEFAULT_TEMPLATE);
+  let Some(relative) = ctx.config.templates.get(key) else {
+    if name.is_some() {
+      let known: Vec<&str> = ctx.config.templates.keys().map(String::as_str).collect();
+      bail!(
+        "unknown template: {key}. Known templates: {}",
+        known.join(", ")
+      );
+    }
+    return Ok(None);
+  };
+  let path = ctx.paths.root().join(".agency").join(relative);
+  if !path.is_file() {
+    bail!("Template {key} not found at {}", path.display());
+  }
+  let body =
+    fs::read_to_string(&path).with_context(|| format!("failed to read {}", path.display()))?;
+  Ok(Some(body))
+}
+
+/// Replace `$SLUG`, `$ID` and `$DATE` (UTC, `YYYY-MM-DD`) in a template body.
+#[must_use]
+pub fn render_template(body: &str, task: &TaskRef, date: &str) -> String {
+  body
+    .replace("$SLUG", &task.slug)
+    .replace("$ID", &task.id.to_string())
+    .replace("$DATE", date)
+}
+
+/// Today's UTC date formatted as `YYYY-MM-DD`.
+#[must_use]
+pub fn today_utc() -> String {
+  let secs = now_ms() / 1000;
+  let days = i64::try_from(secs / 86_400).unwrap_or(0);
+  let (year, month, day) = civil_from_days(days);
+  format!("{year:04}-{month:02}-{day:02}")
+}
+
+/// Convert days since 1970-01-01 into a (year, month, day) civil date.
+pub(crate) fn civil_from_days(days: i64) -> (i64, u32, u32) {
+  let shifted = days + 719_468;
+  let era = shifted.div_euclid(146_097);
+  let day_of_era = shifted.rem_euclid(146_097);
+  let year_of_era =
+    (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+  let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+  let month_index = (5 * day_of_year + 2) / 153;
+  let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+  let month = if month_index < 10 {
+    month_index + 3
+  } else {
+    month_index - 9
+  };
+  let year = year_of_era + era * 400 + i64::from(month <= 2);
+  (
+    year,
+    u32::try_from(month).unwrap_or(1),
+    u32::try_from(day).unwrap_or(1),
+  )
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{civil_from_days, render_template};
+  use crate::utils::task::TaskRef;
+
+  #[test]
+  fn civil_from_days_handles_epoch_and_leap_years() {
+    assert_eq!(civil_from_days(0), (1970, 1, 1));
+    assert_eq!(civil_from_days(19_782), (2024, 2, 29));
+    assert_eq!(civil_from_days(20_742), (2026, 10, 16));
+  }
+
+  #[test]
+  fn render_template_substitutes_tokens() {
+    let task = TaskRef {
+      id: 4,
+      slug: "fix-login".to_string(),
+    };
+    let out = render_template("# $SLUG ($ID) $DATE", &task, "2026-01-02");
+    assert_eq!(out, "# fix-login (4) 2026-01-02");
+  }
+}
@@ -49,22 +49,22 @@ pub fn print_table(headers: &[&str], rows: &[Vec<String>]) {
       header_line.push_str(&" ".repeat(spaces));
     }
   }
-  println!("{}", header_line.dimmed());
+  anstream::println!("{}", header_line.dimmed());
 
   // 3) Render rows with the same spacing rule based on visible lengths
   for row in rows {
     for (i, cell) in row.iter().enumerate().take(cols) {
       let cell = cell.as_str();
       let vlen = visible_len(cell);
-      print!("{cell}");
+      anstream::print!("{cell}");
       if i + 1 < cols {
         let spaces = widths[i].saturating_sub(vlen) + 1;
         for _ in 0..spaces {
-          print!(" ");
+          anstream::print!(" ");
         }
       }
     }
-    println!();
+    anstream::println!();
   }
 }
 
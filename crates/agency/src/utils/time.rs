@@ -0,0 +1,58 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Current wall-clock time in milliseconds since the Unix epoch.
+#[must_use]
+pub fn now_ms() -> u64 {
+  SystemTime::now()
+    .duration_since(UNIX_EPOCH)
+    .map(|duration| u64::try_from(duration.as_millis()).unwrap_or(u64::MAX))
+    .unwrap_or(0)
+}
+
+/// Compact age of `timestamp_ms` relative to now, e.g. `42s ago`, `3m ago` or `2d ago`.
+#[must_use]
+pub fn format_relative(timestamp_ms: u64) -> String {
+  format_relative_at(timestamp_ms, now_ms())
+}
+
+/// Like [`format_relative`] with an explicit current time.
+///
+/// Timestamps in the future (clock skew between processes) and ages below one
+/// second render as `just now`. Only the largest unit is shown.
+#[must_use]
+pub fn format_relative_at(timestamp_ms: u64, now_ms: u64) -> String {
+  let secs = now_ms.saturating_sub(timestamp_ms) / 1000;
+  match secs {
+    0 => "just now".to_string(),
+    1..60 => format!("{secs}s ago"),
+    60..3600 => format!("{}m ago", secs / 60),
+    3600..86_400 => format!("{}h ago", secs / 3600),
+    _ => format!("{}d ago", secs / 86_400),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::format_relative_at;
+
+  const NOW: u64 = 1_000_000_000;
+
+  fn ago(secs: u64) -> String {
+    format_relative_at(NOW - secs * 1000, NOW)
+  }
+
+  #[test]
+  fn relative_time_switches_units_at_boundaries() {
+    assert_eq!(ago(0), "just now");
+    assert_eq!(ago(59), "59s ago");
+    assert_eq!(ago(60), "1m ago");
+    assert_eq!(ago(3599), "59m ago");
+    assert_eq!(ago(3600), "1h ago");
+    assert_eq!(ago(2 * 86_400 + 5), "2d ago");
+  }
+
+  #[test]
+  fn future_timestamps_are_just_now() {
+    assert_eq!(format_relative_at(NOW + 5_000, NOW), "just now");
+  }
+}
@@ -1,11 +1,15 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
-use std::time::{Duration, Instant};
+use std::time::{Duration, Instant, UNIX_EPOCH};
 
 use anyhow::{Context, Result};
 
-use crate::config::AgencyConfig;
-use crate::daemon_protocol::{SessionInfo, TaskMeta};
+use crate::config::{AgencyConfig, AgencyPaths, compute_log_path};
+use crate::daemon_protocol::{AttachmentInfo, SessionInfo, TaskMeta};
+use crate::utils::recorder;
+use crate::utils::session::prompt_file_path;
+use crate::utils::task::TaskRef;
+use crate::utils::time::now_ms;
 
 pub fn tmux_socket_path(cfg: &AgencyConfig) -> PathBuf {
   if let Ok(env_path) = std::env::var("AGENCY_TMUX_SOCKET_PATH") {
@@ -31,6 +35,12 @@ pub fn tmux_args_base(cfg: &AgencyConfig) -> Vec<String> {
 
 const GUARD_SESSION: &str = "__agency_guard__";
 const SERVER_READY_TIMEOUT: Duration = Duration::from_secs(2);
+/// Default seconds without output before a session counts as idle.
+const DEFAULT_DWELL_SECS: u64 = 1;
+/// Default `[tmux] status_left`.
+const DEFAULT_STATUS_LEFT: &str = " Agency ";
+/// Default `[tmux] window_format`, shown centered in the status bar.
+const DEFAULT_WINDOW_FORMAT: &str = " Task $SLUG (Id: $ID) ";
 
 /// Ensure the socket directory exists with proper permissions (0700).
 ///
@@ -287,6 +297,14 @@ pub fn session_name(task_id: u32, slug: &str) -> String {
   format!("agency-{task_id}-{slug}")
 }
 
+/// Status bar text from a `[tmux]` template, or `default`, with `$SLUG` and `$ID` replaced.
+fn status_text(template: Option<&str>, default: &str, task: &TaskMeta) -> String {
+  template
+    .unwrap_or(default)
+    .replace("$SLUG", &task.slug)
+    .replace("$ID", &task.id.to_string())
+}
+
 pub fn start_session(
   cfg: &AgencyConfig,
   project_root: &Path,
@@ -324,6 +342,10 @@ pub fn start_session(
   let _ = tmux_set_option_append(cfg, "", "terminal-features", ",xterm-256color:RGB");
   // Environment for child processes
   let _ = tmux_set_env_global(cfg, "COLORTERM", "truecolor");
+  // Scrollback must be set globally before the pane exists to take effect
+  if let Some(lines) = cfg.daemon.as_ref().and_then(|d| d.scrollback_lines) {
+    let _ = tmux_set_option_global(cfg, "history-limit", &lines.to_string());
+  }
 
   // Create session and launch program
   // Use -e flags to pass env vars to the new session (tmux 3.2+)
@@ -345,28 +367,47 @@ pub fn start_session(
   // Auto-close the session when the agent exits
   tmux_set_option(cfg, &name, "remain-on-exit", "off")?;
 
-  // Enable a minimal, theme-friendly status bar
-  tmux_set_option(cfg, &name, "status", "on")?;
-  tmux_set_option(cfg, &name, "status-style", "bg=default,fg=cyan")?;
-  tmux_set_option(cfg, &name, "status-left", " Agency ")?;
-  tmux_set_option(cfg, &name, "status-justify", "centre")?;
-  // Hide non-current window titles and use the current one for centered task text
-  tmux_set_window_option(cfg, &name, "window-status-format", " ")?;
-  tmux_set_window_option(
-    cfg,
-    &name,
-    "window-status-current-format",
-    &format!(" Task {} (Id: {}) ", task.slug, task.id),
-  )?;
+  // Enable a minimal, theme-friendly status bar unless `[tmux] status` is off
+  let tmux_cfg = cfg.tmux.clone().unwrap_or_default();
+  if tmux_cfg.status == Some(false) {
+    tmux_set_option(cfg, &name, "status", "off")?;
+  } else {
+    tmux_set_option(cfg, &name, "status", "on")?;
+    tmux_set_option(cfg, &name, "status-style", "bg=default,fg=cyan")?;
+    let left = status_text(tmux_cfg.status_left.as_deref(), DEFAULT_STATUS_LEFT, task);
+    tmux_set_option(cfg, &name, "status-left", &left)?;
+    tmux_set_option(cfg, &name, "status-justify", "centre")?;
+    // Hide non-current window titles and use the current one for centered task text
+    tmux_set_window_option(cfg, &name, "window-status-format", " ")?;
+    let title = status_text(
+      tmux_cfg.window_format.as_deref(),
+      DEFAULT_WINDOW_FORMAT,
+      task,
+    );
+    tmux_set_window_option(cfg, &name, "window-status-current-format", &title)?;
+  }
   // After sourcing configs, compute the actual detach binding and prefix
-  let prefix = read_tmux_prefix(cfg).unwrap_or_else(|_| "C-b".to_string());
-  let right = match find_detach_binding(cfg) {
-    DetachBinding::WithPrefix { key } => format!(" Press {prefix}+{key} to detach "),
-    DetachBinding::Prefixless { key } => format!(" Press {key} to detach "),
-    DetachBinding::None => format!(" Press {prefix}+d to detach "),
+  let detach_sequence = cfg
+    .daemon
+    .as_ref()
+    .map(|daemon| daemon.detach_sequence.as_slice())
+    .unwrap_or_default();
+  let right = if detach_sequence.is_empty() {
+    let prefix = read_tmux_prefix(cfg).unwrap_or_else(|_| "C-b".to_string());
+    match find_detach_binding(cfg) {
+      DetachBinding::WithPrefix { key } => format!(" Press {prefix}+{key} to detach "),
+      DetachBinding::Prefixless { key } => format!(" Press {key} to detach "),
+      DetachBinding::None => format!(" Press {prefix}+d to detach "),
+    }
+  } else {
+    let keys = bind_detach_keys(cfg, detach_sequence)?;
+    format!(" Press {} to detach ", keys.join(" "))
   };
   tmux_set_option(cfg, &name, "status-right", &right)?;
 
+  // Forget the agent exit status of an earlier session of this task
+  let _ = std::fs::remove_file(agent_exit_path(project_root, &name));
+
   // Store project root for filtering
   tmux_set_option(
     cfg,
@@ -380,7 +421,23 @@ pub fn start_session(
   if let Some(parent) = stamp.parent() {
     let _ = std::fs::create_dir_all(parent);
   }
-  let pipe_cmd = format!("sh -c 'cat > {}'", shell_escape(&stamp));
+  let pipe_cmd = match cfg.daemon.as_ref().filter(|d| d.record_output) {
+    Some(daemon) => {
+      let exe = std::env::current_exe().context("failed to get current exe")?;
+      let log = recorder::session_log_path(project_root, task.id, &task.slug);
+      let max_bytes = daemon
+        .record_max_bytes
+        .unwrap_or(recorder::DEFAULT_MAX_BYTES);
+      format!(
+        "'{}' daemon record --stamp '{}' --log '{}' --max-bytes {max_bytes} --daemon-log '{}'",
+        shell_escape(&exe),
+        shell_escape(&stamp),
+        shell_escape(&log),
+        shell_escape(&compute_log_path(cfg))
+      )
+    }
+    None => format!("sh -c 'cat > {}'", shell_escape(&stamp)),
+  };
   run_cmd(
     std::process::Command::new("tmux")
       .args(tmux_args_base(cfg))
@@ -406,6 +463,23 @@ pub fn attach_session(cfg: &AgencyConfig, task: &TaskMeta) -> Result<()> {
   }
 }
 
+/// Attach as an observer: input is dropped and the client's terminal size is
+/// ignored, so observers never shrink the agent's window.
+///
+/// # Errors
+/// Returns an error if tmux fails to attach.
+pub fn attach_session_read_only(cfg: &AgencyConfig, task: &TaskMeta) -> Result<()> {
+  let name = session_name(task.id, &task.slug);
+  let mut tmux_cmd = attach_cmd(cfg, &name);
+  tmux_cmd.arg("-f").arg("read-only,ignore-size");
+  let status = tmux_cmd.status().context("failed to exec tmux attach")?;
+  if status.success() {
+    Ok(())
+  } else {
+    anyhow::bail!("tmux attach failed")
+  }
+}
+
 pub fn spawn_attach_session(
   cfg: &AgencyConfig,
   task: &TaskMeta,
@@ -443,15 +517,262 @@ fn attach_cmd(cfg: &AgencyConfig, target_name: &str) -> std::process::Command {
   tmux_cmd
 }
 
-pub fn kill_session(cfg: &AgencyConfig, task: &TaskMeta) -> Result<()> {
+/// Send `signal` to the process running in the task's pane and return its pid.
+///
+/// # Errors
+/// Returns an error if the pane pid cannot be read or the signal cannot be delivered.
+pub fn signal_session(cfg: &AgencyConfig, task: &TaskMeta, signal: i32) -> Result<u32> {
+  let name = session_name(task.id, &task.slug);
+  let pid = pane_pid(cfg, &name)?;
+  send_signal(pid, signal)?;
+  Ok(pid)
+}
+
+/// Stop (`paused`) or continue every process in the task's pane and record the
+/// state in the session option `@agency_paused`. Returns the pane pid.
+///
+/// # Errors
+/// Returns an error if the pane pid cannot be read, the pane process cannot be
+/// signaled, or tmux fails to store the state.
+pub fn set_session_paused(cfg: &AgencyConfig, task: &TaskMeta, paused: bool) -> Result<u32> {
+  let name = session_name(task.id, &task.slug);
+  let pid = pane_pid(cfg, &name)?;
+  let mut children = descendant_pids(pid);
+  if paused {
+    // Stop the shell first so it does not notice its job stopping and take
+    // back the terminal
+    send_signal(pid, libc::SIGSTOP)?;
+    for child in children {
+      let _ = send_signal(child, libc::SIGSTOP);
+    }
+  } else {
+    children.reverse();
+    for child in children {
+      let _ = send_signal(child, libc::SIGCONT);
+    }
+    send_signal(pid, libc::SIGCONT)?;
+  }
+  tmux_set_option(cfg, &name, "@agency_paused", if paused { "1" } else { "0" })?;
+  Ok(pid)
+}
+
+fn pane_pid(cfg: &AgencyConfig, name: &str) -> Result<u32> {
+  let out = std::process::Command::new("tmux")
+    .args(tmux_args_base(cfg))
+    .arg("display-message")
+    .arg("-p")
+    .arg("-t")
+    .arg(name)
+    .arg("#{pane_pid}")
+    .output()
+    .context("tmux display-message failed")?;
+  if !out.status.success() {
+    anyhow::bail!("tmux display-message failed for {name}");
+  }
+  let pid_txt = String::from_utf8_lossy(&out.stdout).trim().to_string();
+  pid_txt
+    .parse()
+    .with_context(|| format!("invalid pane pid for {name}: {pid_txt}"))
+}
+
+fn send_signal(pid: u32, signal: i32) -> Result<()> {
+  let raw_pid = libc::pid_t::try_from(pid).context("pid out of range")?;
+  // SAFETY: kill has no memory safety requirements; it only takes plain integers
+  let result = unsafe { libc::kill(raw_pid, signal) };
+  if result != 0 {
+    return Err(std::io::Error::last_os_error())
+      .with_context(|| format!("failed to signal pid {pid}"));
+  }
+  Ok(())
+}
+
+/// Descendants of `root`, parents before their children. Empty when `ps` fails.
+fn descendant_pids(root: u32) -> Vec<u32> {
+  let Ok(out) = std::process::Command::new("ps")
+    .args(["-A", "-o", "pid=", "-o", "ppid="])
+    .output()
+  else {
+    return Vec::new();
+  };
+  let pairs: Vec<(u32, u32)> = String::from_utf8_lossy(&out.stdout)
+    .lines()
+    .filter_map(|line| {
+      let mut fields = line.split_whitespace();
+      let pid = fields.next()?.parse().ok()?;
+      let ppid = fields.next()?.parse().ok()?;
+      Some((pid, ppid))
+    })
+    .collect();
+  let mut found = Vec::new();
+  let mut parents = vec![root];
+  while let Some(parent) = parents.pop() {
+    for (pid, _) in pairs.iter().filter(|(_, ppid)| *ppid == parent) {
+      found.push(*pid);
+      parents.push(*pid);
+    }
+  }
+  found
+}
+
+/// Set the task session's window to `cols` x `rows` for every attached client.
+///
+/// This switches the window to manual sizing, so it keeps the size when
+/// clients resize until the next explicit resize or [`reset_window_size`].
+///
+/// # Errors
+/// Returns an error if tmux fails to resize the window.
+pub fn resize_window(cfg: &AgencyConfig, task: &TaskMeta, cols: u16, rows: u16) -> Result<()> {
   let name = session_name(task.id, &task.slug);
+  run_cmd(
+    std::process::Command::new("tmux")
+      .args(tmux_args_base(cfg))
+      .arg("resize-window")
+      .arg("-t")
+      .arg(&name)
+      .arg("-x")
+      .arg(cols.to_string())
+      .arg("-y")
+      .arg(rows.to_string()),
+  )
+  .with_context(|| format!("tmux resize-window for {name} failed"))
+}
+
+/// Undo [`resize_window`]: drop the window's manual sizing so it follows the
+/// session's `window-size` option again.
+///
+/// # Errors
+/// Returns an error if tmux fails to unset the option.
+pub fn reset_window_size(cfg: &AgencyConfig, task: &TaskMeta) -> Result<()> {
+  let name = session_name(task.id, &task.slug);
+  run_cmd(
+    std::process::Command::new("tmux")
+      .args(tmux_args_base(cfg))
+      .args(["set-option", "-w", "-u", "-t", &name, "window-size"]),
+  )
+  .with_context(|| format!("tmux window-size reset for {name} failed"))
+}
+
+/// Kill the task's session and remove its activity stamp, exit file and prompt file.
+///
+/// # Errors
+/// Returns an error if tmux fails to kill the session.
+pub fn kill_session(cfg: &AgencyConfig, project_root: &Path, task: &TaskMeta) -> Result<()> {
+  kill_session_keeping_prompt(cfg, project_root, task)?;
+  let paths = AgencyPaths::new(project_root, project_root);
+  let task = TaskRef {
+    id: task.id,
+    slug: task.slug.clone(),
+  };
+  let _ = std::fs::remove_file(prompt_file_path(&paths, &task));
+  Ok(())
+}
+
+/// Like [`kill_session`], but keeps the prompt file, which a restart has already
+/// written for the session replacing this one.
+///
+/// # Errors
+/// Returns an error if tmux fails to kill the session.
+pub fn kill_session_keeping_prompt(
+  cfg: &AgencyConfig,
+  project_root: &Path,
+  task: &TaskMeta,
+) -> Result<()> {
+  let name = session_name(task.id, &task.slug);
+  // Stopped processes would not react to the hangup of the closing pane
+  if query_session_var(cfg, &name, "#{@agency_paused}").is_ok_and(|value| value == "1") {
+    let _ = set_session_paused(cfg, task, false);
+  }
   run_cmd(
     std::process::Command::new("tmux")
       .args(tmux_args_base(cfg))
       .arg("kill-session")
       .arg("-t")
       .arg(&name),
-  )
+  )?;
+  remove_activity_files(project_root, &name);
+  Ok(())
+}
+
+/// Kill the project's sessions whose pane has been dead for at least `grace`.
+///
+/// Dead panes only linger when `remain-on-exit` is on, e.g. through a user tmux
+/// config. Returns the number of sessions killed, 0 when tmux cannot be queried.
+pub fn reap_dead_sessions(cfg: &AgencyConfig, project_root: &Path, grace: Duration) -> usize {
+  let Ok(output) = std::process::Command::new("tmux")
+    .args(tmux_args_base(cfg))
+    .arg("list-panes")
+    .arg("-a")
+    .arg("-F")
+    .arg("#{session_name}\t#{@agency_root}\t#{pane_dead}\t#{pane_dead_time}")
+    .output()
+  else {
+    return 0;
+  };
+  if !output.status.success() {
+    return 0;
+  }
+  let now_secs = now_ms() / 1000;
+  let root = project_root.display().to_string();
+  let mut reaped = HashSet::new();
+  for line in String::from_utf8_lossy(&output.stdout).lines() {
+    let parts: Vec<&str> = line.split('\t').collect();
+    let [name, session_root, dead, dead_time] = parts[..] else {
+      continue;
+    };
+    if session_root != root || dead != "1" || reaped.contains(name) {
+      continue;
+    }
+    let Ok(dead_since) = dead_time.parse::<u64>() else {
+      continue;
+    };
+    if now_secs.saturating_sub(dead_since) < grace.as_secs() {
+      continue;
+    }
+    let Some((id, slug)) = parse_session_name(name) else {
+      continue;
+    };
+    if kill_session(cfg, project_root, &TaskMeta { id, slug }).is_ok() {
+      reaped.insert(name.to_string());
+    }
+  }
+  reaped.len()
+}
+
+/// Remove the activity stamp and exit file of every session of the project that
+/// is no longer running, e.g. left behind by a crashed tmux server.
+///
+/// Returns the number of files removed. Nothing is removed when tmux cannot be
+/// queried.
+pub fn sweep_stale_activity_files(cfg: &AgencyConfig, project_root: &Path) -> usize {
+  let Ok(entries) = std::fs::read_dir(activity_dir(project_root)) else {
+    return 0;
+  };
+  let Ok(sessions) = list_sessions_for_project(cfg, project_root) else {
+    return 0;
+  };
+  let live: HashSet<String> = sessions
+    .iter()
+    .map(|session| session_name(session.task.id, &session.task.slug))
+    .collect();
+  let mut removed = 0;
+  for entry in entries.flatten() {
+    let file_name = entry.file_name().to_string_lossy().into_owned();
+    let Some(name) = file_name
+      .strip_suffix(".stamp")
+      .or_else(|| file_name.strip_suffix(".exit"))
+    else {
+      continue;
+    };
+    if !live.contains(name) && std::fs::remove_file(entry.path()).is_ok() {
+      removed += 1;
+    }
+  }
+  removed
+}
+
+fn remove_activity_files(project_root: &Path, session_name: &str) {
+  let _ = std::fs::remove_file(activity_stamp_path(project_root, session_name));
+  let _ = std::fs::remove_file(agent_exit_path(project_root, session_name));
 }
 
 fn run_cmd(cmd: &mut std::process::Command) -> Result<()> {
@@ -598,6 +919,46 @@ pub fn send_keys_enter(cfg: &AgencyConfig, target: &str) -> Result<()> {
   )
 }
 
+/// Type `text` into the pane literally, without interpreting tmux key names.
+///
+/// # Errors
+/// Returns an error if tmux fails, e.g. because the session does not exist.
+pub fn send_text(cfg: &AgencyConfig, target: &str, text: &str) -> Result<()> {
+  run_cmd(
+    std::process::Command::new("tmux")
+      .args(tmux_args_base(cfg))
+      .arg("send-keys")
+      .arg("-l")
+      .arg("-t")
+      .arg(target)
+      .arg(text),
+  )
+}
+
+/// Return the pane content of the task's session including its scrollback,
+/// with wrapped lines joined.
+///
+/// # Errors
+/// Returns an error if tmux fails, e.g. because the session does not exist.
+pub fn capture_pane(cfg: &AgencyConfig, task: &TaskMeta) -> Result<String> {
+  let name = session_name(task.id, &task.slug);
+  let out = std::process::Command::new("tmux")
+    .args(tmux_args_base(cfg))
+    .arg("capture-pane")
+    .arg("-p")
+    .arg("-J")
+    .arg("-S")
+    .arg("-")
+    .arg("-t")
+    .arg(&name)
+    .output()
+    .context("tmux capture-pane failed")?;
+  if !out.status.success() {
+    anyhow::bail!("tmux capture-pane failed for {name}");
+  }
+  Ok(String::from_utf8_lossy(&out.stdout).into_owned())
+}
+
 fn load_default_tmux_config(cfg: &AgencyConfig) -> Result<()> {
   // Source user's default tmux config if present.
   if let Ok(home) = std::env::var("HOME") {
@@ -729,6 +1090,62 @@ fn parse_detach_binding(prefix_output: &str, global_output: &str) -> DetachBindi
   DetachBinding::None
 }
 
+/// Bind `daemon.detach_keys` to `detach-client`. Multi-key sequences chain
+/// through `agency-detach-<n>` key tables. Returns the tmux key names.
+fn bind_detach_keys(cfg: &AgencyConfig, sequence: &[u8]) -> Result<Vec<String>> {
+  let keys: Vec<String> = sequence.iter().map(|byte| tmux_key_name(*byte)).collect();
+  for index in 0..keys.len() {
+    let mut tmux_cmd = std::process::Command::new("tmux");
+    tmux_cmd
+      .args(tmux_args_base(cfg))
+      .args(detach_bind_args(&keys, index));
+    run_cmd(&mut tmux_cmd).with_context(|| format!("tmux bind-key {} failed", keys[index]))?;
+  }
+  Ok(keys)
+}
+
+/// `bind-key` arguments for step `index` of the detach sequence. The root
+/// binding only fires in sessions carrying `@agency_root`; elsewhere the key
+/// is passed through to the pane.
+fn detach_bind_args(keys: &[String], index: usize) -> Vec<String> {
+  let key = &keys[index];
+  let action = if index + 1 == keys.len() {
+    "detach-client".to_string()
+  } else {
+    format!("switch-client -T agency-detach-{}", index + 1)
+  };
+  if index == 0 {
+    vec![
+      "bind-key".to_string(),
+      "-T".to_string(),
+      "root".to_string(),
+      key.clone(),
+      "if-shell".to_string(),
+      "-F".to_string(),
+      "#{@agency_root}".to_string(),
+      action,
+      format!("send-keys {key}"),
+    ]
+  } else {
+    let mut args = vec![
+      "bind-key".to_string(),
+      "-T".to_string(),
+      format!("agency-detach-{index}"),
+      key.clone(),
+    ];
+    args.extend(action.split(' ').map(str::to_string));
+    args
+  }
+}
+
+/// tmux name of a control byte from `parse_detach_keys`, e.g. `0x11` -> `C-q`.
+fn tmux_key_name(byte: u8) -> String {
+  match byte {
+    0x01..=0x1a => format!("C-{}", char::from(b'a' + byte - 1)),
+    _ => format!("C-{}", char::from(byte + b'@')),
+  }
+}
+
 fn read_tmux_prefix(cfg: &AgencyConfig) -> Result<String> {
   let p = tmux_show_option_global(cfg, "prefix")?;
   if !p.trim().is_empty() {
@@ -755,7 +1172,7 @@ pub fn list_sessions_for_project(
     .args(tmux_args_base(cfg))
     .arg("list-sessions")
     .arg("-F")
-    .arg("#{session_name}\t#{session_id}\t#{session_created}\t#{@agency_root}\t#{session_attached}")
+    .arg("#{session_name}\t#{session_id}\t#{session_created}\t#{@agency_root}\t#{session_attached}\t#{pane_pid}\t#{@agency_startup_timeout}\t#{@agency_paused}")
     .output();
   let output = match output {
     Ok(o) => o,
@@ -766,10 +1183,11 @@ pub fn list_sessions_for_project(
     return Ok(Vec::new());
   }
   let lines = String::from_utf8_lossy(&output.stdout);
+  let dwell = idle_dwell(cfg);
   let mut out = Vec::new();
   for ln in lines.lines() {
     let parts: Vec<&str> = ln.split('\t').collect();
-    if parts.len() < 5 {
+    if parts.len() < 6 {
       continue;
     }
     let name = parts[0];
@@ -777,6 +1195,9 @@ pub fn list_sessions_for_project(
     let created_txt = parts[2];
     let root = parts[3];
     let clients_txt = parts[4];
+    let pid_txt = parts[5];
+    let startup_timeout = parts.get(6).and_then(|txt| txt.parse::<u64>().ok());
+    let paused = parts.get(7).is_some_and(|txt| *txt == "1");
     if root != project_root.display().to_string() {
       continue;
     }
@@ -786,11 +1207,22 @@ pub fn list_sessions_for_project(
     let session_id: u64 = sid_txt.parse().unwrap_or(0);
     let created_at_ms: u64 = created_txt.parse::<u64>().unwrap_or(0) * 1000;
     let clients: u32 = clients_txt.parse().unwrap_or(0);
+    let pid: u32 = pid_txt.parse().unwrap_or(0);
+    let bytes_out = std::fs::metadata(activity_stamp_path(project_root, name))
+      .map(|meta| meta.len())
+      .unwrap_or(0);
+    let log_dropped_bytes = recorder::dropped_bytes(project_root, id, &slug);
     let cwd = query_session_var(cfg, name, "#{session_path}")?;
     let dead = pane_dead(cfg, name)?;
     let status = if dead {
       "Exited".to_string()
-    } else if is_idle(project_root, name) {
+    } else if paused {
+      "Paused".to_string()
+    } else if startup_timeout
+      .is_some_and(|secs| agent_failed_at_startup(project_root, name, created_at_ms, secs))
+    {
+      "Failed".to_string()
+    } else if is_idle(project_root, name, dwell) {
       "Idle".to_string()
     } else {
       "Running".to_string()
@@ -802,11 +1234,94 @@ pub fn list_sessions_for_project(
       status,
       clients,
       cwd,
+      pid,
+      bytes_out,
+      log_dropped_bytes,
+    });
+  }
+  Ok(out)
+}
+
+/// List tmux clients attached to the project's task sessions.
+///
+/// # Errors
+/// Returns an error if tmux cannot be spawned.
+pub fn list_attachments_for_project(
+  cfg: &AgencyConfig,
+  project_root: &Path,
+) -> Result<Vec<AttachmentInfo>> {
+  let output = std::process::Command::new("tmux")
+    .args(tmux_args_base(cfg))
+    .arg("list-clients")
+    .arg("-F")
+    .arg("#{client_name}\t#{session_name}\t#{session_id}\t#{@agency_root}\t#{client_width}\t#{client_height}\t#{client_activity}")
+    .output();
+  let output = match output {
+    Ok(o) => o,
+    Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+    Err(e) => return Err(e).context("tmux list-clients failed"),
+  };
+  if !output.status.success() {
+    return Ok(Vec::new());
+  }
+  let now = now_ms();
+  let root = project_root.display().to_string();
+  let mut out = Vec::new();
+  for line in String::from_utf8_lossy(&output.stdout).lines() {
+    let parts: Vec<&str> = line.split('\t').collect();
+    let [client, name, sid, agency_root, width, height, activity] = parts.as_slice() else {
+      continue;
+    };
+    if *agency_root != root {
+      continue;
+    }
+    let Some((id, slug)) = parse_session_name(name) else {
+      continue;
+    };
+    let activity_ms = activity.parse::<u64>().unwrap_or(0) * 1000;
+    out.push(AttachmentInfo {
+      attachment_id: (*client).to_string(),
+      session_id: sid.trim_start_matches('$').parse().unwrap_or(0),
+      task: TaskMeta { id, slug },
+      rows: height.parse().unwrap_or(0),
+      cols: width.parse().unwrap_or(0),
+      idle_ms: now.saturating_sub(activity_ms),
     });
   }
   Ok(out)
 }
 
+/// Count Agency sessions and their attached clients across all projects.
+/// Returns zeros when the tmux server is not running.
+#[must_use]
+pub fn count_sessions_and_clients(cfg: &AgencyConfig) -> (u32, u32) {
+  let output = std::process::Command::new("tmux")
+    .args(tmux_args_base(cfg))
+    .arg("list-sessions")
+    .arg("-F")
+    .arg("#{session_name}\t#{session_attached}")
+    .output();
+  let Ok(output) = output else {
+    return (0, 0);
+  };
+  if !output.status.success() {
+    return (0, 0);
+  }
+  let mut sessions: u32 = 0;
+  let mut clients: u32 = 0;
+  for line in String::from_utf8_lossy(&output.stdout).lines() {
+    let Some((name, attached)) = line.split_once('\t') else {
+      continue;
+    };
+    if parse_session_name(name).is_none() {
+      continue;
+    }
+    sessions += 1;
+    clients += attached.parse::<u32>().unwrap_or(0);
+  }
+  (sessions, clients)
+}
+
 fn parse_session_name(name: &str) -> Option<(u32, String)> {
   let prefix = "agency-";
   if !name.starts_with(prefix) {
@@ -851,15 +1366,70 @@ fn pane_dead(cfg: &AgencyConfig, target: &str) -> Result<bool> {
   Ok(s.lines().any(|l| l.trim() == "1"))
 }
 
-fn activity_stamp_path(project_root: &Path, session_name: &str) -> PathBuf {
+/// Directory holding the activity stamps and exit files of the project's sessions.
+fn activity_dir(project_root: &Path) -> PathBuf {
   project_root
     .join(".agency")
     .join("state")
     .join("tmux-activity")
-    .join(format!("{session_name}.stamp"))
 }
 
-fn is_idle(project_root: &Path, name: &str) -> bool {
+fn activity_stamp_path(project_root: &Path, session_name: &str) -> PathBuf {
+  activity_dir(project_root).join(format!("{session_name}.stamp"))
+}
+
+/// File the agent wrapper writes the agent's exit status to.
+#[must_use]
+pub fn agent_exit_path(project_root: &Path, session_name: &str) -> PathBuf {
+  activity_dir(project_root).join(format!("{session_name}.exit"))
+}
+
+/// Enable startup failure detection for the session `target`.
+///
+/// # Errors
+/// Returns an error if tmux fails to set the session option.
+pub fn set_startup_timeout(cfg: &AgencyConfig, target: &str, secs: u64) -> Result<()> {
+  tmux_set_option(cfg, target, "@agency_startup_timeout", &secs.to_string())
+}
+
+/// True when the agent exited with a non-zero status within `timeout_secs`
+/// of the session's creation.
+fn agent_failed_at_startup(
+  project_root: &Path,
+  name: &str,
+  created_at_ms: u64,
+  timeout_secs: u64,
+) -> bool {
+  let path = agent_exit_path(project_root, name);
+  let Ok(code) = std::fs::read_to_string(&path) else {
+    return false;
+  };
+  if code.trim() == "0" {
+    return false;
+  }
+  let Some(exited_at_ms) = std::fs::metadata(&path)
+    .and_then(|meta| meta.modified())
+    .ok()
+    .and_then(|mtime| mtime.duration_since(UNIX_EPOCH).ok())
+    .and_then(|age| u64::try_from(age.as_millis()).ok())
+  else {
+    return false;
+  };
+  // `session_created` has second resolution, so allow for the truncated part
+  exited_at_ms.saturating_sub(created_at_ms) < (timeout_secs + 1) * 1000
+}
+
+/// How long a session must be without output before it is reported as idle.
+fn idle_dwell(cfg: &AgencyConfig) -> Duration {
+  let secs = cfg
+    .daemon
+    .as_ref()
+    .and_then(|d| d.dwell_secs)
+    .unwrap_or(DEFAULT_DWELL_SECS);
+  Duration::from_secs(secs)
+}
+
+fn is_idle(project_root: &Path, name: &str, dwell: Duration) -> bool {
   let p = activity_stamp_path(project_root, name);
   let Ok(meta) = std::fs::metadata(&p) else {
     return false;
@@ -868,12 +1438,19 @@ fn is_idle(project_root: &Path, name: &str) -> bool {
   let age = std::time::SystemTime::now()
     .duration_since(mtime)
     .unwrap_or_default();
-  age >= std::time::Duration::from_secs(1)
+  age >= dwell
 }
 
 #[cfg(test)]
 mod tests {
-  use super::{DetachBinding, parse_detach_binding};
+  use super::{
+    DEFAULT_WINDOW_FORMAT, DetachBinding, activity_stamp_path, agent_exit_path,
+    agent_failed_at_startup, detach_bind_args, idle_dwell, is_idle, parse_detach_binding,
+    status_text, tmux_key_name,
+  };
+  use crate::config::{AgencyConfig, DaemonConfig};
+  use crate::daemon_protocol::TaskMeta;
+  use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
   #[test]
   fn parse_prefix_table_detach() {
@@ -899,6 +1476,54 @@ mod tests {
     assert_eq!(got, DetachBinding::Prefixless { key: "M-d".into() });
   }
 
+  #[test]
+  fn status_text_renders_configured_window_format() {
+    let cfg: AgencyConfig =
+      toml::from_str("[tmux]\nwindow_format = \"#[bold]$SLUG#[default] #$ID\"\n").expect("parse");
+    let task = TaskMeta {
+      id: 7,
+      slug: "fix-login".to_string(),
+    };
+    let template = cfg.tmux.and_then(|tmux| tmux.window_format);
+    assert_eq!(
+      status_text(template.as_deref(), DEFAULT_WINDOW_FORMAT, &task),
+      "#[bold]fix-login#[default] #7"
+    );
+    assert_eq!(
+      status_text(None, DEFAULT_WINDOW_FORMAT, &task),
+      " Task fix-login (Id: 7) "
+    );
+  }
+
+  #[test]
+  fn detach_root_binding_only_fires_in_agency_sessions() {
+    let keys = vec!["C-q".to_string(), "C-x".to_string()];
+    assert_eq!(
+      detach_bind_args(&keys, 0),
+      [
+        "bind-key",
+        "-T",
+        "root",
+        "C-q",
+        "if-shell",
+        "-F",
+        "#{@agency_root}",
+        "switch-client -T agency-detach-1",
+        "send-keys C-q",
+      ]
+    );
+    assert_eq!(
+      detach_bind_args(&keys, 1),
+      ["bind-key", "-T", "agency-detach-1", "C-x", "detach-client"]
+    );
+  }
+
+  #[test]
+  fn tmux_key_name_maps_control_bytes() {
+    assert_eq!(tmux_key_name(0x11), "C-q");
+    assert_eq!(tmux_key_name(0x1d), "C-]");
+  }
+
   #[test]
   fn prefer_prefix_over_prefixless_when_both() {
     let pref = "bind-key -T prefix d detach-client\n";
@@ -906,4 +1531,61 @@ mod tests {
     let got = parse_detach_binding(pref, glob);
     assert_eq!(got, DetachBinding::WithPrefix { key: "d".into() });
   }
+
+  #[test]
+  fn idle_waits_for_configured_dwell() {
+    let root = tempfile::tempdir().expect("temp dir");
+    let stamp = activity_stamp_path(root.path(), "agency-1-task");
+    std::fs::create_dir_all(stamp.parent().unwrap()).expect("stamp dir");
+    let file = std::fs::File::create(&stamp).expect("stamp");
+    let cfg = AgencyConfig {
+      daemon: Some(DaemonConfig {
+        dwell_secs: Some(3),
+        ..Default::default()
+      }),
+      ..Default::default()
+    };
+    let dwell = idle_dwell(&cfg);
+    assert_eq!(dwell, Duration::from_secs(3));
+
+    file
+      .set_modified(SystemTime::now() - Duration::from_secs(2))
+      .expect("set mtime");
+    assert!(!is_idle(root.path(), "agency-1-task", dwell));
+
+    file
+      .set_modified(SystemTime::now() - Duration::from_secs(4))
+      .expect("set mtime");
+    assert!(is_idle(root.path(), "agency-1-task", dwell));
+  }
+
+  #[test]
+  fn startup_failure_needs_error_exit_within_timeout() {
+    let root = tempfile::tempdir().expect("temp dir");
+    let exit_file = agent_exit_path(root.path(), "agency-1-task");
+    let exit_dir = exit_file.parent().expect("exit file has a parent");
+    std::fs::create_dir_all(exit_dir).expect("exit dir");
+    let now_ms = u64::try_from(
+      SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("clock")
+        .as_millis(),
+    )
+    .expect("millis");
+    let failed =
+      |created_at_ms| agent_failed_at_startup(root.path(), "agency-1-task", created_at_ms, 5);
+    assert!(!failed(now_ms));
+
+    std::fs::write(&exit_file, "0\n").expect("write exit");
+    assert!(!failed(now_ms));
+
+    std::fs::write(&exit_file, "127\n").expect("write exit");
+    assert!(failed(now_ms));
+    assert!(!failed(now_ms - 60_000));
+  }
+
+  #[test]
+  fn idle_dwell_defaults_to_one_second() {
+    assert_eq!(idle_dwell(&AgencyConfig::default()), Duration::from_secs(1));
+  }
 }
@@ -0,0 +1,69 @@
+use std::fs;
+use std::io::{self, Write as _};
+use std::path::Path;
+use std::thread;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+
+/// Prefix of a transcript line that pauses instead of printing, e.g. `@sleep 500`.
+const SLEEP_DIRECTIVE: &str = "@sleep ";
+
+/// One step of a transcript.
+#[derive(Debug, PartialEq, Eq)]
+enum Step<'a> {
+  Print(&'a str),
+  Sleep(Duration),
+}
+
+/// Print the lines of a transcript `file` to stdout, pausing `delay` after
+/// each one.
+///
+/// Runs as the agent of `[agents.<name>]` entries with a `transcript_file`,
+/// which gives tests a deterministic stand-in for a real agent. Lines of the
+/// form `@sleep <ms>` pause instead of printing, e.g. to let a session go idle.
+///
+/// # Errors
+/// Returns an error if the transcript cannot be read or stdout is closed.
+pub fn replay(file: &Path, delay: Duration) -> Result<()> {
+  let content =
+    fs::read_to_string(file).with_context(|| format!("failed to read {}", file.display()))?;
+  let mut stdout = io::stdout().lock();
+  for line in content.lines() {
+    match parse_step(line) {
+      Step::Print(text) => {
+        writeln!(stdout, "{text}")?;
+        stdout.flush()?;
+        thread::sleep(delay);
+      }
+      Step::Sleep(pause) => thread::sleep(pause),
+    }
+  }
+  Ok(())
+}
+
+fn parse_step(line: &str) -> Step<'_> {
+  line
+    .strip_prefix(SLEEP_DIRECTIVE)
+    .and_then(|millis| millis.trim().parse().ok())
+    .map_or(Step::Print(line), |millis| {
+      Step::Sleep(Duration::from_millis(millis))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{Step, parse_step};
+  use std::time::Duration;
+
+  #[test]
+  fn sleep_directives_pause_and_other_lines_print() {
+    assert_eq!(
+      parse_step("@sleep 250"),
+      Step::Sleep(Duration::from_millis(250))
+    );
+    assert_eq!(parse_step("Thinking..."), Step::Print("Thinking..."));
+    assert_eq!(parse_step("@sleep soon"), Step::Print("@sleep soon"));
+    assert_eq!(parse_step(""), Step::Print(""));
+  }
+}
@@ -1,4 +1,5 @@
 use std::io::{self, IsTerminal, Read, Write};
+use std::process::{Command, Stdio};
 
 use anyhow::{Context, Result};
 
@@ -70,6 +71,39 @@ impl Tty {
     }
     Ok(default)
   }
+
+  /// Show `text` through `$PAGER` (default `less -R`) when interactive,
+  /// otherwise write it to stdout unchanged.
+  ///
+  /// # Errors
+  /// Returns an error if the pager cannot be started or stdout cannot be written.
+  pub fn page(&self, text: &str) -> Result<()> {
+    if !self.is_interactive {
+      io::stdout()
+        .write_all(text.as_bytes())
+        .context("failed to write to stdout")?;
+      return Ok(());
+    }
+    let pager = std::env::var("PAGER")
+      .ok()
+      .filter(|value| !value.trim().is_empty())
+      .unwrap_or_else(|| "less -R".to_string());
+    // Like git, let less quit on short output and keep colors unless LESS is set
+    let less_opts = std::env::var("LESS").unwrap_or_else(|_| "FRX".to_string());
+    let mut child = Command::new("sh")
+      .arg("-c")
+      .arg(&pager)
+      .env("LESS", less_opts)
+      .stdin(Stdio::piped())
+      .spawn()
+      .with_context(|| format!("failed to start pager `{pager}`"))?;
+    if let Some(mut stdin) = child.stdin.take() {
+      // The pager may quit before reading everything; a broken pipe is fine
+      let _ = stdin.write_all(text.as_bytes());
+    }
+    child.wait().context("failed to wait for pager")?;
+    Ok(())
+  }
 }
 
 impl Default for Tty {
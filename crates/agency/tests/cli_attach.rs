@@ -1,5 +1,8 @@
 mod common;
 
+use std::process::Stdio;
+use std::time::{Duration, Instant};
+
 use crate::common::test_env::TestEnv;
 use anyhow::Result;
 
@@ -27,3 +30,119 @@ fn attach_follow_conflicts_with_task_and_session() -> Result<()> {
     Ok(())
   })
 }
+
+#[test]
+fn attach_read_only_conflicts_with_follow() -> Result<()> {
+  TestEnv::run(|env| -> Result<()> {
+    env.init_repo()?;
+
+    env
+      .agency()?
+      .arg("attach")
+      .arg("--read-only")
+      .arg("--follow")
+      .assert()
+      .failure();
+    Ok(())
+  })
+}
+
+#[test]
+#[ignore = "needs-tty"]
+fn attach_reconnect_reattaches_after_restart() -> Result<()> {
+  TestEnv::run(|env| -> Result<()> {
+    env.init_repo()?;
+    if !env.sockets_available() {
+      eprintln!(
+        "Skipping attach_reconnect_reattaches_after_restart: Unix sockets not available in sandbox"
+      );
+      return Ok(());
+    }
+    let (id, _slug) = env.new_task("reconnect-task", &["-a", "sh"])?;
+    env.agency_daemon_start()?;
+    env
+      .agency()?
+      .args(["start", &id.to_string()])
+      .assert()
+      .success();
+
+    let mut client = env.spawn_agency_in_pty(&["attach", "--reconnect", &id.to_string()])?;
+    let mut first = Vec::new();
+    env.wait_for(|| {
+      first = env.agency_tmux_clients("#{session_id}")?;
+      Ok(first.len() == 1)
+    })?;
+
+    env
+      .agency()?
+      .args(["restart", &id.to_string()])
+      .assert()
+      .success();
+    env.wait_for(|| {
+      let current = env.agency_tmux_clients("#{session_id}")?;
+      Ok(current.len() == 1 && current != first)
+    })?;
+
+    let _ = client.kill();
+    let _ = client.wait();
+    env.agency_daemon_stop_all()?;
+
+    Ok(())
+  })
+}
+
+#[test]
+#[ignore = "needs-tty"]
+fn attach_wait_ready_joins_a_session_that_is_still_starting() -> Result<()> {
+  TestEnv::run(|env| -> Result<()> {
+    env.init_repo()?;
+    if !env.sockets_available() {
+      eprintln!(
+        "Skipping attach_wait_ready_joins_a_session_that_is_still_starting: Unix sockets not available in sandbox"
+      );
+      return Ok(());
+    }
+    let (id, _slug) = env.new_task("wait-ready-task", &["-a", "sh"])?;
+    env.agency_daemon_start()?;
+
+    let mut start = env
+      .agency_process()
+      .args(["start", &id.to_string()])
+      .stdout(Stdio::null())
+      .stderr(Stdio::null())
+      .spawn()?;
+    // Read-only attach fails right away without a session, so this only
+    // succeeds by waiting for the concurrent start
+    let mut client = env.spawn_agency_in_pty(&[
+      "attach",
+      "--read-only",
+      "--wait-ready-ms",
+      "10000",
+      &id.to_string(),
+    ])?;
+    assert!(start.wait()?.success(), "start failed");
+
+    let deadline = Instant::now() + Duration::from_secs(10);
+    let attached = loop {
+      let clients = env.agency_tmux_clients("#{client_readonly}")?;
+      if clients == ["1"] || Instant::now() >= deadline {
+        break clients == ["1"];
+      }
+      assert!(
+        client.try_wait()?.is_none(),
+        "attach exited before a session was ready"
+      );
+      std::thread::sleep(Duration::from_millis(100));
+    };
+
+    let _ = client.kill();
+    let _ = client.wait();
+    env.agency_daemon_stop_all()?;
+    assert!(
+      attached,
+      "expected a read-only client attached to the session"
+    );
+
+    Ok(())
+  })
+}
@@ -2,6 +2,7 @@ mod common;
 
 use crate::common::test_env::TestEnv;
 use anyhow::Result;
+use predicates::prelude::*;
 use temp_env::with_vars;
 
 #[test]
@@ -41,6 +42,59 @@ fn new_bootstraps_git_ignored_root_files_with_defaults() -> Result<()> {
   })
 }
 
+#[test]
+fn new_bootstrap_skips_agencyignore_matches() -> Result<()> {
+  TestEnv::run(|env| -> Result<()> {
+    env.init_repo()?;
+
+    std::fs::write(env.path().join(".gitignore"), ".env\n.env.local\n")?;
+    env.write_file(".agencyignore", "# local only\n*.local\n")?;
+    env.write_file(".env", "KEY=VALUE\n")?;
+    env.write_file(".env.local", "LOCAL=1\n")?;
+
+    let (id, slug) = env.new_task("bootstrap-agencyignore", &[])?;
+    env.bootstrap_task(id)?;
+    let wt = env.worktree_dir_path(id, &slug);
+
+    assert!(wt.join(".env").is_file());
+    assert!(!wt.join(".env.local").exists());
+
+    Ok(())
+  })
+}
+
+#[test]
+fn worktree_root_config_relocates_worktrees() -> Result<()> {
+  TestEnv::run(|env| -> Result<()> {
+    env.init_repo()?;
+    let wt_root = tempfile::tempdir()?;
+    env.write_file(
+      ".agency/agency.toml",
+      &format!("worktree_root = \"{}\"\n", wt_root.path().display()),
+    )?;
+    std::fs::write(env.path().join(".gitignore"), ".env\n")?;
+    env.write_file(".env", "KEY=VALUE\n")?;
+
+    let (id, slug) = env.new_task("external-worktree", &[])?;
+    env.bootstrap_task(id)?;
+    let project_dirs: Vec<_> = std::fs::read_dir(wt_root.path())?.collect::<Result<_, _>>()?;
+    assert_eq!(project_dirs.len(), 1, "expected one project directory");
+    let wt = project_dirs[0].path().join(format!("{id}-{slug}"));
+
+    assert!(wt.join(".env").is_file());
+    assert!(!env.worktree_dir_path(id, &slug).exists());
+    let expected = wt.canonicalize()?.display().to_string();
+    env
+      .agency()?
+      .args(["path", &id.to_string()])
+      .assert()
+      .success()
+      .stdout(predicates::str::contains(expected).from_utf8());
+
+    Ok(())
+  })
+}
+
 #[test]
 fn new_bootstrap_respects_config_includes_and_excludes() -> Result<()> {
   TestEnv::run(|env| -> Result<()> {
@@ -0,0 +1,49 @@
+mod common;
+
+use crate::common::test_env::TestEnv;
+use anyhow::Result;
+use predicates::prelude::*;
+
+#[test]
+fn commit_stages_changes_and_uses_template() -> Result<()> {
+  TestEnv::run(|env| -> Result<()> {
+    env.init_repo()?;
+    env.write_file(
+      ".agency/agency.toml",
+      "commit_template = \"Task $ID: $SLUG\"\n[agents.sh]\ncmd = [\"sh\"]\n",
+    )?;
+    let (id, slug) = env.new_task("commit-task", &[])?;
+    env.bootstrap_task(id)?;
+    let branch = env.branch_name(id, &slug);
+
+    env
+      .agency()?
+      .args(["commit", &id.to_string()])
+      .assert()
+      .failure()
+      .stderr(predicates::str::contains("Nothing to commit").from_utf8());
+
+    let worktree = env.worktree_dir_path(id, &slug);
+    std::fs::write(worktree.join("notes.txt"), "hello\n")?;
+    env
+      .agency()?
+      .args(["commit", &id.to_string()])
+      .assert()
+      .success();
+    let subject = env.git_stdout(&["log", "-1", "--format=%s", &branch])?;
+    assert_eq!(subject, format!("Task {id}: {slug}"));
+    let files = env.git_stdout(&["show", "--name-only", "--format=", &branch])?;
+    assert_eq!(files, "notes.txt");
+
+    std::fs::write(worktree.join("notes.txt"), "changed\n")?;
+    env
+      .agency()?
+      .args(["commit", &id.to_string(), "-m", "Custom message"])
+      .assert()
+      .success();
+    let subject = env.git_stdout(&["log", "-1", "--format=%s", &branch])?;
+    assert_eq!(subject, "Custom message");
+
+    Ok(())
+  })
+}
@@ -142,3 +142,125 @@ fn complete_works_when_already_merged() -> Result<()> {
     Ok(())
   })
 }
+
+#[test]
+fn complete_push_updates_remote_base() -> Result<()> {
+  TestEnv::run(|env| -> Result<()> {
+    env.init_repo()?;
+    let remote_dir = env.path().join("remote.git");
+    let remote = remote_dir.to_str().expect("utf8 path");
+    env.git_stdout(&["init", "--bare", "-q", remote])?;
+    env.git_stdout(&["remote", "add", "origin", remote])?;
+
+    let (id, slug) = env.new_task("complete-push", &["--draft"])?;
+    env.bootstrap_task(id)?;
+    let _ = env.git_commit_empty_tree_to_task_branch(id, &slug, "test")?;
+
+    env
+      .agency()?
+      .args(["complete", &id.to_string(), "--yes", "--push"])
+      .assert()
+      .success();
+
+    let local = env.git_stdout(&["rev-parse", "main"])?;
+    let pushed = env.git_stdout(&["--git-dir", remote, "rev-parse", "main"])?;
+    assert_eq!(local, pushed, "remote main should match local main");
+    assert!(
+      !env.task_file_path(id, &slug).exists(),
+      "task file should be removed after a successful push"
+    );
+
+    Ok(())
+  })
+}
+
+#[test]
+fn complete_push_failure_keeps_task() -> Result<()> {
+  TestEnv::run(|env| -> Result<()> {
+    env.init_repo()?;
+    let (id, slug) = env.new_task("complete-push-fail", &["--draft"])?;
+    env.bootstrap_task(id)?;
+    let _ = env.git_commit_empty_tree_to_task_branch(id, &slug, "test")?;
+
+    env
+      .agency()?
+      .args(["complete", &id.to_string(), "--yes", "--push", "missing"])
+      .assert()
+      .failure()
+      .stderr(predicates::str::contains("was kept"));
+
+    assert!(
+      env.branch_exists(id, &slug)?,
+      "branch should be kept when the push fails"
+    );
+    assert!(
+      env.task_file_path(id, &slug).exists(),
+      "task file should be kept when the push fails"
+    );
+
+    Ok(())
+  })
+}
+
+#[test]
+fn complete_no_delete_merges_and_keeps_artifacts() -> Result<()> {
+  TestEnv::run(|env| -> Result<()> {
+    env.init_repo()?;
+    let (id, slug) = env.new_task("complete-keep", &["--draft"])?;
+    env.bootstrap_task(id)?;
+    let _ = env.git_commit_empty_tree_to_task_branch(id, &slug, "test")?;
+    let old_main = env.git_branch_head_id("main")?;
+
+    let output = env
+      .agency()?
+      .args(["complete", &id.to_string(), "--no-delete"])
+      .output()?;
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("kept"), "stdout: {stdout}");
+
+    assert_ne!(old_main, env.git_branch_head_id("main")?);
+    assert!(env.branch_exists(id, &slug)?, "branch should be kept");
+    assert!(env.task_file_path(id, &slug).exists());
+    assert!(env.worktree_dir_path(id, &slug).exists());
+    let history = env.agency()?.args(["history", &id.to_string()]).output()?;
+    let history = String::from_utf8_lossy(&history.stdout);
+    assert!(history.contains("Completed"), "history: {history}");
+
+    Ok(())
+  })
+}
+
+#[test]
+fn complete_squash_lands_one_commit_and_cleans_up() -> Result<()> {
+  TestEnv::run(|env| -> Result<()> {
+    env.init_repo()?;
+    let (id, slug) = env.new_task("complete-squash", &["--draft"])?;
+    env.bootstrap_task(id)?;
+    env.git_commit_file_in_worktree(id, &slug, "a.txt", "a", "wip 1")?;
+    env.git_commit_file_in_worktree(id, &slug, "b.txt", "b", "wip 2")?;
+    let old_main = env.git_branch_head_id("main")?;
+
+    env
+      .agency()?
+      .args(["complete", &id.to_string(), "--squash", "-y"])
+      .assert()
+      .success();
+
+    let range = format!("{old_main}..main");
+    assert_eq!(env.git_stdout(&["rev-list", "--count", &range])?, "1");
+    let subject = env.git_stdout(&["log", "-1", "--format=%s", "main"])?;
+    assert_eq!(subject, slug);
+    let files = env.git_stdout(&["show", "--name-only", "--format=", "main"])?;
+    assert_eq!(files, "a.txt\nb.txt");
+
+    assert!(!env.branch_exists(id, &slug)?, "branch should be removed");
+    assert!(!env.task_file_path(id, &slug).exists());
+    assert!(!env.worktree_dir_path(id, &slug).exists());
+    let history = env.agency()?.args(["history", &id.to_string()]).output()?;
+    let history = String::from_utf8_lossy(&history.stdout);
+    assert!(history.contains("Completed"), "history: {history}");
+
+    Ok(())
+  })
+}
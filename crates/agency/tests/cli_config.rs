@@ -0,0 +1,58 @@
+mod common;
+
+use crate::common::test_env::TestEnv;
+use anyhow::Result;
+use predicates::prelude::*;
+
+#[test]
+fn config_validate_reports_errors_and_fails() -> Result<()> {
+  TestEnv::run(|env| -> Result<()> {
+    env.init_repo()?;
+    env.write_file(
+      ".agency/agency.toml",
+      r#"agent = "missing"
+
+[agents.broken]
+cmd = []
+
+[daemon]
+detach_keys = "alt-q"
+"#,
+    )?;
+
+    env
+      .agency()?
+      .args(["config", "validate"])
+      .assert()
+      .failure()
+      .stderr(predicates::str::contains("agent 'missing' is not defined").from_utf8())
+      .stderr(predicates::str::contains("agents.broken.cmd is empty").from_utf8())
+      .stderr(predicates::str::contains("invalid daemon.detach_keys 'alt-q'").from_utf8())
+      .stderr(predicates::str::contains("Config has 3 error(s)").from_utf8());
+    Ok(())
+  })
+}
+
+#[test]
+fn config_validate_accepts_valid_file_from_path() -> Result<()> {
+  TestEnv::run(|env| -> Result<()> {
+    env.init_repo()?;
+    let file = env.write_file(
+      "ci/agency.toml",
+      r#"agent = "claude"
+
+[daemon]
+detach_keys = "ctrl-p ctrl-q"
+"#,
+    )?;
+
+    env
+      .agency()?
+      .args(["config", "validate", "--path"])
+      .arg(&file)
+      .assert()
+      .success()
+      .stdout(predicates::str::contains("Config is valid").from_utf8());
+    Ok(())
+  })
+}
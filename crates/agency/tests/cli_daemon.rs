@@ -1,6 +1,9 @@
 mod common;
 
 use crate::common::test_env::TestEnv;
+use agency::daemon_protocol::{
+  C2D, C2DControl, D2C, D2CControl, DaemonErrorCode, ProjectKey, read_frame, write_frame,
+};
 use anyhow::Result;
 use predicates::prelude::*;
 
@@ -169,3 +172,1266 @@ fn sessions_bails_when_daemon_not_running() -> Result<()> {
     Ok(())
   })
 }
+
+#[test]
+fn stop_reports_task_not_running() -> Result<()> {
+  TestEnv::run(|env| -> Result<()> {
+    env.init_repo()?;
+    if !env.sockets_available() {
+      eprintln!("Skipping stop_reports_task_not_running: Unix sockets not available in sandbox");
+      return Ok(());
+    }
+    let (id, slug) = env.new_task("idle-task", &[])?;
+
+    env.agency_daemon_start()?;
+
+    env
+      .agency()?
+      .arg("stop")
+      .arg(id.to_string())
+      .assert()
+      .failure()
+      .stderr(predicates::str::contains(format!("Task {id}-{slug} is not running")).from_utf8());
+
+    env.agency_daemon_stop()?;
+
+    Ok(())
+  })
+}
+
+#[test]
+fn logs_reads_log_file_without_daemon() -> Result<()> {
+  TestEnv::run(|env| -> Result<()> {
+    env.init_repo()?;
+    let log = env.runtime_dir().join("agency.logs.jsonl");
+    std::fs::write(
+      &log,
+      concat!(
+        r#"{"ts":"2026-01-01T00:00:00Z","level":"INFO","target":"agency","message":"quiet line"}"#,
+        "\n",
+        r#"{"ts":"2026-01-01T00:00:01Z","level":"WARN","target":"agency","message":"loud line"}"#,
+        "\n",
+      ),
+    )?;
+
+    env
+      .agency()?
+      .args(["logs", "--level", "warn"])
+      .assert()
+      .success()
+      .stdout(predicates::str::contains("WARN  agency loud line").from_utf8())
+      .stdout(predicates::str::contains("quiet line").not().from_utf8());
+
+    env
+      .agency()?
+      .args(["logs", "-n", "1"])
+      .assert()
+      .success()
+      .stdout(predicates::str::contains("loud line").from_utf8())
+      .stdout(predicates::str::contains("quiet line").not().from_utf8());
+
+    Ok(())
+  })
+}
+
+#[test]
+fn daemon_logs_shows_startup_line() -> Result<()> {
+  TestEnv::run(|env| -> Result<()> {
+    env.init_repo()?;
+    if !env.sockets_available() {
+      eprintln!("Skipping daemon_logs_shows_startup_line: Unix sockets not available in sandbox");
+      return Ok(());
+    }
+
+    env.agency_daemon_start()?;
+
+    env
+      .agency()?
+      .args(["daemon", "logs", "--level", "info"])
+      .assert()
+      .success()
+      .stdout(predicates::str::contains("INFO").from_utf8())
+      .stdout(predicates::str::contains("Starting daemon").from_utf8());
+
+    env
+      .agency()?
+      .args(["daemon", "logs", "--level", "error"])
+      .assert()
+      .success()
+      .stdout(
+        predicates::str::contains("Starting daemon")
+          .not()
+          .from_utf8(),
+      );
+
+    env.agency_daemon_stop()?;
+
+    Ok(())
+  })
+}
+
+#[test]
+fn daemon_record_tees_output_to_stamp_and_log() -> Result<()> {
+  TestEnv::run(|env| -> Result<()> {
+    env.init_repo()?;
+    let stamp = env.path().join(".agency/state/tmux-activity/s.stamp");
+    let log = env.path().join(".agency/state/logs/1-task.log");
+    std::fs::create_dir_all(stamp.parent().unwrap())?;
+
+    env
+      .agency()?
+      .args(["daemon", "record", "--stamp"])
+      .arg(&stamp)
+      .arg("--log")
+      .arg(&log)
+      .args(["--max-bytes", "1024"])
+      .write_stdin("\u{1b}[32mhello\u{1b}[0m\n")
+      .assert()
+      .success();
+
+    assert_eq!(std::fs::read_to_string(&log)?, "\u{1b}[32mhello\u{1b}[0m\n");
+    assert!(std::fs::read_to_string(&stamp)?.contains("hello"));
+
+    Ok(())
+  })
+}
+
+#[test]
+fn stop_all_stops_every_session_in_project() -> Result<()> {
+  TestEnv::run(|env| -> Result<()> {
+    env.init_repo()?;
+    if !env.sockets_available() {
+      eprintln!(
+        "Skipping stop_all_stops_every_session_in_project: Unix sockets not available in sandbox"
+      );
+      return Ok(());
+    }
+    let (first, _) = env.new_task("stop-all-one", &["-a", "sh"])?;
+    let (second, _) = env.new_task("stop-all-two", &["-a", "sh"])?;
+
+    env.agency_daemon_start()?;
+    for id in [first, second] {
+      env
+        .agency()?
+        .args(["start", &id.to_string()])
+        .assert()
+        .success();
+    }
+
+    env
+      .agency()?
+      .args(["stop", "--all"])
+      .assert()
+      .success()
+      .stdout(predicates::str::contains("Stopped 2 session(s)").from_utf8());
+
+    env
+      .agency()?
+      .args(["stop", "--all"])
+      .assert()
+      .success()
+      .stdout(predicates::str::contains("No running sessions to stop").from_utf8());
+
+    env.agency_daemon_stop_all()?;
+
+    Ok(())
+  })
+}
+
+#[test]
+fn daemon_health_counts_running_sessions() -> Result<()> {
+  TestEnv::run(|env| -> Result<()> {
+    env.init_repo()?;
+    if !env.sockets_available() {
+      eprintln!(
+        "Skipping daemon_health_counts_running_sessions: Unix sockets not available in sandbox"
+      );
+      return Ok(());
+    }
+    let (first, _) = env.new_task("health-one", &["-a", "sh"])?;
+    let (second, _) = env.new_task("health-two", &["-a", "sh"])?;
+
+    env.agency_daemon_start()?;
+    for id in [first, second] {
+      env
+        .agency()?
+        .arg("start")
+        .arg(id.to_string())
+        .assert()
+        .success();
+    }
+
+    let socket = env.runtime_dir().join("agency.sock");
+    let mut stream = std::os::unix::net::UnixStream::connect(&socket)?;
+    write_frame(&mut stream, &C2D::Control(C2DControl::Health))?;
+    let reply: D2C = read_frame(&mut stream)?;
+    assert!(
+      matches!(
+        reply,
+        D2C::Control(D2CControl::Health {
+          session_count: 2,
+          attached_client_count: 0,
+          ..
+        })
+      ),
+      "unexpected reply: {reply:?}"
+    );
+
+    env
+      .agency()?
+      .args(["daemon", "health"])
+      .assert()
+      .success()
+      .stdout(predicates::str::contains("Sessions:    2").from_utf8());
+
+    env.agency_daemon_stop_all()?;
+
+    Ok(())
+  })
+}
+
+#[test]
+fn signal_kill_ends_running_session() -> Result<()> {
+  TestEnv::run(|env| -> Result<()> {
+    env.init_repo()?;
+    if !env.sockets_available() {
+      eprintln!("Skipping signal_kill_ends_running_session: Unix sockets not available in sandbox");
+      return Ok(());
+    }
+    let (id, slug) = env.new_task("signal-task", &["-a", "sh"])?;
+
+    env.agency_daemon_start()?;
+    env
+      .agency()?
+      .arg("start")
+      .arg(id.to_string())
+      .assert()
+      .success();
+
+    env
+      .agency()?
+      .args(["signal", &id.to_string(), "--signal", "kill"])
+      .assert()
+      .success()
+      .stdout(predicates::str::contains("Sent SIGKILL").from_utf8());
+
+    env.wait_for(|| {
+      let output = env.agency()?.arg("stop").arg(id.to_string()).output()?;
+      let stderr = String::from_utf8_lossy(&output.stderr);
+      Ok(stderr.contains(&format!("Task {id}-{slug} is not running")))
+    })?;
+
+    env
+      .agency()?
+      .args(["signal", &id.to_string(), "--signal", "HUP"])
+      .assert()
+      .failure();
+
+    env.agency_daemon_stop_all()?;
+
+    Ok(())
+  })
+}
+
+#[test]
+fn start_agent_override_keeps_front_matter() -> Result<()> {
+  TestEnv::run(|env| -> Result<()> {
+    env.init_repo()?;
+    if !env.sockets_available() {
+      eprintln!(
+        "Skipping start_agent_override_keeps_front_matter: Unix sockets not available in sandbox"
+      );
+      return Ok(());
+    }
+    env.write_file(
+      ".agency/agency.toml",
+      "[agents.sh]\ncmd = [\"sh\"]\n\n[agents.alt]\ncmd = [\"sh\"]\n",
+    )?;
+    let (id, slug) = env.new_task("override-task", &["-a", "sh"])?;
+
+    env.agency_daemon_start()?;
+    env
+      .agency()?
+      .args(["start", &id.to_string(), "--agent", "nope"])
+      .assert()
+      .failure()
+      .stderr(predicates::str::contains("unknown agent: nope").from_utf8());
+
+    env
+      .agency()?
+      .args(["start", &id.to_string(), "--agent", "alt"])
+      .assert()
+      .success();
+    let task_content = env.read_task_file(id, &slug)?;
+    assert!(task_content.contains("agent: sh"));
+
+    env.agency_daemon_stop_all()?;
+
+    Ok(())
+  })
+}
+
+#[test]
+fn start_rejects_sessions_over_concurrency_limit() -> Result<()> {
+  TestEnv::run(|env| -> Result<()> {
+    env.init_repo()?;
+    if !env.sockets_available() {
+      eprintln!(
+        "Skipping start_rejects_sessions_over_concurrency_limit: Unix sockets not available in sandbox"
+      );
+      return Ok(());
+    }
+    env.write_file(
+      ".agency/agency.toml",
+      "concurrency = 1\n\n[agents.sh]\ncmd = [\"sh\"]\n",
+    )?;
+    let (first, first_slug) = env.new_task("limit-one", &["-a", "sh"])?;
+    let (second, _) = env.new_task("limit-two", &["-a", "sh"])?;
+
+    env.agency_daemon_start()?;
+    env
+      .agency()?
+      .args(["start", &first.to_string()])
+      .assert()
+      .success();
+    env
+      .agency()?
+      .args(["start", &second.to_string()])
+      .assert()
+      .failure()
+      .stderr(predicates::str::contains("concurrency limit reached (1)").from_utf8())
+      .stderr(predicates::str::contains(format!("{first}-{first_slug}")).from_utf8());
+
+    env.agency_daemon_stop_all()?;
+
+    Ok(())
+  })
+}
+
+#[test]
+fn sessions_json_includes_pid_and_stats() -> Result<()> {
+  TestEnv::run(|env| -> Result<()> {
+    env.init_repo()?;
+    if !env.sockets_available() {
+      eprintln!(
+        "Skipping sessions_json_includes_pid_and_stats: Unix sockets not available in sandbox"
+      );
+      return Ok(());
+    }
+    let (id, slug) = env.new_task("json-session", &["-a", "sh"])?;
+
+    env.agency_daemon_start()?;
+    env
+      .agency()?
+      .arg("start")
+      .arg(id.to_string())
+      .assert()
+      .success();
+
+    let output = env.agency()?.args(["sessions", "--json"]).output()?;
+    assert!(output.status.success());
+    let items: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    let items = items.as_array().expect("json array");
+    assert_eq!(items.len(), 1);
+    let item = &items[0];
+    assert_eq!(item["task_id"], id);
+    assert_eq!(item["slug"], slug);
+    assert!(item["pid"].as_u64().unwrap_or(0) > 0);
+    assert!(item["elapsed_ms"].is_u64());
+    assert!(item["bytes_out"].is_u64());
+    assert_eq!(item["log_dropped_bytes"], 0);
+
+    env.agency_daemon_stop_all()?;
+
+    Ok(())
+  })
+}
+
+#[test]
+fn daemon_answers_batch_requests_in_order() -> Result<()> {
+  TestEnv::run(|env| -> Result<()> {
+    env.init_repo()?;
+    if !env.sockets_available() {
+      eprintln!(
+        "Skipping daemon_answers_batch_requests_in_order: Unix sockets not available in sandbox"
+      );
+      return Ok(());
+    }
+    env.agency_daemon_start()?;
+
+    let socket = env.runtime_dir().join("agency.sock");
+    let mut stream = std::os::unix::net::UnixStream::connect(&socket)?;
+    let requests = vec![
+      C2DControl::Ping { nonce: 1 },
+      C2DControl::GetVersion,
+      C2DControl::Ping { nonce: 2 },
+      C2DControl::Batch { requests: vec![] },
+    ];
+    write_frame(&mut stream, &C2D::Control(C2DControl::Batch { requests }))?;
+    let reply: D2C = read_frame(&mut stream)?;
+    let D2C::Control(D2CControl::Batch { replies }) = reply else {
+      panic!("unexpected reply: {reply:?}");
+    };
+    assert_eq!(replies.len(), 4);
+    assert_eq!(replies[0], D2CControl::Pong { nonce: 1 });
+    assert!(matches!(replies[1], D2CControl::Version { .. }));
+    assert_eq!(replies[2], D2CControl::Pong { nonce: 2 });
+    assert!(matches!(
+      replies[3],
+      D2CControl::Error {
+        code: DaemonErrorCode::InvalidRequest,
+        ..
+      }
+    ));
+
+    env.agency_daemon_stop_all()?;
+
+    Ok(())
+  })
+}
+
+#[test]
+fn daemon_update_task_changes_only_given_frontmatter_fields() -> Result<()> {
+  TestEnv::run(|env| -> Result<()> {
+    env.init_repo()?;
+    if !env.sockets_available() {
+      eprintln!(
+        "Skipping daemon_update_task_changes_only_given_frontmatter_fields: Unix sockets not available in sandbox"
+      );
+      return Ok(());
+    }
+    let (id, slug) = env.new_task("retarget", &["-a", "sh"])?;
+    let body = "\nKeep this body\n\n  exactly as it is\n";
+    std::fs::write(
+      env.task_file_path(id, &slug),
+      format!("---\nagent: sh\nbase_branch: main\n---\n{body}"),
+    )?;
+    env.agency_daemon_start()?;
+
+    let socket = env.runtime_dir().join("agency.sock");
+    let mut stream = std::os::unix::net::UnixStream::connect(&socket)?;
+    let request = C2DControl::UpdateTask {
+      project: ProjectKey {
+        repo_root: env.path().display().to_string(),
+      },
+      task: slug.clone(),
+      agent: None,
+      base_branch: Some("release".to_string()),
+    };
+    write_frame(&mut stream, &C2D::Control(request))?;
+    let reply: D2C = read_frame(&mut stream)?;
+    let D2C::Control(D2CControl::TaskUpdated { task }) = reply else {
+      panic!("unexpected reply: {reply:?}");
+    };
+    assert_eq!(task.base_branch.as_deref(), Some("release"));
+
+    let content = env.read_task_file(id, &slug)?;
+    assert!(content.contains("agent: sh"));
+    assert!(content.contains("base_branch: release"));
+    let stored_body = content.split_once("\n---\n").map(|(_, rest)| rest);
+    assert_eq!(stored_body, Some(body));
+
+    let mut stream = std::os::unix::net::UnixStream::connect(&socket)?;
+    let request = C2DControl::UpdateTask {
+      project: ProjectKey {
+        repo_root: env.path().display().to_string(),
+      },
+      task: "no-such-task".to_string(),
+      agent: Some("sh".to_string()),
+      base_branch: None,
+    };
+    write_frame(&mut stream, &C2D::Control(request))?;
+    let reply: D2C = read_frame(&mut stream)?;
+    assert!(
+      matches!(
+        reply,
+        D2C::Control(D2CControl::Error {
+          code: DaemonErrorCode::TaskNotFound,
+          ..
+        })
+      ),
+      "unexpected reply: {reply:?}"
+    );
+    assert_eq!(DaemonErrorCode::TaskNotFound.to_string(), "error[-32001]");
+
+    env.agency_daemon_stop_all()?;
+
+    Ok(())
+  })
+}
+
+#[test]
+fn daemon_list_project_state_filters_by_status_and_label() -> Result<()> {
+  TestEnv::run(|env| -> Result<()> {
+    env.init_repo()?;
+    if !env.sockets_available() {
+      eprintln!(
+        "Skipping daemon_list_project_state_filters_by_status_and_label: Unix sockets not available in sandbox"
+      );
+      return Ok(());
+    }
+    let (stopped_id, _) = env.new_task("stopped-task", &["-l", "api"])?;
+    env.bootstrap_task(stopped_id)?;
+    env.new_task("draft-task", &["-l", "api"])?;
+    env.new_task("other-task", &[])?;
+    env.agency_daemon_start()?;
+
+    let socket = env.runtime_dir().join("agency.sock");
+    let list = |statuses: &[&str], labels: &[&str]| -> Result<D2CControl> {
+      let to_vec = |items: &[&str]| Some(items.iter().map(|item| (*item).to_string()).collect());
+      let mut stream = std::os::unix::net::UnixStream::connect(&socket)?;
+      let request = C2DControl::ListProjectState {
+        project: ProjectKey {
+          repo_root: env.path().display().to_string(),
+        },
+        status_filter: to_vec(statuses),
+        label_filter: to_vec(labels),
+      };
+      write_frame(&mut stream, &C2D::Control(request))?;
+      let D2C::Control(reply) = read_frame(&mut stream)?;
+      Ok(reply)
+    };
+    let slugs = |reply: D2CControl| -> Vec<String> {
+      let D2CControl::ProjectState { tasks, .. } = reply else {
+        panic!("unexpected reply: {reply:?}");
+      };
+      tasks.into_iter().map(|task| task.slug).collect()
+    };
+
+    assert_eq!(slugs(list(&["stopped"], &[])?), ["stopped-task"]);
+    assert_eq!(slugs(list(&[], &["api"])?), ["stopped-task", "draft-task"]);
+    assert_eq!(slugs(list(&["draft"], &["api"])?), ["draft-task"]);
+    assert_eq!(slugs(list(&[], &[])?).len(), 3);
+    let D2CControl::Error { code, .. } = list(&["sleeping"], &[])? else {
+      panic!("unknown status should be rejected");
+    };
+    assert_eq!(code, DaemonErrorCode::InvalidRequest);
+
+    env.agency_daemon_stop_all()?;
+
+    Ok(())
+  })
+}
+
+#[test]
+fn daemon_stop_sends_goodbye_to_subscribers() -> Result<()> {
+  TestEnv::run(|env| -> Result<()> {
+    env.init_repo()?;
+    if !env.sockets_available() {
+      eprintln!(
+        "Skipping daemon_stop_sends_goodbye_to_subscribers: Unix sockets not available in sandbox"
+      );
+      return Ok(());
+    }
+    env.agency_daemon_start()?;
+
+    let socket = env.runtime_dir().join("agency.sock");
+    let mut stream = std::os::unix::net::UnixStream::connect(&socket)?;
+    stream.set_read_timeout(Some(std::time::Duration::from_secs(5)))?;
+    let project = ProjectKey {
+      repo_root: env.path().display().to_string(),
+    };
+    write_frame(
+      &mut stream,
+      &C2D::Control(C2DControl::SubscribeEvents { project }),
+    )?;
+    let initial: D2C = read_frame(&mut stream)?;
+    assert!(matches!(
+      initial,
+      D2C::Control(D2CControl::ProjectState { .. })
+    ));
+
+    env.agency_daemon_stop_all()?;
+
+    let mut said_goodbye = false;
+    while let Ok(D2C::Control(msg)) = read_frame::<_, D2C>(&mut stream) {
+      if msg == D2CControl::Goodbye {
+        said_goodbye = true;
+        break;
+      }
+    }
+    assert!(said_goodbye, "subscriber did not receive Goodbye");
+    assert!(!socket.exists());
+
+    Ok(())
+  })
+}
+
+#[test]
+fn daemon_attachments_lists_attached_clients() -> Result<()> {
+  TestEnv::run(|env| -> Result<()> {
+    env.init_repo()?;
+    if !env.sockets_available() {
+      eprintln!(
+        "Skipping daemon_attachments_lists_attached_clients: Unix sockets not available in sandbox"
+      );
+      return Ok(());
+    }
+    let (first, first_slug) = env.new_task("attach-one", &["-a", "sh"])?;
+    let (second, second_slug) = env.new_task("attach-two", &["-a", "sh"])?;
+
+    env.agency_daemon_start()?;
+    for id in [first, second] {
+      env
+        .agency()?
+        .args(["start", &id.to_string()])
+        .assert()
+        .success();
+    }
+
+    // Control-mode clients attach without needing a terminal
+    let mut clients = Vec::new();
+    for (id, slug) in [(first, &first_slug), (second, &second_slug)] {
+      let client = env
+        .agency_tmux()
+        .args(["-C", "attach", "-t", &format!("agency-{id}-{slug}")])
+        .stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::null())
+        .spawn()?;
+      clients.push(client);
+    }
+
+    env.wait_for(|| {
+      let output = env.agency()?.args(["daemon", "attachments"]).output()?;
+      let stdout = String::from_utf8_lossy(&output.stdout);
+      let rows = stdout
+        .lines()
+        .filter(|line| line.contains("attach-"))
+        .count();
+      Ok(
+        rows == 2
+          && stdout.contains(&format!("{first}-{first_slug}"))
+          && stdout.contains(&format!("{second}-{second_slug}")),
+      )
+    })?;
+
+    for mut client in clients {
+      let _ = client.kill();
+      let _ = client.wait();
+    }
+    env.agency_daemon_stop_all()?;
+
+    Ok(())
+  })
+}
+
+#[test]
+fn transcript_agent_replays_lines_in_order() -> Result<()> {
+  TestEnv::run(|env| -> Result<()> {
+    env.init_repo()?;
+    if !env.sockets_available() {
+      eprintln!(
+        "Skipping transcript_agent_replays_lines_in_order: Unix sockets not available in sandbox"
+      );
+      return Ok(());
+    }
+    env.write_file(
+      ".agency/transcript.txt",
+      "scripted-one\n@sleep 50\nscripted-two\nscripted-three\n",
+    )?;
+    env.write_file(
+      ".agency/agency.toml",
+      "[agents.transcript]\ntranscript_file = \"<root>/.agency/transcript.txt\"\ntranscript_delay_ms = 10\n",
+    )?;
+    let (id, slug) = env.new_task("transcript-task", &["-a", "transcript"])?;
+    env.agency_daemon_start()?;
+    env
+      .agency()?
+      .args(["start", &id.to_string()])
+      .assert()
+      .success();
+
+    env.wait_for(|| {
+      let output = env
+        .agency_tmux()
+        .args(["capture-pane", "-p", "-t", &format!("agency-{id}-{slug}")])
+        .output()?;
+      let pane = String::from_utf8_lossy(&output.stdout);
+      let replayed: Vec<&str> = pane
+        .lines()
+        .map(str::trim)
+        .filter(|line| line.starts_with("scripted-"))
+        .collect();
+      Ok(replayed == ["scripted-one", "scripted-two", "scripted-three"])
+    })?;
+
+    env.agency_daemon_stop_all()?;
+
+    Ok(())
+  })
+}
+
+#[test]
+fn stopping_a_session_removes_its_activity_stamp() -> Result<()> {
+  TestEnv::run(|env| -> Result<()> {
+    env.init_repo()?;
+    if !env.sockets_available() {
+      eprintln!(
+        "Skipping stopping_a_session_removes_its_activity_stamp: Unix sockets not available in sandbox"
+      );
+      return Ok(());
+    }
+    let activity_dir = env.path().join(".agency/state/tmux-activity");
+    let ghost = activity_dir.join("agency-99-ghost.stamp");
+    env.write_file(".agency/state/tmux-activity/agency-99-ghost.stamp", "")?;
+    let (id, slug) = env.new_task("stamp-task", &["-a", "sh"])?;
+    env.agency_daemon_start()?;
+    env
+      .agency()?
+      .args(["start", &id.to_string()])
+      .assert()
+      .success();
+
+    let stamp = activity_dir.join(format!("agency-{id}-{slug}.stamp"));
+    env.wait_for(|| Ok(stamp.is_file()))?;
+    assert!(!ghost.exists(), "stamp without a session should be swept");
+
+    env
+      .agency()?
+      .args(["stop", &id.to_string()])
+      .assert()
+      .success();
+    assert!(!stamp.exists(), "stamp should be removed with its session");
+
+    env.agency_daemon_stop_all()?;
+
+    Ok(())
+  })
+}
+
+#[test]
+fn daemon_resize_applies_size_to_all_attachments_of_session() -> Result<()> {
+  TestEnv::run(|env| -> Result<()> {
+    env.init_repo()?;
+    if !env.sockets_available() {
+      eprintln!(
+        "Skipping daemon_resize_applies_size_to_all_attachments_of_session: Unix sockets not available in sandbox"
+      );
+      return Ok(());
+    }
+    let (id, slug) = env.new_task("resize-task", &["-a", "sh"])?;
+    env.agency_daemon_start()?;
+    env
+      .agency()?
+      .args(["start", &id.to_string()])
+      .assert()
+      .success();
+
+    let mut clients = Vec::new();
+    for _ in 0..2 {
+      let client = env
+        .agency_tmux()
+        .args(["-C", "attach", "-t", &format!("agency-{id}-{slug}")])
+        .stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::null())
+        .spawn()?;
+      clients.push(client);
+    }
+    let mut driver = String::new();
+    env.wait_for(|| {
+      let output = env.agency()?.args(["daemon", "attachments"]).output()?;
+      let stdout = String::from_utf8_lossy(&output.stdout);
+      let rows: Vec<&str> = stdout
+        .lines()
+        .filter(|line| line.contains("resize-task"))
+        .collect();
+      if let Some(id) = rows.first().and_then(|row| row.split_whitespace().next()) {
+        driver = id.to_string();
+      }
+      Ok(rows.len() == 2)
+    })?;
+
+    env
+      .agency()?
+      .args(["daemon", "resize", &driver, "--rows", "20", "--cols", "70"])
+      .assert()
+      .success()
+      .stdout(predicates::str::contains("to 70x20 for 2 client(s)").from_utf8());
+    let sizes = env.agency_tmux_clients("#{window_width}x#{window_height}")?;
+    assert_eq!(sizes, ["70x20", "70x20"]);
+
+    env
+      .agency()?
+      .args(["daemon", "resize", &driver, "--auto"])
+      .assert()
+      .success()
+      .stdout(predicates::str::contains("Restored automatic size").from_utf8());
+    let window_size = env
+      .agency_tmux()
+      .args(["show-options", "-w", "-v", "-t"])
+      .arg(format!("agency-{id}-{slug}"))
+      .arg("window-size")
+      .output()?;
+    assert_eq!(String::from_utf8_lossy(&window_size.stdout).trim(), "");
+
+    env
+      .agency()?
+      .args(["daemon", "resize", "no-such-client"])
+      .args(["--rows", "20", "--cols", "70"])
+      .assert()
+      .failure()
+      .stderr(predicates::str::contains("No attachment no-such-client").from_utf8());
+
+    for mut client in clients {
+      let _ = client.kill();
+      let _ = client.wait();
+    }
+    env.agency_daemon_stop_all()?;
+
+    Ok(())
+  })
+}
+
+#[test]
+fn daemon_restart_hard_kills_sessions_and_restarts() -> Result<()> {
+  TestEnv::run(|env| -> Result<()> {
+    env.init_repo()?;
+    if !env.sockets_available() {
+      eprintln!(
+        "Skipping daemon_restart_hard_kills_sessions_and_restarts: Unix sockets not available in sandbox"
+      );
+      return Ok(());
+    }
+    let (id, _) = env.new_task("hard-restart", &["-a", "sh"])?;
+    env.agency_daemon_start()?;
+    env
+      .agency()?
+      .args(["start", &id.to_string()])
+      .assert()
+      .success();
+
+    // Without --yes the non-interactive default is to cancel
+    env
+      .agency()?
+      .args(["daemon", "restart", "--hard"])
+      .assert()
+      .success()
+      .stdout(predicates::str::contains("Cancelled").from_utf8());
+
+    env
+      .agency()?
+      .args(["daemon", "restart", "--hard", "--yes"])
+      .assert()
+      .success()
+      .stdout(predicates::str::contains("Killed 1 session(s)").from_utf8());
+
+    env
+      .agency()?
+      .args(["daemon", "status"])
+      .assert()
+      .success()
+      .stdout(predicates::str::contains("Sessions: 0").from_utf8());
+
+    env.agency_daemon_stop_all()?;
+
+    Ok(())
+  })
+}
+
+#[test]
+fn daemon_status_json_reports_running_and_stopped() -> Result<()> {
+  TestEnv::run(|env| -> Result<()> {
+    env.init_repo()?;
+
+    let output = env
+      .agency()?
+      .args(["daemon", "status", "--json"])
+      .output()?;
+    assert!(output.status.success());
+    let status: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    assert_eq!(status, serde_json::json!({ "running": false }));
+
+    if !env.sockets_available() {
+      eprintln!(
+        "Skipping daemon_status_json_reports_running_and_stopped: Unix sockets not available in sandbox"
+      );
+      return Ok(());
+    }
+    env.agency_daemon_start()?;
+
+    let output = env
+      .agency()?
+      .args(["daemon", "status", "--json"])
+      .output()?;
+    assert!(output.status.success());
+    let status: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    assert_eq!(status["running"], true);
+    assert!(status["pid"].as_u64().unwrap_or(0) > 0);
+    assert!(status["version"].is_string());
+    assert!(
+      status["socket_path"]
+        .as_str()
+        .is_some_and(|path| path.ends_with("agency.sock"))
+    );
+    assert_eq!(status["session_count"], 0);
+    assert!(status["uptime_secs"].is_u64());
+
+    env.agency_daemon_stop_all()?;
+
+    Ok(())
+  })
+}
+
+#[test]
+fn sessions_watch_lists_sessions_and_fails_when_daemon_stops() -> Result<()> {
+  TestEnv::run(|env| -> Result<()> {
+    env.init_repo()?;
+    if !env.sockets_available() {
+      eprintln!(
+        "Skipping sessions_watch_lists_sessions_and_fails_when_daemon_stops: Unix sockets not available in sandbox"
+      );
+      return Ok(());
+    }
+    let (id, slug) = env.new_task("watch-task", &["-a", "sh"])?;
+
+    env.agency_daemon_start()?;
+    env
+      .agency()?
+      .args(["start", &id.to_string()])
+      .assert()
+      .success();
+
+    std::thread::scope(|scope| -> Result<()> {
+      let stopper = scope.spawn(|| -> Result<()> {
+        // Stop the daemon once the watcher has subscribed
+        for _ in 0..100 {
+          let output = env.agency()?.args(["daemon", "health"]).output()?;
+          if String::from_utf8_lossy(&output.stdout).contains("Subscribers: 1") {
+            break;
+          }
+          std::thread::sleep(std::time::Duration::from_millis(100));
+        }
+        env.agency_daemon_stop_all()?;
+        Ok(())
+      });
+
+      env
+        .agency()?
+        .args(["sessions", "--watch"])
+        .timeout(std::time::Duration::from_secs(20))
+        .assert()
+        .failure()
+        .stdout(predicates::str::contains(format!("{id}-{slug}")).from_utf8())
+        .stderr(predicates::str::contains("Daemon connection lost").from_utf8());
+
+      stopper.join().expect("stopper thread")
+    })
+  })
+}
+
+#[test]
+fn restart_starts_fresh_agent_for_running_task() -> Result<()> {
+  TestEnv::run(|env| -> Result<()> {
+    env.init_repo()?;
+    if !env.sockets_available() {
+      eprintln!(
+        "Skipping restart_starts_fresh_agent_for_running_task: Unix sockets not available in sandbox"
+      );
+      return Ok(());
+    }
+    let starts_log = env.path().join("starts.log");
+    env.write_file(
+      ".agency/agency.toml",
+      &format!(
+        "[agents.sh]\ncmd = [\"sh\", \"-c\", \"echo started >> {}; exec sh\"]\n",
+        starts_log.display()
+      ),
+    )?;
+    let (id, _slug) = env.new_task("restart-task", &["-a", "sh"])?;
+
+    env.agency_daemon_start()?;
+    env
+      .agency()?
+      .args(["restart", &id.to_string()])
+      .assert()
+      .failure()
+      .stderr(predicates::str::contains("is not running").from_utf8());
+
+    env
+      .agency()?
+      .args(["start", &id.to_string()])
+      .assert()
+      .success();
+    env.wait_for(|| {
+      let log = std::fs::read_to_string(&starts_log).unwrap_or_default();
+      Ok(log.lines().count() == 1)
+    })?;
+
+    env
+      .agency()?
+      .args(["restart", &id.to_string()])
+      .assert()
+      .success()
+      .stdout(predicates::str::contains("Restarted session").from_utf8());
+    env.wait_for(|| {
+      let log = std::fs::read_to_string(&starts_log).unwrap_or_default();
+      Ok(log.lines().count() == 2)
+    })?;
+
+    // A restart that cannot plan the new session keeps the old one running
+    env.write_file(".agency/agency.toml", "")?;
+    env
+      .agency()?
+      .args(["restart", &id.to_string()])
+      .assert()
+      .failure()
+      .stderr(predicates::str::contains("unknown agent: sh").from_utf8());
+    let output = env.agency()?.args(["sessions", "--json"]).output()?;
+    let sessions: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    assert_eq!(sessions.as_array().map(Vec::len), Some(1));
+
+    env.agency_daemon_stop_all()?;
+
+    Ok(())
+  })
+}
+
+#[test]
+fn tui_list_shows_registered_tuis_and_focus() -> Result<()> {
+  TestEnv::run(|env| -> Result<()> {
+    env.init_repo()?;
+    if !env.sockets_available() {
+      eprintln!(
+        "Skipping tui_list_shows_registered_tuis_and_focus: Unix sockets not available in sandbox"
+      );
+      return Ok(());
+    }
+    env.agency_daemon_start()?;
+
+    let socket = env.runtime_dir().join("agency.sock");
+    let project = ProjectKey {
+      repo_root: env.path().display().to_string(),
+    };
+    let request = |control: C2DControl| -> Result<D2CControl> {
+      let mut stream = std::os::unix::net::UnixStream::connect(&socket)?;
+      write_frame(&mut stream, &C2D::Control(control))?;
+      let D2C::Control(reply) = read_frame(&mut stream)?;
+      Ok(reply)
+    };
+    // The test process stands in for the TUI so the daemon sees a live pid
+    let pid = std::process::id();
+    let D2CControl::TuiRegistered { tui_id } = request(C2DControl::TuiRegister {
+      project: project.clone(),
+      pid,
+    })?
+    else {
+      panic!("expected TuiRegistered reply");
+    };
+    request(C2DControl::TuiFocusTaskChange {
+      project,
+      tui_id,
+      task_id: Some(7),
+    })?;
+
+    let output = env.agency()?.args(["tui", "list", "--json"]).output()?;
+    assert!(output.status.success(), "tui list failed: {output:?}");
+    let items: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    assert_eq!(
+      items,
+      serde_json::json!([{ "tui_id": tui_id, "pid": pid, "focused_task_id": 7 }])
+    );
+
+    env.agency_daemon_stop_all()?;
+
+    Ok(())
+  })
+}
+
+#[test]
+fn daemon_reaps_sessions_with_dead_panes_after_grace_period() -> Result<()> {
+  TestEnv::run(|env| -> Result<()> {
+    env.init_repo()?;
+    if !env.sockets_available() {
+      eprintln!(
+        "Skipping daemon_reaps_sessions_with_dead_panes_after_grace_period: Unix sockets not available in sandbox"
+      );
+      return Ok(());
+    }
+    env.write_file(
+      ".agency/agency.toml",
+      "[agents.sh]\ncmd = [\"sh\"]\n\n[daemon]\nsession_reap_secs = 1\n",
+    )?;
+    let (id, slug) = env.new_task("reap-task", &["-a", "sh"])?;
+    env.agency_daemon_start()?;
+    env
+      .agency()?
+      .args(["start", &id.to_string()])
+      .assert()
+      .success();
+
+    // Keep the pane around after its process dies, like a crashed agent
+    let session = format!("agency-{id}-{slug}");
+    let tmux = |args: &[&str]| -> Result<String> {
+      let output = env.agency_tmux().args(args).output()?;
+      Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    };
+    tmux(&["set-option", "-w", "-t", &session, "remain-on-exit", "on"])?;
+    let pane_pid = tmux(&["list-panes", "-t", &session, "-F", "#{pane_pid}"])?;
+    std::process::Command::new("kill")
+      .args(["-KILL", &pane_pid])
+      .status()?;
+    env.wait_for(|| Ok(tmux(&["list-panes", "-t", &session, "-F", "#{pane_dead}"])? == "1"))?;
+
+    let deadline = std::time::Instant::now() + std::time::Duration::from_secs(5);
+    loop {
+      let output = env.agency()?.args(["sessions", "--json"]).output()?;
+      let sessions: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+      if sessions == serde_json::json!([]) {
+        break;
+      }
+      assert!(
+        std::time::Instant::now() < deadline,
+        "dead session was not reaped: {sessions}"
+      );
+      std::thread::sleep(std::time::Duration::from_millis(200));
+    }
+    assert!(
+      !env
+        .path()
+        .join(".agency/state/tmux-activity")
+        .join(format!("{session}.stamp"))
+        .exists()
+    );
+
+    env.agency_daemon_stop_all()?;
+
+    Ok(())
+  })
+}
+
+#[test]
+fn session_scrollback_survives_daemon_restart() -> Result<()> {
+  TestEnv::run(|env| -> Result<()> {
+    env.init_repo()?;
+    if !env.sockets_available() {
+      eprintln!(
+        "Skipping session_scrollback_survives_daemon_restart: Unix sockets not available in sandbox"
+      );
+      return Ok(());
+    }
+    let (id, slug) = env.new_task("scrollback-task", &["-a", "sh"])?;
+    env.agency_daemon_start()?;
+    env
+      .agency()?
+      .args(["start", &id.to_string()])
+      .assert()
+      .success();
+
+    // Output lives in the tmux pane history, which outlives the daemon
+    let session = format!("agency-{id}-{slug}");
+    let history = || -> Result<String> {
+      let output = env
+        .agency_tmux()
+        .args(["capture-pane", "-p", "-S", "-", "-t", &session])
+        .output()?;
+      Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    };
+    env
+      .agency_tmux()
+      .args([
+        "send-keys",
+        "-t",
+        &session,
+        "echo before-$((40+2))",
+        "Enter",
+      ])
+      .status()?;
+    env.wait_for(|| Ok(history()?.contains("before-42")))?;
+
+    // Without --yes the tmux server keeps running
+    env.agency_daemon_stop()?;
+    env.agency_daemon_start()?;
+
+    assert!(history()?.contains("before-42"));
+    env
+      .agency()?
+      .args(["sessions", "--json"])
+      .assert()
+      .success()
+      .stdout(predicates::str::contains("scrollback-task").from_utf8());
+
+    env.agency_daemon_stop_all()?;
+
+    Ok(())
+  })
+}
+
+#[test]
+fn pause_stops_agent_process_and_resume_continues_it() -> Result<()> {
+  TestEnv::run(|env| -> Result<()> {
+    env.init_repo()?;
+    if !env.sockets_available() {
+      eprintln!(
+        "Skipping pause_stops_agent_process_and_resume_continues_it: Unix sockets not available in sandbox"
+      );
+      return Ok(());
+    }
+    env.write_file(
+      ".agency/agency.toml",
+      "[agents.sleeper]\ncmd = [\"sleep\", \"30\"]\n",
+    )?;
+    let (id, slug) = env.new_task("pause-task", &["-a", "sleeper"])?;
+    env.agency_daemon_start()?;
+    env
+      .agency()?
+      .args(["start", &id.to_string()])
+      .assert()
+      .success();
+
+    let output = env
+      .agency_tmux()
+      .args([
+        "display-message",
+        "-p",
+        "-t",
+        &format!("agency-{id}-{slug}"),
+      ])
+      .arg("#{pane_pid}")
+      .output()?;
+    let pane_pid = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    let mut agent_pid = String::new();
+    env.wait_for(|| {
+      let output = std::process::Command::new("pgrep")
+        .args(["-P", &pane_pid, "sleep"])
+        .output()?;
+      agent_pid = String::from_utf8_lossy(&output.stdout).trim().to_string();
+      Ok(!agent_pid.is_empty())
+    })?;
+    let state = || -> Result<String> {
+      let output = std::process::Command::new("ps")
+        .args(["-o", "stat=", "-p", &agent_pid])
+        .output()?;
+      Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    };
+    let status = || -> Result<String> {
+      let output = env.agency()?.args(["sessions", "--json"]).output()?;
+      let sessions: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+      Ok(
+        sessions[0]["status"]
+          .as_str()
+          .unwrap_or_default()
+          .to_string(),
+      )
+    };
+
+    env
+      .agency()?
+      .args(["pause", &id.to_string()])
+      .assert()
+      .success();
+    assert!(state()?.starts_with('T'), "agent should be stopped");
+    assert_eq!(status()?, "Paused");
+
+    env
+      .agency()?
+      .args(["resume", &id.to_string()])
+      .assert()
+      .success();
+    assert!(!state()?.starts_with('T'), "agent should run again");
+    assert_ne!(status()?, "Paused");
+
+    env.agency_daemon_stop_all()?;
+
+    Ok(())
+  })
+}
@@ -0,0 +1,50 @@
+mod common;
+
+use crate::common::test_env::TestEnv;
+use anyhow::Result;
+use predicates::prelude::*;
+
+#[test]
+fn diff_prints_task_changes_against_base() -> Result<()> {
+  TestEnv::run(|env| -> Result<()> {
+    env.init_repo()?;
+    let (id, slug) = env.new_task("review-me", &[])?;
+    env.bootstrap_task(id)?;
+    env.git_commit_file_in_worktree(id, &slug, "feature.txt", "new line\n", "add feature")?;
+
+    env
+      .agency()?
+      .args(["diff", &slug])
+      .assert()
+      .success()
+      .stdout(predicates::str::contains("+++ b/feature.txt").from_utf8())
+      .stdout(predicates::str::contains("+new line").from_utf8());
+
+    env
+      .agency()?
+      .args(["diff", &id.to_string(), "--stat"])
+      .assert()
+      .success()
+      .stdout(predicates::str::contains("feature.txt | 1 +").from_utf8())
+      .stdout(predicates::str::contains("+new line").not().from_utf8());
+
+    Ok(())
+  })
+}
+
+#[test]
+fn diff_reports_no_changes_without_branch() -> Result<()> {
+  TestEnv::run(|env| -> Result<()> {
+    env.init_repo()?;
+    let (id, _slug) = env.new_task("untouched", &[])?;
+
+    env
+      .agency()?
+      .args(["diff", &id.to_string()])
+      .assert()
+      .success()
+      .stdout(predicates::str::contains("no changes").from_utf8());
+
+    Ok(())
+  })
+}
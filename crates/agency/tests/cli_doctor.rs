@@ -0,0 +1,24 @@
+mod common;
+
+use crate::common::test_env::TestEnv;
+use anyhow::Result;
+use predicates::prelude::*;
+
+#[test]
+fn doctor_fails_outside_a_git_repository() -> Result<()> {
+  TestEnv::run(|env| -> Result<()> {
+    // Test dirs live inside this repo, so stop git discovery at their parent
+    let ceiling = env.path().parent().expect("test dir has a parent");
+
+    env
+      .agency()?
+      .env("GIT_CEILING_DIRECTORIES", ceiling)
+      .arg("doctor")
+      .assert()
+      .failure()
+      .stderr(predicates::str::contains("fail  Not inside a git repository").from_utf8())
+      .stderr(predicates::str::contains("check(s) failed").from_utf8());
+
+    Ok(())
+  })
+}
@@ -2,6 +2,7 @@ mod common;
 
 use crate::common::test_env::TestEnv;
 use anyhow::Result;
+use predicates::prelude::*;
 
 #[test]
 fn edit_opens_markdown_via_editor() -> Result<()> {
@@ -20,6 +21,48 @@ fn edit_opens_markdown_via_editor() -> Result<()> {
   })
 }
 
+#[test]
+fn edit_prefers_editor_flag_then_agency_editor() -> Result<()> {
+  TestEnv::run(|env| -> Result<()> {
+    env.init_repo()?;
+    let (id, _slug) = env.new_task("editor-task", &["--draft"])?;
+    let agency_editor = env.path().join("agency-editor.sh");
+    env.write_executable_script(
+      &agency_editor,
+      "#!/bin/sh\necho \"agency-editor $1\" > agency-editor.out\n",
+    )?;
+    let flag_editor = env.path().join("flag-editor.sh");
+    env.write_executable_script(
+      &flag_editor,
+      "#!/bin/sh\necho flag-editor > flag-editor.out\n",
+    )?;
+
+    env
+      .agency()?
+      .env("AGENCY_EDITOR", &agency_editor)
+      .arg("edit")
+      .arg(id.to_string())
+      .assert()
+      .success();
+    let invoked = std::fs::read_to_string(env.path().join("agency-editor.out"))?;
+    assert!(invoked.starts_with("agency-editor "), "{invoked}");
+    assert!(invoked.contains("editor-task"), "{invoked}");
+
+    env
+      .agency()?
+      .env("AGENCY_EDITOR", &agency_editor)
+      .arg("--editor")
+      .arg(&flag_editor)
+      .arg("edit")
+      .arg(id.to_string())
+      .assert()
+      .success();
+    assert!(env.path().join("flag-editor.out").is_file());
+
+    Ok(())
+  })
+}
+
 #[test]
 fn reset_prunes_worktree_and_branch_keeps_markdown() -> Result<()> {
   TestEnv::run(|env| -> Result<()> {
@@ -52,3 +95,54 @@ fn reset_prunes_worktree_and_branch_keeps_markdown() -> Result<()> {
     Ok(())
   })
 }
+
+#[test]
+fn reset_to_base_keeps_worktree_and_discards_changes() -> Result<()> {
+  TestEnv::run(|env| -> Result<()> {
+    env.init_repo()?;
+    let (id, slug) = env.new_task("reset-base", &["--draft"])?;
+    env.bootstrap_task(id)?;
+    env.git_commit_file_in_worktree(id, &slug, "work.txt", "work", "task work")?;
+    let wt_dir = env.worktree_dir_path(id, &slug);
+    std::fs::write(wt_dir.join("scratch.txt"), "untracked")?;
+
+    env.write_file("base.txt", "base")?;
+    env.git_stdout(&["add", "base.txt"])?;
+    env.git_stdout(&["commit", "-m", "advance base"])?;
+    let base_head = env.git_branch_head_id("main")?;
+    let branch = env.branch_name(id, &slug);
+
+    // Committed work is only discarded after confirmation
+    env
+      .agency()?
+      .args(["reset", &id.to_string(), "--to-base", "--force"])
+      .assert()
+      .success()
+      .stdout(predicates::str::contains("Cancelled").from_utf8());
+    assert_ne!(env.git_branch_head_id(&branch)?, base_head);
+
+    // The untracked file blocks the reset until --force is given
+    env
+      .agency()?
+      .args(["reset", &id.to_string(), "--to-base", "-y"])
+      .assert()
+      .failure()
+      .stderr(predicates::str::contains("--force").from_utf8());
+    assert!(wt_dir.join("scratch.txt").is_file());
+
+    env
+      .agency()?
+      .args(["reset", &id.to_string(), "--to-base", "-y", "--force"])
+      .assert()
+      .success();
+
+    assert_eq!(env.git_branch_head_id(&branch)?, base_head);
+    let wt = wt_dir.display().to_string();
+    assert_eq!(env.git_stdout(&["-C", &wt, "status", "--porcelain"])?, "");
+    assert!(wt_dir.join("base.txt").is_file());
+    assert!(!wt_dir.join("work.txt").exists());
+    assert!(env.task_file_path(id, &slug).is_file());
+
+    Ok(())
+  })
+}
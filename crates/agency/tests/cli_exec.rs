@@ -1,5 +1,8 @@
 mod common;
 
+use std::process::Stdio;
+use std::time::{Duration, Instant};
+
 use anyhow::Result;
 use predicates::prelude::*;
 
@@ -209,3 +212,70 @@ fn exec_works_with_slug() -> Result<()> {
     Ok(())
   })
 }
+
+#[test]
+fn exec_timeout_kills_command_and_exits_124() -> Result<()> {
+  TestEnv::run(|env| -> Result<()> {
+    env.init_repo()?;
+    let (id, _slug) = env.new_task("timeout-test", &[])?;
+    env.bootstrap_task(id)?;
+
+    let started = Instant::now();
+    env
+      .agency()?
+      .args(["exec", &id.to_string(), "--timeout", "1"])
+      .args(["--", "sleep", "10"])
+      .assert()
+      .code(124)
+      .stderr(predicate::str::contains("timed out after 1s").from_utf8());
+    assert!(started.elapsed() < Duration::from_secs(5));
+
+    // Commands finishing before the deadline keep their exit code
+    env
+      .agency()?
+      .args(["exec", &id.to_string(), "--timeout", "5"])
+      .args(["--", "sh", "-c", "exit 3"])
+      .assert()
+      .code(3);
+
+    Ok(())
+  })
+}
+
+#[test]
+fn exec_timeout_forwards_interrupt_to_command() -> Result<()> {
+  TestEnv::run(|env| -> Result<()> {
+    env.init_repo()?;
+    let (id, slug) = env.new_task("interrupt-test", &[])?;
+    env.bootstrap_task(id)?;
+    let started_marker = env.worktree_dir_path(id, &slug).join("started");
+
+    let mut exec = env
+      .agency_process()
+      .args(["exec", &id.to_string(), "--timeout", "30", "--"])
+      .args([
+        "sh",
+        "-c",
+        "trap 'exit 7' INT; touch started; sleep 20 & wait",
+      ])
+      .stdout(Stdio::null())
+      .stderr(Stdio::null())
+      .spawn()?;
+    env.wait_for_within(Duration::from_secs(5), || Ok(started_marker.exists()))?;
+
+    let started = Instant::now();
+    let kill = std::process::Command::new("kill")
+      .args(["-INT", &exec.id().to_string()])
+      .status()?;
+    assert!(kill.success());
+    let mut status = None;
+    env.wait_for_within(Duration::from_secs(5), || {
+      status = exec.try_wait()?;
+      Ok(status.is_some())
+    })?;
+    assert_eq!(status.and_then(|status| status.code()), Some(7));
+    assert!(started.elapsed() < Duration::from_secs(5));
+
+    Ok(())
+  })
+}
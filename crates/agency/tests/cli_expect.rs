@@ -0,0 +1,49 @@
+mod common;
+
+use crate::common::test_env::TestEnv;
+use anyhow::Result;
+use predicates::prelude::*;
+
+#[test]
+fn expect_waits_for_pattern_after_input_and_fails_on_timeout() -> Result<()> {
+  TestEnv::run(|env| -> Result<()> {
+    env.init_repo()?;
+    if !env.sockets_available() {
+      eprintln!(
+        "Skipping expect_waits_for_pattern_after_input_and_fails_on_timeout: Unix sockets not available in sandbox"
+      );
+      return Ok(());
+    }
+    let (id, _) = env.new_task("expect-task", &["-a", "sh"])?;
+    env.agency_daemon_start()?;
+    env
+      .agency()?
+      .args(["start", &id.to_string()])
+      .assert()
+      .success();
+
+    // The arithmetic keeps the typed command itself from matching
+    env
+      .agency()?
+      .args([
+        "expect",
+        &id.to_string(),
+        r"answer-\d+",
+        "--input",
+        "echo answer-$((40 + 2))",
+      ])
+      .assert()
+      .success()
+      .stdout(predicates::str::contains("answer-42").from_utf8());
+
+    env
+      .agency()?
+      .args(["expect", &id.to_string(), "never-printed", "-t", "300"])
+      .assert()
+      .failure()
+      .stderr(predicates::str::contains("Timed out").from_utf8());
+
+    env.agency_daemon_stop_all()?;
+    Ok(())
+  })
+}
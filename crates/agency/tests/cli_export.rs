@@ -0,0 +1,122 @@
+mod common;
+
+use crate::common::test_env::TestEnv;
+use anyhow::Result;
+use predicates::prelude::*;
+
+#[test]
+fn export_and_import_restore_task_with_files_and_changes() -> Result<()> {
+  TestEnv::run(|env| -> Result<()> {
+    env.init_repo()?;
+    env.write_file("notes.txt", "remember this")?;
+    let (id, slug) = env.new_task("handoff", &["--draft", "--file", "notes.txt"])?;
+    env.bootstrap_task(id)?;
+    env.git_commit_file_in_worktree(id, &slug, "feature.txt", "done", "add feature")?;
+    let wt = env.worktree_dir_path(id, &slug);
+    std::fs::write(wt.join("latin1.txt"), b"caf\xe9\n")?;
+    let wt_arg = wt.display().to_string();
+    env.git_stdout(&["-C", &wt_arg, "add", "latin1.txt"])?;
+    env.git_stdout(&["-C", &wt_arg, "commit", "-m", "add latin1"])?;
+
+    let archive = env.path().join("handoff.tar.gz");
+    let archive_arg = archive.display().to_string();
+    env
+      .agency()?
+      .args(["export", &id.to_string(), "--output", &archive_arg])
+      .assert()
+      .success();
+    assert!(archive.exists());
+
+    env
+      .agency()?
+      .args(["import", &archive_arg])
+      .assert()
+      .success()
+      .stdout(predicates::str::contains("Imported task").from_utf8());
+
+    let new_id = id + 1;
+    let new_slug = format!("{slug}2");
+    assert!(env.task_file_path(new_id, &new_slug).exists());
+    let attached = env
+      .path()
+      .join(".agency")
+      .join("files")
+      .join(format!("{new_id}-{new_slug}"))
+      .join("1-notes.txt");
+    assert_eq!(std::fs::read_to_string(attached)?, "remember this");
+
+    assert!(env.branch_exists(new_id, &new_slug)?);
+    let branch = env.branch_name(new_id, &new_slug);
+    let content = env.git_stdout(&["show", &format!("{branch}:feature.txt")])?;
+    assert_eq!(content, "done");
+    let latin1 = env
+      .git()
+      .args(["show", &format!("{branch}:latin1.txt")])
+      .output()?;
+    assert_eq!(latin1.stdout, b"caf\xe9\n");
+
+    Ok(())
+  })
+}
+
+#[test]
+fn import_failure_removes_the_partial_task() -> Result<()> {
+  TestEnv::run(|env| -> Result<()> {
+    env.init_repo()?;
+    let (id, slug) = env.new_task("clash", &["--draft"])?;
+    env.bootstrap_task(id)?;
+    env.git_commit_file_in_worktree(id, &slug, "feature.txt", "task", "add feature")?;
+    let archive = env.path().join("clash.tar.gz").display().to_string();
+    env
+      .agency()?
+      .args(["export", &id.to_string(), "--output", &archive])
+      .assert()
+      .success();
+
+    env.write_file("feature.txt", "main")?;
+    env.git_stdout(&["add", "feature.txt"])?;
+    env.git_stdout(&["commit", "-m", "add feature on main"])?;
+
+    env
+      .agency()?
+      .args(["import", &archive])
+      .assert()
+      .failure()
+      .stderr(predicates::str::contains("git apply failed").from_utf8());
+
+    let (new_id, new_slug) = (id + 1, format!("{slug}2"));
+    assert!(!env.task_file_path(new_id, &new_slug).exists());
+    assert!(!env.branch_exists(new_id, &new_slug)?);
+    assert!(!env.worktree_dir_path(new_id, &new_slug).exists());
+
+    Ok(())
+  })
+}
+
+#[test]
+fn export_without_branch_includes_markdown_only() -> Result<()> {
+  TestEnv::run(|env| -> Result<()> {
+    env.init_repo()?;
+    let (id, slug) = env.new_task("draft-only", &["--draft"])?;
+
+    env
+      .agency()?
+      .current_dir(env.path())
+      .args(["export", &id.to_string()])
+      .assert()
+      .success()
+      .stdout(predicates::str::contains("exporting markdown and files only").from_utf8());
+    let archive = env.path().join(format!("{id}-{slug}.tar.gz"));
+    assert!(archive.exists());
+
+    env
+      .agency()?
+      .args(["import", &archive.display().to_string()])
+      .assert()
+      .success();
+    assert!(env.task_file_path(id + 1, &format!("{slug}2")).exists());
+    assert!(!env.branch_exists(id + 1, &format!("{slug}2"))?);
+
+    Ok(())
+  })
+}
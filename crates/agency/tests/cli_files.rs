@@ -0,0 +1,88 @@
+mod common;
+
+use std::io::{BufRead as _, BufReader, Write as _};
+use std::net::TcpListener;
+
+use crate::common::test_env::TestEnv;
+use anyhow::Result;
+use predicates::prelude::*;
+
+/// Serve `connections` HTTP requests on localhost and return the base URL.
+fn serve(connections: usize) -> Result<String> {
+  let listener = TcpListener::bind("127.0.0.1:0")?;
+  let base = format!("http://{}", listener.local_addr()?);
+  std::thread::spawn(move || {
+    for stream in listener.incoming().take(connections) {
+      let Ok(mut stream) = stream else {
+        continue;
+      };
+      let mut reader = BufReader::new(stream.try_clone().expect("clone stream"));
+      let mut request_line = String::new();
+      let _ = reader.read_line(&mut request_line);
+      let mut header = String::new();
+      while reader.read_line(&mut header).is_ok_and(|read| read > 2) {
+        header.clear();
+      }
+      let path = request_line.split_whitespace().nth(1).unwrap_or("/");
+      let (status, headers, body) = match path {
+        "/old" => ("302 Found", "Location: /docs/spec.md\r\n", ""),
+        "/docs/spec.md" => ("200 OK", "Content-Type: text/markdown\r\n", "# Spec\n"),
+        "/tool" => ("200 OK", "Content-Type: application/x-msdownload\r\n", "MZ"),
+        _ => ("404 Not Found", "Content-Type: text/plain\r\n", "missing"),
+      };
+      let _ = write!(
+        stream,
+        "HTTP/1.1 {status}\r\n{headers}Content-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+      );
+    }
+  });
+  Ok(base)
+}
+
+#[test]
+fn files_add_from_url_follows_redirects_and_rejects_bad_responses() -> Result<()> {
+  TestEnv::run(|env| -> Result<()> {
+    env.init_repo()?;
+    let (id, slug) = env.new_task("url-task", &[])?;
+    let base = serve(4)?;
+
+    env
+      .agency()?
+      .env("NO_PROXY", "*")
+      .args(["files", "add", &id.to_string(), "--from-url"])
+      .arg(format!("{base}/old"))
+      .assert()
+      .success()
+      .stdout(predicates::str::contains("spec.md").from_utf8());
+    let stored = env
+      .path()
+      .join(".agency/files")
+      .join(format!("{id}-{slug}"))
+      .join("1-spec.md");
+    assert_eq!(std::fs::read_to_string(stored)?, "# Spec\n");
+
+    env
+      .agency()?
+      .env("NO_PROXY", "*")
+      .args(["files", "add", &id.to_string(), "--from-url"])
+      .arg(format!("{base}/gone"))
+      .assert()
+      .failure()
+      .stderr(predicates::str::contains("HTTP status 404").from_utf8());
+
+    env
+      .agency()?
+      .env("NO_PROXY", "*")
+      .args(["files", "add", &id.to_string(), "--from-url"])
+      .arg(format!("{base}/tool"))
+      .assert()
+      .failure()
+      .stderr(
+        predicates::str::contains("unsupported content type 'application/x-msdownload'")
+          .from_utf8(),
+      );
+
+    Ok(())
+  })
+}
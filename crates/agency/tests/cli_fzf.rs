@@ -41,3 +41,33 @@ fn fzf_no_tasks_shows_error() -> Result<()> {
     Ok(())
   })
 }
+
+#[test]
+fn fzf_preview_prints_task_file_and_handles_empty_body() -> Result<()> {
+  TestEnv::run(|env| -> Result<()> {
+    env.init_repo()?;
+    let (id, _) = env.new_task("described-task", &["--description", "Fix the login form"])?;
+    env.new_task("empty-task", &["--description", ""])?;
+
+    env
+      .agency()?
+      .args(["__fzf-preview", &id.to_string()])
+      .assert()
+      .success()
+      .stdout(predicate::str::contains("Fix the login form").from_utf8())
+      .stdout(
+        predicate::str::contains("(no description)")
+          .not()
+          .from_utf8(),
+      );
+
+    env
+      .agency()?
+      .args(["__fzf-preview", "empty-task"])
+      .assert()
+      .success()
+      .stdout(predicate::str::contains("(no description)").from_utf8());
+
+    Ok(())
+  })
+}
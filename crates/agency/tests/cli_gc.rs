@@ -1,6 +1,7 @@
 mod common;
 
 use anyhow::Result;
+use predicates::prelude::*;
 
 #[test]
 fn gc_removes_orphans_safely() -> Result<()> {
@@ -35,3 +36,55 @@ fn gc_removes_orphans_safely() -> Result<()> {
     Ok(())
   })
 }
+
+#[test]
+fn gc_worktrees_only_keeps_orphan_branches() -> Result<()> {
+  common::test_env::TestEnv::run(|env| -> Result<()> {
+    env.init_repo()?;
+
+    env.git_new_branch(98, "orphan")?;
+    env.git_add_worktree(99, "ghost")?;
+
+    env
+      .agency()?
+      .args(["gc", "--worktrees-only"])
+      .assert()
+      .success()
+      .stdout(predicates::str::contains("Garbage collected 1 worktrees").from_utf8());
+
+    assert!(env.branch_exists(98, "orphan")?);
+    assert!(!env.git_worktree_exists(99, "ghost"));
+    assert!(env.branch_exists(99, "ghost")?);
+
+    Ok(())
+  })
+}
+
+#[test]
+fn gc_branches_only_keeps_orphan_worktrees() -> Result<()> {
+  common::test_env::TestEnv::run(|env| -> Result<()> {
+    env.init_repo()?;
+
+    env.git_new_branch(98, "orphan")?;
+    env.git_add_worktree(99, "ghost")?;
+
+    env
+      .agency()?
+      .args(["gc", "--branches-only"])
+      .assert()
+      .success()
+      .stdout(predicates::str::contains("Garbage collected 1 branches").from_utf8());
+
+    assert!(!env.branch_exists(98, "orphan")?);
+    assert!(env.git_worktree_exists(99, "ghost"));
+    assert!(env.branch_exists(99, "ghost")?);
+
+    env
+      .agency()?
+      .args(["gc", "--branches-only", "--worktrees-only"])
+      .assert()
+      .failure();
+
+    Ok(())
+  })
+}
@@ -0,0 +1,53 @@
+mod common;
+
+use crate::common::test_env::TestEnv;
+use anyhow::Result;
+use predicates::prelude::*;
+
+#[test]
+fn history_records_session_start_and_stop() -> Result<()> {
+  TestEnv::run(|env| -> Result<()> {
+    env.init_repo()?;
+    if !env.sockets_available() {
+      eprintln!(
+        "Skipping history_records_session_start_and_stop: Unix sockets not available in sandbox"
+      );
+      return Ok(());
+    }
+    let (id, slug) = env.new_task("tracked", &["-a", "sh"])?;
+
+    env.agency_daemon_start()?;
+    env
+      .agency()?
+      .args(["start", &id.to_string()])
+      .assert()
+      .success();
+    env
+      .agency()?
+      .args(["stop", &slug])
+      .assert()
+      .success();
+
+    let events_file = env.path().join(".agency/state/events.jsonl");
+    env.wait_for(|| {
+      let data = std::fs::read_to_string(&events_file).unwrap_or_default();
+      Ok(data.lines().count() == 2)
+    })?;
+    let data = std::fs::read_to_string(&events_file)?;
+    let lines: Vec<&str> = data.lines().collect();
+    assert!(lines[0].contains(r#""from":"Draft""#));
+    assert!(lines[1].contains(r#""to":"Stopped""#));
+
+    env
+      .agency()?
+      .args(["history", &slug])
+      .assert()
+      .success()
+      .stdout(predicates::str::contains("Draft -> ").from_utf8())
+      .stdout(predicates::str::contains("-> Stopped").from_utf8());
+
+    env.agency_daemon_stop_all()?;
+
+    Ok(())
+  })
+}
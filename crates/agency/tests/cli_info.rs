@@ -0,0 +1,106 @@
+mod common;
+
+use crate::common::test_env::TestEnv;
+use anyhow::Result;
+use predicates::prelude::*;
+
+#[test]
+fn info_task_id_detects_task_from_worktree_cwd() -> Result<()> {
+  TestEnv::run(|env| -> Result<()> {
+    env.init_repo()?;
+    let (id, slug) = env.new_task("prompt-task", &["--draft"])?;
+    env.bootstrap_task(id)?;
+    let nested = env.worktree_dir_path(id, &slug).join("src");
+    std::fs::create_dir_all(&nested)?;
+
+    env
+      .agency()?
+      .current_dir(&nested)
+      .args(["info", "--task-id"])
+      .assert()
+      .success()
+      .stdout(format!("{id}-{slug}\n"));
+
+    let link = env.path().join("task-link");
+    std::os::unix::fs::symlink(env.worktree_dir_path(id, &slug), &link)?;
+    env
+      .agency()?
+      .current_dir(&link)
+      .args(["info", "--task-id"])
+      .assert()
+      .success()
+      .stdout(format!("{id}-{slug}\n"));
+
+    Ok(())
+  })
+}
+
+#[test]
+fn info_task_id_fails_outside_worktree() -> Result<()> {
+  TestEnv::run(|env| -> Result<()> {
+    env.init_repo()?;
+    let (id, _slug) = env.new_task("prompt-task", &["--draft"])?;
+    env.bootstrap_task(id)?;
+
+    env
+      .agency()?
+      .current_dir(env.path())
+      .args(["info", "--task-id"])
+      .assert()
+      .failure()
+      .stderr(predicates::str::contains("Not inside a task worktree").from_utf8());
+
+    Ok(())
+  })
+}
+
+#[test]
+fn info_json_reports_task_context_inside_worktree() -> Result<()> {
+  TestEnv::run(|env| -> Result<()> {
+    env.init_repo()?;
+    let (id, slug) = env.new_task("json-task", &["--draft", "-a", "sh"])?;
+    env.bootstrap_task(id)?;
+    env.write_file("notes.md", "# Notes\n")?;
+    env
+      .agency()?
+      .args(["files", "add", &id.to_string(), "notes.md"])
+      .assert()
+      .success();
+
+    let worktree = env.worktree_dir_path(id, &slug);
+    let output = env
+      .agency()?
+      .current_dir(&worktree)
+      .args(["info", "--json"])
+      .output()?;
+    assert!(output.status.success());
+    let info: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    assert_eq!(info["task"]["id"], id);
+    assert_eq!(info["task"]["slug"], slug.as_str());
+    assert_eq!(info["task"]["agent"], "sh");
+    assert_eq!(info["task"]["base_branch"], "main");
+    assert_eq!(info["branch"], format!("agency/{id}-{slug}"));
+    assert!(
+      info["worktree"]
+        .as_str()
+        .is_some_and(|path| path.ends_with(&format!("{id}-{slug}")))
+    );
+    assert_eq!(info["files"][0]["name"], "notes.md");
+    assert!(
+      info["files"][0]["path"]
+        .as_str()
+        .is_some_and(|path| path.ends_with("1-notes.md"))
+    );
+    assert!(info["session"].is_null());
+
+    env
+      .agency()?
+      .current_dir(env.path())
+      .args(["info", "--json"])
+      .assert()
+      .success()
+      .stdout(predicates::str::contains("\"task\": null").from_utf8());
+
+    Ok(())
+  })
+}
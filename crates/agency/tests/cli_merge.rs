@@ -172,3 +172,126 @@ fn merge_fails_when_no_changes() -> Result<()> {
     Ok(())
   })
 }
+
+#[test]
+fn merge_squash_creates_single_commit() -> Result<()> {
+  TestEnv::run(|env| -> Result<()> {
+    env.init_repo()?;
+    let (id, slug) = env.new_task("merge-squash", &["--draft"])?;
+    env.bootstrap_task(id)?;
+
+    env.git_commit_file_in_worktree(id, &slug, "a.txt", "a", "wip 1")?;
+    env.git_commit_file_in_worktree(id, &slug, "b.txt", "b", "wip 2")?;
+
+    let old_main = env.git_branch_head_id("main")?;
+
+    env
+      .agency()?
+      .arg("merge")
+      .arg("--squash")
+      .arg("-y")
+      .arg(id.to_string())
+      .assert()
+      .success();
+
+    let parent = env.git_stdout(&["rev-parse", "main^"])?;
+    assert_eq!(parent, old_main.to_string(), "main should gain one commit");
+    let subject = env.git_stdout(&["log", "-1", "--format=%s", "main"])?;
+    assert_eq!(subject, slug);
+    let files = env.git_stdout(&["show", "--name-only", "--format=", "main"])?;
+    assert_eq!(files, "a.txt\nb.txt");
+
+    let task_head = env.git_stdout(&["rev-parse", &env.branch_name(id, &slug)])?;
+    let main_head = env.git_stdout(&["rev-parse", "main"])?;
+    assert_eq!(
+      task_head, main_head,
+      "task branch should follow squash commit"
+    );
+
+    Ok(())
+  })
+}
+
+#[test]
+fn merge_squash_fails_when_diff_is_empty() -> Result<()> {
+  TestEnv::run(|env| -> Result<()> {
+    env.init_repo()?;
+    let (id, slug) = env.new_task("merge-squash-empty", &["--draft"])?;
+    env.bootstrap_task(id)?;
+
+    let _ = env.git_commit_empty_tree_to_task_branch(id, &slug, "test")?;
+
+    env
+      .agency()?
+      .arg("merge")
+      .arg("--squash")
+      .arg("-y")
+      .arg(id.to_string())
+      .assert()
+      .failure()
+      .stderr(predicates::str::contains("No changes to merge"));
+
+    Ok(())
+  })
+}
+
+#[test]
+fn merge_no_ff_creates_merge_commit_with_two_parents() -> Result<()> {
+  TestEnv::run(|env| -> Result<()> {
+    env.init_repo()?;
+    let (id, slug) = env.new_task("merge-no-ff", &["--draft"])?;
+    env.bootstrap_task(id)?;
+
+    env.git_commit_file_in_worktree(id, &slug, "a.txt", "a", "wip 1")?;
+    let old_main = env.git_branch_head_id("main")?;
+    let task_head = env.git_stdout(&["rev-parse", &env.branch_name(id, &slug)])?;
+
+    env
+      .agency()?
+      .args(["merge", "--no-ff", "-y", &id.to_string()])
+      .assert()
+      .success();
+
+    let parents = env.git_stdout(&["log", "-1", "--format=%P", "main"])?;
+    assert_eq!(parents, format!("{old_main} {task_head}"));
+    let subject = env.git_stdout(&["log", "-1", "--format=%s", "main"])?;
+    assert_eq!(subject, format!("Merge {id}-{slug} into main"));
+    assert!(env.task_file_path(id, &slug).is_file());
+
+    Ok(())
+  })
+}
+
+#[test]
+fn merge_no_ff_aborts_on_conflict_and_keeps_task() -> Result<()> {
+  TestEnv::run(|env| -> Result<()> {
+    env.init_repo()?;
+    let (id, slug) = env.new_task("merge-no-ff-conflict", &["--draft"])?;
+    env.bootstrap_task(id)?;
+
+    env.git_commit_file_in_worktree(id, &slug, "c.txt", "task", "task side")?;
+    env.write_file("c.txt", "main")?;
+    env.git_add_all_and_commit("main side")?;
+    let old_main = env.git_branch_head_id("main")?;
+
+    env
+      .agency()?
+      .args(["merge", "--no-ff", "-y", &id.to_string()])
+      .assert()
+      .failure()
+      .stderr(predicates::str::contains("conflicts in:\n  c.txt"));
+
+    assert_eq!(env.git_branch_head_id("main")?, old_main);
+    assert!(env.task_file_path(id, &slug).is_file());
+    assert!(env.git_status_porcelain()?.is_empty());
+
+    env
+      .agency()?
+      .args(["merge", "--no-ff", "--squash", &id.to_string()])
+      .assert()
+      .failure()
+      .stderr(predicates::str::contains("cannot be used with"));
+
+    Ok(())
+  })
+}
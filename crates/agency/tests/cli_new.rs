@@ -130,6 +130,46 @@ cmd=["bash","-lc","echo <root> > root.txt"]
   })
 }
 
+#[test]
+fn new_runs_bootstrap_cmds_in_order_and_stops_on_failure() -> Result<()> {
+  TestEnv::run(|env| -> Result<()> {
+    env.init_repo()?;
+
+    env.write_file(
+      ".agency/agency.toml",
+      r#"
+[bootstrap]
+cmds = [["bash","-c","touch first.txt"], ["bash","-c","test -f first.txt && echo <root> > second.txt"]]
+"#,
+    )?;
+    let (id, slug) = env.new_task("boot-steps", &[])?;
+    env.bootstrap_task(id)?;
+    let wt = env.worktree_dir_path(id, &slug);
+    assert!(wt.join("first.txt").is_file());
+    let data = std::fs::read_to_string(wt.join("second.txt"))?;
+    let expect_root = env.path().canonicalize()?.display().to_string();
+    assert_eq!(data.trim(), expect_root);
+
+    env.write_file(
+      ".agency/agency.toml",
+      r#"
+[bootstrap]
+cmds = [["false"], ["touch", "second.txt"]]
+"#,
+    )?;
+    let (id, slug) = env.new_task("boot-steps-fail", &[])?;
+    env
+      .agency()?
+      .args(["bootstrap", &id.to_string()])
+      .assert()
+      .success()
+      .stdout(predicates::str::contains("Bootstrap step 1/2 exited").from_utf8());
+    assert!(!env.worktree_dir_path(id, &slug).join("second.txt").exists());
+
+    Ok(())
+  })
+}
+
 #[test]
 fn new_writes_yaml_header_when_agent_specified() -> Result<()> {
   TestEnv::run(|env| -> Result<()> {
@@ -322,6 +362,45 @@ fn new_reads_description_from_stdin_when_no_arg() -> Result<()> {
   })
 }
 
+#[test]
+fn new_stdin_flag_stores_body_verbatim() -> Result<()> {
+  TestEnv::run(|env| -> Result<()> {
+    env.init_repo()?;
+    let body = "    cargo test --workspace\n\n## Notes\n\n- keep `--offline`\n";
+
+    let output = env
+      .agency()?
+      .args(["new", "--draft", "--stdin", "piped-task"])
+      .write_stdin(body)
+      .output()?;
+    assert!(output.status.success());
+
+    let (id, slug) = TestEnv::parse_new_task_output(&output.stdout)?;
+    let task_content = env.read_task_file(id, &slug)?;
+    assert!(
+      task_content.ends_with(&format!("---\n{body}")),
+      "task should end with the stdin body verbatim: {task_content:?}"
+    );
+
+    env
+      .agency()?
+      .args(["new", "--draft", "--stdin", "clash-task"])
+      .args(["--description", "x"])
+      .write_stdin(body)
+      .assert()
+      .failure();
+    env
+      .agency()?
+      .args(["new", "--draft", "--stdin", "empty-task"])
+      .write_stdin("\n  \n")
+      .assert()
+      .failure()
+      .stderr(predicates::str::contains("No task description on stdin").from_utf8());
+
+    Ok(())
+  })
+}
+
 #[test]
 fn new_prefers_arg_over_stdin() -> Result<()> {
   TestEnv::run(|env| -> Result<()> {
@@ -350,6 +429,120 @@ fn new_prefers_arg_over_stdin() -> Result<()> {
   })
 }
 
+#[test]
+fn new_uses_remote_default_branch_on_detached_head() -> Result<()> {
+  TestEnv::run(|env| -> Result<()> {
+    env.init_repo()?;
+    env.git_stdout(&["update-ref", "refs/remotes/origin/trunk", "HEAD"])?;
+    env.git_stdout(&[
+      "symbolic-ref",
+      "refs/remotes/origin/HEAD",
+      "refs/remotes/origin/trunk",
+    ])?;
+    env.git_checkout_detach()?;
+
+    let (id, slug) = env.new_task("test-task", &["--draft", "--description", "test"])?;
+    let task_content = env.read_task_file(id, &slug)?;
+    assert!(task_content.contains("base_branch: trunk"));
+
+    Ok(())
+  })
+}
+
+#[test]
+fn new_prefers_configured_default_base_branch_on_detached_head() -> Result<()> {
+  TestEnv::run(|env| -> Result<()> {
+    env.init_repo()?;
+    env.write_file(
+      ".agency/agency.toml",
+      "default_base_branch = \"develop\"\n\n[agents.sh]\ncmd = [\"sh\"]\n",
+    )?;
+    env.git_checkout_detach()?;
+
+    let (id, slug) = env.new_task("test-task", &["--draft", "--description", "test"])?;
+    let task_content = env.read_task_file(id, &slug)?;
+    assert!(task_content.contains("base_branch: develop"));
+
+    Ok(())
+  })
+}
+
+#[test]
+fn new_prepends_named_template_with_tokens() -> Result<()> {
+  TestEnv::run(|env| -> Result<()> {
+    env.init_repo()?;
+    env.write_file(
+      ".agency/agency.toml",
+      "[templates]\nbug = \"templates/bug.md\"\n\n[agents.sh]\ncmd = [\"sh\"]\n",
+    )?;
+    env.write_file(
+      ".agency/templates/bug.md",
+      "# $SLUG (#$ID)\n\n- [ ] Reproduce\n",
+    )?;
+
+    let (id, slug) = env.new_task(
+      "login-bug",
+      &["--template", "bug", "--description", "Fix the login"],
+    )?;
+    let task_content = env.read_task_file(id, &slug)?;
+    assert!(
+      task_content.contains(&format!(
+        "# login-bug (#{id})\n\n- [ ] Reproduce\n\nFix the login"
+      )),
+      "unexpected task content: {task_content}"
+    );
+
+    Ok(())
+  })
+}
+
+#[test]
+fn new_uses_default_template_when_omitted() -> Result<()> {
+  TestEnv::run(|env| -> Result<()> {
+    env.init_repo()?;
+    env.write_file(
+      ".agency/agency.toml",
+      "[templates]\ndefault = \"templates/default.md\"\n\n[agents.sh]\ncmd = [\"sh\"]\n",
+    )?;
+    env.write_file(".agency/templates/default.md", "Created $DATE\n")?;
+
+    let (id, slug) = env.new_task("plain", &[])?;
+    let task_content = env.read_task_file(id, &slug)?;
+    assert!(task_content.contains("Created 20"));
+    assert!(!task_content.contains("$DATE"));
+    assert!(task_content.contains("Automated test"));
+
+    Ok(())
+  })
+}
+
+#[test]
+fn new_fails_for_missing_template_file() -> Result<()> {
+  TestEnv::run(|env| -> Result<()> {
+    env.init_repo()?;
+    env.write_file(
+      ".agency/agency.toml",
+      "[templates]\nbug = \"templates/missing.md\"\n\n[agents.sh]\ncmd = [\"sh\"]\n",
+    )?;
+
+    env
+      .agency()?
+      .args(["new", "--draft", "--template", "bug", "--description", "x", "task"])
+      .assert()
+      .failure()
+      .stderr(predicates::str::contains("Template bug not found").from_utf8());
+
+    env
+      .agency()?
+      .args(["new", "--draft", "--template", "nope", "--description", "x", "task"])
+      .assert()
+      .failure()
+      .stderr(predicates::str::contains("unknown template: nope").from_utf8());
+
+    Ok(())
+  })
+}
+
 #[test]
 fn new_ignores_empty_stdin() -> Result<()> {
   TestEnv::run(|env| -> Result<()> {
@@ -398,3 +591,167 @@ fn new_trims_stdin_description() -> Result<()> {
     Ok(())
   })
 }
+
+#[test]
+fn new_from_branch_adopts_existing_branch() -> Result<()> {
+  TestEnv::run(|env| -> Result<()> {
+    env.init_repo()?;
+    env.git_stdout(&["branch", "feature/login"])?;
+
+    let (id, slug) = env.new_task("adopt-login", &["--from-branch", "feature/login"])?;
+    let content = env.read_task_file(id, &slug)?;
+    assert!(content.contains("branch: feature/login"));
+
+    env
+      .agency()?
+      .args(["branch", &id.to_string()])
+      .assert()
+      .success()
+      .stdout(predicate::str::contains("feature/login"));
+
+    env.bootstrap_task(id)?;
+    let wt = env.worktree_dir_path(id, &slug).display().to_string();
+    assert_eq!(
+      env.git_stdout(&["-C", &wt, "rev-parse", "--abbrev-ref", "HEAD"])?,
+      "feature/login"
+    );
+    assert!(
+      !env.branch_exists(id, &slug)?,
+      "no agency/ branch should be created for an adopted branch"
+    );
+
+    env.git_commit_file_in_worktree(id, &slug, "login.txt", "login", "Add login")?;
+    env
+      .agency()?
+      .args(["complete", &id.to_string(), "--yes"])
+      .assert()
+      .success();
+    assert!(env.path().join("login.txt").exists());
+
+    Ok(())
+  })
+}
+
+#[test]
+fn new_from_branch_rejects_missing_and_managed_branches() -> Result<()> {
+  TestEnv::run(|env| -> Result<()> {
+    env.init_repo()?;
+    env.git_stdout(&["branch", "feature/taken"])?;
+
+    env
+      .agency()?
+      .args(["new", "--draft", "adopt-missing", "--from-branch", "feature/missing"])
+      .assert()
+      .failure()
+      .stderr(predicate::str::contains("does not exist"));
+
+    let (id, slug) = env.new_task("adopt-first", &["--from-branch", "feature/taken"])?;
+    env
+      .agency()?
+      .args(["new", "--draft", "adopt-second", "--from-branch", "feature/taken"])
+      .assert()
+      .failure()
+      .stderr(predicate::str::contains(format!(
+        "already managed by task {id}-{slug}"
+      )));
+
+    Ok(())
+  })
+}
+
+#[test]
+fn new_with_base_starts_from_and_merges_into_that_branch() -> Result<()> {
+  TestEnv::run(|env| -> Result<()> {
+    env.init_repo()?;
+    env.git_stdout(&["checkout", "-q", "-b", "feature-x"])?;
+    env.write_file("feature.txt", "feature")?;
+    env.git_stdout(&["add", "feature.txt"])?;
+    env.git_stdout(&["commit", "-q", "-m", "Add feature"])?;
+    env.git_stdout(&["checkout", "-q", "main"])?;
+    let feature_tip = env.git_branch_head_id("feature-x")?;
+    let main_tip = env.git_branch_head_id("main")?;
+
+    let (id, slug) = env.new_task("on-feature", &["--base", "feature-x"])?;
+    let content = env.read_task_file(id, &slug)?;
+    assert!(content.contains("base_branch: feature-x"));
+
+    env.bootstrap_task(id)?;
+    let wt = env.worktree_dir_path(id, &slug).display().to_string();
+    assert_eq!(
+      env.git_stdout(&["-C", &wt, "rev-parse", "HEAD"])?,
+      feature_tip.to_string()
+    );
+
+    env.git_commit_file_in_worktree(id, &slug, "task.txt", "task", "Task work")?;
+    env
+      .agency()?
+      .args(["merge", &id.to_string()])
+      .assert()
+      .success();
+    assert_eq!(
+      env.git_branch_head_id("feature-x")?,
+      env.git_branch_head_id(&env.branch_name(id, &slug))?
+    );
+    assert_eq!(env.git_branch_head_id("main")?, main_tip);
+
+    env
+      .agency()?
+      .args(["new", "--draft", "no-base", "--base", "feature-missing"])
+      .assert()
+      .failure()
+      .stderr(predicate::str::contains("feature-missing does not exist"));
+
+    Ok(())
+  })
+}
+
+#[test]
+fn new_with_same_idempotency_key_returns_existing_task() -> Result<()> {
+  TestEnv::run(|env| -> Result<()> {
+    env.init_repo()?;
+    let args = ["--idempotency-key", "retry-1"];
+    let (id, slug) = env.new_task("keyed", &args)?;
+
+    env
+      .agency()?
+      .args(["new", "--draft", "--description", "Automated test", "keyed"])
+      .args(args)
+      .assert()
+      .success()
+      .stdout(predicates::str::contains(format!("keyed (id {id}) already created")).from_utf8());
+
+    let tasks: Vec<_> = std::fs::read_dir(env.path().join(".agency/tasks"))?
+      .filter_map(std::result::Result::ok)
+      .map(|entry| entry.file_name().to_string_lossy().to_string())
+      .collect();
+    assert_eq!(tasks, vec![format!("{id}-{slug}.md")]);
+
+    let (other_id, _) = env.new_task("keyed", &["--idempotency-key", "retry-2"])?;
+    assert_ne!(other_id, id);
+
+    Ok(())
+  })
+}
+
+#[test]
+fn new_uses_repo_from_git_dir_env() -> Result<()> {
+  TestEnv::run(|env| -> Result<()> {
+    env.init_repo()?;
+    let elsewhere = tempfile::tempdir()?;
+
+    env
+      .agency()?
+      .current_dir(elsewhere.path())
+      .env("GIT_DIR", env.path().join(".git"))
+      .env("GIT_WORK_TREE", env.path())
+      .args(["new", "--draft", "via-git-dir"])
+      .args(["--description", "Automated test"])
+      .assert()
+      .success();
+
+    assert!(env.task_file_path(1, "via-git-dir").is_file());
+    assert!(!elsewhere.path().join(".agency").exists());
+
+    Ok(())
+  })
+}
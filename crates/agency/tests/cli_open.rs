@@ -25,3 +25,65 @@ fn open_opens_worktree_via_editor() -> Result<()> {
     Ok(())
   })
 }
+
+#[test]
+fn open_cmd_overrides_editor_and_validates_program() -> Result<()> {
+  TestEnv::run(|env| -> Result<()> {
+    env.init_repo()?;
+    let (id, slug) = env.new_task("open-cmd-task", &["--draft"])?;
+    env.write_file(
+      ".agency/agency.toml",
+      "open_cmd = [\"sh\", \"-c\", \"basename \\\"$0\\\" > opened.txt\"]\n",
+    )?;
+
+    env.with_env_vars(
+      &[("EDITOR", Some("false".to_string()))],
+      |env| -> Result<()> {
+        env
+          .agency()?
+          .args(["open", &id.to_string()])
+          .assert()
+          .success();
+        let opened = std::fs::read_to_string(env.path().join("opened.txt"))?;
+        assert_eq!(opened.trim(), format!("{id}-{slug}"));
+
+        env
+          .agency()?
+          .args(["open", &id.to_string(), "--cmd", "true"])
+          .assert()
+          .success();
+
+        env
+          .agency()?
+          .args(["open", &id.to_string(), "--cmd", "agency-missing-opener"])
+          .assert()
+          .failure()
+          .stderr(predicates::str::contains(
+            "not found in PATH: agency-missing-opener",
+          ));
+        Ok(())
+      },
+    )?;
+
+    Ok(())
+  })
+}
+
+#[test]
+fn new_open_bootstraps_worktree_then_opens_it() -> Result<()> {
+  TestEnv::run(|env| -> Result<()> {
+    env.init_repo()?;
+    env.write_file(
+      ".agency/agency.toml",
+      "open_cmd = [\"sh\", \"-c\", \"test -d \\\"$0\\\" && basename \\\"$0\\\" > opened.txt\"]\n\n[agents.sh]\ncmd = [\"sh\"]\n",
+    )?;
+
+    let (id, slug) = env.new_task("new-open-task", &["--open"])?;
+
+    assert!(env.worktree_dir_path(id, &slug).is_dir());
+    let opened = std::fs::read_to_string(env.path().join("opened.txt"))?;
+    assert_eq!(opened.trim(), format!("{id}-{slug}"));
+
+    Ok(())
+  })
+}
@@ -34,6 +34,40 @@ fn path_prints_absolute_worktree_path_by_id_and_slug() -> Result<()> {
   })
 }
 
+#[test]
+fn path_prints_files_dir_and_task_file() -> Result<()> {
+  TestEnv::run(|env| -> Result<()> {
+    env.init_repo()?;
+    let (id, slug) = env.new_task("delta-task", &[])?;
+
+    let root = env.path().canonicalize()?;
+    let files_dir = root.join(".agency/files").join(format!("{id}-{slug}"));
+    assert!(!files_dir.exists());
+    env
+      .agency()?
+      .args(["path", &slug, "--files"])
+      .assert()
+      .success()
+      .stdout(format!("{}\n", files_dir.display()));
+
+    let task_file = env.task_file_path(id, &slug).canonicalize()?;
+    env
+      .agency()?
+      .args(["path", &id.to_string(), "--task-file"])
+      .assert()
+      .success()
+      .stdout(format!("{}\n", task_file.display()));
+
+    env
+      .agency()?
+      .args(["path", &slug, "--files", "--task-file"])
+      .assert()
+      .failure();
+
+    Ok(())
+  })
+}
+
 #[test]
 fn branch_prints_branch_name_by_id_and_slug() -> Result<()> {
   TestEnv::run(|env| -> Result<()> {
@@ -59,3 +93,47 @@ fn branch_prints_branch_name_by_id_and_slug() -> Result<()> {
     Ok(())
   })
 }
+
+#[test]
+fn branch_push_sets_up_remote_branch() -> Result<()> {
+  TestEnv::run(|env| -> Result<()> {
+    env.init_repo()?;
+    let remote_dir = env.path().join("remote.git");
+    let remote = remote_dir.to_str().expect("utf8 path");
+    env.git_stdout(&["init", "--bare", "-q", remote])?;
+    env.git_stdout(&["remote", "add", "origin", remote])?;
+    let (id, slug) = env.new_task("push-task", &["--draft"])?;
+    let branch = env.branch_name(id, &slug);
+
+    env
+      .agency()?
+      .args(["branch", &slug, "--remote"])
+      .assert()
+      .success()
+      .stdout(format!("origin/{branch}\n"));
+
+    env
+      .agency()?
+      .args(["branch", &slug, "--push"])
+      .assert()
+      .failure()
+      .stderr(predicates::str::contains("does not exist yet").from_utf8());
+
+    env.bootstrap_task(id)?;
+    env
+      .agency()?
+      .args(["branch", &slug, "--push"])
+      .assert()
+      .success()
+      .stdout(format!("origin/{branch}\n"));
+
+    let local = env.git_stdout(&["rev-parse", &branch])?;
+    let pushed = env.git_stdout(&["--git-dir", remote, "rev-parse", &branch])?;
+    assert_eq!(local, pushed, "remote branch should match the task branch");
+    let upstream = format!("{branch}@{{upstream}}");
+    let upstream = env.git_stdout(&["rev-parse", "--abbrev-ref", &upstream])?;
+    assert_eq!(upstream, format!("origin/{branch}"));
+
+    Ok(())
+  })
+}
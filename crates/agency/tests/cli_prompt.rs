@@ -0,0 +1,41 @@
+mod common;
+
+use crate::common::test_env::TestEnv;
+use anyhow::Result;
+use predicates::prelude::*;
+
+#[test]
+fn prompt_prints_resolved_command_with_task_body_and_redacted_secrets() -> Result<()> {
+  TestEnv::run(|env| -> Result<()> {
+    env.init_repo()?;
+    env.write_file(
+      ".agency/agency.toml",
+      "[agents.echo]\ncmd = [\"echo\", \"$AGENCY_TASK\"]\n\n[agents.echo.env]\nAPI_TOKEN = \"hunter2\"\n",
+    )?;
+    let (id, slug) = env.new_task(
+      "prompt-task",
+      &["--agent", "echo", "--description", "Fix the flaky parser"],
+    )?;
+
+    env
+      .agency()?
+      .args(["prompt", &id.to_string()])
+      .assert()
+      .success()
+      .stdout(predicates::str::contains("Agent: echo").from_utf8())
+      .stdout(predicates::str::contains("Command: echo 'Fix the flaky parser'").from_utf8())
+      .stdout(predicates::str::contains("API_TOKEN=<redacted>").from_utf8())
+      .stdout(predicates::str::contains("hunter2").not().from_utf8());
+    assert!(!env.worktree_dir_path(id, &slug).exists());
+
+    let output = env.agency()?.args(["prompt", &slug, "--json"]).output()?;
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    assert_eq!(json["agent"], "echo");
+    assert_eq!(json["argv"][1], "Fix the flaky parser");
+    assert_eq!(json["prompt"], "Fix the flaky parser");
+    assert_eq!(json["env"]["API_TOKEN"], "<redacted>");
+
+    Ok(())
+  })
+}
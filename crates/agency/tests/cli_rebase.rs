@@ -0,0 +1,70 @@
+mod common;
+
+use crate::common::test_env::TestEnv;
+use anyhow::Result;
+use predicates::prelude::*;
+
+#[test]
+fn rebase_replays_task_commits_onto_base() -> Result<()> {
+  TestEnv::run(|env| -> Result<()> {
+    env.init_repo()?;
+    let (id, slug) = env.new_task("rebase-ok", &["--draft"])?;
+    env.bootstrap_task(id)?;
+    env.git_commit_file_in_worktree(id, &slug, "task.txt", "task", "task change")?;
+
+    env.write_file("base.txt", "base")?;
+    env.git_add_all_and_commit("base change")?;
+    let main_head = env.git_stdout(&["rev-parse", "main"])?;
+
+    let wt_dir = env.worktree_dir_path(id, &slug);
+    let wt = wt_dir.display().to_string();
+    std::fs::write(wt_dir.join("dirty.txt"), "dirty")?;
+
+    env
+      .agency()?
+      .arg("rebase")
+      .arg(id.to_string())
+      .assert()
+      .success()
+      .stdout(predicates::str::contains("1 commits replayed").from_utf8());
+
+    let parent = env.git_stdout(&["-C", &wt, "rev-parse", "HEAD^"])?;
+    assert_eq!(parent, main_head);
+    assert!(wt_dir.join("base.txt").exists());
+    assert!(wt_dir.join("dirty.txt").exists());
+    assert_eq!(env.git_stdout(&["stash", "list"])?, "");
+
+    Ok(())
+  })
+}
+
+#[test]
+fn rebase_aborts_on_conflict() -> Result<()> {
+  TestEnv::run(|env| -> Result<()> {
+    env.init_repo()?;
+    let (id, slug) = env.new_task("rebase-conflict", &["--draft"])?;
+    env.bootstrap_task(id)?;
+    env.git_commit_file_in_worktree(id, &slug, "same.txt", "task", "task change")?;
+
+    env.write_file("same.txt", "base")?;
+    env.git_add_all_and_commit("base change")?;
+
+    let wt = env.worktree_dir_path(id, &slug).display().to_string();
+    let before = env.git_stdout(&["-C", &wt, "rev-parse", "HEAD"])?;
+
+    env
+      .agency()?
+      .arg("rebase")
+      .arg(id.to_string())
+      .assert()
+      .failure()
+      .stderr(predicates::str::contains("same.txt").from_utf8());
+
+    let after = env.git_stdout(&["-C", &wt, "rev-parse", "HEAD"])?;
+    assert_eq!(before, after);
+    let head = env.git_stdout(&["-C", &wt, "symbolic-ref", "--short", "HEAD"])?;
+    assert_eq!(head, env.branch_name(id, &slug));
+
+    Ok(())
+  })
+}
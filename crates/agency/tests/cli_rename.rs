@@ -0,0 +1,61 @@
+mod common;
+
+use crate::common::test_env::TestEnv;
+use anyhow::Result;
+use predicates::prelude::*;
+
+#[test]
+fn rename_moves_file_branch_and_worktree() -> Result<()> {
+  TestEnv::run(|env| -> Result<()> {
+    env.init_repo()?;
+    let (id, slug) = env.new_task("old-name", &["--draft"])?;
+    env.bootstrap_task(id)?;
+    env.git_commit_file_in_worktree(id, &slug, "a.txt", "a", "add a")?;
+
+    env
+      .agency()?
+      .arg("rename")
+      .arg(id.to_string())
+      .arg("New Name")
+      .assert()
+      .success()
+      .stdout(predicates::str::contains("new-name").from_utf8());
+
+    assert!(!env.task_file_path(id, &slug).exists());
+    assert!(!env.branch_exists(id, &slug)?);
+    assert!(!env.worktree_dir_path(id, &slug).exists());
+
+    assert!(env.task_file_path(id, "new-name").exists());
+    assert!(env.branch_exists(id, "new-name")?);
+    let wt_dir = env.worktree_dir_path(id, "new-name");
+    assert!(wt_dir.join("a.txt").exists());
+
+    let wt = wt_dir.display().to_string();
+    let head = env.git_stdout(&["-C", &wt, "symbolic-ref", "--short", "HEAD"])?;
+    assert_eq!(head, env.branch_name(id, "new-name"));
+
+    Ok(())
+  })
+}
+
+#[test]
+fn rename_refuses_existing_slug() -> Result<()> {
+  TestEnv::run(|env| -> Result<()> {
+    env.init_repo()?;
+    let (id, slug) = env.new_task("first", &["--draft"])?;
+    let (_other_id, other_slug) = env.new_task("second", &["--draft"])?;
+
+    env
+      .agency()?
+      .arg("rename")
+      .arg(id.to_string())
+      .arg(&other_slug)
+      .assert()
+      .failure()
+      .stderr(predicates::str::contains("already exists").from_utf8());
+
+    assert!(env.task_file_path(id, &slug).exists());
+
+    Ok(())
+  })
+}
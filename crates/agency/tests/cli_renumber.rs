@@ -0,0 +1,99 @@
+mod common;
+
+use crate::common::test_env::TestEnv;
+use anyhow::Result;
+use predicates::prelude::*;
+
+#[test]
+fn renumber_moves_file_branch_and_worktree() -> Result<()> {
+  TestEnv::run(|env| -> Result<()> {
+    env.init_repo()?;
+    let (id, slug) = env.new_task("imported", &["--draft"])?;
+    env.bootstrap_task(id)?;
+    env.git_commit_file_in_worktree(id, &slug, "a.txt", "a", "add a")?;
+
+    env
+      .agency()?
+      .arg("renumber")
+      .arg(id.to_string())
+      .arg("7")
+      .assert()
+      .success();
+
+    assert!(!env.task_file_path(id, &slug).exists());
+    assert!(!env.branch_exists(id, &slug)?);
+    assert!(!env.worktree_dir_path(id, &slug).exists());
+
+    assert!(env.task_file_path(7, &slug).exists());
+    assert!(env.branch_exists(7, &slug)?);
+    let wt_dir = env.worktree_dir_path(7, &slug);
+    assert!(wt_dir.join("a.txt").exists());
+
+    let wt = wt_dir.display().to_string();
+    let head = env.git_stdout(&["-C", &wt, "symbolic-ref", "--short", "HEAD"])?;
+    assert_eq!(head, env.branch_name(7, &slug));
+
+    Ok(())
+  })
+}
+
+#[test]
+fn renumber_moves_session_logs_and_history() -> Result<()> {
+  TestEnv::run(|env| -> Result<()> {
+    env.init_repo()?;
+    let (id, slug) = env.new_task("recorded", &["--draft"])?;
+    let logs = env.path().join(".agency/state/logs");
+    std::fs::create_dir_all(&logs)?;
+    std::fs::write(logs.join(format!("{id}-{slug}.log")), "output")?;
+    std::fs::write(logs.join(format!("{id}-{slug}.log.1")), "older output")?;
+    let event =
+      format!(r#"{{"ts_ms":0,"task_id":{id},"slug":"{slug}","from":"Draft","to":"Running"}}"#);
+    std::fs::write(env.path().join(".agency/state/events.jsonl"), event + "\n")?;
+
+    env
+      .agency()?
+      .args(["renumber", &id.to_string(), "7"])
+      .assert()
+      .success();
+
+    assert_eq!(
+      std::fs::read_to_string(logs.join(format!("7-{slug}.log")))?,
+      "output"
+    );
+    assert!(logs.join(format!("7-{slug}.log.1")).exists());
+    assert!(!logs.join(format!("{id}-{slug}.log")).exists());
+    env
+      .agency()?
+      .args(["history", "7"])
+      .assert()
+      .success()
+      .stdout(predicates::str::contains("Draft -> Running").from_utf8());
+
+    Ok(())
+  })
+}
+
+#[test]
+fn renumber_refuses_taken_id() -> Result<()> {
+  TestEnv::run(|env| -> Result<()> {
+    env.init_repo()?;
+    let (first, first_slug) = env.new_task("first", &["--draft"])?;
+    let (second, second_slug) = env.new_task("second", &["--draft"])?;
+
+    env
+      .agency()?
+      .arg("move")
+      .arg(second.to_string())
+      .arg(first.to_string())
+      .assert()
+      .failure()
+      .stderr(
+        predicates::str::contains(format!("already used by {first}-{first_slug}")).from_utf8(),
+      );
+
+    assert!(env.task_file_path(first, &first_slug).exists());
+    assert!(env.task_file_path(second, &second_slug).exists());
+
+    Ok(())
+  })
+}
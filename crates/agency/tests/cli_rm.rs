@@ -91,3 +91,27 @@ fn rm_with_y_flag_skips_confirmation() -> Result<()> {
     Ok(())
   })
 }
+
+#[test]
+fn rm_keep_branch_removes_task_and_worktree_but_keeps_branch() -> Result<()> {
+  TestEnv::run(|env| -> Result<()> {
+    env.init_repo()?;
+    let (id, slug) = env.new_task("keep-branch-task", &[])?;
+
+    env.bootstrap_task(id)?;
+
+    env
+      .agency()?
+      .args(["rm", &id.to_string(), "--keep-branch", "--yes"])
+      .assert()
+      .success()
+      .stdout(predicates::str::contains("Keeps: branch").from_utf8())
+      .stdout(predicates::str::contains("removed").from_utf8());
+
+    assert!(env.branch_exists(id, &slug)?);
+    assert!(!env.task_file_path(id, &slug).exists());
+    assert!(!env.worktree_dir_path(id, &slug).exists());
+
+    Ok(())
+  })
+}
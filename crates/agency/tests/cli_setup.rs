@@ -55,6 +55,34 @@ fn setup_creates_global_config_via_wizard() -> Result<()> {
   })
 }
 
+#[test]
+fn setup_non_interactive_writes_config_from_flags() -> Result<()> {
+  TestEnv::run(|env| -> Result<()> {
+    let cfg = env.xdg_home_dir().join("agency").join("agency.toml");
+    env.init_repo()?;
+
+    env
+      .agency()?
+      .args(["setup", "--non-interactive"])
+      .assert()
+      .failure()
+      .stderr(predicates::str::contains("--agent is required").from_utf8());
+    assert!(!cfg.exists(), "failed setup must not write the config");
+
+    env
+      .agency()?
+      .args(["setup", "--agent", "claude", "--shell", "zsh", "--yes"])
+      .assert()
+      .success()
+      .stdout(predicates::str::contains("Created global config").from_utf8());
+
+    let data = std::fs::read_to_string(&cfg)?;
+    assert!(data.contains("agent = \"claude\""), "{data}");
+    assert!(data.contains("shell = [\"zsh\"]"), "{data}");
+    Ok(())
+  })
+}
+
 #[test]
 fn setup_updates_existing_config_and_warns() -> Result<()> {
   TestEnv::run(|env| -> Result<()> {
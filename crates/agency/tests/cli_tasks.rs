@@ -2,6 +2,7 @@ mod common;
 
 use crate::common::test_env::TestEnv;
 use anyhow::Result;
+use predicates::prelude::*;
 
 #[test]
 fn tasks_shows_stored_base_branch_not_current_head() -> Result<()> {
@@ -42,3 +43,403 @@ fn tasks_shows_stored_base_branch_not_current_head() -> Result<()> {
     Ok(())
   })
 }
+
+#[test]
+fn tasks_shows_and_filters_labels() -> Result<()> {
+  TestEnv::run(|env| -> Result<()> {
+    env.init_repo()?;
+    let (id, slug) = env.new_task("api-task", &["-l", "backend", "--label", "api,backend"])?;
+    let (_, docs_slug) = env.new_task("docs-task", &["-l", "docs"])?;
+    let (_, plain_slug) = env.new_task("plain-task", &[])?;
+
+    let content = std::fs::read_to_string(env.task_file_path(id, &slug))?;
+    assert!(
+      content.contains("labels:\n- backend\n- api\n"),
+      "labels should be stored once each in the front matter, got:\n{content}"
+    );
+
+    let output = env.agency()?.arg("tasks").output()?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("LABELS"), "got:\n{stdout}");
+    assert!(stdout.contains("backend,api"), "got:\n{stdout}");
+
+    let output = env
+      .agency()?
+      .args(["tasks", "--label", "backend", "--label", "api"])
+      .output()?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains(&slug), "got:\n{stdout}");
+    assert!(!stdout.contains(&docs_slug), "got:\n{stdout}");
+    assert!(!stdout.contains(&plain_slug), "got:\n{stdout}");
+
+    let output = env
+      .agency()?
+      .args(["tasks", "--json", "-l", "docs"])
+      .output()?;
+    let items: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    let items = items.as_array().expect("json array");
+    assert_eq!(items.len(), 1);
+    assert_eq!(items[0]["slug"], docs_slug);
+    assert_eq!(items[0]["labels"], serde_json::json!(["docs"]));
+
+    Ok(())
+  })
+}
+
+#[test]
+fn priority_is_set_on_new_and_update_and_sorts_tasks() -> Result<()> {
+  TestEnv::run(|env| -> Result<()> {
+    env.init_repo()?;
+    let (low_id, low_slug) = env.new_task("low-task", &["--priority", "low"])?;
+    let (plain_id, plain_slug) = env.new_task("plain-task", &[])?;
+
+    let content = std::fs::read_to_string(env.task_file_path(low_id, &low_slug))?;
+    assert!(content.contains("priority: low\n"), "got:\n{content}");
+
+    env
+      .agency()?
+      .args(["update", &plain_id.to_string(), "--priority", "high"])
+      .assert()
+      .success();
+    let content = std::fs::read_to_string(env.task_file_path(plain_id, &plain_slug))?;
+    assert!(content.contains("priority: high\n"), "got:\n{content}");
+    assert!(content.contains("Automated test"), "got:\n{content}");
+
+    env
+      .agency()?
+      .args(["update", &plain_id.to_string()])
+      .assert()
+      .failure()
+      .stderr(predicates::str::contains("Nothing to update"));
+
+    let output = env
+      .agency()?
+      .args(["tasks", "--json", "--sort", "priority"])
+      .output()?;
+    let items: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    let items = items.as_array().expect("json array");
+    let order: Vec<_> = items.iter().map(|item| item["priority"].clone()).collect();
+    assert_eq!(order, [serde_json::json!("high"), serde_json::json!("low")]);
+
+    let output = env.agency()?.arg("tasks").output()?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("PRIORITY"), "got:\n{stdout}");
+
+    Ok(())
+  })
+}
+
+#[test]
+fn tasks_sort_orders_rows_by_field() -> Result<()> {
+  TestEnv::run(|env| -> Result<()> {
+    env.init_repo()?;
+    for slug in ["charlie", "alpha", "bravo"] {
+      env.new_task(slug, &[])?;
+    }
+
+    let slug_order = |args: &[&str]| -> Result<Vec<String>> {
+      let output = env.agency()?.args(args).output()?;
+      assert!(output.status.success());
+      let items: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+      Ok(
+        items
+          .as_array()
+          .expect("json array")
+          .iter()
+          .map(|item| item["slug"].as_str().unwrap_or_default().to_string())
+          .collect(),
+      )
+    };
+    assert_eq!(
+      slug_order(&["tasks", "--json", "--sort", "slug"])?,
+      ["alpha", "bravo", "charlie"]
+    );
+    assert_eq!(
+      slug_order(&["tasks", "--json", "--sort", "slug", "--reverse"])?,
+      ["charlie", "bravo", "alpha"]
+    );
+
+    let output = env.agency()?.args(["tasks", "--sort", "slug"]).output()?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let alpha = stdout.find("alpha").expect("alpha row");
+    let charlie = stdout.find("charlie").expect("charlie row");
+    assert!(alpha < charlie, "got:\n{stdout}");
+
+    env
+      .agency()?
+      .args(["tasks", "--sort", "size"])
+      .assert()
+      .failure()
+      .stderr(
+        predicates::str::contains(
+          "Unknown sort field 'size' (valid: id, slug, status, uncommitted, commits, priority)",
+        )
+        .from_utf8(),
+      );
+
+    Ok(())
+  })
+}
+
+#[test]
+fn tasks_renders_configured_columns_in_order() -> Result<()> {
+  TestEnv::run(|env| -> Result<()> {
+    env.init_repo()?;
+    env.write_file(
+      ".agency/agency.toml",
+      "[tasks]\ncolumns = [\"status\", \"id\", \"slug\"]\n\n[agents.sh]\ncmd = [\"sh\"]\n",
+    )?;
+    let (_, slug) = env.new_task("column-task", &["-l", "docs"])?;
+
+    let output = env.agency()?.arg("tasks").output()?;
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("STATUS ID SLUG"), "got:\n{stdout}");
+    for absent in ["UNCOMMITTED", "COMMITS", "BASE", "AGENT", "LABELS"] {
+      assert!(!stdout.contains(absent), "unexpected {absent}:\n{stdout}");
+    }
+    assert!(stdout.contains(&slug), "got:\n{stdout}");
+
+    env.write_file(
+      ".agency/agency.toml",
+      "[tasks]\ncolumns = [\"id\", \"owner\"]\n",
+    )?;
+    env
+      .agency()?
+      .args(["config", "validate"])
+      .assert()
+      .failure()
+      .stderr(predicates::str::contains("unknown variant `owner`").from_utf8());
+
+    Ok(())
+  })
+}
+
+#[test]
+fn tasks_json_emits_drafts_without_daemon() -> Result<()> {
+  TestEnv::run(|env| -> Result<()> {
+    env.init_repo()?;
+    let (id, slug) = env.new_task("json-task", &[])?;
+
+    let output = env.agency()?.arg("tasks").arg("--json").output()?;
+    assert!(output.status.success());
+
+    let items: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    let items = items.as_array().expect("json array");
+    assert_eq!(items.len(), 1);
+    let item = &items[0];
+    assert_eq!(item["id"], id);
+    assert_eq!(item["slug"], slug);
+    assert_eq!(item["status"], "Draft");
+    assert_eq!(item["uncommitted_add"], 0);
+    assert_eq!(item["uncommitted_del"], 0);
+    assert_eq!(item["commits_ahead"], 0);
+    assert_eq!(item["base"], "main");
+    assert_eq!(item["agent"], "claude");
+
+    Ok(())
+  })
+}
+
+#[test]
+fn tasks_size_reports_worktree_disk_usage() -> Result<()> {
+  TestEnv::run(|env| -> Result<()> {
+    env.init_repo()?;
+    let (id, slug) = env.new_task("sized-task", &[])?;
+    env.new_task("draft-task", &[])?;
+    env.bootstrap_task(id)?;
+    std::fs::write(
+      env.worktree_dir_path(id, &slug).join("blob.bin"),
+      [7u8; 4096],
+    )?;
+
+    let output = env.agency()?.args(["tasks", "--size"]).output()?;
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert!(stdout.contains("SIZE"), "missing SIZE header:\n{stdout}");
+    let sized = stdout
+      .lines()
+      .find(|line| line.contains("sized-task"))
+      .expect("sized-task row");
+    assert!(
+      sized.trim_end().ends_with("KiB"),
+      "expected a KiB size:\n{stdout}"
+    );
+    let draft = stdout
+      .lines()
+      .find(|line| line.contains("draft-task"))
+      .expect("draft-task row");
+    assert!(
+      draft.trim_end().ends_with('-'),
+      "expected '-' for no worktree:\n{stdout}"
+    );
+
+    Ok(())
+  })
+}
+
+#[test]
+fn tasks_status_filters_by_derived_status() -> Result<()> {
+  TestEnv::run(|env| -> Result<()> {
+    env.init_repo()?;
+    let (draft_id, _) = env.new_task("draft-task", &[])?;
+    let (stopped_id, _) = env.new_task("stopped-task", &[])?;
+    env.bootstrap_task(stopped_id)?;
+    let running_id = if env.sockets_available() {
+      let (id, _) = env.new_task("running-task", &["-a", "sh"])?;
+      env.agency_daemon_start()?;
+      env
+        .agency()?
+        .args(["start", &id.to_string()])
+        .assert()
+        .success();
+      Some(id)
+    } else {
+      eprintln!(
+        "Skipping running part of tasks_status_filters_by_derived_status: Unix sockets not available in sandbox"
+      );
+      None
+    };
+
+    let ids = |filter: &str| -> Result<Vec<u64>> {
+      let output = env
+        .agency()?
+        .args(["tasks", "--json", "--status", filter])
+        .output()?;
+      assert!(output.status.success());
+      let items: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+      Ok(
+        items
+          .as_array()
+          .expect("json array")
+          .iter()
+          .filter_map(|item| item["id"].as_u64())
+          .collect(),
+      )
+    };
+    assert_eq!(ids("draft")?, vec![u64::from(draft_id)]);
+    assert_eq!(
+      ids("Stopped,draft")?,
+      vec![u64::from(draft_id), u64::from(stopped_id)]
+    );
+    if let Some(id) = running_id {
+      assert_eq!(ids("running,idle")?, vec![u64::from(id)]);
+      env.agency_daemon_stop_all()?;
+    }
+
+    env
+      .agency()?
+      .args(["tasks", "--status", "sleeping"])
+      .assert()
+      .failure()
+      .stderr(predicates::str::contains(
+        "valid: draft, stopped, running, idle, paused, exited",
+      ));
+
+    Ok(())
+  })
+}
+
+#[test]
+fn tasks_reports_failed_when_agent_exits_during_startup() -> Result<()> {
+  TestEnv::run(|env| -> Result<()> {
+    env.init_repo()?;
+    if !env.sockets_available() {
+      eprintln!(
+        "Skipping tasks_reports_failed_when_agent_exits_during_startup: Unix sockets not available in sandbox"
+      );
+      return Ok(());
+    }
+    env.write_file(
+      ".agency/agency.toml",
+      "[agents.broken]\ncmd = [\"false\"]\nstartup_timeout_secs = 30\n",
+    )?;
+    let (id, _) = env.new_task("broken-task", &["-a", "broken"])?;
+    env.agency_daemon_start()?;
+    env
+      .agency()?
+      .args(["start", &id.to_string()])
+      .assert()
+      .success();
+
+    let mut status = String::new();
+    for _ in 0..50 {
+      let output = env.agency()?.args(["tasks", "--json"]).output()?;
+      let items: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+      status = items[0]["status"].as_str().unwrap_or_default().to_string();
+      if status == "Failed" {
+        break;
+      }
+      std::thread::sleep(std::time::Duration::from_millis(100));
+    }
+    assert_eq!(status, "Failed");
+
+    env.agency_daemon_stop_all()?;
+    Ok(())
+  })
+}
+
+#[test]
+fn tasks_color_flag_controls_ansi_escapes() -> Result<()> {
+  TestEnv::run(|env| -> Result<()> {
+    env.init_repo()?;
+    env.new_task("colored", &[])?;
+
+    let always = env
+      .agency()?
+      .args(["--color", "always", "tasks"])
+      .output()?;
+    assert!(
+      String::from_utf8(always.stdout)?.contains('\u{1b}'),
+      "--color always should emit ANSI escapes"
+    );
+
+    let never = env
+      .agency()?
+      .env("CLICOLOR_FORCE", "1")
+      .args(["tasks", "--color", "never"])
+      .output()?;
+    let stdout = String::from_utf8(never.stdout)?;
+    assert!(stdout.contains("colored"), "{stdout}");
+    assert!(!stdout.contains('\u{1b}'), "{stdout:?}");
+
+    let no_color = env.agency()?.env("NO_COLOR", "1").arg("tasks").output()?;
+    assert!(!String::from_utf8(no_color.stdout)?.contains('\u{1b}'));
+
+    let piped = env.agency()?.arg("tasks").output()?;
+    assert!(!String::from_utf8(piped.stdout)?.contains('\u{1b}'));
+
+    Ok(())
+  })
+}
+
+#[test]
+fn tasks_watch_renders_table_until_interrupted() -> Result<()> {
+  TestEnv::run(|env| -> Result<()> {
+    env.init_repo()?;
+    let (_, slug) = env.new_task("watched-task", &[])?;
+
+    let assert = env
+      .agency()?
+      .args(["tasks", "--watch"])
+      .timeout(std::time::Duration::from_secs(1))
+      .assert()
+      .interrupted();
+    let stdout = String::from_utf8_lossy(&assert.get_output().stdout).to_string();
+    assert_eq!(
+      stdout.matches(&slug).count(),
+      1,
+      "expected a single render, got:\n{stdout}"
+    );
+
+    env
+      .agency()?
+      .args(["tasks", "--watch", "--json"])
+      .assert()
+      .failure()
+      .stderr(predicates::str::contains("cannot be used with"));
+
+    Ok(())
+  })
+}
@@ -0,0 +1,51 @@
+mod common;
+
+use crate::common::test_env::TestEnv;
+use anyhow::Result;
+use predicates::prelude::*;
+
+#[test]
+fn worktree_prune_clears_dangling_entries_only() -> Result<()> {
+  TestEnv::run(|env| -> Result<()> {
+    env.init_repo()?;
+    let (gone_id, gone_slug) = env.new_task("gone-task", &[])?;
+    let (live_id, live_slug) = env.new_task("live-task", &[])?;
+    env.bootstrap_task(gone_id)?;
+    env.bootstrap_task(live_id)?;
+    let gone_dir = env.worktree_dir_path(gone_id, &gone_slug);
+    std::fs::remove_dir_all(&gone_dir)?;
+
+    env
+      .agency()?
+      .args(["worktree", "prune"])
+      .assert()
+      .success()
+      .stdout(predicates::str::contains("Pruned stale worktree entries: 1").from_utf8());
+
+    let list = env.git_stdout(&["worktree", "list", "--porcelain"])?;
+    assert!(
+      !list.contains(&format!("{gone_id}-{gone_slug}")),
+      "got:\n{list}"
+    );
+    assert!(
+      list.contains(&format!("{live_id}-{live_slug}")),
+      "got:\n{list}"
+    );
+    assert!(env.branch_exists(gone_id, &gone_slug)?);
+    env
+      .agency()?
+      .args(["path", &live_id.to_string()])
+      .assert()
+      .success()
+      .stdout(predicates::str::contains(format!("{live_id}-{live_slug}")).from_utf8());
+
+    env
+      .agency()?
+      .args(["worktree", "prune"])
+      .assert()
+      .success()
+      .stdout(predicates::str::contains("No stale worktree entries").from_utf8());
+
+    Ok(())
+  })
+}
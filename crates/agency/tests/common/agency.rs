@@ -24,6 +24,16 @@ impl TestEnv {
     Ok(())
   }
 
+  /// Stop the daemon together with its tmux server, skipping the prompt.
+  pub fn agency_daemon_stop_all(&self) -> Result<()> {
+    self
+      .agency()?
+      .args(["daemon", "stop", "--yes"])
+      .assert()
+      .success();
+    Ok(())
+  }
+
   pub fn new_task(&self, slug: &str, extra_args: &[&str]) -> Result<(u32, String)> {
     let agen_dir = self.path().join(".agency");
     let cfg_path = agen_dir.join("agency.toml");
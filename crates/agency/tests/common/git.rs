@@ -101,6 +101,22 @@ impl TestEnv {
     Ok(new_id.into())
   }
 
+  pub fn git_commit_file_in_worktree(
+    &self,
+    id: u32,
+    slug: &str,
+    relative: &str,
+    body: &str,
+    message: &str,
+  ) -> Result<()> {
+    let wt_dir = self.worktree_dir_path(id, slug);
+    std::fs::write(wt_dir.join(relative), body).context("write worktree file")?;
+    let wt = wt_dir.display().to_string();
+    let _ = self.git_stdout(&["-C", &wt, "add", relative])?;
+    let _ = self.git_stdout(&["-C", &wt, "commit", "-m", message])?;
+    Ok(())
+  }
+
   pub fn git_status_porcelain(&self) -> Result<String> {
     self.git_stdout(&["status", "--porcelain", "--untracked-files=no"])
   }
@@ -82,12 +82,18 @@ printf '%s\n' 'Automated test' >\"$file\"
     Self::run_with_editor("vi", f)
   }
 
+  pub fn wait_for<F>(&self, assert_fn: F) -> Result<()>
+  where
+    F: FnMut() -> Result<bool>,
+  {
+    self.wait_for_within(Duration::from_secs(1), assert_fn)
+  }
+
   #[allow(clippy::unused_self)]
-  pub fn wait_for<F>(&self, mut assert_fn: F) -> Result<()>
+  pub fn wait_for_within<F>(&self, timeout: Duration, mut assert_fn: F) -> Result<()>
   where
     F: FnMut() -> Result<bool>,
   {
-    let timeout = Duration::from_secs(1);
     let deadline = Instant::now() + timeout;
     loop {
       if assert_fn()? {
@@ -179,7 +185,34 @@ printf '%s\n' 'Automated test' >\"$file\"
   }
 
   pub fn agency(&self) -> Result<Command> {
-    let mut cmd = Command::cargo_bin("agency")?;
+    Ok(Command::from_std(self.agency_process()))
+  }
+
+  /// Like [`Self::agency`], but as a plain process for tests that spawn it.
+  pub fn agency_process(&self) -> std::process::Command {
+    self.isolated_command(cargo::cargo_bin("agency"))
+  }
+
+  /// Spawn agency with `args` inside a pseudo terminal from `script`, for
+  /// commands like attach that require one. Stdin stays open, so the client
+  /// keeps running until it is killed; its output is discarded.
+  pub fn spawn_agency_in_pty(&self, args: &[&str]) -> Result<std::process::Child> {
+    let program = cargo::cargo_bin("agency").display().to_string();
+    let command_line =
+      shell_words::join(std::iter::once(program.as_str()).chain(args.iter().copied()));
+    self
+      .isolated_command("script")
+      .args(["-qec", &command_line, "/dev/null"])
+      .env("TERM", "xterm")
+      .env("SHELL", "/bin/sh")
+      .stdin(std::process::Stdio::piped())
+      .stdout(std::process::Stdio::null())
+      .spawn()
+      .context("failed to spawn script for a pty")
+  }
+
+  fn isolated_command(&self, program: impl AsRef<std::ffi::OsStr>) -> std::process::Command {
+    let mut cmd = std::process::Command::new(program);
     cmd.current_dir(self.path());
     // Set isolated paths - these override any inherited values but don't clear other env vars
     // which allows tests using with_vars to still work
@@ -195,7 +228,7 @@ printf '%s\n' 'Automated test' >\"$file\"
     }
     // Remove SHELL so tests get predictable /bin/sh fallback
     cmd.env_remove("SHELL");
-    Ok(cmd)
+    cmd
   }
 
   pub fn agency_tty(&self) -> std::process::Command {
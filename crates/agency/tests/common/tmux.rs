@@ -77,4 +77,28 @@ impl TestEnv {
     }
     Ok(String::from_utf8_lossy(&output.stdout).to_string())
   }
+
+  /// tmux command against the server agency runs task sessions on.
+  pub fn agency_tmux(&self) -> std::process::Command {
+    let mut cmd = std::process::Command::new("tmux");
+    cmd
+      .arg("-S")
+      .arg(self.runtime_dir().join("agency-tmux.sock"));
+    cmd
+  }
+
+  /// One line per client attached to agency's tmux server, formatted with `format`.
+  pub fn agency_tmux_clients(&self, format: &str) -> Result<Vec<String>> {
+    let output = self
+      .agency_tmux()
+      .args(["list-clients", "-F", format])
+      .output()
+      .context("tmux list-clients for test failed")?;
+    Ok(
+      String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(str::to_string)
+        .collect(),
+    )
+  }
 }
@@ -119,3 +119,106 @@ fn invalid_toml_fails_with_actionable_error() {
     "error should mention invalid TOML: {msg}"
   );
 }
+
+#[test]
+fn project_scrollback_lines_win_over_global() -> Result<()> {
+  let dir = Builder::new().tempdir_in(common::test_env::tmp_root())?;
+  let xdg_root = Builder::new().tempdir_in(common::test_env::tmp_root())?;
+  temp_env::with_var(
+    "XDG_CONFIG_HOME",
+    Some(xdg_root.path().as_os_str()),
+    || -> Result<()> {
+      let cfg = load_config(dir.path())?;
+      let lines = cfg.daemon.and_then(|d| d.scrollback_lines);
+      assert_eq!(lines, None, "scrollback should default to tmux setting");
+
+      // Global
+      let global_dir = xdg_root.path().join("agency");
+      fs::create_dir_all(&global_dir)?;
+      fs::write(
+        global_dir.join("agency.toml"),
+        "[daemon]\nscrollback_lines = 10000\n",
+      )?;
+      let cfg = load_config(dir.path())?;
+      assert_eq!(cfg.daemon.and_then(|d| d.scrollback_lines), Some(10000));
+
+      // Project
+      let project_dir = dir.path().join(".agency");
+      fs::create_dir_all(&project_dir)?;
+      fs::write(
+        project_dir.join("agency.toml"),
+        "[daemon]\nscrollback_lines = 50000\n",
+      )?;
+      let cfg = load_config(dir.path())?;
+      assert_eq!(cfg.daemon.and_then(|d| d.scrollback_lines), Some(50000));
+
+      // Environment
+      temp_env::with_var("AGENCY_SCROLLBACK_LINES", Some("70000"), || {
+        let cfg = load_config(dir.path())?;
+        assert_eq!(cfg.daemon.and_then(|d| d.scrollback_lines), Some(70000));
+        Ok::<_, anyhow::Error>(())
+      })?;
+      temp_env::with_var("AGENCY_SCROLLBACK_LINES", Some("lots"), || {
+        let err = load_config(dir.path()).expect_err("invalid override should fail");
+        let message = err.to_string();
+        assert!(
+          message.contains("invalid AGENCY_SCROLLBACK_LINES 'lots'"),
+          "error should name the variable: {message}"
+        );
+      });
+      Ok(())
+    },
+  )?;
+  Ok(())
+}
+
+#[test]
+fn project_agent_env_merges_over_global_per_key() -> Result<()> {
+  let dir = Builder::new().tempdir_in(common::test_env::tmp_root())?;
+  let xdg_root = Builder::new().tempdir_in(common::test_env::tmp_root())?;
+  temp_env::with_var(
+    "XDG_CONFIG_HOME",
+    Some(xdg_root.path().as_os_str()),
+    || -> Result<()> {
+      // Global
+      let global_dir = xdg_root.path().join("agency");
+      fs::create_dir_all(&global_dir)?;
+      fs::write(
+        global_dir.join("agency.toml"),
+        r#"[agents.claude.env]
+API_KEY = "global-key"
+SHARED = "global"
+"#,
+      )?;
+
+      // Project
+      let project_dir = dir.path().join(".agency");
+      fs::create_dir_all(&project_dir)?;
+      fs::write(
+        project_dir.join("agency.toml"),
+        r#"[agents.claude.env]
+SHARED = "project"
+EXTRA = "$HOME/bin"
+"#,
+      )?;
+
+      let cfg = load_config(dir.path())?;
+      let claude = cfg.agents.get("claude").expect("claude present");
+      assert_eq!(
+        claude.env.get("API_KEY").map(String::as_str),
+        Some("global-key")
+      );
+      assert_eq!(
+        claude.env.get("SHARED").map(String::as_str),
+        Some("project")
+      );
+      assert_eq!(
+        claude.env.get("EXTRA").map(String::as_str),
+        Some("$HOME/bin")
+      );
+      assert!(!claude.cmd.is_empty(), "default cmd should be kept");
+      Ok(())
+    },
+  )?;
+  Ok(())
+}